@@ -0,0 +1,97 @@
+//! Solver micro-benchmarks. Criterion can't be vendored into this build, so
+//! this is a small self-contained harness in the same spirit: warm up, run
+//! for a fixed wall-clock budget, report the mean time per iteration.
+//!
+//! Run with `cargo bench`, optionally passing a filter: `cargo bench -- linear`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use lightsout::{search::{a_star, Search}, BatchSolver, Board};
+
+const BUDGET: Duration = Duration::from_millis(500);
+
+fn bench(filter: &Option<String>, name: &str, mut f: impl FnMut()) {
+    if filter.as_ref().is_some_and(|filter| !name.contains(filter.as_str())) {
+        return;
+    }
+    let warmup = Instant::now();
+    while warmup.elapsed() < BUDGET / 5 {
+        f();
+    }
+    let start = Instant::now();
+    // Grow the batch between clock reads so timing overhead doesn't swamp
+    // the nanosecond-scale benchmarks.
+    let mut iterations: u32 = 0;
+    let mut batch = 1;
+    while start.elapsed() < BUDGET {
+        for _ in 0..batch {
+            f();
+        }
+        iterations += batch;
+        batch = (batch * 2).min(1 << 16);
+    }
+    let per_iter = start.elapsed() / iterations;
+    println!("{name:<32} {per_iter:>12.2?}/iter ({iterations} iterations)");
+}
+
+fn board(width: usize, height: usize, seed: u64) -> Board {
+    let mut board = Board::new(width, height);
+    board.randomize(seed);
+    board
+}
+
+/// The first few seeds giving solvable boards of the given size, so every
+/// run benchmarks the same puzzles.
+fn canonical_seeds(width: usize, height: usize, count: usize) -> Vec<u64> {
+    (0..)
+        .filter(|&seed| board(width, height, seed).solve_linear().is_some())
+        .take(count)
+        .collect()
+}
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+
+    let base = board(5, 5, 0);
+    bench(&filter, "toggle/5x5", || {
+        black_box(black_box(&base).clone_toggle(2, 2));
+    });
+    bench(&filter, "score/5x5", || {
+        black_box(black_box(&base).score());
+    });
+    bench(&filter, "moves/5x5", || {
+        black_box(black_box(&base).moves());
+    });
+
+    for seed in canonical_seeds(5, 5, 3) {
+        let start = board(5, 5, seed);
+        bench(&filter, &format!("a_star/5x5/seed-{seed}"), || {
+            black_box(a_star(black_box(start.clone()), 25));
+        });
+    }
+
+    for size in [5, 10, 20, 40, 64] {
+        let start = board(size, size, 0);
+        bench(&filter, &format!("linear/{size}x{size}"), || {
+            black_box(black_box(&start).solve_linear());
+        });
+    }
+
+    for size in [5, 10, 20] {
+        let boards: Vec<Board> = (0..BatchSolver::LANES as u64)
+            .map(|seed| board(size, size, seed))
+            .collect();
+        let solver = BatchSolver::new(size, size);
+        bench(&filter, &format!("batch/{size}x{size}/x{}", boards.len()), || {
+            black_box(solver.solve(black_box(&boards)));
+        });
+        bench(&filter, &format!("linear/{size}x{size}/x{}", boards.len()), || {
+            for board in &boards {
+                black_box(board.solve_linear());
+            }
+        });
+    }
+}
@@ -0,0 +1,61 @@
+//! Batch difficulty analysis: thousands of random boards of a few sizes,
+//! solved together by the bit-sliced [`BatchSolver`], summarised by how
+//! many are solvable and how long their shortest solutions run.
+//!
+//! Run with `cargo run --release --example difficulty`, optionally passing
+//! the boards to sample per size: `cargo run --example difficulty -- 2000`.
+
+use lightsout::{analysis::Heatmap, BatchSolver, Board};
+
+const SIZES: &[(usize, usize)] = &[(4, 4), (5, 5), (6, 6), (7, 7), (9, 9)];
+
+fn main() {
+    let samples: u64 = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("the sample count should be a number"))
+        .unwrap_or(10_000);
+    println!(
+        "{:>6}{:>8}{:>11}{:>8}{:>8}{:>8}{:>10}",
+        "size", "kernel", "solvable", "mean", "median", "max", "unique"
+    );
+    for &(width, height) in SIZES {
+        let boards: Vec<Board> = (0..samples)
+            .map(|seed| {
+                let mut board = Board::new(width, height);
+                board.randomize(seed);
+                board
+            })
+            .collect();
+        let solver = BatchSolver::new(width, height);
+        let solutions = solver.solve_parallel(&boards, 4);
+
+        let mut lengths = Vec::new();
+        let mut heatmap = Heatmap::new(width, height);
+        for (board, solution) in boards.iter().zip(&solutions) {
+            let Some(solution) = solution else {
+                continue;
+            };
+            lengths.push(solution.count());
+            // Finding every minimal solution walks the whole kernel, so
+            // only the first few hundred boards go into the heatmap.
+            if heatmap.samples < 200 {
+                heatmap.add(board);
+            }
+        }
+        lengths.sort_unstable();
+        let solvable = lengths.len();
+        let mean = lengths.iter().sum::<usize>() as f64 / solvable.max(1) as f64;
+        let median = lengths.get(solvable / 2).copied().unwrap_or(0);
+        let max = lengths.last().copied().unwrap_or(0);
+        println!(
+            "{:>6}{:>8}{:>10.1}%{:>8.1}{:>8}{:>8}{:>9.0}%",
+            format!("{width}x{height}"),
+            boards[0].kernel_dimension(),
+            solvable as f64 * 100.0 / samples as f64,
+            mean,
+            median,
+            max,
+            heatmap.unique as f64 * 100.0 / heatmap.samples.max(1) as f64,
+        );
+    }
+}
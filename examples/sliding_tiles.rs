@@ -0,0 +1,143 @@
+//! A puzzle of its own searched by the library's A*: the eight-tile sliding
+//! puzzle, where a tile next to the gap slides into it and the goal is the
+//! tiles in order with the gap last.
+//!
+//! Anything implementing [`Search`] gets the same frontiers, depth limits
+//! and reports as boards do. Scores go up as states get better, so this one
+//! counts tiles already home, and moves are numbered by the cell the gap
+//! moves to.
+//!
+//! Run with `cargo run --example sliding_tiles`.
+
+use std::fmt::{self, Display};
+
+use lightsout::search::{a_star, Search};
+
+const SIDE: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Tiles {
+    /// Tile numbers in row-major order, with 0 for the gap.
+    cells: [u8; SIDE * SIDE],
+}
+
+impl Tiles {
+    fn solved() -> Tiles {
+        let mut cells = [0; SIDE * SIDE];
+        for (i, cell) in cells.iter_mut().enumerate().take(SIDE * SIDE - 1) {
+            *cell = i as u8 + 1;
+        }
+        Tiles { cells }
+    }
+
+    fn gap(&self) -> usize {
+        self.cells.iter().position(|&tile| tile == 0).unwrap()
+    }
+
+    /// Cells the gap can move to.
+    fn neighbours(&self) -> Vec<usize> {
+        let gap = self.gap();
+        let (x, y) = (gap % SIDE, gap / SIDE);
+        let mut cells = Vec::new();
+        if x > 0 {
+            cells.push(gap - 1);
+        }
+        if x + 1 < SIDE {
+            cells.push(gap + 1);
+        }
+        if y > 0 {
+            cells.push(gap - SIDE);
+        }
+        if y + 1 < SIDE {
+            cells.push(gap + SIDE);
+        }
+        cells
+    }
+
+    fn slide(&self, to: usize) -> Tiles {
+        let mut next = self.clone();
+        next.cells.swap(self.gap(), to);
+        next
+    }
+}
+
+impl Display for Tiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(SIDE) {
+            for &tile in row {
+                match tile {
+                    0 => write!(f, " .")?,
+                    tile => write!(f, " {tile}")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Search for Tiles {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        let goal = Tiles::solved();
+        let home = self
+            .cells
+            .iter()
+            .zip(goal.cells)
+            .filter(|&(&tile, goal)| tile == goal);
+        home.filter(|&(&tile, _)| tile != 0).count()
+    }
+
+    fn end(&self) -> bool {
+        *self == Tiles::solved()
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        self.neighbours()
+            .into_iter()
+            .map(|to| (self.slide(to), to))
+            .collect()
+    }
+}
+
+fn main() {
+    // Scramble by sliding the gap around from the solved position, never
+    // straight back, so the puzzle is sure to be solvable.
+    let mut start = Tiles::solved();
+    let (mut seed, mut previous) = (7u64, usize::MAX);
+    for _ in 0..16 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let neighbours: Vec<usize> = start
+            .neighbours()
+            .into_iter()
+            .filter(|&to| to != previous)
+            .collect();
+        previous = start.gap();
+        start = start.slide(neighbours[(seed >> 33) as usize % neighbours.len()]);
+    }
+    println!("Start:\n{start}");
+
+    let (solution, report) = a_star(start.clone(), 60);
+    let Some(solution) = solution else {
+        println!("No solution within 60 slides");
+        return;
+    };
+    // A* here is best-first on the score, so the path found is a short one
+    // rather than the shortest.
+    let mut tiles = start;
+    let mut moved = Vec::new();
+    for &to in solution.history.iter().chain(&solution.latest_move_index) {
+        moved.push(tiles.cells[to].to_string());
+        tiles = tiles.slide(to);
+    }
+    println!("Slide tiles {}", moved.join(" "));
+    println!(
+        "Solved in {} slides, exploring {} states",
+        moved.len(),
+        report.explored
+    );
+    assert!(tiles.end());
+}
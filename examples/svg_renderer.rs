@@ -0,0 +1,75 @@
+//! A renderer of its own: a board and its solution drawn as an SVG image,
+//! from nothing but the public board API. Lit cells are filled, presses
+//! get a dot, cells the goal wants lit have a ring, and obstacles are left
+//! out.
+//!
+//! Run with `cargo run --example svg_renderer -- 5x5-c408f38 > board.svg`,
+//! passing any puzzle code.
+
+use std::fmt::Write;
+
+use lightsout::{BitMask, Board};
+
+/// Pixels per cell, and the gap around each.
+const CELL: usize = 40;
+const GAP: usize = 4;
+
+fn svg(board: &Board, presses: Option<&BitMask>) -> String {
+    let rules = board.ruleset();
+    let (width, height) = (board.width() * CELL, board.height() * CELL);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"{width}\" height=\"{height}\" fill=\"#101010\"/>\n"
+    );
+    for ((x, y), lit) in board.cells() {
+        if rules.is_obstacle(x, y) {
+            continue;
+        }
+        let (left, top) = (x * CELL + GAP, y * CELL + GAP);
+        let size = CELL - 2 * GAP;
+        let fill = if lit { "#ffd60a" } else { "#303030" };
+        writeln!(
+            svg,
+            "<rect x=\"{left}\" y=\"{top}\" width=\"{size}\" height=\"{size}\" rx=\"6\" fill=\"{fill}\"/>"
+        )
+        .unwrap();
+        let (cx, cy) = (x * CELL + CELL / 2, y * CELL + CELL / 2);
+        if rules.goal.as_ref().is_some_and(|goal| goal.get(x, y)) {
+            writeln!(
+                svg,
+                "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{}\" fill=\"none\" stroke=\"#56b4e9\" stroke-width=\"3\"/>",
+                CELL / 3
+            )
+            .unwrap();
+        }
+        if presses.is_some_and(|presses| presses.get(x, y)) {
+            writeln!(
+                svg,
+                "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{}\" fill=\"#dc2828\"/>",
+                CELL / 8
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn main() {
+    let code = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "5x5-c408f38".to_string());
+    let board = match Board::from_code(&code) {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(3);
+        }
+    };
+    let solution = board.solve_linear();
+    if solution.is_none() {
+        eprintln!("warning: {code} can't be solved, so no presses are marked");
+    }
+    print!("{}", svg(&board, solution.as_ref()));
+}
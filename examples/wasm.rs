@@ -0,0 +1,88 @@
+//! A WebAssembly binding: a handful of plain `extern "C"` functions over
+//! linear memory, enough for JavaScript to solve boards without
+//! wasm-bindgen or any other glue crate. Only the `no_std` core is used,
+//! so it builds without the `std` feature, which would need a source of
+//! randomness the browser target lacks:
+//!
+//! ```text
+//! cargo build --release --example wasm --target wasm32-unknown-unknown --no-default-features
+//! ```
+//!
+//! From JavaScript, with `exports` the instance's exports:
+//!
+//! ```text
+//! const code = new TextEncoder().encode("5x5-c408f38");
+//! const input = exports.lightsout_alloc(code.length);
+//! new Uint8Array(exports.memory.buffer, input, code.length).set(code);
+//! const output = exports.lightsout_alloc(4 * 64);
+//! const count = exports.lightsout_solve(input, code.length, output, 64);
+//! const cells = new Uint32Array(exports.memory.buffer, output, Math.max(count, 0));
+//! ```
+//!
+//! On other targets it builds as an ordinary shared library with the same
+//! exports, and `main`-less examples like this one are checked by
+//! `cargo test` along with the rest.
+
+use lightsout::Board;
+
+/// `lightsout_solve` results that aren't a press count.
+const UNSOLVABLE: i32 = -1;
+const BAD_CODE: i32 = -2;
+const TOO_MANY_PRESSES: i32 = -3;
+
+/// Reserve `len` bytes for the host to write into, aligned for the `u32`
+/// cells `lightsout_solve` writes.
+#[no_mangle]
+pub extern "C" fn lightsout_alloc(len: usize) -> *mut u8 {
+    let words = vec![0u32; len.div_ceil(4)].into_boxed_slice();
+    Box::into_raw(words) as *mut u8
+}
+
+/// Give back memory from [`lightsout_alloc`], with the same `len`.
+///
+/// # Safety
+///
+/// `ptr` must have come from `lightsout_alloc(len)` and not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn lightsout_free(ptr: *mut u8, len: usize) {
+    let words = std::ptr::slice_from_raw_parts_mut(ptr as *mut u32, len.div_ceil(4));
+    drop(Box::from_raw(words));
+}
+
+/// Solve the board whose puzzle code is the `code_len` bytes at `code`,
+/// writing the shortest solution's presses as row-major cell indices to
+/// `out`, which has room for `out_cap` of them. Returns how many presses
+/// there are, or a negative code: -1 unsolvable, -2 not a puzzle code, -3
+/// more presses than fit.
+///
+/// # Safety
+///
+/// `code` must point at `code_len` readable bytes, and `out` at `out_cap`
+/// writable, aligned `u32`s.
+#[no_mangle]
+pub unsafe extern "C" fn lightsout_solve(
+    code: *const u8,
+    code_len: usize,
+    out: *mut u32,
+    out_cap: usize,
+) -> i32 {
+    let code = std::slice::from_raw_parts(code, code_len);
+    let Some(board) = std::str::from_utf8(code)
+        .ok()
+        .and_then(|code| Board::from_code(code).ok())
+    else {
+        return BAD_CODE;
+    };
+    let Some(solution) = board.solve_linear() else {
+        return UNSOLVABLE;
+    };
+    let presses = solution.positions();
+    if presses.len() > out_cap {
+        return TOO_MANY_PRESSES;
+    }
+    let out = std::slice::from_raw_parts_mut(out, out_cap);
+    for (slot, (x, y)) in out.iter_mut().zip(presses.iter()) {
+        *slot = (y * board.width() + x) as u32;
+    }
+    presses.len() as i32
+}
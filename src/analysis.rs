@@ -0,0 +1,194 @@
+//! Statistics over boards and their solutions.
+
+use std::ops::Range;
+
+use crate::core::{BatchSolver, BitMask, Board, BoardBuilder, BuildError};
+use crate::generate::Generated;
+#[cfg(feature = "image")]
+use crate::png;
+
+/// How often each cell is pressed across the minimal solutions of a set of
+/// boards. A board with several minimal solutions spreads its weight evenly
+/// across them, so every board counts the same.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    pub width: usize,
+    pub height: usize,
+    counts: Vec<f64>,
+    pub samples: usize,
+    /// Boards counted that had exactly one minimal solution.
+    pub unique: usize,
+}
+
+impl Heatmap {
+    pub fn new(width: usize, height: usize) -> Heatmap {
+        Heatmap {
+            width,
+            height,
+            counts: vec![0.0; width * height],
+            samples: 0,
+            unique: 0,
+        }
+    }
+
+    /// Add a board's minimal solutions; unsolvable boards are skipped.
+    /// Returns whether the board was counted.
+    pub fn add(&mut self, board: &Board) -> bool {
+        self.add_solutions(&board.minimal_solutions())
+    }
+
+    /// Add one board given all of its minimal solutions, for callers that
+    /// already have them, e.g. from a [`StateTable`](crate::search::StateTable).
+    /// An empty slice is an unsolvable board and is skipped.
+    pub fn add_solutions(&mut self, minimal: &[BitMask]) -> bool {
+        if minimal.is_empty() {
+            return false;
+        }
+        let weight = 1.0 / minimal.len() as f64;
+        for solution in minimal {
+            for (x, y) in solution.positions() {
+                self.counts[y * self.width + x] += weight;
+            }
+        }
+        self.samples += 1;
+        if minimal.len() == 1 {
+            self.unique += 1;
+        }
+        true
+    }
+
+    /// Fraction of boards whose minimal solution presses this cell.
+    pub fn frequency(&self, x: usize, y: usize) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.counts[y * self.width + x] / self.samples as f64
+        }
+    }
+
+    /// Lowest and highest frequency over all cells.
+    pub fn range(&self) -> (f64, f64) {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| self.frequency(x, y))
+            .fold((f64::INFINITY, 0.0), |(min, max), f| (min.min(f), max.max(f)))
+    }
+
+    /// Colour for a frequency between the coldest and hottest cells, running
+    /// from dark blue through red to yellow.
+    pub fn color(&self, x: usize, y: usize) -> [u8; 3] {
+        let (min, max) = self.range();
+        let t = if max > min {
+            (self.frequency(x, y) - min) / (max - min)
+        } else {
+            0.0
+        };
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [channel(t * 2.0), channel(t * 2.0 - 1.0), channel(0.4 - t)]
+    }
+
+    /// The heatmap as a PNG with each cell drawn `scale` pixels square.
+    #[cfg(feature = "image")]
+    pub fn to_png(&self, scale: usize) -> Vec<u8> {
+        let (width, height) = (self.width * scale, self.height * scale);
+        let pixels: Vec<[u8; 3]> = (0..height)
+            .flat_map(|py| (0..width).map(move |px| (px / scale, py / scale)))
+            .map(|(x, y)| self.color(x, y))
+            .collect();
+        png::encode_rgb(width, height, &pixels)
+    }
+}
+
+/// What [`prospect`] looks for among the boards it sweeps, beyond their
+/// being solvable.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Criteria {
+    /// Exactly this many presses in the optimal solution.
+    pub optimal: Option<usize>,
+    /// A single minimal solution.
+    pub unique: bool,
+    /// A start that some rotation or reflection maps onto itself; see
+    /// [`Board::is_symmetric`].
+    pub symmetric: bool,
+    /// Only the boards with the longest optimum of any meeting the rest, so
+    /// a sweep keeps the hardest it came across.
+    pub hardest: bool,
+}
+
+impl Criteria {
+    /// Everything but [`Criteria::hardest`], which depends on the rest of
+    /// the sweep. Uniqueness goes last, as the one that costs a search.
+    fn accepts(&self, board: &Board, optimal: usize) -> bool {
+        self.optimal.is_none_or(|wanted| wanted == optimal)
+            && (!self.symmetric || board.is_symmetric())
+            && (!self.unique || board.minimal_solutions().len() == 1)
+    }
+}
+
+/// What a [`prospect`] sweep tried, and the boards it kept in seed order.
+#[derive(Clone, Debug)]
+pub struct Sweep {
+    pub seeds: u64,
+    pub solvable: u64,
+    pub matches: Vec<Generated>,
+}
+
+/// Seeds solved per batch by each of a sweep's threads.
+const SWEEP_PASSES: usize = 16;
+
+/// Build a board from `builder` for every seed in `seeds`, solve them in
+/// bit-sliced batches on `threads` worker threads, and keep the solvable
+/// ones meeting `criteria`. Every seed is tried, so the same range always
+/// finds the same boards.
+///
+/// ```
+/// use lightsout::{analysis::{prospect, Criteria}, BoardBuilder};
+///
+/// let criteria = Criteria { hardest: true, ..Criteria::default() };
+/// let sweep = prospect(&BoardBuilder::new().width(3).height(3), 0..512, 2, &criteria).unwrap();
+/// assert_eq!(sweep.seeds, 512);
+/// // Every 3x3 board is solvable, and the hardest need all nine presses.
+/// assert_eq!(sweep.solvable, 512);
+/// assert!(!sweep.matches.is_empty());
+/// assert!(sweep.matches.iter().all(|found| found.optimal == 9));
+/// ```
+pub fn prospect(
+    builder: &BoardBuilder,
+    seeds: Range<u64>,
+    threads: usize,
+    criteria: &Criteria,
+) -> Result<Sweep, BuildError> {
+    let first = builder.build()?;
+    let solver = BatchSolver::new(first.width(), first.height());
+    let batch = (BatchSolver::LANES * threads.max(1) * SWEEP_PASSES) as u64;
+    let mut sweep = Sweep {
+        seeds: seeds.end.saturating_sub(seeds.start),
+        solvable: 0,
+        matches: Vec::new(),
+    };
+    let mut start = seeds.start;
+    while start < seeds.end {
+        let end = start.saturating_add(batch).min(seeds.end);
+        let boards = (start..end)
+            .map(|seed| builder.clone().seed(seed).build())
+            .collect::<Result<Vec<Board>, BuildError>>()?;
+        let solutions = solver.solve_parallel(&boards, threads);
+        for ((seed, board), solution) in (start..end).zip(boards).zip(solutions) {
+            let Some(solution) = solution else {
+                continue;
+            };
+            sweep.solvable += 1;
+            let optimal = solution.count();
+            let hardest = sweep.matches.first().map_or(0, |found| found.optimal);
+            if criteria.hardest && optimal < hardest || !criteria.accepts(&board, optimal) {
+                continue;
+            }
+            if criteria.hardest && optimal > hardest {
+                sweep.matches.clear();
+            }
+            sweep.matches.push(Generated { seed, board, optimal });
+        }
+        start = end;
+    }
+    Ok(sweep)
+}
@@ -0,0 +1,186 @@
+//! The parts of a chat bot that don't need a network: reading a board
+//! pasted into a message as emoji or text, and writing the reply as a press
+//! list and an image. The bot itself, which logs in to Discord or IRC and
+//! calls [`reply`] for each message it's asked about, lives outside this
+//! crate along with its network dependencies.
+
+use crate::{
+    cell_name,
+    core::{BitMask, Board, ParseError},
+    png,
+    theme::{Theme, CLASSIC},
+};
+
+/// Emoji for lit and unlit cells when writing boards back to chat.
+pub const LIT: &str = "🟨";
+pub const UNLIT: &str = "⬛";
+/// A lit or unlit cell to press, for drawing solutions.
+pub const LIT_PRESS: &str = "🟧";
+pub const UNLIT_PRESS: &str = "🟥";
+
+/// Emoji and Discord shortcodes that stand for cells, with whether they're
+/// lit. Anything light is lit and anything dark unlit.
+const CELLS: &[(&str, bool)] = &[
+    ("🟨", true),
+    ("⬜", true),
+    ("🟡", true),
+    ("⚪", true),
+    ("💡", true),
+    ("🌕", true),
+    ("⭐", true),
+    ("⬛", false),
+    ("⚫", false),
+    ("🌑", false),
+    (":yellow_square:", true),
+    (":white_large_square:", true),
+    (":yellow_circle:", true),
+    (":white_circle:", true),
+    (":bulb:", true),
+    (":full_moon:", true),
+    (":star:", true),
+    (":black_large_square:", false),
+    (":black_circle:", false),
+    (":new_moon:", false),
+];
+
+/// A line of a message with each cell emoji or shortcode replaced by `#` or
+/// `.`, or `None` if anything else is left on it.
+fn board_line(line: &str) -> Option<String> {
+    // Emoji sometimes arrive with a variation selector after them.
+    let mut line = line.replace('\u{fe0f}', "");
+    for (cell, lit) in CELLS {
+        line = line.replace(cell, if *lit { "#" } else { "." });
+    }
+    let cells = line.chars().filter(|c| !c.is_whitespace());
+    let board = cells.clone().all(|c| "#Xx1*.Oo0-_█░".contains(c));
+    (board && cells.count() > 0).then_some(line)
+}
+
+/// The board in a chat message: the first run of lines made only of cells,
+/// as emoji, shortcodes or the text [`Board::from_str`] reads, so the
+/// command word, code fences and any chatter around the grid are ignored.
+/// A message without a grid is read as a puzzle code if any word in it is
+/// one.
+///
+/// [`Board::from_str`]: core::str::FromStr
+pub fn parse_message(message: &str) -> Result<Board, ParseError> {
+    let grid: Vec<String> = message
+        .lines()
+        .map(board_line)
+        .skip_while(Option::is_none)
+        .map_while(|line| line)
+        .collect();
+    if !grid.is_empty() {
+        return grid.join("\n").parse();
+    }
+    message
+        .split_whitespace()
+        .map(|word| word.trim_matches('`'))
+        .find_map(|word| Board::from_code(word).ok())
+        .ok_or(ParseError::Empty)
+}
+
+/// The board as emoji, one row per line, with the cells in `presses`
+/// marked.
+pub fn to_emoji(board: &Board, presses: Option<&BitMask>) -> String {
+    let mut out = String::new();
+    for (y, row) in board.rows().enumerate() {
+        for (x, lit) in row.enumerate() {
+            let pressed = presses.is_some_and(|presses| presses.get(x, y));
+            out += match (lit, pressed) {
+                (true, false) => LIT,
+                (false, false) => UNLIT,
+                (true, true) => LIT_PRESS,
+                (false, true) => UNLIT_PRESS,
+            };
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The board as a PNG in `theme`'s colours with each cell `scale` pixels
+/// square and a mark on each cell in `presses`. Obstacles are left as
+/// background.
+pub fn to_png(board: &Board, presses: Option<&BitMask>, scale: usize, theme: &Theme) -> Vec<u8> {
+    let scale = scale.max(4);
+    let rules = board.ruleset();
+    let (width, height) = (board.width() * scale, board.height() * scale);
+    let pixels: Vec<[u8; 3]> = (0..height)
+        .flat_map(|py| (0..width).map(move |px| (px, py)))
+        .map(|(px, py)| {
+            let (x, y, dx, dy) = (px / scale, py / scale, px % scale, py % scale);
+            // A one pixel gutter between cells.
+            if rules.is_obstacle(x, y) || dx == 0 || dy == 0 {
+                return theme.background;
+            }
+            let lit = board.get(x, y);
+            let centre = scale / 2;
+            let (cx, cy) = (dx.abs_diff(centre), dy.abs_diff(centre));
+            let in_mark = if theme.shapes {
+                cx + cy <= scale / 4
+            } else {
+                cx.pow(2) + cy.pow(2) <= (scale / 5).pow(2)
+            };
+            if in_mark && presses.is_some_and(|presses| presses.get(x, y)) {
+                return theme.color(lit, true);
+            }
+            let edge = scale / 8 + 1;
+            let inside = dx > edge && dy > edge && scale - dx > edge && scale - dy > edge;
+            if theme.shapes && !lit && inside {
+                theme.background
+            } else {
+                theme.color(lit, false)
+            }
+        })
+        .collect();
+    png::encode_rgb(width, height, &pixels)
+}
+
+/// What the bot sends back: a message and an image to attach, if any.
+#[derive(Clone, Debug)]
+pub struct Reply {
+    pub text: String,
+    pub image: Option<Vec<u8>>,
+}
+
+/// Pixels per cell in reply images.
+const REPLY_SCALE: usize = 32;
+
+/// Answer a message asking for a board to be solved with the fewest
+/// presses, naming them in reading order and marking them on the image.
+pub fn reply(message: &str) -> Reply {
+    let board = match parse_message(message) {
+        Ok(board) => board,
+        Err(err) => {
+            return Reply {
+                text: format!("I couldn't read a board there: {err}."),
+                image: None,
+            }
+        }
+    };
+    let Some(solution) = board.solve_linear() else {
+        return Reply {
+            text: "That board can't be solved.".to_string(),
+            image: Some(to_png(&board, None, REPLY_SCALE, &CLASSIC)),
+        };
+    };
+    let presses: Vec<String> = solution
+        .positions()
+        .into_iter()
+        .map(|(x, y)| cell_name(x, y))
+        .collect();
+    let text = match presses.len() {
+        0 => "That board is already solved.".to_string(),
+        1 => format!("Press {}.\n{}", presses[0], to_emoji(&board, Some(&solution))),
+        count => format!(
+            "Press {} ({count} presses).\n{}",
+            presses.join(", "),
+            to_emoji(&board, Some(&solution))
+        ),
+    };
+    Reply {
+        text,
+        image: Some(to_png(&board, Some(&solution), REPLY_SCALE, &CLASSIC)),
+    }
+}
@@ -0,0 +1,276 @@
+//! Solutions kept on disk between runs, so puzzles that come round again,
+//! like a daily puzzle checked by many people or repeats in a server's
+//! traffic, are answered without solving them again.
+//!
+//! Boards are stored under their [`Board::canonical_form`], so a puzzle
+//! cached once answers for every rotation and reflection of it too, with
+//! the presses turned to match. Each entry also carries a tag, such as the
+//! algorithm that solved it, since different algorithms may give different
+//! solutions to the same board.
+//!
+//! The file is plain text, one entry per line: the tag, the canonical
+//! board's code, and its presses as cell names separated by commas, `-`
+//! for none, or `unsolvable`. New entries are appended, and later lines
+//! win over earlier ones for the same board. Past the size limit the
+//! oldest entries are dropped, and the file is rewritten once it holds
+//! twice as many lines as entries.
+//!
+//! ```no_run
+//! use lightsout::{cache::SolutionCache, Board};
+//!
+//! let mut cache = SolutionCache::open("solutions.txt", 10_000).unwrap();
+//! let board = Board::from_code("5x5-c408f38").unwrap();
+//! if cache.get("linear", &board).is_none() {
+//!     let presses = board.solve_linear().map(|solution| solution.positions());
+//!     cache.insert("linear", &board, presses.as_deref()).unwrap();
+//! }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::core::{cell_name, parse_cell, Board};
+
+/// Presses for a canonical board, in canonical cells, or `None` if it
+/// can't be solved.
+type Presses = Option<Vec<(usize, usize)>>;
+
+/// One line of the cache file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub tag: String,
+    /// The puzzle code of the canonical board.
+    pub code: String,
+    pub presses: Presses,
+}
+
+impl Entry {
+    fn line(&self) -> String {
+        let presses = match &self.presses {
+            None => "unsolvable".to_string(),
+            Some(presses) if presses.is_empty() => "-".to_string(),
+            Some(presses) => {
+                let names: Vec<String> = presses.iter().map(|&(x, y)| cell_name(x, y)).collect();
+                names.join(",")
+            }
+        };
+        format!("{} {} {presses}", self.tag, self.code)
+    }
+
+    /// Read a line back, or `None` if it isn't one or names a press off
+    /// its board, since stored presses are trusted as a board's cells.
+    fn parse(line: &str) -> Option<Entry> {
+        let mut fields = line.split_whitespace();
+        let (tag, code, presses) = (fields.next()?, fields.next()?, fields.next()?);
+        if fields.next().is_some() {
+            return None;
+        }
+        let board = Board::from_code(code).ok()?;
+        let presses = match presses {
+            "unsolvable" => None,
+            "-" => Some(Vec::new()),
+            names => Some(
+                names
+                    .split(',')
+                    .map(parse_cell)
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+        };
+        let on_board = |&(x, y): &(usize, usize)| x < board.width() && y < board.height();
+        if !presses.iter().flatten().all(on_board) {
+            return None;
+        }
+        Some(Entry {
+            tag: tag.to_string(),
+            code: code.to_string(),
+            presses,
+        })
+    }
+}
+
+/// A cache file and the entries read from it, oldest first.
+#[derive(Debug)]
+pub struct SolutionCache {
+    path: PathBuf,
+    max_entries: usize,
+    /// Each entry by tag and code, with the stamp that orders it in `ages`.
+    entries: HashMap<(String, String), (u64, Presses)>,
+    /// Keys of `entries` by when they were last written.
+    ages: BTreeMap<u64, (String, String)>,
+    next_stamp: u64,
+    /// Lines in the file, counting ones later lines replaced.
+    lines: usize,
+    /// Lines of the file that couldn't be read and were skipped.
+    pub skipped: usize,
+}
+
+impl SolutionCache {
+    /// Read the cache at `path`, which doesn't have to exist yet, keeping
+    /// at most `max_entries` of its most recent entries.
+    pub fn open(path: impl AsRef<Path>, max_entries: usize) -> io::Result<SolutionCache> {
+        let mut cache = SolutionCache {
+            path: path.as_ref().to_path_buf(),
+            max_entries,
+            entries: HashMap::new(),
+            ages: BTreeMap::new(),
+            next_stamp: 0,
+            lines: 0,
+            skipped: 0,
+        };
+        let text = match fs::read_to_string(&cache.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            cache.lines += 1;
+            match Entry::parse(line) {
+                Some(entry) => cache.remember(entry),
+                None => cache.skipped += 1,
+            }
+        }
+        Ok(cache)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The presses stored for `board` under `tag`, turned to fit it:
+    /// `None` if there's no entry, `Some(None)` if it was found
+    /// unsolvable.
+    pub fn get(&self, tag: &str, board: &Board) -> Option<Option<Vec<(usize, usize)>>> {
+        let (canonical, cells) = board.canonical_form_mapped();
+        let key = (tag.to_string(), canonical.to_code());
+        let (_, presses) = self.entries.get(&key)?;
+        let width = canonical.width();
+        let turned = |presses: &Vec<(usize, usize)>| {
+            presses.iter().map(|&(x, y)| cells[y * width + x]).collect()
+        };
+        Some(presses.as_ref().map(turned))
+    }
+
+    /// Store `presses` for `board` under `tag`, `None` meaning it's
+    /// unsolvable, and append it to the file.
+    ///
+    /// Panics if `tag` has whitespace in it or a press is off the board.
+    pub fn insert(
+        &mut self,
+        tag: &str,
+        board: &Board,
+        presses: Option<&[(usize, usize)]>,
+    ) -> io::Result<()> {
+        assert!(
+            !tag.is_empty() && !tag.contains(char::is_whitespace),
+            "cache tags must be one word"
+        );
+        let (canonical, cells) = board.canonical_form_mapped();
+        let width = canonical.width();
+        // Back from this board's cells to the canonical board's.
+        let mut canonical_cell = HashMap::new();
+        for (index, &cell) in cells.iter().enumerate() {
+            canonical_cell.insert(cell, (index % width, index / width));
+        }
+        let presses =
+            presses.map(|presses| presses.iter().map(|cell| canonical_cell[cell]).collect());
+        let entry = Entry {
+            tag: tag.to_string(),
+            code: canonical.to_code(),
+            presses,
+        };
+        let line = entry.line();
+        self.remember(entry);
+        if self.lines + 1 > self.max_entries.max(1) * 2 {
+            return self.compact();
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// The canonical boards of entries whose [`Board::puzzle_id`] starts
+    /// with `id`, each once however many tags it's stored under.
+    pub fn find_id(&self, id: &str) -> Vec<Board> {
+        let mut found: Vec<Board> = Vec::new();
+        for (_, code) in self.ages.values() {
+            let Ok(board) = Board::from_code(code) else {
+                continue;
+            };
+            if board.matches_id(id) && !found.contains(&board) {
+                found.push(board);
+            }
+        }
+        found
+    }
+
+    /// Every entry, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
+        self.ages.values().map(|key| Entry {
+            tag: key.0.clone(),
+            code: key.1.clone(),
+            presses: self.entries[key].1.clone(),
+        })
+    }
+
+    /// Rewrite the file with only the entries kept, replacing it in one
+    /// step so a reader never sees it half written.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let mut text = String::new();
+        for entry in self.entries() {
+            text.push_str(&entry.line());
+            text.push('\n');
+        }
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, text)?;
+        fs::rename(&temporary, &self.path)?;
+        self.lines = self.entries.len();
+        self.skipped = 0;
+        Ok(())
+    }
+
+    /// Drop every entry and empty the file.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.entries.clear();
+        self.ages.clear();
+        self.compact()
+    }
+
+    /// Add `entry` as the newest, replacing any older one for the same
+    /// board and dropping the oldest past the limit.
+    fn remember(&mut self, entry: Entry) {
+        let key = (entry.tag, entry.code);
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        if let Some((old, _)) = self.entries.insert(key.clone(), (stamp, entry.presses)) {
+            self.ages.remove(&old);
+        }
+        self.ages.insert(stamp, key);
+        while self.entries.len() > self.max_entries {
+            let Some((_, oldest)) = self.ages.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Difficulty ratings fitted to how people actually play, from recorded
+//! sessions, for packs whose static ratings (the optimal length, or an
+//! author's guess) don't match what players find hard.
+//!
+//! Two things are fitted. A [`Model`] predicts the presses, and seconds if
+//! sessions were timed, a person takes on a board from features any board
+//! has, by least squares, so it rates boards nobody has played yet. Then an
+//! Elo-style rating for each board starts from the model's prediction and
+//! moves with every session on it, pitting the board against the player:
+//! a session scores its efficiency, the optimal length over the presses
+//! made, halved for each hint and zero if the board wasn't cleared. Boards
+//! that beat players' expectations drift up, and ones they find easy drift
+//! down. Players named in several sessions get ratings of their own, so a
+//! strong player clearing a board says less about it than a novice doing
+//! the same; anonymous sessions are played against [`BASE_RATING`].
+//!
+//! Ratings are on the chess scale: a board rated 400 above a player would
+//! see them score about a tenth as well as one matched to them.
+//!
+//! ```
+//! use lightsout::{calibrate::{Calibration, Session}, Board};
+//!
+//! let easy = Board::from_code("5x5-00008e0").unwrap();
+//! let hard = Board::from_code("5x5-c408f38").unwrap();
+//! let sessions = [
+//!     Session::new(easy.clone(), 1, true),
+//!     Session::new(hard.clone(), 9, true),
+//!     Session::new(hard.clone(), 14, false),
+//! ];
+//! let calibration = Calibration::fit(&sessions).unwrap();
+//! assert!(calibration.rating(&hard).unwrap() > calibration.rating(&easy).unwrap());
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::core::Board;
+
+/// The rating of an average player, and of a board they'd be expected to
+/// clear about half as efficiently as possible.
+pub const BASE_RATING: f64 = 1500.0;
+/// Rating difference for a tenfold difference in odds.
+const SCALE: f64 = 400.0;
+/// How far one session moves a rating.
+const K: f64 = 32.0;
+/// Keeps the least squares solvable when a feature never varies, as the
+/// kernel doesn't across one size of board.
+const RIDGE: f64 = 1e-3;
+
+/// One recorded attempt at a board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    pub board: Board,
+    /// Who played, if the recording says, to rate players as well.
+    pub player: Option<String>,
+    /// Presses made, counting ones later undone.
+    pub presses: usize,
+    pub hints: usize,
+    pub solved: bool,
+    /// How long the session took, if it was timed.
+    pub seconds: Option<f64>,
+}
+
+impl Session {
+    pub fn new(board: Board, presses: usize, solved: bool) -> Session {
+        Session {
+            board,
+            player: None,
+            presses,
+            hints: 0,
+            solved,
+            seconds: None,
+        }
+    }
+}
+
+/// Numbers describing a board that the [`Model`] weighs: a constant, the
+/// optimal length, the lights on and the kernel dimension. `None` for
+/// boards that can't be cleared.
+pub fn features(board: &Board) -> Option<[f64; 4]> {
+    let optimal = board.optimal_length()?;
+    Some([
+        1.0,
+        optimal as f64,
+        board.lit_count() as f64,
+        board.kernel_dimension() as f64,
+    ])
+}
+
+/// Names of the [`features`], in order.
+pub const FEATURES: [&str; 4] = ["constant", "optimal", "lit", "kernel"];
+
+/// A linear fit of one measure of effort to the [`features`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fit {
+    pub weights: [f64; 4],
+    /// Share of the measure's variance the fit explains.
+    pub r_squared: f64,
+    /// Sessions it was fitted to.
+    pub samples: usize,
+}
+
+impl Fit {
+    /// Least squares over `(features, measure)` pairs; `None` if there are
+    /// none.
+    fn new(samples: &[([f64; 4], f64)]) -> Option<Fit> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut normal = [[0.0; 5]; 4];
+        for (x, y) in samples {
+            for i in 0..4 {
+                for j in 0..4 {
+                    normal[i][j] += x[i] * x[j];
+                }
+                normal[i][4] += x[i] * y;
+            }
+        }
+        for (i, row) in normal.iter_mut().enumerate().skip(1) {
+            row[i] += RIDGE * samples.len() as f64;
+        }
+        let weights = solve(normal)?;
+        let mean = samples.iter().map(|(_, y)| y).sum::<f64>() / samples.len() as f64;
+        let (mut residual, mut total) = (0.0, 0.0);
+        for (x, y) in samples {
+            residual += (y - dot(&weights, x)).powi(2);
+            total += (y - mean).powi(2);
+        }
+        let r_squared = if total > 0.0 {
+            1.0 - residual / total
+        } else {
+            1.0
+        };
+        Some(Fit {
+            weights,
+            r_squared,
+            samples: samples.len(),
+        })
+    }
+
+    pub fn predict(&self, features: &[f64; 4]) -> f64 {
+        dot(&self.weights, features)
+    }
+}
+
+fn dot(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Gaussian elimination with partial pivoting on an augmented 4x5 system.
+fn solve(mut rows: [[f64; 5]; 4]) -> Option<[f64; 4]> {
+    for column in 0..4 {
+        let pivot =
+            (column..4).max_by(|&a, &b| rows[a][column].abs().total_cmp(&rows[b][column].abs()))?;
+        if rows[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        rows.swap(column, pivot);
+        let pivot = rows[column];
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != column {
+                let factor = row[column] / pivot[column];
+                for (value, pivot) in row.iter_mut().zip(pivot).skip(column) {
+                    *value -= factor * pivot;
+                }
+            }
+        }
+    }
+    Some([0, 1, 2, 3].map(|i| rows[i][4] / rows[i][i]))
+}
+
+/// Predicted effort on a board, fitted to cleared sessions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Model {
+    pub presses: Fit,
+    /// Only when some sessions were timed.
+    pub seconds: Option<Fit>,
+}
+
+impl Model {
+    /// Fit to the cleared sessions in `sessions`, or `None` if there are
+    /// none.
+    pub fn fit(sessions: &[Session]) -> Option<Model> {
+        let cleared: Vec<_> = sessions
+            .iter()
+            .filter(|session| session.solved)
+            .filter_map(|session| Some((features(&session.board)?, session)))
+            .collect();
+        let presses: Vec<_> = cleared
+            .iter()
+            .map(|(features, session)| (*features, session.presses as f64))
+            .collect();
+        let seconds: Vec<_> = cleared
+            .iter()
+            .filter_map(|(features, session)| Some((*features, session.seconds?)))
+            .collect();
+        Some(Model {
+            presses: Fit::new(&presses)?,
+            seconds: Fit::new(&seconds),
+        })
+    }
+
+    /// Presses a person is predicted to take, never fewer than the optimum.
+    pub fn presses(&self, board: &Board) -> Option<f64> {
+        let features = features(board)?;
+        Some(self.presses.predict(&features).max(features[1]))
+    }
+
+    pub fn seconds(&self, board: &Board) -> Option<f64> {
+        let fit = self.seconds.as_ref()?;
+        Some(fit.predict(&features(board)?).max(0.0))
+    }
+
+    /// The rating a board starts from before anyone has played it: the
+    /// one an average player would be expected to score the predicted
+    /// efficiency against.
+    pub fn prior(&self, board: &Board) -> Option<f64> {
+        let optimal = board.optimal_length()? as f64;
+        let presses = self.presses(board)?;
+        if presses == 0.0 {
+            return Some(BASE_RATING - SCALE);
+        }
+        let efficiency = (optimal / presses).clamp(0.05, 0.95);
+        Some(BASE_RATING + SCALE * ((1.0 - efficiency) / efficiency).log10())
+    }
+}
+
+/// How well `session` went, from 0 to 1.
+fn score(session: &Session, optimal: usize) -> f64 {
+    if !session.solved {
+        return 0.0;
+    }
+    let efficiency = optimal as f64 / session.presses.max(optimal).max(1) as f64;
+    efficiency * 0.5f64.powi(session.hints.min(16) as i32)
+}
+
+/// The score a player rated `player` is expected to make on a board rated
+/// `board`.
+fn expected(player: f64, board: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((board - player) / SCALE))
+}
+
+/// A board's calibrated rating.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    /// Sessions that moved it, none if it's the model's prior.
+    pub sessions: usize,
+}
+
+/// A [`Model`] and the ratings of every board and named player in the
+/// sessions it was fitted to. Boards are rated up to symmetry, so sessions
+/// on a rotation or reflection of a board count towards it.
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    pub model: Model,
+    /// By the code of the board's [`Board::canonical_form`].
+    pub boards: HashMap<String, Rating>,
+    pub players: BTreeMap<String, f64>,
+}
+
+impl Calibration {
+    /// Fit the model, then play every session in order to rate boards and
+    /// players. Fails if no session cleared a board.
+    pub fn fit(sessions: &[Session]) -> Result<Calibration, String> {
+        let model = Model::fit(sessions).ok_or("no session cleared its board")?;
+        let mut calibration = Calibration {
+            model,
+            boards: HashMap::new(),
+            players: BTreeMap::new(),
+        };
+        for session in sessions {
+            let Some(optimal) = session.board.optimal_length().filter(|&n| n > 0) else {
+                continue;
+            };
+            let Some(prior) = calibration.model.prior(&session.board) else {
+                continue;
+            };
+            let code = session.board.canonical_form().to_code();
+            let board = calibration.boards.entry(code).or_insert(Rating {
+                rating: prior,
+                sessions: 0,
+            });
+            let player = session.player.as_ref().map(|name| {
+                calibration
+                    .players
+                    .entry(name.clone())
+                    .or_insert(BASE_RATING)
+            });
+            let player_rating = player.as_deref().copied().unwrap_or(BASE_RATING);
+            // The board wins what the player falls short of expectations by.
+            let surprise = score(session, optimal) - expected(player_rating, board.rating);
+            board.rating -= K * surprise;
+            board.sessions += 1;
+            if let Some(player) = player {
+                *player += K * surprise;
+            }
+        }
+        Ok(calibration)
+    }
+
+    /// `board`'s rating: calibrated if it or a symmetry of it was played,
+    /// otherwise the model's prior. `None` if it can't be cleared.
+    pub fn rating(&self, board: &Board) -> Option<f64> {
+        self.board_rating(board).map(|rating| rating.rating)
+    }
+
+    /// [`Calibration::rating`], with how many sessions went into it.
+    pub fn board_rating(&self, board: &Board) -> Option<Rating> {
+        if let Some(rating) = self.boards.get(&board.canonical_form().to_code()) {
+            return Some(rating.clone());
+        }
+        Some(Rating {
+            rating: self.model.prior(board)?,
+            sessions: 0,
+        })
+    }
+}
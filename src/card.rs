@@ -0,0 +1,193 @@
+//! Puzzle cards: a picture of a board with a strip of dark and light
+//! modules under it spelling out the board's code, so a printed or shared
+//! card scans back into exactly the puzzle it shows, rules and all.
+//!
+//! The strip is a dark frame 34 modules wide, a timing row of alternating
+//! modules, and 32 bits a row of payload: the code's length as two bytes,
+//! the code, and a CRC-32 of both. Reading finds the frame from the bottom
+//! of the image and measures the modules off it, so cards survive being
+//! scaled, as long as modules stay four or more pixels across, and a few
+//! specks, but not turning or perspective: crop and straighten photos
+//! first.
+
+use crate::{
+    core::Board,
+    png::{self, Image},
+};
+
+/// Pixels per module in written cards.
+const MODULE: usize = 6;
+/// Modules across a board cell, and between cells.
+const CELL: usize = 4;
+const GAP: usize = 1;
+/// Light modules around everything, and between the board and the strip.
+const MARGIN: usize = 4;
+const SEPARATION: usize = 3;
+/// Payload bits a strip row holds, inside the frame.
+const ROW_BITS: usize = 32;
+const STRIP_WIDTH: usize = ROW_BITS + 2;
+
+const DARK: [u8; 3] = [24, 24, 24];
+const LIGHT: [u8; 3] = [255, 255, 255];
+const UNLIT: [u8; 3] = [214, 214, 214];
+
+fn payload(board: &Board) -> Vec<bool> {
+    let code = board.to_code();
+    let mut bytes = (code.len() as u16).to_be_bytes().to_vec();
+    bytes.extend(code.as_bytes());
+    bytes.extend(png::crc32(&bytes).to_be_bytes());
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0))
+        .collect()
+}
+
+/// A PNG card of `board`, lit cells dark so they print well.
+pub fn encode(board: &Board) -> Vec<u8> {
+    let bits = payload(board);
+    let rows = bits.len().div_ceil(ROW_BITS);
+    let board_width = (board.width() * (CELL + GAP)).saturating_sub(GAP);
+    let board_height = (board.height() * (CELL + GAP)).saturating_sub(GAP);
+    let inner = board_width.max(STRIP_WIDTH);
+    let (width, height) = (inner + 2 * MARGIN, board_height + SEPARATION + rows + 3 + 2 * MARGIN);
+    let mut modules = vec![LIGHT; width * height];
+
+    let left = MARGIN + (inner - board_width) / 2;
+    let rules = board.ruleset();
+    for ((x, y), lit) in board.cells() {
+        if rules.is_obstacle(x, y) {
+            continue;
+        }
+        let (mx, my) = (left + x * (CELL + GAP), MARGIN + y * (CELL + GAP));
+        for dy in 0..CELL {
+            for dx in 0..CELL {
+                modules[(my + dy) * width + mx + dx] = if lit { DARK } else { UNLIT };
+            }
+        }
+    }
+
+    let (left, top) = (MARGIN + (inner - STRIP_WIDTH) / 2, MARGIN + board_height + SEPARATION);
+    for row in 0..rows + 3 {
+        for column in 0..STRIP_WIDTH {
+            let frame = row == 0 || row == rows + 2 || column == 0 || column == STRIP_WIDTH - 1;
+            let dark = frame
+                || if row == 1 {
+                    column % 2 == 0
+                } else {
+                    bits.get((row - 2) * ROW_BITS + column - 1) == Some(&true)
+                };
+            if dark {
+                modules[(top + row) * width + left + column] = DARK;
+            }
+        }
+    }
+
+    let pixels: Vec<[u8; 3]> = (0..height * MODULE)
+        .flat_map(|y| (0..width * MODULE).map(move |x| (x / MODULE, y / MODULE)))
+        .map(|(x, y)| modules[y * width + x])
+        .collect();
+    png::encode_rgb(width * MODULE, height * MODULE, &pixels)
+}
+
+/// The board a PNG card shows, see [`read`].
+pub fn decode(png: &[u8]) -> Result<Board, String> {
+    read(&png::decode(png)?)
+}
+
+/// The board on a card in `image`, from its strip.
+pub fn read(image: &Image) -> Result<Board, String> {
+    const MISSING: &str = "couldn't find the strip under the board";
+    let luminance = image.luminance();
+    let (darkest, lightest) = luminance
+        .iter()
+        .fold((u8::MAX, 0), |(low, high), &value| (low.min(value), high.max(value)));
+    let dark = |x: usize, y: usize| {
+        (luminance[y * image.width + x] as usize) * 2 < darkest as usize + lightest as usize
+    };
+
+    // Whether most of the pixels in a box are dark, to ride out specks.
+    let mostly_dark = |xs: std::ops::Range<usize>, ys: std::ops::Range<usize>| {
+        let count = xs.len() * ys.len();
+        let dark = ys.flat_map(|y| xs.clone().map(move |x| (x, y))).filter(|&(x, y)| dark(x, y));
+        dark.count() * 2 > count
+    };
+
+    // The strip's bottom edge is the lowest long dark run, bridging gaps
+    // of a pixel or two once it's going, so a speck beside it doesn't
+    // start it early.
+    let longest_run = |y: usize| {
+        let (mut best, mut run, mut gap) = ((0, 0), None, 0);
+        for x in 0..image.width {
+            match (dark(x, y), run) {
+                (true, None) => run = Some((x, x + 1)),
+                (true, Some((from, _))) => run = Some((from, x + 1)),
+                (false, Some((from, to))) if gap < 2 && to - from > 2 => {
+                    gap += 1;
+                    continue;
+                }
+                (false, Some(_)) => run = None,
+                (false, None) => {}
+            }
+            gap = 0;
+            if let Some((from, to)) = run {
+                if to - from > best.1 - best.0 {
+                    best = (from, to);
+                }
+            }
+        }
+        best
+    };
+    let minimum = (image.width / 12).max(STRIP_WIDTH);
+    let (bottom, (left, right)) = (0..image.height)
+        .rev()
+        .map(|y| (y, longest_run(y)))
+        .find(|(_, (from, to))| to - from >= minimum)
+        .ok_or(MISSING)?;
+    let module = (right - left) as f64 / STRIP_WIDTH as f64;
+    // The middle half of a module along one axis, clear of blur from its
+    // neighbours and of specks the edges may have been measured from.
+    let span = |start: f64, size: f64, limit: usize| {
+        let from = ((start + size / 4.0) as usize).min(limit - 1);
+        from..((start + size * 3.0 / 4.0) as usize).clamp(from + 1, limit)
+    };
+    let frame = span(left as f64, module, image.width);
+    // Up the left edge to the top, over a speck or two on the way.
+    let mut top = bottom;
+    for y in (0..bottom).rev() {
+        if mostly_dark(frame.clone(), y..y + 1) {
+            top = y;
+        } else if top - y > 1 {
+            break;
+        }
+    }
+    let rows = ((bottom + 1 - top) as f64 / module).round() as usize;
+    if rows < 4 {
+        return Err(MISSING.to_string());
+    }
+    let row_height = (bottom + 1 - top) as f64 / rows as f64;
+    let module_at = |column: usize, row: usize| {
+        let (x, y) = (left as f64 + column as f64 * module, top as f64 + row as f64 * row_height);
+        mostly_dark(span(x, module, image.width), span(y, row_height, image.height))
+    };
+    if (1..STRIP_WIDTH - 1).any(|column| module_at(column, 1) != (column % 2 == 0)) {
+        return Err("the strip's timing row is unreadable".to_string());
+    }
+
+    let bits: Vec<bool> = (2..rows - 1)
+        .flat_map(|row| (1..STRIP_WIDTH - 1).map(move |column| (column, row)))
+        .map(|(column, row)| module_at(column, row))
+        .collect();
+    let bytes: Vec<u8> = bits
+        .chunks_exact(8)
+        .map(|byte| byte.iter().fold(0, |value, &bit| value << 1 | bit as u8))
+        .collect();
+    let corrupt = || "the strip is damaged".to_string();
+    let len = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+    let checked = bytes.get(..2 + len).ok_or_else(corrupt)?;
+    let crc = bytes.get(2 + len..6 + len).ok_or_else(corrupt)?;
+    if png::crc32(checked).to_be_bytes() != crc {
+        return Err(corrupt());
+    }
+    let code = std::str::from_utf8(&checked[2..]).map_err(|_| corrupt())?;
+    Board::from_code(code).map_err(|err| err.to_string())
+}
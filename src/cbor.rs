@@ -0,0 +1,174 @@
+//! CBOR (RFC 8949) encoding of [`Json`] values, for clients that would
+//! rather not pay for text. Decoding covers what an encoder of JSON-like
+//! data produces: integers, floats, text, arrays, maps with text keys and
+//! the simple values; byte strings, tags and indefinite lengths are
+//! rejected.
+
+use std::io::Read;
+
+use crate::json::Json;
+
+/// Nesting limit, so hostile input can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const SIMPLE: u8 = 7;
+
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const NULL: u8 = 0xf6;
+const FLOAT64: u8 = 0xfb;
+
+pub fn encode(value: &Json) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_value(&mut out, value);
+    out
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    match n {
+        0..=23 => out.push(major | n as u8),
+        24..=0xff => out.extend([major | 24, n as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((n as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((n as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(n.to_be_bytes());
+        }
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Json) {
+    match value {
+        Json::Null => out.push(NULL),
+        Json::Bool(false) => out.push(FALSE),
+        Json::Bool(true) => out.push(TRUE),
+        // Whole numbers go out as integers, which is what they usually are.
+        Json::Number(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(63) => {
+            if *n >= 0.0 {
+                write_head(out, UNSIGNED, *n as u64);
+            } else {
+                write_head(out, NEGATIVE, (-1.0 - n) as u64);
+            }
+        }
+        Json::Number(n) => {
+            out.push(FLOAT64);
+            out.extend(n.to_be_bytes());
+        }
+        Json::String(s) => {
+            write_head(out, TEXT, s.len() as u64);
+            out.extend(s.as_bytes());
+        }
+        Json::Array(items) => {
+            write_head(out, ARRAY, items.len() as u64);
+            items.iter().for_each(|item| write_value(out, item));
+        }
+        Json::Object(entries) => {
+            write_head(out, MAP, entries.len() as u64);
+            for (key, value) in entries {
+                write_head(out, TEXT, key.len() as u64);
+                out.extend(key.as_bytes());
+                write_value(out, value);
+            }
+        }
+    }
+}
+
+/// Read one value from `reader`, leaving anything after it unread.
+pub fn decode(reader: &mut impl Read) -> Result<Json, String> {
+    read_value(reader, 0)
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], String> {
+    let mut bytes = [0; N];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|_| "unexpected end of input".to_string())?;
+    Ok(bytes)
+}
+
+/// The argument following an initial byte's additional information.
+fn read_argument(reader: &mut impl Read, info: u8) -> Result<u64, String> {
+    Ok(match info {
+        0..=23 => info as u64,
+        24 => u8::from_be_bytes(read_bytes(reader)?) as u64,
+        25 => u16::from_be_bytes(read_bytes(reader)?) as u64,
+        26 => u32::from_be_bytes(read_bytes(reader)?) as u64,
+        27 => u64::from_be_bytes(read_bytes(reader)?),
+        31 => return Err("indefinite lengths aren't supported".to_string()),
+        _ => return Err(format!("invalid additional information {info}")),
+    })
+}
+
+fn read_text(reader: &mut impl Read, len: u64) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(len)
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+    if bytes.len() as u64 != len {
+        return Err("unexpected end of input".to_string());
+    }
+    String::from_utf8(bytes).map_err(|_| "text is not UTF-8".to_string())
+}
+
+/// An IEEE 754 half-precision float.
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f64;
+    sign * match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+fn read_value(reader: &mut impl Read, depth: usize) -> Result<Json, String> {
+    if depth > MAX_DEPTH {
+        return Err("nested too deeply".to_string());
+    }
+    let [initial] = read_bytes(reader)?;
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    if major == SIMPLE {
+        return match info {
+            20 => Ok(Json::Bool(false)),
+            21 => Ok(Json::Bool(true)),
+            22 | 23 => Ok(Json::Null),
+            25 => Ok(Json::Number(half_to_f64(u16::from_be_bytes(read_bytes(reader)?)))),
+            26 => Ok(Json::Number(f32::from_be_bytes(read_bytes(reader)?) as f64)),
+            27 => Ok(Json::Number(f64::from_be_bytes(read_bytes(reader)?))),
+            _ => Err(format!("unsupported simple value {info}")),
+        };
+    }
+    let argument = read_argument(reader, info)?;
+    match major {
+        UNSIGNED => Ok(Json::Number(argument as f64)),
+        NEGATIVE => Ok(Json::Number(-1.0 - argument as f64)),
+        TEXT => Ok(Json::String(read_text(reader, argument)?)),
+        ARRAY => (0..argument)
+            .map(|_| read_value(reader, depth + 1))
+            .collect::<Result<_, _>>()
+            .map(Json::Array),
+        MAP => (0..argument)
+            .map(|_| match read_value(reader, depth + 1)? {
+                Json::String(key) => Ok((key, read_value(reader, depth + 1)?)),
+                _ => Err("map keys must be text".to_string()),
+            })
+            .collect::<Result<_, _>>()
+            .map(Json::Object),
+        _ => Err(format!("unsupported major type {major}")),
+    }
+}
@@ -0,0 +1,578 @@
+#[cfg(feature = "image")]
+use std::fs;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use lightsout::{
+    analysis::{prospect, Criteria, Heatmap, Sweep},
+    calibrate::{Calibration, Fit, Session, FEATURES},
+    generate::{generate_solvable, Generated},
+    json::Json,
+    pack::{lookup, Pack, Puzzle},
+    search::{OpeningBook, StateTable},
+    Board, BoardBuilder,
+};
+use rand::prelude::*;
+
+use super::{
+    manifest::Manifest,
+    replay,
+    solve::{context, solve_board},
+    Algorithm, Args, Outcome, Output, Verbosity,
+};
+
+const USAGE: &str = "usage: lightsout analyze heatmap [pack/puzzle] [--count N] [--png FILE]
+       lightsout analyze counterexamples [--algorithm NAME] [--count N]
+       lightsout analyze prospect [--seeds N] [--seed N] [--target-optimal N] [--unique] [--symmetric] [--hardest] [--manifest FILE]
+       lightsout analyze tradeoff [board or pack/puzzle] [--timeout S] [--output csv|json]
+       lightsout analyze replay FILE [-v]
+       lightsout analyze calibrate DIR [--pack PACK] [--out FILE]
+       lightsout analyze book [--count N] [--seed N]";
+
+/// How many of the worst boards a counterexample search reports.
+const WORST_SHOWN: usize = 10;
+
+/// Pixels per cell in exported heatmap images.
+#[cfg(feature = "image")]
+const PNG_SCALE: usize = 32;
+
+fn print_heatmap(heatmap: &Heatmap) {
+    for y in 0..heatmap.height {
+        for x in 0..heatmap.width {
+            let [r, g, b] = heatmap.color(x, y);
+            print!("\x1b[48;2;{r};{g};{b}m  \x1b[0m");
+        }
+        print!("   ");
+        for x in 0..heatmap.width {
+            print!("{:>4.0}%", heatmap.frequency(x, y) * 100.0);
+        }
+        println!();
+    }
+    println!(
+        "Press frequency over minimal solutions of {} board{}",
+        heatmap.samples,
+        if heatmap.samples == 1 { "" } else { "s" }
+    );
+    println!(
+        "{} of {} had a unique minimal solution",
+        heatmap.unique, heatmap.samples
+    );
+}
+
+/// Add a board to the heatmap, taking its minimal solutions from the shared
+/// state table under `--algorithm exhaustive`.
+fn add(args: &Args, heatmap: &mut Heatmap, board: &Board) -> Result<bool, String> {
+    if args.algorithm != Algorithm::Exhaustive {
+        return Ok(heatmap.add(board));
+    }
+    let table = context().state_table(board).ok_or_else(|| {
+        format!(
+            "exhaustive tables only cover boards up to {} cells",
+            StateTable::MAX_CELLS
+        )
+    })?;
+    Ok(heatmap.add_solutions(&table.minimal_solutions(board)))
+}
+
+fn heatmap(args: &Args) -> Result<Heatmap, String> {
+    match args.positional.get(1) {
+        Some(reference) => {
+            let puzzle = lookup(reference)?;
+            let mut heatmap = Heatmap::new(puzzle.board.width(), puzzle.board.height());
+            if !add(args, &mut heatmap, &puzzle.board)? {
+                return Err(format!("{reference} is unsolvable"));
+            }
+            Ok(heatmap)
+        }
+        None => {
+            let mut heatmap = Heatmap::new(args.width, args.height);
+            let first_seed = args.seed.unwrap_or_else(random);
+            for generated in generate_solvable(
+                args.width,
+                args.height,
+                first_seed,
+                args.count,
+                args.threads,
+            ) {
+                add(args, &mut heatmap, &generated.board)?;
+            }
+            Ok(heatmap)
+        }
+    }
+}
+
+/// A board the solver under test did badly on.
+struct Counterexample {
+    seed: u64,
+    board: Board,
+    moves: Option<usize>,
+    optimal: usize,
+}
+
+impl Counterexample {
+    /// Extra presses over the optimum, with failures to solve counting as
+    /// worse than any solution.
+    fn excess(&self) -> usize {
+        self.moves
+            .map_or(usize::MAX, |moves| moves.saturating_sub(self.optimal))
+    }
+}
+
+/// Solve `--count` random solvable boards with `--algorithm` and report the
+/// ones where it falls furthest short of the optimal solution.
+fn counterexamples(args: &Args) -> Result<(), String> {
+    let first_seed = args.seed.unwrap_or_else(random);
+    let mut found: Vec<Counterexample> = generate_solvable(
+        args.width,
+        args.height,
+        first_seed,
+        args.count,
+        args.threads,
+    )
+    .map(|generated| Counterexample {
+        moves: solve_board(args, generated.board.clone(), None)
+            .presses
+            .map(|presses| presses.len()),
+        seed: generated.seed,
+        board: generated.board,
+        optimal: generated.optimal,
+    })
+    .collect();
+    let samples = found.len();
+    found.sort_by_key(|example| (std::cmp::Reverse(example.excess()), example.seed));
+    let worse = found.iter().filter(|example| example.excess() > 0).count();
+    if !args.shows(Verbosity::Normal) {
+        return Ok(());
+    }
+    for example in found.iter().take(WORST_SHOWN.min(worse)) {
+        let moves = example
+            .moves
+            .map_or("unsolved".to_string(), |moves| moves.to_string());
+        println!(
+            "Seed: {} ({moves} presses, optimal {}) {}",
+            example.seed,
+            example.optimal,
+            example.board.to_code()
+        );
+        if args.shows(Verbosity::Verbose) {
+            println!("{}", example.board);
+        }
+    }
+    println!(
+        "{} was worse than optimal on {worse} of {samples} boards",
+        args.algorithm
+    );
+    Ok(())
+}
+
+/// Sweep `--seeds` seeds upwards from `--seed` for boards meeting the
+/// criteria asked for, and list each with the seed that makes it.
+fn prospect_seeds(args: &Args) -> Result<(), String> {
+    let criteria = Criteria {
+        optimal: args.target_optimal,
+        unique: args.unique,
+        symmetric: args.symmetric,
+        hardest: args.hardest,
+    };
+    let first_seed = args.seed.unwrap_or(0);
+    let seeds = first_seed..first_seed.saturating_add(args.seeds);
+    let builder = args.board_builder()?;
+    let sweep = match &args.manifest {
+        None => prospect(&builder, seeds, args.threads, &criteria).map_err(|err| err.to_string())?,
+        Some(path) => {
+            let job = format!(
+                "analyze prospect of {}x{} {} boards, density {}, {criteria:?}",
+                args.width,
+                args.height,
+                args.rules.as_deref().unwrap_or("classic"),
+                args.density.map_or("random".to_string(), |density| density.to_string())
+            );
+            let mut manifest = Manifest::open(path, &job)?;
+            prospect_resumable(args, &builder, seeds, &criteria, &mut manifest)?
+        }
+    };
+    if !args.shows(Verbosity::Normal) {
+        return Ok(());
+    }
+    for found in &sweep.matches {
+        println!(
+            "Seed: {} ({} presses, id {}) {}",
+            found.seed,
+            found.optimal,
+            found.board.puzzle_id(),
+            found.board.to_code()
+        );
+        if args.shows(Verbosity::Verbose) {
+            println!("{}", found.board);
+        }
+    }
+    println!(
+        "{} of {} seeds matched ({} solvable)",
+        sweep.matches.len(),
+        sweep.seeds,
+        sweep.solvable
+    );
+    Ok(())
+}
+
+/// Seeds in each piece of a sweep a manifest records.
+const PROSPECT_CHUNK: u64 = 10_000;
+
+/// [`prospect`] a chunk of seeds at a time, recording each chunk's
+/// findings in `manifest` and taking the chunks an earlier run recorded
+/// from there. Chunks start every [`PROSPECT_CHUNK`] seeds from the first,
+/// so a sweep run again with more `--seeds` reuses all but the last one.
+fn prospect_resumable(
+    args: &Args,
+    builder: &BoardBuilder,
+    seeds: std::ops::Range<u64>,
+    criteria: &Criteria,
+    manifest: &mut Manifest,
+) -> Result<Sweep, String> {
+    let mut sweep = Sweep {
+        seeds: seeds.end.saturating_sub(seeds.start),
+        solvable: 0,
+        matches: Vec::new(),
+    };
+    let mut start = seeds.start;
+    while start < seeds.end {
+        let end = start.saturating_add(PROSPECT_CHUNK).min(seeds.end);
+        let recorded = manifest.entries().iter().find(|entry| {
+            let seed = |key| entry.get(key).and_then(Json::as_u64);
+            seed("start") == Some(start) && seed("end") == Some(end)
+        });
+        let chunk = match recorded {
+            Some(entry) => {
+                let bad = || format!("the manifest's entry for seeds {start}..{end} is malformed");
+                let solvable = entry.get("solvable").and_then(Json::as_u64).ok_or_else(bad)?;
+                let found = entry.get("matches").and_then(Json::as_array).ok_or_else(bad)?;
+                let matches = found
+                    .iter()
+                    .map(|found| {
+                        let [seed, optimal] = found.as_array().ok_or_else(bad)? else {
+                            return Err(bad());
+                        };
+                        let (seed, optimal) =
+                            seed.as_u64().zip(optimal.as_u64()).ok_or_else(bad)?;
+                        let board = builder
+                            .clone()
+                            .seed(seed)
+                            .build()
+                            .map_err(|err| err.to_string())?;
+                        let optimal = optimal as usize;
+                        Ok(Generated { seed, board, optimal })
+                    })
+                    .collect::<Result<_, String>>()?;
+                Sweep {
+                    seeds: end - start,
+                    solvable,
+                    matches,
+                }
+            }
+            None => {
+                let chunk = prospect(builder, start..end, args.threads, criteria)
+                    .map_err(|err| err.to_string())?;
+                let matches = chunk
+                    .matches
+                    .iter()
+                    .map(|found| Json::Array(vec![found.seed.into(), found.optimal.into()]));
+                manifest.record(Json::object([
+                    ("start", start.into()),
+                    ("end", end.into()),
+                    ("solvable", chunk.solvable.into()),
+                    ("matches", Json::Array(matches.collect())),
+                ]))?;
+                chunk
+            }
+        };
+        sweep.solvable += chunk.solvable;
+        sweep.matches.extend(chunk.matches);
+        start = end;
+    }
+    // Each chunk kept its own hardest; only the hardest of those are the
+    // sweep's.
+    if criteria.hardest {
+        let hardest = sweep.matches.iter().map(|found| found.optimal).max();
+        sweep.matches.retain(|found| Some(found.optimal) == hardest);
+    }
+    Ok(sweep)
+}
+
+/// An improvement the anytime solver made: a solution this many presses
+/// long, found this long after it started and after trying this many
+/// solutions.
+struct Point {
+    elapsed: Duration,
+    presses: usize,
+    tried: u128,
+}
+
+/// Run the anytime solver on a board, stopping at `--timeout` if it's
+/// set, and print the length of the best solution against the time it
+/// took to find, for judging how long a board of that size is worth
+/// waiting on.
+fn tradeoff(args: &Args) -> Result<(), String> {
+    let board = match args.positional.get(1) {
+        Some(reference) => Board::from_code(reference)
+            .or_else(|_| lookup(reference).map(|puzzle| puzzle.board))?,
+        // Only one board in 2^kernel is solvable, so try seeds up from the
+        // first until one is.
+        None => {
+            let first_seed = args.seed.unwrap_or_else(random);
+            let mut seeds = (0..).map(|offset| first_seed.wrapping_add(offset));
+            loop {
+                let board = args.random_board(seeds.next().unwrap())?;
+                if board.solve_chase().is_some() {
+                    break board;
+                }
+            }
+        }
+    };
+    let cancel = args.cancel.clone().unwrap_or_default();
+    if let Some(timeout) = args.timeout {
+        let cancel = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(timeout);
+            cancel.cancel();
+        });
+    }
+    let start = Instant::now();
+    let mut points = Vec::new();
+    let best = board.solve_anytime(&cancel, |solution, tried| {
+        points.push(Point {
+            elapsed: start.elapsed(),
+            presses: solution.count(),
+            tried,
+        })
+    });
+    let (elapsed, finished) = (start.elapsed(), !cancel.is_cancelled());
+    if best.is_none() {
+        return Err(format!("{} is unsolvable", board.to_code()));
+    }
+    if !args.shows(Verbosity::Normal) {
+        return Ok(());
+    }
+    let dimension = board.kernel_dimension();
+    let solutions = 1u128
+        .checked_shl(dimension as u32)
+        .map_or(format!("2^{dimension}"), |solutions| solutions.to_string());
+    match args.output {
+        Output::Json => {
+            let points = points.iter().map(|point| {
+                Json::object([
+                    ("seconds", point.elapsed.as_secs_f64().into()),
+                    ("presses", point.presses.into()),
+                    ("tried", (point.tried as f64).into()),
+                ])
+            });
+            let report = Json::object([
+                ("code", board.to_code().into()),
+                ("solutions", 2f64.powi(dimension as i32).into()),
+                ("seconds", elapsed.as_secs_f64().into()),
+                ("finished", finished.into()),
+                ("points", Json::Array(points.collect())),
+            ]);
+            println!("{report}");
+        }
+        Output::Csv => {
+            println!("seconds,presses,tried");
+            for point in &points {
+                let seconds = point.elapsed.as_secs_f64();
+                println!("{seconds:.6},{},{}", point.presses, point.tried);
+            }
+        }
+        Output::Text => {
+            println!("{:>12}{:>9}{:>14}", "seconds", "presses", "tried");
+            for point in &points {
+                let seconds = point.elapsed.as_secs_f64();
+                println!("{seconds:>12.6}{:>9}{:>14}", point.presses, point.tried);
+            }
+            let seconds = elapsed.as_secs_f64();
+            if finished {
+                println!("Tried all {solutions} solutions in {seconds:.3}s: the last is optimal");
+            } else {
+                println!("Stopped after {seconds:.3}s, before trying all {solutions} solutions");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Random boards an opening book is built from when the size is too large
+/// to analyse every board, unless `--count` says otherwise.
+const BOOK_SAMPLES: usize = 200_000;
+
+/// Build an opening book for the configured size and print it as an entry
+/// of the table in `search/book_data.rs`.
+fn book(args: &Args) -> Result<(), String> {
+    // `--count` defaults to a handful of boards for the other analyses.
+    let samples = if args.count == Args::default().count {
+        BOOK_SAMPLES
+    } else {
+        args.count
+    };
+    let book = OpeningBook::analyze(args.width, args.height, samples, args.seed.unwrap_or(0))
+        .ok_or(format!("no opening book for {}x{} boards", args.width, args.height))?;
+    if !args.shows(Verbosity::Normal) {
+        return Ok(());
+    }
+    println!("    ({}, {}, &[", book.width(), book.height());
+    for line in book.entries().chunks(16) {
+        let entries: Vec<String> = line.iter().map(u8::to_string).collect();
+        println!("        {},", entries.join(", "));
+    }
+    println!("    ]),");
+    Ok(())
+}
+
+/// Every replay in `dir`, in file name order, as a session. Files that
+/// aren't replays are skipped with a warning.
+fn read_sessions(dir: &str) -> Result<Vec<Session>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|err| format!("{dir}: {err}"))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    let mut sessions = Vec::new();
+    for path in paths {
+        match replay::read(&path.display().to_string()) {
+            Ok(replay) => sessions.push(replay.session()),
+            Err(err) => eprintln!("warning: skipping {err}"),
+        }
+    }
+    Ok(sessions)
+}
+
+fn print_fit(measure: &str, fit: &Fit) {
+    let terms: Vec<String> = FEATURES
+        .iter()
+        .zip(fit.weights)
+        .skip(1)
+        .map(|(name, weight)| format!("{weight:+.2} {name}"))
+        .collect();
+    println!(
+        "{measure} = {:.2} {} (R² {:.2} over {} sessions)",
+        fit.weights[0],
+        terms.join(" "),
+        fit.r_squared,
+        fit.samples
+    );
+}
+
+/// Fit difficulty ratings to the replays in a directory and print them for
+/// each board played, or each puzzle of `--pack`. With `--out`, write the
+/// pack there with the ratings as its difficulties.
+fn calibrate(args: &Args) -> Result<(), String> {
+    let dir = args.positional.get(1).ok_or(USAGE)?;
+    if args.out.is_some() && args.pack.is_none() {
+        return Err("--out needs the --pack to write ratings into".to_string());
+    }
+    let sessions = read_sessions(dir)?;
+    let calibration = Calibration::fit(&sessions)?;
+    let mut pack = match &args.pack {
+        Some(pack) => Pack::open(pack)?,
+        None => {
+            let mut pack = Pack {
+                name: "played".to_string(),
+                author: None,
+                puzzles: Vec::new(),
+            };
+            // Each board played, once however many sessions it had.
+            for session in &sessions {
+                let canonical = session.board.canonical_form();
+                let mut puzzles = pack.puzzles.iter();
+                if !puzzles.any(|puzzle| puzzle.board.canonical_form() == canonical) {
+                    let board = session.board.clone();
+                    pack.puzzles.push(Puzzle::new(board.to_code(), board));
+                }
+            }
+            pack
+        }
+    };
+    let normal = args.shows(Verbosity::Normal);
+    if normal {
+        let cleared = sessions.iter().filter(|session| session.solved).count();
+        println!("{} sessions, {cleared} cleared", sessions.len());
+        print_fit("presses", &calibration.model.presses);
+        if let Some(seconds) = &calibration.model.seconds {
+            print_fit("seconds", seconds);
+        }
+        println!(
+            "\n{:<16} {:>7} {:>8} {:>7} {:>9} {:>6}",
+            "puzzle", "optimal", "sessions", "presses", "predicted", "rating"
+        );
+    }
+    for puzzle in &mut pack.puzzles {
+        let Some(rating) = calibration.board_rating(&puzzle.board) else {
+            continue;
+        };
+        puzzle.difficulty = Some(rating.rating.round());
+        if !normal {
+            continue;
+        }
+        let canonical = puzzle.board.canonical_form();
+        let presses: Vec<usize> = sessions
+            .iter()
+            .filter(|session| session.solved && session.board.canonical_form() == canonical)
+            .map(|session| session.presses)
+            .collect();
+        let mean = match presses.len() {
+            0 => "-".to_string(),
+            n => format!("{:.1}", presses.iter().sum::<usize>() as f64 / n as f64),
+        };
+        let optimal = puzzle.board.optimal_length().unwrap_or_default();
+        let predicted = calibration.model.presses(&puzzle.board).unwrap_or_default();
+        println!(
+            "{:<16} {optimal:>7} {:>8} {mean:>7} {predicted:>9.1} {:>6.0}",
+            puzzle.name, rating.sessions, rating.rating
+        );
+    }
+    if normal && !calibration.players.is_empty() {
+        println!("\n{:<16} {:>6}", "player", "rating");
+        for (player, rating) in &calibration.players {
+            println!("{player:<16} {rating:>6.0}");
+        }
+    }
+    if let Some(out) = &args.out {
+        pack.save(out)?;
+    }
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let result = match args.positional.first().map(String::as_str) {
+        Some("heatmap") => heatmap(args).and_then(|heatmap| {
+            if let Some(path) = &args.png {
+                #[cfg(feature = "image")]
+                fs::write(path, heatmap.to_png(PNG_SCALE))
+                    .map_err(|err| format!("{path}: {err}"))?;
+                #[cfg(not(feature = "image"))]
+                return Err(format!("{path}: lightsout was built without the image feature"));
+            }
+            if args.shows(Verbosity::Normal) {
+                print_heatmap(&heatmap);
+            }
+            Ok(())
+        }),
+        Some("counterexamples") => counterexamples(args),
+        Some("book") => book(args),
+        Some("prospect") => prospect_seeds(args),
+        Some("tradeoff") => tradeoff(args),
+        Some("calibrate") => calibrate(args),
+        Some("replay") => match args.positional.get(1) {
+            Some(path) => replay::analyze(args, path),
+            None => Err(USAGE.to_string()),
+        },
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => Outcome::Solved,
+        Err(err) => {
+            eprintln!("error: {err}");
+            Outcome::InputError
+        }
+    }
+}
@@ -0,0 +1,673 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr, time::Duration};
+
+use lightsout::{
+    generate::{default_threads, Shape},
+    mutate::{Operator, OPERATORS},
+    search::MoveOrder,
+    theme::{Theme, CLASSIC},
+    BitMask, Board, BoardBuilder, CancellationToken, Goals, Preference,
+    PressConstraints, Ruleset,
+};
+
+use super::{config, solve::Progress};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Solve,
+    Generate,
+    Play,
+    Pack,
+    Tutorial,
+    Analyze,
+    Completions,
+    Daemon,
+    Rpc,
+    Serve,
+    Feed,
+    Diff,
+    Reduce,
+    Dedupe,
+    Mutate,
+    FromImage,
+    Card,
+    Scan,
+    Print,
+    Gui,
+    Demo,
+    Selftest,
+    Crosscheck,
+    Debug,
+    Profile,
+    Stream,
+    Cache,
+}
+
+/// Subcommands with a one-line description, for help text and completions.
+pub const COMMANDS: &[(&str, Command, &str)] = &[
+    ("solve", Command::Solve, "solve a random board, or pack/puzzle (the default)"),
+    ("generate", Command::Generate, "generate solvable boards in bulk"),
+    ("stream", Command::Stream, "write solvable boards to stdout at --rate, forever or for --rounds"),
+    ("play", Command::Play, "play a random board, or pack/puzzle, interactively"),
+    ("gui", Command::Gui, "play in a browser window: click cells, hints, solution playback"),
+    ("demo", Command::Demo, "generate boards and animate solving them, forever or for --rounds"),
+    ("selftest", Command::Selftest, "run known boards through every algorithm and check the formats"),
+    ("crosscheck", Command::Crosscheck, "check that linear, astar and exhaustive agree on --count random boards"),
+    ("debug", Command::Debug, "step A* through a board or pack/puzzle one expansion at a time"),
+    ("profile", Command::Profile, "compare two search profiles written by solve --profile"),
+    ("cache", Command::Cache, "summarize, list or clear the --cache of solved boards"),
+    ("tutorial", Command::Tutorial, "learn the light-chasing strategy step by step"),
+    ("pack", Command::Pack, "solve, verify or play puzzles from a pack file"),
+    ("analyze", Command::Analyze, "board statistics: heatmap, counterexamples, prospect, tradeoff, replay, calibrate"),
+    ("diff", Command::Diff, "compare two boards and find the presses between them"),
+    ("reduce", Command::Reduce, "cut a press sequence down to the fewest with its effect"),
+    ("dedupe", Command::Dedupe, "remove puzzles from a pack that repeat up to symmetry"),
+    ("mutate", Command::Mutate, "perturb a board or pack/puzzle into related solvable ones"),
+    ("from-image", Command::FromImage, "turn a PNG into the nearest solvable board"),
+    ("card", Command::Card, "write a board or pack/puzzle as a scannable card image to --png"),
+    ("scan", Command::Scan, "read the board back off a card image and solve it"),
+    ("print", Command::Print, "lay out a --pack as a printable PDF sheet at --out"),
+    ("feed", Command::Feed, "write RSS, Atom and JSON feeds of daily puzzles to a directory"),
+    ("completions", Command::Completions, "print a shell completion script"),
+    ("daemon", Command::Daemon, "serve solves over a unix socket, keeping tables warm"),
+    ("serve", Command::Serve, "serve solves over HTTP, with a bounded job queue, and cooperative play"),
+    ("rpc", Command::Rpc, "speak JSON-RPC on stdin and stdout, for editors and GUIs"),
+];
+
+/// Switches that take no value, for help text and completions.
+pub const SWITCHES: &[(&str, &str)] = &[
+    ("-q, --quiet", "print nothing, only set the exit code"),
+    ("-v, --verbose", "print search statistics; repeat (-vv) for each press"),
+    ("-e, --explain", "walk through the linear algebra behind the solution"),
+    ("--auto-algorithm", "switch from astar to linear if the search looks too slow"),
+    ("--unique", "only generate or prospect boards with a single minimal solution"),
+    ("--symmetric", "only prospect boards a rotation or reflection maps onto themselves"),
+    ("--hardest", "only keep the prospected boards with the longest optimum found"),
+    ("--route", "order presses to keep hand travel short"),
+    ("--bloom", "put a bloom filter sized from --max-nodes in front of astar's explored set"),
+    ("--solutions", "end printed sheets with pages of solutions"),
+    ("--mute", "no sound cues in play"),
+    ("--vs-ai", "play against the computer, taking turns: whoever clears the board wins"),
+    ("--regressions", "also run selftest on the reported cases in regressions.toml"),
+    ("-h, --help", "print this help"),
+];
+
+/// Settings accepted both as `--name value` flags and config file keys.
+pub const SETTINGS: &[(&str, &str)] = &[
+    ("config", "read settings from this file instead of lightsout.toml"),
+    ("width", "board width"),
+    ("height", "board height"),
+    ("seed", "random seed (the first seed when generating)"),
+    ("count", "number of boards to generate, or days of puzzles in a feed"),
+    ("threads", "worker threads for generation and bitsliced batches"),
+    ("algorithm", "solver: astar, linear, exhaustive, bitsliced, chase, hybrid or auto"),
+    ("id", "solve or play the puzzle with this id, or the start of it, from the built-in packs or --cache"),
+    ("input", "solve every board in this file (- for stdin) instead of a random one"),
+    ("output", "output format: text, json or csv"),
+    ("render", "board drawing: blocks, or plain words for screen readers"),
+    ("theme", "block colours: classic, deuteranopia, protanopia or high-contrast"),
+    ("append", "append csv rows to this file instead of printing them"),
+    ("png", "write analysis heatmaps, or cards, as PNG images to this file"),
+    ("warm-start", "seed astar with the solution from this algorithm"),
+    ("density", "chance of each cell of a random board starting lit"),
+    ("rules", "variant rules for random boards, e.g. wrap+square"),
+    ("prefer", "pick among shortest solutions: fewest, rows, chase or travel"),
+    ("target-optimal", "only generate boards needing exactly this many presses"),
+    ("shape", "generate left-right, top-bottom, mirrored or border boards, or like a board file"),
+    ("constraints", "cells solutions must press, or avoid with !, e.g. \"a1 !c3\""),
+    ("goals", "patterns that all count as solved: off, on, rows or hex, e.g. \"off on\""),
+    ("timeout", "give up on a solve after this many seconds"),
+    ("max-nodes", "give up on astar after exploring this many states"),
+    ("manifest", "record finished solve or prospect work in this file, and resume from it"),
+    ("order", "order astar pushes children in: chase, lexical, lit-reduction or random(SEED)"),
+    ("cache", "keep solutions in this file and answer repeated boards from it"),
+    ("cache-size", "most boards --cache keeps, dropping the oldest (default 10000)"),
+    ("profile", "write a profile of the astar search to this file, for lightsout profile"),
+    ("workers", "solves serve runs at once"),
+    ("queue", "connections serve holds waiting for a worker before answering 429"),
+    ("max-cells", "largest board serve accepts, in cells"),
+    ("site", "URL feed links point under, e.g. https://example.com/puzzles"),
+    ("date", "last day feed covers, as YYYY-MM-DD (default today)"),
+    ("record", "write a replay of play to this file, for analyze replay"),
+    ("presses", "press sequence for reduce, e.g. \"a1 b2 a1 c3\""),
+    ("mutations", "changes mutate may make: quiet, press and pair, e.g. press,pair"),
+    ("steps", "changes mutate makes to each board"),
+    ("pack", "pack file, or built-in pack name, print lays out or analyze calibrate rates"),
+    ("out", "file print writes its PDF to, or analyze calibrate its rated --pack"),
+    ("volume", "loudness of play's sound cues, from 0 to 1"),
+    ("fog", "play seeing only cells within this many of your last press, revealing the board at the end"),
+    ("delay", "seconds demo waits between presses"),
+    ("rounds", "boards demo shows, or stream writes, before stopping (default forever)"),
+    ("seeds", "seeds analyze prospect sweeps upwards from --seed (default 100000)"),
+    ("rate", "boards stream writes per second, minute or hour, e.g. 10/m (default 1/s), or max"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+    Debug,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            "csv" => Ok(Output::Csv),
+            _ => Err(format!("unknown output format {s:?} (expected text, json or csv)")),
+        }
+    }
+}
+
+/// How boards are drawn in text output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Render {
+    Blocks,
+    /// Row by row in words, for screen readers.
+    Plain,
+}
+
+impl FromStr for Render {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocks" => Ok(Render::Blocks),
+            "plain" => Ok(Render::Plain),
+            _ => Err(format!("unknown rendering {s:?} (expected blocks or plain)")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    AStar,
+    Linear,
+    Exhaustive,
+    Bitsliced,
+    Chase,
+    /// Linear algebra for the fewest presses, then a search restricted to
+    /// them for the order to make them in. Variants are searched with A*.
+    Hybrid,
+    /// Whichever of the others suits the board, see `solve::choose_algorithm`.
+    Auto,
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "astar" | "a-star" => Ok(Algorithm::AStar),
+            "linear" => Ok(Algorithm::Linear),
+            "exhaustive" => Ok(Algorithm::Exhaustive),
+            "bitsliced" => Ok(Algorithm::Bitsliced),
+            "chase" => Ok(Algorithm::Chase),
+            "hybrid" => Ok(Algorithm::Hybrid),
+            "auto" => Ok(Algorithm::Auto),
+            _ => Err(format!(
+                "unknown algorithm {s:?} \
+                 (expected astar, linear, exhaustive, bitsliced, chase, hybrid or auto)"
+            )),
+        }
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Algorithm::AStar => "astar",
+            Algorithm::Linear => "linear",
+            Algorithm::Exhaustive => "exhaustive",
+            Algorithm::Bitsliced => "bitsliced",
+            Algorithm::Chase => "chase",
+            Algorithm::Hybrid => "hybrid",
+            Algorithm::Auto => "auto",
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Args {
+    pub command: Command,
+    pub positional: Vec<String>,
+    pub width: usize,
+    pub height: usize,
+    pub seed: Option<u64>,
+    pub count: usize,
+    pub threads: usize,
+    pub algorithm: Algorithm,
+    pub verbosity: Verbosity,
+    pub input: Option<String>,
+    pub output: Output,
+    pub render: Render,
+    pub theme: &'static Theme,
+    pub append: Option<String>,
+    pub explain: bool,
+    pub auto_algorithm: bool,
+    pub unique: bool,
+    pub route: bool,
+    pub bloom: bool,
+    pub png: Option<String>,
+    pub warm_start: Option<Algorithm>,
+    pub density: Option<f64>,
+    pub rules: Option<String>,
+    pub constraints: Option<String>,
+    pub goals: Option<String>,
+    pub target_optimal: Option<usize>,
+    pub shape: Option<String>,
+    pub prefer: Preference,
+    pub timeout: Option<Duration>,
+    pub max_nodes: Option<usize>,
+    pub manifest: Option<String>,
+    pub order: MoveOrder,
+    pub profile: Option<String>,
+    pub cache: Option<String>,
+    pub id: Option<String>,
+    pub cache_size: usize,
+    pub workers: usize,
+    pub queue: usize,
+    pub max_cells: usize,
+    pub site: Option<String>,
+    pub date: Option<String>,
+    pub presses: Option<String>,
+    pub record: Option<String>,
+    pub mutations: Vec<Operator>,
+    pub steps: usize,
+    pub pack: Option<String>,
+    pub out: Option<String>,
+    pub solutions: bool,
+    pub volume: f64,
+    pub mute: bool,
+    pub vs_ai: bool,
+    /// How far from the last press play shows cells, or `None` for all.
+    pub fog: Option<usize>,
+    pub regressions: bool,
+    pub delay: f64,
+    pub rounds: Option<usize>,
+    /// Boards per second, infinite for as fast as they come.
+    pub rate: f64,
+    pub seeds: u64,
+    pub symmetric: bool,
+    pub hardest: bool,
+    /// Not a setting: stops solves early, like `timeout` running out.
+    pub cancel: Option<CancellationToken>,
+    /// Not a setting: frontends embedding the solver set it to watch long
+    /// searches.
+    pub on_progress: Option<Progress>,
+}
+
+impl Args {
+    /// Whether output at `level` should be printed.
+    pub fn shows(&self, level: Verbosity) -> bool {
+        self.verbosity >= level
+    }
+
+    /// A builder for boards of the configured size, density and rules.
+    pub fn board_builder(&self) -> Result<BoardBuilder, String> {
+        let mut builder = BoardBuilder::new().width(self.width).height(self.height);
+        if let Some(density) = self.density {
+            builder = builder.density(density);
+        }
+        if let Some(rules) = &self.rules {
+            let ruleset =
+                Ruleset::parse(rules, self.width, self.height).map_err(|err| err.to_string())?;
+            builder = builder.ruleset(ruleset);
+        }
+        Ok(builder)
+    }
+
+    /// A random board from the configured options and `seed`.
+    pub fn random_board(&self, seed: u64) -> Result<Board, String> {
+        self.board_builder()?
+            .seed(seed)
+            .build()
+            .map_err(|err| err.to_string())
+    }
+
+    /// `--constraints` for a board of `board`'s size.
+    pub fn press_constraints(&self, board: &Board) -> Result<Option<PressConstraints>, String> {
+        self.constraints
+            .as_deref()
+            .map(|text| PressConstraints::parse(text, board.width(), board.height()))
+            .transpose()
+            .map_err(|err| err.to_string())
+    }
+
+    /// `--goals` for a board of `board`'s size.
+    pub fn goals(&self, board: &Board) -> Result<Option<Goals>, String> {
+        self.goals
+            .as_deref()
+            .map(|text| Goals::parse(text, board.width(), board.height()))
+            .transpose()
+            .map_err(|err| err.to_string())
+    }
+
+    /// `--shape` for boards of the configured size: a shape by name, or
+    /// else a file holding a board, drawn or as a code, to look like.
+    pub fn generation_shape(&self) -> Result<Option<Shape>, String> {
+        let Some(shape) = &self.shape else {
+            return Ok(None);
+        };
+        if let Ok(shape) = shape.parse() {
+            return Ok(Some(shape));
+        }
+        let text = std::fs::read_to_string(shape).map_err(|err| format!("{shape}: {err}"))?;
+        let board = text
+            .trim()
+            .parse::<Board>()
+            .or_else(|_| Board::from_code(text.trim()))
+            .map_err(|err| format!("{shape}: {err}"))?;
+        if (board.width(), board.height()) != (self.width, self.height) {
+            return Err(format!(
+                "{shape}: the image is {}x{}, but boards are {}x{}",
+                board.width(),
+                board.height(),
+                self.width,
+                self.height
+            ));
+        }
+        let mut mask = BitMask::new(self.width, self.height);
+        for ((x, y), lit) in board.cells() {
+            mask.set(x, y, lit);
+        }
+        Ok(Some(Shape::Image(mask)))
+    }
+
+    /// Apply one setting by name, shared by command line flags (`--name`)
+    /// and config file keys (`name = value`).
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name.replace('_', "-").as_str() {
+            "width" => self.width = parse(name, value)?,
+            "height" => self.height = parse(name, value)?,
+            "seed" => self.seed = Some(parse(name, value)?),
+            "count" | "batch" => self.count = parse(name, value)?,
+            "threads" => self.threads = parse(name, value)?,
+            "algorithm" => self.algorithm = value.parse()?,
+            "input" => self.input = Some(value.to_string()),
+            "output" => self.output = value.parse()?,
+            "render" => self.render = value.parse()?,
+            "theme" => {
+                self.theme = Theme::named(value).ok_or(format!(
+                    "unknown theme {value:?} (expected classic, deuteranopia, protanopia or high-contrast)"
+                ))?
+            }
+            "append" => self.append = Some(value.to_string()),
+            "png" => self.png = Some(value.to_string()),
+            "warm-start" => self.warm_start = Some(value.parse()?),
+            "density" => self.density = Some(parse(name, value)?),
+            "rules" => self.rules = Some(value.to_string()),
+            "constraints" => self.constraints = Some(value.to_string()),
+            "goals" => self.goals = Some(value.to_string()),
+            "prefer" => self.prefer = value.parse()?,
+            "target-optimal" => self.target_optimal = Some(parse(name, value)?),
+            "shape" => self.shape = Some(value.to_string()),
+            "timeout" => {
+                let seconds: f64 = parse(name, value)?;
+                let timeout = Duration::try_from_secs_f64(seconds)
+                    .map_err(|_| format!("invalid value {value:?} for {name}"))?;
+                self.timeout = Some(timeout);
+            }
+            "max-nodes" => self.max_nodes = Some(parse(name, value)?),
+            "manifest" => self.manifest = Some(value.to_string()),
+            "order" => self.order = value.parse()?,
+            "profile" => self.profile = Some(value.to_string()),
+            "cache" => self.cache = Some(value.to_string()),
+            "id" => self.id = Some(value.to_string()),
+            "cache-size" => self.cache_size = parse(name, value)?,
+            "workers" => self.workers = parse(name, value)?,
+            "queue" => self.queue = parse(name, value)?,
+            "max-cells" => self.max_cells = parse(name, value)?,
+            "site" => self.site = Some(value.to_string()),
+            "date" => self.date = Some(value.to_string()),
+            "presses" => self.presses = Some(value.to_string()),
+            "record" => self.record = Some(value.to_string()),
+            "mutations" => {
+                self.mutations = value
+                    .split(',')
+                    .map(|name| name.trim().parse())
+                    .collect::<Result<_, _>>()?;
+            }
+            "steps" => self.steps = parse(name, value)?,
+            "pack" => self.pack = Some(value.to_string()),
+            "out" => self.out = Some(value.to_string()),
+            "delay" => self.delay = parse(name, value)?,
+            "rounds" => self.rounds = Some(parse(name, value)?),
+            "fog" => self.fog = Some(parse(name, value)?),
+            "rate" => self.rate = parse_rate(value)?,
+            "seeds" => self.seeds = parse(name, value)?,
+            "volume" => {
+                self.volume = parse(name, value)?;
+                if !(0.0..=1.0).contains(&self.volume) {
+                    return Err(format!("volume must be between 0 and 1, got {value}"));
+                }
+            }
+            _ => return Err(format!("unknown setting {name}")),
+        }
+        Ok(())
+    }
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            command: Command::Solve,
+            positional: Vec::new(),
+            width: 5,
+            height: 5,
+            seed: None,
+            count: 10,
+            threads: default_threads(),
+            algorithm: Algorithm::AStar,
+            verbosity: Verbosity::Normal,
+            input: None,
+            output: Output::Text,
+            render: Render::Blocks,
+            theme: &CLASSIC,
+            append: None,
+            explain: false,
+            auto_algorithm: false,
+            unique: false,
+            route: false,
+            bloom: false,
+            png: None,
+            warm_start: None,
+            density: None,
+            rules: None,
+            constraints: None,
+            goals: None,
+            target_optimal: None,
+            shape: None,
+            prefer: Preference::Fewest,
+            timeout: None,
+            max_nodes: None,
+            manifest: None,
+            order: MoveOrder::Chase,
+            profile: None,
+            cache: None,
+            id: None,
+            cache_size: 10_000,
+            workers: default_threads(),
+            queue: 64,
+            max_cells: 1024,
+            site: None,
+            date: None,
+            presses: None,
+            record: None,
+            mutations: OPERATORS.to_vec(),
+            steps: 3,
+            pack: None,
+            out: None,
+            solutions: false,
+            volume: 0.5,
+            mute: false,
+            vs_ai: false,
+            fog: None,
+            regressions: false,
+            delay: 0.4,
+            rounds: None,
+            rate: 1.0,
+            seeds: 100_000,
+            symmetric: false,
+            hardest: false,
+            cancel: None,
+            on_progress: None,
+        }
+    }
+}
+
+/// Lines of `  name  help`, with every help starting two columns past the
+/// longest name.
+fn columns<'a>(rows: impl Iterator<Item = (String, &'a str)> + Clone) -> String {
+    let width = rows.clone().map(|(name, _)| name.len()).max().unwrap_or(0) + 2;
+    rows.map(|(name, help)| format!("  {name:<width$}{help}\n")).collect()
+}
+
+pub fn usage() -> String {
+    let mut usage = String::from("usage: lightsout [command] [--setting value ...]\n\ncommands:\n");
+    usage += &columns(COMMANDS.iter().map(|(name, _, help)| (name.to_string(), *help)));
+    usage += "\nswitches:\n";
+    usage += &columns(SWITCHES.iter().map(|(name, help)| (name.to_string(), *help)));
+    usage += "\nsettings:\n";
+    usage += &columns(SETTINGS.iter().map(|(name, help)| (format!("--{name}"), *help)));
+    usage
+}
+
+/// Build the arguments from defaults, then the config file (`--config`, or
+/// `lightsout.toml` in the working directory if present), then the flags.
+/// Returns `Ok(None)` when only help was asked for.
+pub fn parse_args() -> Result<Option<Args>, String> {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if argv.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return Ok(None);
+    }
+    let mut args = Args::default();
+
+    let config_path = match argv.iter().position(|arg| arg == "--config") {
+        Some(i) => Some(PathBuf::from(argv.get(i + 1).ok_or("--config needs a value")?)),
+        None => Some(PathBuf::from(config::DEFAULT_PATH)).filter(|path| path.exists()),
+    };
+    if let Some(path) = config_path {
+        for (key, value) in config::load(&path)? {
+            args.set(&key, &value)
+                .map_err(|err| format!("{}: {err}", path.display()))?;
+        }
+    }
+
+    let mut argv = argv.into_iter().peekable();
+    if let Some(first) = argv.next_if(|arg| !arg.starts_with('-')) {
+        args.command = COMMANDS
+            .iter()
+            .find(|(name, _, _)| *name == first)
+            .map(|(_, command, _)| *command)
+            .ok_or(format!("unknown command {first}"))?;
+    }
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "-q" | "--quiet" => {
+                args.verbosity = Verbosity::Quiet;
+                continue;
+            }
+            "-v" | "--verbose" => {
+                args.verbosity = match args.verbosity {
+                    Verbosity::Verbose | Verbosity::Debug => Verbosity::Debug,
+                    _ => Verbosity::Verbose,
+                };
+                continue;
+            }
+            "-e" | "--explain" => {
+                args.explain = true;
+                continue;
+            }
+            "--auto-algorithm" => {
+                args.auto_algorithm = true;
+                continue;
+            }
+            "--route" => {
+                args.route = true;
+                continue;
+            }
+            "--bloom" => {
+                args.bloom = true;
+                continue;
+            }
+            "--unique" => {
+                args.unique = true;
+                continue;
+            }
+            "--symmetric" => {
+                args.symmetric = true;
+                continue;
+            }
+            "--hardest" => {
+                args.hardest = true;
+                continue;
+            }
+            "--solutions" => {
+                args.solutions = true;
+                continue;
+            }
+            "--mute" => {
+                args.mute = true;
+                continue;
+            }
+            "--vs-ai" => {
+                args.vs_ai = true;
+                continue;
+            }
+            "--regressions" => {
+                args.regressions = true;
+                continue;
+            }
+            "-vv" => {
+                args.verbosity = Verbosity::Debug;
+                continue;
+            }
+            _ => (),
+        }
+        let Some(name) = arg.strip_prefix("--") else {
+            args.positional.push(arg);
+            continue;
+        };
+        let value = argv.next().ok_or(format!("{arg} needs a value"))?;
+        if name != "config" {
+            args.set(name, &value)?;
+        }
+    }
+    Ok(Some(args))
+}
+
+/// A rate like `10/s`, `3/m` or `100/h`, or just a number per second, as
+/// per second; `max` is infinite.
+fn parse_rate(value: &str) -> Result<f64, String> {
+    if value == "max" {
+        return Ok(f64::INFINITY);
+    }
+    let (count, per) = value.split_once('/').unwrap_or((value, "s"));
+    let seconds = match per {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(format!("invalid rate {value:?} (expected e.g. 10/s, 10/m or 10/h)")),
+    };
+    match count.parse::<f64>() {
+        Ok(count) if count > 0.0 && count.is_finite() => Ok(count / seconds),
+        _ => Err(format!("invalid rate {value:?} (expected e.g. 10/s, 10/m or 10/h)")),
+    }
+}
+
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {value:?} for {name}"))
+}
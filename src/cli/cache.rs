@@ -0,0 +1,119 @@
+//! `lightsout cache [list|clear] --cache FILE`: look inside the cache of
+//! solved boards that `--cache` keeps, or empty it.
+
+use std::collections::BTreeMap;
+
+use lightsout::{cache::SolutionCache, json::Json};
+
+use super::{Args, Outcome, Output, Verbosity};
+
+const USAGE: &str = "usage: lightsout cache [list|clear] --cache FILE";
+
+/// How many times each key turns up.
+fn tally<'a>(keys: impl Iterator<Item = &'a str>) -> BTreeMap<&'a str, usize> {
+    let mut counts = BTreeMap::new();
+    for key in keys {
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn summary(args: &Args, cache: &SolutionCache) {
+    let entries: Vec<_> = cache.entries().collect();
+    let unsolvable = entries
+        .iter()
+        .filter(|entry| entry.presses.is_none())
+        .count();
+    let tags = tally(entries.iter().map(|entry| entry.tag.as_str()));
+    let sizes = tally(entries.iter().map(|entry| {
+        entry
+            .code
+            .split_once('-')
+            .map_or(entry.code.as_str(), |(size, _)| size)
+    }));
+    if args.output == Output::Json {
+        let counts = |counts: &BTreeMap<&str, usize>| {
+            Json::object(counts.iter().map(|(&key, &count)| (key, count.into())))
+        };
+        let json = Json::object([
+            ("path", cache.path().display().to_string().into()),
+            ("entries", cache.len().into()),
+            ("max_entries", cache.max_entries().into()),
+            ("unsolvable", unsolvable.into()),
+            ("skipped", cache.skipped.into()),
+            ("tags", counts(&tags)),
+            ("sizes", counts(&sizes)),
+        ]);
+        println!("{json}");
+        return;
+    }
+    println!(
+        "{}: {} of at most {} boards, {unsolvable} unsolvable",
+        cache.path().display(),
+        cache.len(),
+        cache.max_entries()
+    );
+    if cache.skipped > 0 {
+        println!("{} unreadable lines skipped", cache.skipped);
+    }
+    for (heading, counts) in [("by algorithm", &tags), ("by size", &sizes)] {
+        if counts.is_empty() {
+            continue;
+        }
+        println!("{heading}");
+        for (key, count) in counts {
+            println!("  {key:<20}{count:>8}");
+        }
+    }
+}
+
+fn list(args: &Args, cache: &SolutionCache) {
+    for entry in cache.entries() {
+        let presses = entry.presses.as_ref().map(Vec::len);
+        if args.output == Output::Json {
+            let json = Json::object([
+                ("tag", entry.tag.into()),
+                ("code", entry.code.into()),
+                ("moves", presses.into()),
+            ]);
+            println!("{json}");
+            continue;
+        }
+        let moves = presses.map_or("unsolvable".to_string(), |moves| format!("{moves} moves"));
+        println!("{:<16} {} ({moves})", entry.tag, entry.code);
+    }
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let Some(path) = &args.cache else {
+        eprintln!("error: {USAGE}");
+        return Outcome::InputError;
+    };
+    let mut cache = match SolutionCache::open(path, args.cache_size) {
+        Ok(cache) => cache,
+        Err(err) => {
+            eprintln!("error: couldn't read {path}: {err}");
+            return Outcome::InputError;
+        }
+    };
+    match args.positional.first().map(String::as_str) {
+        None if args.shows(Verbosity::Normal) => summary(args, &cache),
+        Some("list") if args.shows(Verbosity::Normal) => list(args, &cache),
+        None | Some("list") => {}
+        Some("clear") => {
+            let cleared = cache.len();
+            if let Err(err) = cache.clear() {
+                eprintln!("error: couldn't write {path}: {err}");
+                return Outcome::InputError;
+            }
+            if args.shows(Verbosity::Normal) {
+                println!("Removed {cleared} boards from {path}");
+            }
+        }
+        Some(_) => {
+            eprintln!("error: {USAGE}");
+            return Outcome::InputError;
+        }
+    }
+    Outcome::Solved
+}
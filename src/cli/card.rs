@@ -0,0 +1,60 @@
+use lightsout::{card, pack::lookup};
+use rand::prelude::*;
+
+use super::{
+    solve::{print_text, solve_board, to_json},
+    Args, Outcome, Output, Verbosity,
+};
+
+/// Write a card of a pack/puzzle, or a random board, to `--png`.
+pub fn run(args: &Args) -> Outcome {
+    let Some(path) = &args.png else {
+        eprintln!("error: card needs --png, the file to write the card to");
+        return Outcome::InputError;
+    };
+    let board = match args.positional.first() {
+        Some(reference) => lookup(reference).map(|puzzle| puzzle.board),
+        None => args.random_board(args.seed.unwrap_or_else(random)),
+    };
+    let board = match board {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    if let Err(err) = std::fs::write(path, card::encode(&board)) {
+        eprintln!("error: {path}: {err}");
+        return Outcome::InputError;
+    }
+    if args.shows(Verbosity::Normal) {
+        println!("{board}");
+        println!("Wrote a card of {} to {path}", board.to_code());
+    }
+    Outcome::Solved
+}
+
+/// Read the board back off a card image and solve it.
+pub fn scan(args: &Args) -> Outcome {
+    let Some(path) = args.positional.first() else {
+        eprintln!("error: usage: lightsout scan <card.png>");
+        return Outcome::InputError;
+    };
+    let board = std::fs::read(path)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| card::decode(&bytes));
+    let board = match board {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("error: {path}: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let solved = solve_board(args, board, None);
+    match args.output {
+        Output::Json if args.shows(Verbosity::Normal) => println!("{}", to_json(&solved)),
+        Output::Json => (),
+        _ => print_text(args, &solved),
+    }
+    solved.outcome
+}
@@ -0,0 +1,115 @@
+//! The system clipboard, through whichever command line tool the platform
+//! has for it, so there's no windowing library to link: pbcopy and pbpaste
+//! on macOS, clip and PowerShell on Windows, and wl-clipboard, xclip or
+//! xsel on Linux, whichever suits the session. Without one, copying asks
+//! the terminal to do it with an OSC 52 escape, which most terminals honor,
+//! over SSH too; pasting has no such fallback.
+
+use std::{
+    env,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use super::wire::base64;
+
+type Tool = (&'static str, &'static [&'static str]);
+
+/// Copying tools tried in order, with their arguments, each taking the
+/// text on stdin.
+const COPIERS: &[Tool] = if cfg!(target_os = "macos") {
+    &[("pbcopy", &[])]
+} else if cfg!(windows) {
+    &[("clip", &[])]
+} else {
+    &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ]
+};
+
+/// Pasting tools tried in order, each printing the clipboard.
+const PASTERS: &[Tool] = if cfg!(target_os = "macos") {
+    &[("pbpaste", &[])]
+} else if cfg!(windows) {
+    &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])]
+} else {
+    &[
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-o"]),
+        ("xsel", &["--clipboard", "--output"]),
+    ]
+};
+
+/// Whether `program` is installed and has a display to talk to: Wayland's
+/// tools need a Wayland session and X's an X one.
+fn usable(program: &str) -> bool {
+    let session = match program {
+        "wl-copy" | "wl-paste" => env::var_os("WAYLAND_DISPLAY").is_some(),
+        "xclip" | "xsel" => env::var_os("DISPLAY").is_some(),
+        _ => true,
+    };
+    session
+        && env::var_os("PATH").is_some_and(|path| {
+            env::split_paths(&path).any(|dir| {
+                dir.join(program).is_file() || dir.join(format!("{program}.exe")).is_file()
+            })
+        })
+}
+
+fn tool(tools: &[Tool]) -> Option<Tool> {
+    tools.iter().copied().find(|(program, _)| usable(program))
+}
+
+/// Put `text` on the clipboard, returning how: the tool's name, or
+/// `"the terminal"` for the escape code.
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    let Some((program, args)) = tool(COPIERS) else {
+        print!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+        std::io::stdout().flush().map_err(|err| err.to_string())?;
+        return Ok("the terminal");
+    };
+    let fail = |err: std::io::Error| format!("{program}: {err}");
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(fail)?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(text.as_bytes())
+        .map_err(fail)?;
+    let status = child.wait().map_err(fail)?;
+    if !status.success() {
+        return Err(format!("{program} failed ({status})"));
+    }
+    Ok(program)
+}
+
+/// The text on the clipboard.
+pub fn paste() -> Result<String, String> {
+    let (program, args) = tool(PASTERS).ok_or_else(|| {
+        let names: Vec<&str> = PASTERS.iter().map(|(program, _)| *program).collect();
+        let names = match names.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+            None => String::new(),
+        };
+        format!("no clipboard tool to paste with; install {names}")
+    })?;
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|err| format!("{program}: {err}"))?;
+    if !output.status.success() {
+        return Err(format!("{program} failed ({})", output.status));
+    }
+    String::from_utf8(output.stdout).map_err(|_| format!("{program} gave text that isn't UTF-8"))
+}
@@ -0,0 +1,82 @@
+use super::args::{COMMANDS, SETTINGS, SWITCHES};
+
+fn commands() -> Vec<&'static str> {
+    COMMANDS.iter().map(|(name, _, _)| *name).collect()
+}
+
+fn flags() -> Vec<String> {
+    SWITCHES
+        .iter()
+        .flat_map(|(names, _)| names.split(", "))
+        .map(String::from)
+        .chain(SETTINGS.iter().map(|(name, _)| format!("--{name}")))
+        .collect()
+}
+
+pub fn script(shell: &str) -> Result<String, String> {
+    match shell {
+        "bash" => Ok(format!(
+            r#"_lightsout() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    if [ "$COMP_CWORD" -eq 1 ]; then
+        COMPREPLY=($(compgen -W "{} {}" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W "{}" -- "$cur"))
+    fi
+}}
+complete -F _lightsout lightsout
+"#,
+            commands().join(" "),
+            flags().join(" "),
+            flags().join(" ")
+        )),
+        "zsh" => {
+            let flags: Vec<String> = SWITCHES
+                .iter()
+                .map(|(names, help)| match names.split_once(", ") {
+                    Some((short, long)) => {
+                        format!("'({short} {long})'{{{short},{long}}}'[{help}]'")
+                    }
+                    None => format!("'{names}[{help}]'"),
+                })
+                .chain(
+                    SETTINGS
+                        .iter()
+                        .map(|(name, help)| format!("'--{name}[{help}]:value:'")),
+                )
+                .collect();
+            Ok(format!(
+                "#compdef lightsout\n_arguments \\\n    '1:command:({})' \\\n    {}\n",
+                commands().join(" "),
+                flags.join(" \\\n    ")
+            ))
+        }
+        "fish" => {
+            let mut script = String::new();
+            for (name, _, help) in COMMANDS {
+                script += &format!(
+                    "complete -c lightsout -n __fish_use_subcommand -a {name} -d '{help}'\n"
+                );
+            }
+            for (names, help) in SWITCHES {
+                match names.split_once(", ") {
+                    Some((short, long)) => {
+                        script += &format!(
+                            "complete -c lightsout -s {} -l {} -d '{help}'\n",
+                            &short[1..],
+                            &long[2..]
+                        )
+                    }
+                    None => {
+                        script += &format!("complete -c lightsout -l {} -d '{help}'\n", &names[2..])
+                    }
+                }
+            }
+            for (name, help) in SETTINGS {
+                script += &format!("complete -c lightsout -l {name} -r -d '{help}'\n");
+            }
+            Ok(script)
+        }
+        _ => Err(format!("unsupported shell {shell:?} (expected bash, zsh or fish)")),
+    }
+}
@@ -0,0 +1,51 @@
+//! `lightsout.toml` support: a flat subset of TOML, `key = value` per line
+//! with `#` comments and optional `[section]` headers, where keys inside a
+//! section are read as `section.key`.
+
+use std::{fs, path::Path};
+
+pub const DEFAULT_PATH: &str = "lightsout.toml";
+
+pub fn load(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    parse(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+pub fn parse(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut section = String::new();
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            section = format!("{}.", name.trim());
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(format!("line {}: expected `key = value`", number + 1))?;
+        let value = value.trim();
+        let value = match value.strip_prefix('"') {
+            Some(rest) => rest
+                .strip_suffix('"')
+                .ok_or(format!("line {}: unterminated string", number + 1))?,
+            None => value,
+        };
+        entries.push((format!("{section}{}", key.trim()), value.to_string()));
+    }
+    Ok(entries)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
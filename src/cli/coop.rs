@@ -0,0 +1,321 @@
+//! `GET /coop` on `serve`: cooperative play, everyone in a room pressing
+//! one shared board over a WebSocket, for classrooms and parties.
+//!
+//! A player's first message joins a room, which is made on first use:
+//! `{"join": "name"}`, with `"board": "5x5-c408f38"` to start a new room on
+//! that board rather than a random clearable one of `--width`, `--height`
+//! and `--rules`. After that they send any of
+//!
+//! - `{"press": [x, y], "version": n}`, `n` being the last version they saw
+//! - `{"reset": true}`, to put the board back as it was dealt
+//! - `{"new": true}`, to deal another of the same size and rules
+//!
+//! Each room takes messages one at a time, in the order they arrive, and a
+//! [`SharedBoard`] refuses presses that clash with one made since the
+//! sender's version. Everyone in the room hears `{"event": "pressed"}` with
+//! the press and who made it, `{"event": "board"}` for a reset or a new
+//! board, and `{"event": "players"}` as people come and go. A refused press
+//! gets `{"event": "conflict"}` with the reason, to its sender only. Every
+//! event carries the version, the number of players and the board as
+//! `gui` draws it, and once the board is clear, each player's presses.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufReader, Write},
+    net::{Shutdown, TcpStream},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::Duration,
+};
+
+use lightsout::{
+    coop::{Conflict, SharedBoard},
+    json::{self, Json},
+    BitMask, Board, BoardBuilder,
+};
+use rand::prelude::*;
+
+use super::{
+    serve::{upgrade, view, Request, Response},
+    websocket, Args,
+};
+
+/// Players in all rooms together, beyond which `/coop` answers 429.
+const MAX_PLAYERS: usize = 256;
+/// How long a player may stay silent before they're dropped.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+/// Largest message a player may send.
+const MAX_MESSAGE: usize = 4 << 10;
+const MAX_ROOM_NAME: usize = 64;
+
+static PLAYERS: AtomicUsize = AtomicUsize::new(0);
+static NEXT_PLAYER: AtomicU64 = AtomicU64::new(1);
+
+/// A player's connection, written to by whichever session is telling the
+/// room about a change.
+type Writer = Arc<Mutex<TcpStream>>;
+
+struct Room {
+    board: SharedBoard,
+    players: Vec<(u64, Writer)>,
+}
+
+/// Every room with someone in it, by name.
+fn rooms() -> &'static Mutex<HashMap<String, Arc<Mutex<Room>>>> {
+    static ROOMS: OnceLock<Mutex<HashMap<String, Arc<Mutex<Room>>>>> = OnceLock::new();
+    ROOMS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Writes to a shared connection, held back until flushed so each frame
+/// goes out whole, never interleaved with another session's.
+struct Relay {
+    writer: Writer,
+    pending: Vec<u8>,
+}
+
+impl Relay {
+    fn new(writer: &Writer) -> Relay {
+        Relay {
+            writer: Arc::clone(writer),
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Write for Relay {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut stream = self.writer.lock().unwrap();
+        stream.write_all(&self.pending)?;
+        self.pending.clear();
+        stream.flush()
+    }
+}
+
+fn send(writer: &Writer, message: &Json) -> io::Result<()> {
+    let text = message.to_string();
+    websocket::write_frame(&mut Relay::new(writer), websocket::TEXT, text.as_bytes())
+}
+
+/// Send `message` to everyone in `room` but `skip`, dropping players who
+/// can't be reached.
+fn broadcast(room: &mut Room, message: &Json, skip: Option<u64>) {
+    room.players
+        .retain(|(player, writer)| Some(*player) == skip || send(writer, message).is_ok());
+}
+
+/// An event about `room` with `details` first, then the state every event
+/// carries.
+fn event(name: &str, room: &Room, details: Vec<(&str, Json)>) -> Json {
+    let mut entries = vec![("event", name.into())];
+    entries.extend(details);
+    entries.push(("version", room.board.version().into()));
+    entries.push(("players", room.players.len().into()));
+    entries.push(("board", view(room.board.board())));
+    if room.board.board().is_solved() {
+        let tally = room.board.tally().into_iter();
+        let tally = tally.map(|(player, presses)| (player.to_string(), presses.into()));
+        entries.push(("tally", Json::object(tally)));
+    }
+    Json::object(entries)
+}
+
+fn cell(x: usize, y: usize) -> Json {
+    Json::Array(vec![x.into(), y.into()])
+}
+
+fn reason(conflict: Conflict) -> String {
+    match conflict {
+        Conflict::NotPressable => "that cell can't be pressed".to_string(),
+        Conflict::Cleared => "the board is already clear".to_string(),
+        Conflict::Pressed { player } => format!("player {player} pressed that cell first"),
+        Conflict::Replaced => "the board was reset or replaced since that version".to_string(),
+        Conflict::Unknown => "the board hasn't reached that version".to_string(),
+    }
+}
+
+/// A random board clearable by construction, the size and rules of `like`:
+/// random presses on a dark one, as `gui` deals them.
+fn deal(like: &Board) -> Result<Board, String> {
+    let (width, height) = (like.width(), like.height());
+    let dark = BoardBuilder::new()
+        .width(width)
+        .height(height)
+        .ruleset(like.ruleset().without_goal())
+        .build()
+        .map_err(|err| err.to_string())?;
+    let mut rng = StdRng::seed_from_u64(random());
+    // Presses that cancel out leave it dark, which isn't much of a game.
+    for _ in 0..16 {
+        let mut presses = BitMask::new(width, height);
+        for (x, y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+            presses.set(x, y, rng.gen_bool(0.5) && !dark.ruleset().is_obstacle(x, y));
+        }
+        let mut board = dark.clone();
+        board.apply_press_set(&presses);
+        if !board.is_solved() {
+            return Ok(board);
+        }
+    }
+    Ok(dark)
+}
+
+/// Accept a player if there's room for another.
+pub(super) fn admit(request: &Request, stream: &mut TcpStream) -> Result<(), Response> {
+    if PLAYERS.load(Ordering::Relaxed) >= MAX_PLAYERS {
+        return Err(Response::error(429, "too many players, try again shortly"));
+    }
+    upgrade(request, stream)?;
+    PLAYERS.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Put `player` in the room `message` names, making it if it's new.
+fn join(
+    args: &Args,
+    player: u64,
+    writer: &Writer,
+    message: &Json,
+) -> Result<(String, Arc<Mutex<Room>>), String> {
+    let name = message
+        .get("join")
+        .and_then(Json::as_str)
+        .ok_or(r#"join a room first, with {"join": name}"#)?;
+    if name.is_empty() || name.len() > MAX_ROOM_NAME {
+        return Err(format!("room names are 1 to {MAX_ROOM_NAME} bytes"));
+    }
+    let mut rooms = rooms().lock().unwrap();
+    let room = match rooms.get(name) {
+        Some(room) => Arc::clone(room),
+        None => {
+            let board = match message.get("board").and_then(Json::as_str) {
+                Some(code) => Board::from_code(code).map_err(|err| err.to_string())?,
+                None => {
+                    let dark = args.board_builder()?.build();
+                    deal(&dark.map_err(|err| err.to_string())?)?
+                }
+            };
+            if board.width() * board.height() > args.max_cells {
+                return Err(format!("boards are limited to {} cells", args.max_cells));
+            }
+            let room = Room {
+                board: SharedBoard::new(board),
+                players: Vec::new(),
+            };
+            let room = Arc::new(Mutex::new(room));
+            rooms.insert(name.to_string(), Arc::clone(&room));
+            room
+        }
+    };
+    let mut locked = room.lock().unwrap();
+    locked.players.push((player, Arc::clone(writer)));
+    let joined = event(
+        "joined",
+        &locked,
+        vec![("room", name.into()), ("player", player.into())],
+    );
+    let _ = send(writer, &joined);
+    let players = event("players", &locked, Vec::new());
+    broadcast(&mut locked, &players, Some(player));
+    drop(locked);
+    Ok((name.to_string(), room))
+}
+
+/// Take `player` out of the room `name`, closing it once it's empty.
+fn leave(name: &str, player: u64) {
+    let mut rooms = rooms().lock().unwrap();
+    let Some(room) = rooms.get(name).cloned() else {
+        return;
+    };
+    let mut room = room.lock().unwrap();
+    room.players.retain(|(other, _)| *other != player);
+    if room.players.is_empty() {
+        rooms.remove(name);
+    } else {
+        let players = event("players", &room, Vec::new());
+        broadcast(&mut room, &players, None);
+    }
+}
+
+/// Act on one message from `player` after they've joined `room`.
+fn act(player: u64, room: &Mutex<Room>, writer: &Writer, message: &Json) -> Result<(), String> {
+    let mut room = room.lock().unwrap();
+    let flag = |key| message.get(key).and_then(Json::as_bool) == Some(true);
+    if let Some(press) = message.get("press") {
+        let (x, y) = match press.as_array() {
+            Some([x, y]) => x.as_u64().zip(y.as_u64()),
+            _ => None,
+        }
+        .ok_or("press takes [x, y]")?;
+        let (x, y) = (x as usize, y as usize);
+        let seen = message.get("version").and_then(Json::as_u64);
+        match room.board.press(player, x, y, seen) {
+            Ok(_) => {
+                let details = vec![("player", player.into()), ("press", cell(x, y))];
+                let pressed = event("pressed", &room, details);
+                broadcast(&mut room, &pressed, None);
+            }
+            Err(conflict) => {
+                let details = vec![("press", cell(x, y)), ("reason", reason(conflict).into())];
+                let _ = send(writer, &event("conflict", &room, details));
+            }
+        }
+    } else if flag("reset") || flag("new") {
+        if flag("new") {
+            let board = deal(room.board.board())?;
+            room.board.replace(board);
+        } else {
+            room.board.reset();
+        }
+        let dealt = event("board", &room, vec![("player", player.into())]);
+        broadcast(&mut room, &dealt, None);
+    } else {
+        return Err("expected press, reset or new".to_string());
+    }
+    Ok(())
+}
+
+/// Play with `stream` until the player leaves, goes quiet or can't be
+/// reached. [`admit`] has already accepted them.
+pub(super) fn session(args: &Args, mut reader: BufReader<TcpStream>, stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(IDLE_TIMEOUT));
+    let writer: Writer = Arc::new(Mutex::new(stream));
+    let player = NEXT_PLAYER.fetch_add(1, Ordering::Relaxed);
+    let mut joined = None;
+    while let Ok((opcode, payload)) =
+        websocket::read_message(&mut reader, &mut Relay::new(&writer), MAX_MESSAGE)
+    {
+        if opcode == websocket::CLOSE {
+            break;
+        }
+        let message = std::str::from_utf8(&payload)
+            .map_err(|_| "message is not UTF-8".to_string())
+            .and_then(json::parse);
+        let result = message.and_then(|message| match &joined {
+            None => join(args, player, &writer, &message).map(|room| joined = Some(room)),
+            Some((_, room)) => act(player, room, &writer, &message),
+        });
+        if let Err(err) = result {
+            let error = Json::object([("event", "error".into()), ("error", err.into())]);
+            if send(&writer, &error).is_err() {
+                break;
+            }
+        }
+    }
+    if let Some((name, _)) = joined {
+        leave(&name, player);
+    }
+    PLAYERS.fetch_sub(1, Ordering::Relaxed);
+    let _ = websocket::write_frame(
+        &mut Relay::new(&writer),
+        websocket::CLOSE,
+        &1000u16.to_be_bytes(),
+    );
+    let _ = writer.lock().unwrap().shutdown(Shutdown::Both);
+}
@@ -0,0 +1,140 @@
+//! `lightsout crosscheck`: differential testing for solver work. Random
+//! boards from consecutive seeds go through the linear solver, A* and the
+//! exhaustive tables, which must agree on whether each board can be cleared
+//! and in how few presses, and every solution they give must clear it. Any
+//! disagreement is printed with the command that reproduces it, and the run
+//! exits 1.
+
+use lightsout::{search::StateTable, Board, Preference};
+use rand::prelude::*;
+
+use super::{solve::solve_board, Algorithm, Args, Outcome, Verbosity};
+
+const ALGORITHMS: [Algorithm; 3] = [Algorithm::Linear, Algorithm::AStar, Algorithm::Exhaustive];
+
+/// A* budget when `--max-nodes` isn't given, so one hard board doesn't
+/// stall the run. Boards it runs out on are compared without it.
+const MAX_NODES: usize = 200_000;
+
+/// What one algorithm made of a board.
+enum Answer {
+    Presses(usize),
+    Unsolvable,
+    /// Out of budget, or outside what the algorithm covers.
+    Skipped,
+    /// A solution that leaves lights on.
+    Wrong(usize),
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Presses(count) => write!(f, "{count}"),
+            Answer::Unsolvable => f.write_str("unsolvable"),
+            Answer::Skipped => f.write_str("skipped"),
+            Answer::Wrong(count) => write!(f, "{count} that don't clear it"),
+        }
+    }
+}
+
+fn answer(args: &Args, algorithm: Algorithm, board: &Board, seed: u64) -> Answer {
+    if algorithm == Algorithm::Exhaustive && board.width() * board.height() > StateTable::MAX_CELLS
+    {
+        return Answer::Skipped;
+    }
+    let args = Args {
+        algorithm,
+        max_nodes: args.max_nodes.or(Some(MAX_NODES)),
+        prefer: Preference::Fewest,
+        constraints: None,
+        route: false,
+        warm_start: None,
+        auto_algorithm: false,
+        ..args.clone()
+    };
+    let solved = solve_board(&args, board.clone(), Some(seed));
+    match (solved.outcome, solved.presses) {
+        (_, Some(presses)) if board.verify(&presses) => Answer::Presses(presses.len()),
+        (_, Some(presses)) => Answer::Wrong(presses.len()),
+        (Outcome::Unsolvable, None) => Answer::Unsolvable,
+        (_, None) => Answer::Skipped,
+    }
+}
+
+/// Whether the answers that weren't skipped all agree and are all right.
+fn agree(answers: &[Answer]) -> bool {
+    let mut settled = answers
+        .iter()
+        .filter(|answer| !matches!(answer, Answer::Skipped));
+    let Some(first) = settled.next() else {
+        return true;
+    };
+    settled.chain([first]).all(|answer| match (first, answer) {
+        (Answer::Presses(a), Answer::Presses(b)) => a == b,
+        (Answer::Unsolvable, Answer::Unsolvable) => true,
+        _ => false,
+    })
+}
+
+/// The solve command that makes the board from `seed` again.
+fn reproduce(args: &Args, seed: u64) -> String {
+    let mut command = format!(
+        "lightsout solve --seed {seed} --width {} --height {}",
+        args.width, args.height
+    );
+    if let Some(density) = args.density {
+        command += &format!(" --density {density}");
+    }
+    if let Some(rules) = &args.rules {
+        command += &format!(" --rules {rules}");
+    }
+    command
+}
+
+pub fn run(args: &Args) -> Outcome {
+    if let Err(err) = args.board_builder() {
+        eprintln!("error: {err}");
+        return Outcome::InputError;
+    }
+    let first = args.seed.unwrap_or_else(random);
+    let (mut diverged, mut skipped) = (0, 0);
+    for seed in (0..args.count as u64).map(|i| first.wrapping_add(i)) {
+        let board = match args.random_board(seed) {
+            Ok(board) => board,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return Outcome::InputError;
+            }
+        };
+        let answers = ALGORITHMS.map(|algorithm| answer(args, algorithm, &board, seed));
+        skipped += answers
+            .iter()
+            .filter(|answer| matches!(answer, Answer::Skipped))
+            .count();
+        let line = ALGORITHMS
+            .iter()
+            .zip(&answers)
+            .map(|(algorithm, answer)| format!("{algorithm} {answer}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !agree(&answers) {
+            diverged += 1;
+            if args.shows(Verbosity::Normal) {
+                println!("seed {seed}: {line}\n  {}", reproduce(args, seed));
+            }
+        } else if args.shows(Verbosity::Verbose) {
+            println!("seed {seed}: {line}");
+        }
+    }
+    if args.shows(Verbosity::Normal) {
+        println!(
+            "{} boards from seed {first}: {diverged} diverged, {skipped} answers skipped",
+            args.count
+        );
+    }
+    if diverged > 0 {
+        Outcome::Unsolvable
+    } else {
+        Outcome::Solved
+    }
+}
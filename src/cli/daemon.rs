@@ -0,0 +1,206 @@
+use std::io::{BufRead, BufReader, Write};
+
+use lightsout::json::Json;
+use rand::prelude::*;
+
+use super::{
+    input::parse_item,
+    solve::{context, solve_boards, to_json, Task},
+    wire::Format,
+    Args, Outcome,
+};
+
+/// Keys of a request that describe the board rather than override a setting.
+const BOARD_KEYS: &[&str] = &["board", "constraints"];
+
+/// Answer one request: an object with an optional `board` (anything an input
+/// file's JSON item can be) and `constraints`, plus any settings or the
+/// `route` and `auto-algorithm` switches to override for this solve, as in
+/// `{"board": "...", "algorithm": "linear", "route": true}`. Without a
+/// board, solves a random one from the settings. Replies with the same object
+/// `--output json` prints, or `{"error": ...}`. An array of requests gets an
+/// array of replies, so batch clients can send thousands in one message.
+pub fn respond(args: &Args, request: &Json) -> Json {
+    if let Json::Array(requests) = request {
+        return Json::Array(requests.iter().map(|request| respond(args, request)).collect());
+    }
+    match solve_request(args, request) {
+        Ok(reply) => reply,
+        Err(err) => Json::object([("error", err.into())]),
+    }
+}
+
+pub fn solve_request(args: &Args, request: &Json) -> Result<Json, String> {
+    let (args, task) = request_task(args, request)?;
+    let solved = solve_boards(&args, vec![task]);
+    Ok(to_json(&solved[0]))
+}
+
+/// The arguments and board to solve a request with, before solving it.
+pub fn request_task(args: &Args, request: &Json) -> Result<(Args, Task), String> {
+    let args = request_args(args, request)?;
+    let task = request_board(&args, request)?;
+    Ok((args, task))
+}
+
+/// `args` with a request's settings applied.
+pub fn request_args(args: &Args, request: &Json) -> Result<Args, String> {
+    let Json::Object(entries) = request else {
+        return Err("expected a request object".to_string());
+    };
+    let mut args = args.clone();
+    apply_settings(&mut args, entries)?;
+    Ok(args)
+}
+
+/// The board a request names, or a random one from `args` without one.
+pub fn request_board(args: &Args, request: &Json) -> Result<Task, String> {
+    Ok(if request.get("board").is_some() {
+        let (board, constraints) = parse_item(request)?;
+        Task {
+            board,
+            seed: None,
+            constraints,
+        }
+    } else {
+        let seed = args.seed.unwrap_or_else(random);
+        Task {
+            board: args.random_board(seed)?,
+            seed: Some(seed),
+            constraints: None,
+        }
+    })
+}
+
+/// Override `args` with the settings and switches among a request's
+/// `entries`, leaving out the ones describing the board.
+pub fn apply_settings(args: &mut Args, entries: &[(String, Json)]) -> Result<(), String> {
+    for (key, value) in entries.iter().filter(|(key, _)| !BOARD_KEYS.contains(&key.as_str())) {
+        let switch = match key.replace('_', "-").as_str() {
+            "route" => Some(&mut args.route),
+            "auto-algorithm" => Some(&mut args.auto_algorithm),
+            _ => None,
+        };
+        if let Some(switch) = switch {
+            *switch = value.as_bool().ok_or(format!("{key} must be a boolean"))?;
+            continue;
+        }
+        let value = match value {
+            Json::String(s) => s.clone(),
+            Json::Number(_) | Json::Bool(_) => value.to_string(),
+            _ => return Err(format!("{key} must be a string, number or boolean")),
+        };
+        args.set(key, &value)?;
+    }
+    Ok(())
+}
+
+/// Where the daemon listens unless given a path.
+pub fn default_socket() -> std::path::PathBuf {
+    std::env::temp_dir().join("lightsout.sock")
+}
+
+/// Build the tables for the configured board size up front, so the first
+/// request of that size is as quick as the rest.
+fn warm(args: &Args) {
+    context().batch_solver(args.width, args.height);
+    if let Ok(board) = args.random_board(0) {
+        context().state_table(&board);
+        if !board.ruleset().presses_classically() {
+            context().linear_system(&board);
+        }
+    }
+}
+
+/// Answer requests from one client until it disconnects. Requests are lines
+/// of JSON, unless the client opens with a line like `Content-Type:
+/// application/cbor` choosing a binary format for both directions, where
+/// each message is one value.
+fn serve_client(args: &Args, mut reader: impl BufRead, mut writer: impl Write) {
+    const HEADER: &[u8] = b"Content-Type:";
+    let mut format = Format::Json;
+    if reader.fill_buf().is_ok_and(|buffer| buffer.starts_with(HEADER)) {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() {
+            return;
+        }
+        format = match Format::from_content_type(&line[HEADER.len()..]) {
+            Some(format) => format,
+            None => {
+                let error = format!("unsupported content type {}", line[HEADER.len()..].trim());
+                let _ = writeln!(writer, "{}", Json::object([("error", error.into())]));
+                return;
+            }
+        };
+    }
+    loop {
+        let (reply, resync) = match format.read(&mut reader) {
+            Ok(None) => break,
+            Ok(Some(request)) => (respond(args, &request), true),
+            // A bad line costs just that line, but there's no finding the
+            // next binary value after a bad one.
+            Err(err) => (Json::object([("error", err.into())]), format == Format::Json),
+        };
+        let mut message = format.encode(&reply);
+        if format == Format::Json {
+            message.push(b'\n');
+        }
+        if writer.write_all(&message).is_err() || !resync {
+            break;
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn run(args: &Args) -> Outcome {
+    use std::{
+        io::ErrorKind,
+        os::unix::net::{UnixListener, UnixStream},
+        thread,
+    };
+
+    let path = args
+        .positional
+        .first()
+        .map_or_else(default_socket, std::path::PathBuf::from);
+    let listener = match UnixListener::bind(&path) {
+        Err(err) if err.kind() == ErrorKind::AddrInUse => {
+            if UnixStream::connect(&path).is_ok() {
+                eprintln!("error: a daemon is already listening on {}", path.display());
+                return Outcome::InputError;
+            }
+            // Left behind by a daemon that didn't shut down cleanly.
+            let _ = std::fs::remove_file(&path);
+            UnixListener::bind(&path)
+        }
+        bound => bound,
+    };
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("error: {}: {err}", path.display());
+            return Outcome::InputError;
+        }
+    };
+    warm(args);
+    eprintln!("listening on {}", path.display());
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let args = args.clone();
+        thread::spawn(move || {
+            if let Ok(reader) = stream.try_clone() {
+                serve_client(&args, BufReader::new(reader), stream);
+            }
+        });
+    }
+    Outcome::Solved
+}
+
+#[cfg(not(unix))]
+pub fn run(_args: &Args) -> Outcome {
+    eprintln!("error: the daemon needs unix sockets, which this platform lacks");
+    Outcome::InputError
+}
@@ -0,0 +1,195 @@
+//! `lightsout debug`: the A* search over a board, one expansion at a time,
+//! for working on heuristics and pruning rules. Between steps the frontier's
+//! best entries, the explored set and the scores the heuristic gives each
+//! child can be looked at.
+
+use std::io::{stdin, stdout, BufRead, Write};
+
+use lightsout::{
+    cell_name,
+    pack::lookup,
+    search::{BucketFrontier, Frontier, Search, SearchState, Step, Stepper},
+    Board,
+};
+use rand::prelude::*;
+
+use super::{Args, Outcome, Verbosity};
+
+const HELP: &str = "\
+step [n]      expand the next n states (default 1; an empty line steps once)
+run           step until solved or out of states
+frontier [n]  the next n states to expand (default 5)
+show [i]      draw frontier entry i, or the state expanded last
+scores        the score of each child of the state expanded last
+explored      explored set size and memory
+quit";
+
+/// The board named by the positional argument, as a puzzle code or a
+/// pack/puzzle, or a random one.
+fn board(args: &Args) -> Result<Board, String> {
+    match args.positional.first() {
+        Some(reference) => Board::from_code(reference)
+            .or_else(|_| lookup(reference).map(|puzzle| puzzle.board)),
+        None => args.random_board(args.seed.unwrap_or_else(random)),
+    }
+}
+
+/// The presses leading to `state`, as cell names.
+fn path(board: &Board, state: &SearchState<Board>) -> String {
+    let presses: Vec<String> = state
+        .history
+        .iter()
+        .chain(state.latest_move_index.as_ref())
+        .map(|id| cell_name(id % board.width(), id / board.width()))
+        .collect();
+    match presses.is_empty() {
+        true => "start".to_string(),
+        false => presses.join(" "),
+    }
+}
+
+fn summary(board: &Board, state: &SearchState<Board>) -> String {
+    format!(
+        "score {}, depth {}, {} lit: {}",
+        state.score,
+        state.history.len() + state.latest_move_index.iter().count(),
+        state.latest.lit_count(),
+        path(board, state)
+    )
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let board = match board(args) {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let Some(max_depth) = board.depth_bound() else {
+        println!("The board is unsolvable, so there's nothing to search");
+        return Outcome::Unsolvable;
+    };
+    let quiet = !args.shows(Verbosity::Normal);
+    let mut stepper = Stepper::new(board.clone(), max_depth, BucketFrontier::default());
+    let mut last: Option<SearchState<Board>> = None;
+    if !quiet {
+        print!("{}", args.theme.draw(&board, None));
+        println!("Searching up to {max_depth} presses, starting at score {}", board.score());
+        println!("{HELP}");
+    }
+
+    let mut lines = stdin().lock().lines();
+    loop {
+        if !quiet {
+            print!("debug> ");
+            stdout().flush().ok();
+        }
+        let Some(Ok(line)) = lines.next() else {
+            return Outcome::Unsolvable;
+        };
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("step");
+        let number = words.next().map(str::parse::<usize>);
+        let steps = match (command, number) {
+            (_, Some(Err(_))) => {
+                println!("expected a number after {command}");
+                continue;
+            }
+            ("step" | "s", number) => number.map_or(1, Result::unwrap),
+            ("run" | "r", None) => usize::MAX,
+            ("frontier" | "f", number) => {
+                let count = number.map_or(5, Result::unwrap);
+                let frontier = stepper.frontier();
+                println!("{} states waiting", frontier.len());
+                for (i, state) in frontier.top(count).into_iter().enumerate() {
+                    println!("{i:>4}  {}", summary(&board, state));
+                }
+                continue;
+            }
+            ("show" | "v", Some(Ok(index))) => {
+                match stepper.frontier().top(index + 1).get(index) {
+                    Some(state) => print!("{}", args.theme.draw(&state.latest, None)),
+                    None => println!("the frontier has {} states", stepper.frontier().len()),
+                }
+                continue;
+            }
+            ("show" | "v", None) => {
+                let shown = last.as_ref().map_or(&board, |state| &state.latest);
+                print!("{}", args.theme.draw(shown, None));
+                continue;
+            }
+            ("scores" | "h", None) => {
+                let Some(state) = &last else {
+                    println!("nothing has been expanded yet");
+                    continue;
+                };
+                for (child, index, score) in state.latest.scored_moves(&state.score) {
+                    let press = cell_name(index % board.width(), index / board.width());
+                    println!("{press:>4}  score {score}, {} lit", child.lit_count());
+                }
+                continue;
+            }
+            ("explored" | "e", None) => {
+                let report = stepper.report();
+                println!(
+                    "{} explored, {} waiting (peak {}), {} bytes at peak, {} cut off at depth {}",
+                    report.explored,
+                    stepper.frontier().len(),
+                    report.peak_frontier,
+                    report.peak_memory,
+                    report.depth_cutoffs,
+                    stepper.max_depth(),
+                );
+                continue;
+            }
+            ("quit" | "q", None) => return Outcome::Unsolvable,
+            ("help" | "?", None) => {
+                println!("{HELP}");
+                continue;
+            }
+            _ => {
+                println!("Unrecognized input {line:?}. Try help");
+                continue;
+            }
+        };
+
+        for _ in 0..steps {
+            let verbose = steps == 1 || args.shows(Verbosity::Verbose);
+            match stepper.step() {
+                Step::Expanded { state, pushed } => {
+                    if verbose {
+                        println!(
+                            "#{} expanded {}, pushed {pushed}",
+                            stepper.report().explored,
+                            summary(&board, &state)
+                        );
+                    }
+                    last = Some(state);
+                }
+                Step::CutOff(state) => {
+                    if verbose {
+                        println!("cut off {}", summary(&board, &state));
+                    }
+                }
+                Step::Solved(state) => {
+                    println!(
+                        "Solved after {} expansions: {}",
+                        stepper.report().explored,
+                        path(&board, &state)
+                    );
+                    return Outcome::Solved;
+                }
+                Step::Exhausted => {
+                    println!("The frontier is empty: no solution within {max_depth} presses");
+                    return Outcome::Unsolvable;
+                }
+            }
+        }
+        if steps > 1 && !quiet {
+            if let Some(state) = &last {
+                println!("last expanded {}", summary(&board, state));
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use lightsout::{json::Json, pack::Pack};
+
+use super::{Args, Outcome, Output, Verbosity};
+
+const USAGE: &str = "usage: lightsout dedupe <pack.json> [deduped.json]";
+
+/// Drop the puzzles in a pack file that repeat an earlier one up to
+/// symmetry, writing the rest back to the file, or to a second path if
+/// given, and listing what went.
+pub fn run(args: &Args) -> Outcome {
+    let Some(path) = args.positional.first() else {
+        eprintln!("error: {USAGE}");
+        return Outcome::InputError;
+    };
+    let mut pack = match Pack::load(path) {
+        Ok(pack) => pack,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let removed = pack.dedupe();
+    let output = args.positional.get(1).unwrap_or(path);
+    if let Err(err) = pack.save(output) {
+        eprintln!("error: {err}");
+        return Outcome::InputError;
+    }
+    if !args.shows(Verbosity::Normal) {
+        return Outcome::Solved;
+    }
+    let kept_board = |name: &str| pack.get(name).map(|puzzle| &puzzle.board);
+    if args.output == Output::Json {
+        let entries = removed.iter().map(|(puzzle, original)| {
+            Json::object([
+                ("removed", puzzle.name.clone().into()),
+                ("kept", original.clone().into()),
+                ("identical", (kept_board(original) == Some(&puzzle.board)).into()),
+            ])
+        });
+        let json = Json::object([
+            ("kept", pack.puzzles.len().into()),
+            ("removed", Json::Array(entries.collect())),
+        ]);
+        println!("{json}");
+        return Outcome::Solved;
+    }
+    for (puzzle, original) in &removed {
+        let how = if kept_board(original) == Some(&puzzle.board) {
+            "identical to"
+        } else {
+            "the same up to symmetry as"
+        };
+        println!("removed {}: {how} {original}", puzzle.name);
+    }
+    println!(
+        "Kept {} of {} puzzles",
+        pack.puzzles.len(),
+        pack.puzzles.len() + removed.len()
+    );
+    Outcome::Solved
+}
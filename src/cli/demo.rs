@@ -0,0 +1,105 @@
+//! `lightsout demo`: generate boards one after another and animate the
+//! solver clearing each, until `--rounds` have gone by or forever. Every
+//! solution is replayed and checked, so with `-q`, which skips drawing and
+//! waiting, it doubles as a soak test of the generator and solver.
+
+use std::{thread, time::Duration};
+
+use lightsout::{
+    generate::{generate_boards, Target},
+    BitMask,
+};
+use rand::prelude::*;
+
+use super::{render, solve::solve_board, Args, Outcome, Render, Verbosity};
+
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+fn pause(args: &Args, beats: f64) {
+    if args.shows(Verbosity::Normal) {
+        thread::sleep(Duration::from_secs_f64(args.delay * beats));
+    }
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let shape = match args.generation_shape() {
+        Ok(shape) => shape,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let target = Target {
+        optimal: args.target_optimal,
+        unique: args.unique,
+        shape,
+    };
+    let animate = args.shows(Verbosity::Normal);
+    let (mut seed, mut shown, mut failed) = (args.seed.unwrap_or_else(random), 0, 0);
+    while args.rounds.is_none_or(|rounds| shown < rounds) {
+        // One board at a time, so the generator never runs ahead of what's
+        // been shown.
+        let generated =
+            generate_boards(args.width, args.height, seed, 1, 1, target.clone()).next();
+        let Some(generated) = generated else {
+            eprintln!("error: no board matches the target near seed {seed}");
+            return Outcome::LimitReached;
+        };
+        seed = generated.seed.wrapping_add(1);
+        shown += 1;
+
+        let mut board = generated.board;
+        let title = format!(
+            "Board {shown}, seed {} ({} presses at best)",
+            generated.seed, generated.optimal
+        );
+        if animate && args.render == Render::Plain {
+            println!("{title}");
+            print!("{}", render::board(args, &board));
+        } else if animate {
+            println!("{CLEAR}{title}\n");
+            print!("{}", args.theme.draw(&board, None));
+            println!("\nSolving...");
+        }
+        let solved = solve_board(args, board.clone(), Some(generated.seed));
+        let Some(presses) = solved.presses else {
+            failed += 1;
+            eprintln!("error: seed {}: the solver found no solution", generated.seed);
+            continue;
+        };
+        for (i, &(x, y)) in presses.iter().enumerate() {
+            if animate && args.render == Render::Plain {
+                println!("Move {}. {}", i + 1, render::describe_press(&board, x, y));
+            } else if animate {
+                let mut pressed = BitMask::new(board.width(), board.height());
+                pressed.set(x, y, true);
+                println!("{CLEAR}{title}\n");
+                print!("{}", args.theme.draw(&board, Some(&pressed)));
+                println!("\nPress {} of {}", i + 1, presses.len());
+                pause(args, 1.0);
+            }
+            board.toggle(x, y);
+        }
+        if !board.is_solved() {
+            failed += 1;
+            eprintln!("error: seed {}: the solution doesn't clear the board", generated.seed);
+            continue;
+        }
+        if animate && args.render == Render::Blocks {
+            println!("{CLEAR}{title}\n");
+            print!("{}", args.theme.draw(&board, None));
+            println!("\nSolved by {} in {} presses", solved.algorithm, presses.len());
+        } else if animate {
+            println!("Solved by {} in {} presses\n", solved.algorithm, presses.len());
+        }
+        pause(args, 4.0);
+    }
+    if args.shows(Verbosity::Normal) {
+        println!("Showed {shown} boards, {failed} not cleared");
+    }
+    if failed > 0 {
+        Outcome::Unsolvable
+    } else {
+        Outcome::Solved
+    }
+}
@@ -0,0 +1,106 @@
+use lightsout::{cell_name, json::Json, BitMask, Board};
+
+use super::{input::read_boards, Args, Output, Outcome, Verbosity};
+
+const USAGE: &str = "usage: lightsout diff a.txt b.txt";
+
+/// The one board in a file.
+fn read_one(path: &str) -> Result<Board, String> {
+    let mut boards = read_boards(path)?;
+    match boards.len() {
+        1 => Ok(boards.remove(0).0),
+        count => Err(format!("{path}: expected one board, found {count}")),
+    }
+}
+
+fn cell_list(presses: &BitMask) -> String {
+    let names: Vec<String> = presses
+        .positions()
+        .into_iter()
+        .map(|(x, y)| cell_name(x, y))
+        .collect();
+    names.join(" ")
+}
+
+fn positions_json(mask: &BitMask) -> Json {
+    Json::Array(
+        mask.positions()
+            .into_iter()
+            .map(|(x, y)| Json::Array(vec![x.into(), y.into()]))
+            .collect(),
+    )
+}
+
+/// Show two boards side by side with the cells that differ highlighted, and
+/// the fewest presses that turn the first into the second, if any do.
+pub fn run(args: &Args) -> Outcome {
+    let [first, second] = args.positional.as_slice() else {
+        eprintln!("error: {USAGE}");
+        return Outcome::InputError;
+    };
+    let boards = read_one(first).and_then(|a| Ok((a, read_one(second)?)));
+    let (a, b) = match boards {
+        Ok((a, b)) if (a.width(), a.height()) != (b.width(), b.height()) => {
+            eprintln!(
+                "error: boards are different sizes, {}x{} and {}x{}",
+                a.width(),
+                a.height(),
+                b.width(),
+                b.height()
+            );
+            return Outcome::InputError;
+        }
+        Ok((a, b)) if a.ruleset() != b.ruleset() => {
+            eprintln!("error: boards have different rules");
+            return Outcome::InputError;
+        }
+        Ok(boards) => boards,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+
+    let differing = a.diff(&b);
+    // Pressing a set on `a` gives `b` exactly when it clears their
+    // difference, whatever goal the rules set.
+    let residual = a.xor(&b).with_ruleset(a.ruleset().without_goal());
+    let presses = residual.solve_linear();
+    let outcome = if presses.is_some() {
+        Outcome::Solved
+    } else {
+        Outcome::Unsolvable
+    };
+    if !args.shows(Verbosity::Normal) {
+        return outcome;
+    }
+    if args.output == Output::Json {
+        let json = Json::object([
+            ("differing", positions_json(&differing)),
+            ("presses", presses.as_ref().map(positions_json).into()),
+        ]);
+        println!("{json}");
+        return outcome;
+    }
+
+    let gap = " ".repeat(4);
+    for y in 0..a.height() {
+        println!("{}{gap}{}", args.theme.row(&a, y, Some(&differing)),
+            args.theme.row(&b, y, Some(&differing)));
+    }
+    match differing.count() {
+        0 => println!("The boards are the same"),
+        1 => println!("1 cell differs: {}", cell_list(&differing)),
+        count => println!("{count} cells differ: {}", cell_list(&differing)),
+    }
+    match presses {
+        None => println!("No presses turn {first} into {second}"),
+        Some(presses) if presses.count() == 0 => (),
+        Some(presses) => println!(
+            "Press {} to turn {first} into {second} ({} moves)",
+            cell_list(&presses),
+            presses.count()
+        ),
+    }
+    outcome
+}
@@ -0,0 +1,111 @@
+//! `--explain`: the linear solver's working, written out for teaching
+//! Lights Out as linear algebra over GF(2).
+
+use lightsout::{cell_name, core::LinearExplanation, Board, Rowtype};
+
+fn bits(row: Rowtype, width: usize) -> String {
+    (0..width)
+        .map(|x| if row & (1 << x) != 0 { '1' } else { '0' })
+        .collect()
+}
+
+fn cells(row: Rowtype, y: usize, width: usize) -> String {
+    let names: Vec<String> = (0..width)
+        .filter(|x| row & (1 << x) != 0)
+        .map(|x| cell_name(x, y))
+        .collect();
+    if names.is_empty() {
+        "nothing".to_string()
+    } else {
+        names.join(" ")
+    }
+}
+
+pub fn explain(board: &Board) -> String {
+    if !board.ruleset().presses_classically() {
+        let mut out = format!(
+            "Light-chasing only works with the classic presses, not {}; the board was\n\
+             solved by eliminating one equation per cell instead.\n",
+            board.ruleset()
+        );
+        if let Some(invariant) = board.invariant() {
+            out += &format!("It can't be solved: {invariant}.\n");
+        }
+        return out;
+    }
+    let LinearExplanation {
+        residual,
+        columns,
+        particular,
+        kernel,
+        candidates,
+        chosen,
+    } = board.explain_linear();
+    let (width, height) = (board.width(), board.height());
+    let mut out = String::new();
+
+    out += "Step 1: chase the lights down.\n";
+    out += "  Pressing the cell below each lit cell clears rows 1 to ";
+    out += &format!("{}, one row at a time.\n", height.saturating_sub(1));
+    out += &format!(
+        "  With nothing pressed on row 1, row {height} is left as {} (1 = lit).\n\n",
+        bits(residual, width)
+    );
+
+    out += "Step 2: model the top row as unknowns.\n";
+    out += "  Each top-row press changes what is left on the bottom row after chasing.\n";
+    out += "  Working mod 2, the bottom row is the residual plus the sum of these columns:\n";
+    for (x, column) in columns.iter().enumerate() {
+        out += &format!("    press {:<4} toggles {}\n", cell_name(x, 0), bits(*column, width));
+    }
+    out += &format!(
+        "  so we need top-row presses p with M p = {} over GF(2).\n\n",
+        bits(residual, width)
+    );
+
+    out += "Step 3: solve by Gaussian elimination.\n";
+    let Some(particular) = particular else {
+        out += "  The elimination leaves a row 0 = 1: the residual isn't in the column space\n";
+        out += "  of M, so no set of presses clears this board.\n";
+        if let Some(invariant) = board.invariant() {
+            out += &format!("  Put another way, {invariant}.\n");
+        }
+        return out;
+    };
+    out += &format!("  One solution is p = {}.\n\n", bits(particular, width));
+
+    out += "Step 4: account for the kernel.\n";
+    if kernel.is_empty() {
+        out += "  M has full rank, so that solution is the only one and is minimal.\n\n";
+    } else {
+        out += &format!(
+            "  M has a {}-dimensional nullspace: these top rows chase to nothing,\n",
+            kernel.len()
+        );
+        out += "  so adding any combination of them gives another solution.\n";
+        for vector in &kernel {
+            out += &format!("    {}\n", bits(*vector, width));
+        }
+        out += &format!(
+            "  All {} solutions lie in this coset; their total press counts are:\n",
+            candidates.len()
+        );
+        for (first, count) in &candidates {
+            let marker = if Some(*first) == chosen { "  <- fewest" } else { "" };
+            out += &format!("    p = {}: {count} presses{marker}\n", bits(*first, width));
+        }
+        out += "  Every solution is in this list, so the smallest is minimal.\n\n";
+    }
+
+    let chosen = chosen.unwrap_or(particular);
+    out += "Step 5: press the chosen top row and chase.\n";
+    let (presses, _) = board.chase(chosen);
+    for (y, row) in presses.iter().enumerate() {
+        out += &format!("  row {:<3} press {}\n", y + 1, cells(*row, y, width));
+    }
+    out += &format!(
+        "  {} presses in total.\n",
+        presses.iter().map(|row| row.count_ones()).sum::<u32>()
+    );
+    out
+}
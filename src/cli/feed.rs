@@ -0,0 +1,57 @@
+use std::{fs, path::Path};
+
+use lightsout::feed::{svg, Date, Feed};
+
+use super::{Args, Outcome, Verbosity};
+
+const TITLE: &str = "Lights Out puzzle of the day";
+
+/// Write the files a static site needs for the feed into `dir`: `rss.xml`,
+/// `atom.xml`, `feed.json` and a solution image per day under `solutions/`.
+fn write_feed(dir: &Path, feed: &Feed) -> Result<(), String> {
+    let write = |name: &str, contents: String| {
+        let path = dir.join(name);
+        fs::write(&path, contents).map_err(|err| format!("{}: {err}", path.display()))
+    };
+    let solutions = dir.join("solutions");
+    fs::create_dir_all(&solutions).map_err(|err| format!("{}: {err}", solutions.display()))?;
+    for entry in &feed.entries {
+        write(&Feed::solution_path(entry), svg(&entry.generated.board, Some(&entry.solution)))?;
+    }
+    write("rss.xml", feed.rss())?;
+    write("atom.xml", feed.atom())?;
+    write("feed.json", feed.json().to_string())
+}
+
+/// Build the feed of the last `--count` days' puzzles, up to `--date` or
+/// today, linking to `--site`, in the directory given or the current one.
+pub fn run(args: &Args) -> Outcome {
+    let Some(site) = &args.site else {
+        eprintln!("error: feed needs --site, the URL it will be published under");
+        return Outcome::InputError;
+    };
+    let last = match &args.date {
+        Some(date) => match Date::parse(date) {
+            Some(date) => date,
+            None => {
+                eprintln!("error: invalid date {date:?} (expected YYYY-MM-DD)");
+                return Outcome::InputError;
+            }
+        },
+        None => Date::today(),
+    };
+    let dir = Path::new(args.positional.first().map_or(".", String::as_str));
+    let feed = Feed::new(TITLE, site, last, args.count, args.width, args.height);
+    match write_feed(dir, &feed) {
+        Ok(()) => {
+            if args.shows(Verbosity::Normal) {
+                println!("Wrote {} puzzles to {}", feed.entries.len(), dir.display());
+            }
+            Outcome::Solved
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            Outcome::InputError
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use lightsout::{cell_name, generate::nearest_solvable, json::Json, png};
+
+use super::{Args, Outcome, Output, Verbosity};
+
+const USAGE: &str = "usage: lightsout from-image <image.png> [--width w --height h]";
+
+/// Threshold a PNG into a board of the configured size and print the
+/// nearest solvable board to it, with the cells that had to change.
+pub fn run(args: &Args) -> Outcome {
+    let Some(path) = args.positional.first() else {
+        eprintln!("error: {USAGE}");
+        return Outcome::InputError;
+    };
+    let image = std::fs::read(path)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| png::decode(&bytes));
+    let image = match image {
+        Ok(image) => image,
+        Err(err) => {
+            eprintln!("error: {path}: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let lights = image.threshold(args.width, args.height);
+    let board = nearest_solvable(&lights);
+    if !args.shows(Verbosity::Normal) {
+        return Outcome::Solved;
+    }
+    let flipped: Vec<String> = board
+        .cells()
+        .filter(|&((x, y), lit)| lit != lights.get(x, y))
+        .map(|((x, y), _)| cell_name(x, y))
+        .collect();
+    let presses = board.optimal_length();
+    if args.output == Output::Json {
+        let json = Json::object([
+            ("code", board.to_code().into()),
+            ("flipped", flipped.into()),
+            ("presses", presses.into()),
+        ]);
+        println!("{json}");
+        return Outcome::Solved;
+    }
+    println!("{board}");
+    match flipped.len() {
+        0 => println!("The image was solvable as it was"),
+        1 => println!("Flipped 1 cell to make it solvable: {}", flipped[0]),
+        count => println!("Flipped {count} cells to make it solvable: {}", flipped.join(" ")),
+    }
+    if let Some(presses) = presses {
+        println!("Solvable in {presses} presses");
+    }
+    Outcome::Solved
+}
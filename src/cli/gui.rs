@@ -0,0 +1,190 @@
+//! `lightsout gui`: the game in a window, for players who'd rather click
+//! than type cell names. The window is a page in the system's browser,
+//! served from localhost over the same HTTP code as `serve`, so there's no
+//! toolkit to link. The page only draws; every press, hint and solution
+//! comes from the library through the JSON endpoints below.
+
+use std::{
+    io::BufReader,
+    net::{TcpListener, TcpStream},
+    process::{Command, Stdio},
+    thread,
+};
+
+use lightsout::{
+    json::{self, Json},
+    BitMask, Board, BoardBuilder, Ruleset,
+};
+use rand::prelude::*;
+
+use super::{
+    serve::{read_request, view, Request, Response, READ_TIMEOUT},
+    wire::Format,
+    Args, Outcome, Verbosity,
+};
+
+const PAGE: &str = include_str!("gui.html");
+
+/// Variants the page offers, as [`Ruleset::parse`] reads them.
+const VARIANTS: &[&str] = &["classic", "wrap", "diagonal", "square", "wrap+square"];
+
+fn cell(position: (usize, usize)) -> Json {
+    Json::Array(vec![position.0.into(), position.1.into()])
+}
+
+fn board_of(request: &Json) -> Result<Board, String> {
+    let code = request.get("code").and_then(Json::as_str).ok_or("missing code")?;
+    Board::from_code(code).map_err(|err| err.to_string())
+}
+
+/// A random board clearable by construction: random presses on a dark one.
+fn new_board(args: &Args, request: &Json) -> Result<Board, String> {
+    let size = |key, default| {
+        request
+            .get(key)
+            .and_then(Json::as_u64)
+            .map_or(default, |n| usize::try_from(n).unwrap_or(usize::MAX))
+    };
+    let (width, height) = (size("width", args.width), size("height", args.height));
+    // Either side alone is bounded too, since a zero-width board has no
+    // cells however tall it is and the variant's masks are as tall.
+    let fits = width.checked_mul(height).is_some_and(|cells| cells <= args.max_cells);
+    if !fits || width.max(height) > args.max_cells {
+        return Err(format!("boards are limited to {} cells", args.max_cells));
+    }
+    let variant = request.get("variant").and_then(Json::as_str).unwrap_or("classic");
+    let ruleset = Ruleset::parse(variant, width, height).map_err(|err| err.to_string())?;
+    let mut board = BoardBuilder::new()
+        .width(width)
+        .height(height)
+        .ruleset(ruleset)
+        .build()
+        .map_err(|err| err.to_string())?;
+    let mut rng = StdRng::seed_from_u64(random());
+    let mut presses = BitMask::new(width, height);
+    for (x, y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+        presses.set(x, y, rng.gen_bool(0.5) && !board.ruleset().is_obstacle(x, y));
+    }
+    board.apply_press_set(&presses);
+    Ok(board)
+}
+
+fn api(args: &Args, path: &str, request: &Json) -> Result<Json, String> {
+    match path {
+        "/api/new" => Ok(view(&new_board(args, request)?)),
+        "/api/press" => {
+            let mut board = board_of(request)?;
+            let coordinate = |key| request.get(key).and_then(Json::as_u64).map(|n| n as usize);
+            let (Some(x), Some(y)) = (coordinate("x"), coordinate("y")) else {
+                return Err("missing x or y".to_string());
+            };
+            if x >= board.width() || y >= board.height() || board.ruleset().is_obstacle(x, y) {
+                return Err(format!("({x}, {y}) can't be pressed"));
+            }
+            board.toggle(x, y);
+            Ok(view(&board))
+        }
+        "/api/hint" => {
+            let board = board_of(request)?;
+            Ok(match board.solve_linear().map(|presses| presses.positions()) {
+                Some(presses) => Json::object([
+                    ("press", presses.first().copied().map(cell).into()),
+                    ("left", presses.len().into()),
+                ]),
+                None => Json::object([("press", Json::Null), ("left", Json::Null)]),
+            })
+        }
+        "/api/solve" => {
+            let mut board = board_of(request)?;
+            let Some(presses) = board.solve_linear() else {
+                return Ok(Json::object([("presses", Json::Null)]));
+            };
+            let mut steps = Vec::new();
+            for (x, y) in presses.positions() {
+                board.toggle(x, y);
+                steps.push(Json::object([("press", cell((x, y))), ("board", view(&board))]));
+            }
+            Ok(Json::object([("steps", Json::Array(steps))]))
+        }
+        _ => Err(format!("no route for {path}")),
+    }
+}
+
+fn respond(args: &Args, request: &Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") => Response::html(PAGE),
+        ("GET", "/api/variants") => Response::new(
+            200,
+            Format::Json,
+            &Json::Array(VARIANTS.iter().map(|&variant| variant.into()).collect()),
+        ),
+        ("POST", path) if path.starts_with("/api/") => {
+            let body = std::str::from_utf8(&request.body)
+                .map_err(|_| "body is not UTF-8".to_string())
+                .and_then(json::parse)
+                .and_then(|body| api(args, path, &body));
+            match body {
+                Ok(body) => Response::new(200, Format::Json, &body),
+                Err(err) => Response::error(400, err),
+            }
+        }
+        (_, path) => Response::error(404, format!("no route for {path}")),
+    }
+}
+
+fn handle(args: &Args, mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let response = match read_request(&mut BufReader::new(reader)) {
+        Ok(request) => respond(args, &request),
+        Err(response) => response,
+    };
+    let _ = response.write(&mut stream);
+}
+
+/// Open `url` in the system's browser, if there's a way to.
+fn open(url: &str) -> bool {
+    let command = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).status()
+    } else if cfg!(windows) {
+        Command::new("cmd").args(["/c", "start", "", url]).status()
+    } else {
+        Command::new("xdg-open")
+            .arg(url)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+    };
+    command.is_ok_and(|status| status.success())
+}
+
+/// Serve the page on the address given, or any free port on localhost, and
+/// open it. Runs until interrupted.
+pub fn run(args: &Args) -> Outcome {
+    let address = args.positional.first().map_or("127.0.0.1:0", String::as_str);
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("error: {address}: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let url = match listener.local_addr() {
+        Ok(local) => format!("http://{local}/"),
+        Err(_) => format!("http://{address}/"),
+    };
+    let opened = open(&url);
+    if args.shows(Verbosity::Normal) {
+        match opened {
+            true => eprintln!("playing at {url}, press Ctrl-C to stop"),
+            false => eprintln!("open {url} in a browser to play, press Ctrl-C to stop"),
+        }
+    }
+    for stream in listener.incoming().flatten() {
+        let args = args.clone();
+        thread::spawn(move || handle(&args, stream));
+    }
+    Outcome::Solved
+}
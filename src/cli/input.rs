@@ -0,0 +1,110 @@
+use std::{fs, io::Read};
+
+use lightsout::{json, Board, PressConstraints};
+
+/// A board and the press constraints given alongside it, if any.
+type Entry = (Board, Option<PressConstraints>);
+
+pub fn parse_board(text: &str) -> Result<Board, String> {
+    let text = text.trim();
+    if !text.contains('\n') {
+        if let Ok(board) = Board::from_code(text) {
+            return Ok(board);
+        }
+    }
+    text.parse().map_err(|err| format!("{err}"))
+}
+
+fn parse_constraints(board: &Board, text: &str) -> Result<PressConstraints, String> {
+    PressConstraints::parse(text, board.width(), board.height()).map_err(|err| format!("{err}"))
+}
+
+/// A text chunk: a board, followed by an optional `constraints:` line.
+fn parse_chunk(chunk: &str) -> Result<Entry, String> {
+    let (grid, constraints): (Vec<&str>, Vec<&str>) = chunk
+        .lines()
+        .partition(|line| !line.trim_start().starts_with("constraints:"));
+    let board = parse_board(&grid.join("\n"))?;
+    let constraints = match constraints.as_slice() {
+        [] => None,
+        [line] => {
+            let text = line.trim_start().trim_start_matches("constraints:");
+            Some(parse_constraints(&board, text)?)
+        }
+        _ => return Err("more than one constraints line".to_string()),
+    };
+    Ok((board, constraints))
+}
+
+/// A JSON item: a grid string, a puzzle code, an array of row strings, or
+/// an object with one of those as its `board` and a `constraints` string.
+pub fn parse_item(item: &json::Json) -> Result<Entry, String> {
+    if let Some(board) = item.get("board") {
+        let (board, _) = parse_item(board)?;
+        let constraints = match item.get("constraints") {
+            None => None,
+            Some(text) => {
+                let text = text.as_str().ok_or("constraints must be a string")?;
+                Some(parse_constraints(&board, text)?)
+            }
+        };
+        return Ok((board, constraints));
+    }
+    let grid = match item {
+        json::Json::String(s) => s.clone(),
+        json::Json::Array(rows) => rows
+            .iter()
+            .map(|row| row.as_str().ok_or("rows must be strings"))
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n"),
+        _ => return Err("expected a string, an array of rows or an object".to_string()),
+    };
+    Ok((parse_board(&grid)?, None))
+}
+
+/// Boards from a file (or stdin for `-`): either text grids or puzzle codes
+/// separated by blank lines, or a JSON array whose items are grid strings,
+/// puzzle codes, or arrays of row strings. Text boards may be followed by a
+/// line like `constraints: a1 !c3` pinning presses the solution must make or
+/// avoid; JSON items do the same as `{"board": ..., "constraints": ...}`.
+pub fn read_boards(path: &str) -> Result<Vec<Entry>, String> {
+    let mut text = String::new();
+    if path == "-" {
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|err| format!("stdin: {err}"))?;
+    } else {
+        text = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    }
+
+    let boards = if text.trim_start().starts_with('[') {
+        let value = json::parse(&text).map_err(|err| format!("{path}: {err}"))?;
+        value
+            .as_array()
+            .ok_or(format!("{path}: expected an array of boards"))?
+            .iter()
+            .enumerate()
+            .map(|(i, item)| parse_item(item).map_err(|err| format!("board {}: {err}", i + 1)))
+            .collect::<Result<Vec<_>, _>>()
+    } else {
+        let mut chunks = vec![String::new()];
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                if !chunks.last().unwrap().is_empty() {
+                    chunks.push(String::new());
+                }
+            } else {
+                let chunk = chunks.last_mut().unwrap();
+                chunk.push_str(line);
+                chunk.push('\n');
+            }
+        }
+        chunks
+            .iter()
+            .filter(|chunk| !chunk.is_empty())
+            .enumerate()
+            .map(|(i, chunk)| parse_chunk(chunk).map_err(|err| format!("board {}: {err}", i + 1)))
+            .collect()
+    };
+    boards.map_err(|err| format!("{path}: {err}"))
+}
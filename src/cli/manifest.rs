@@ -0,0 +1,80 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+};
+
+use lightsout::json::{self, Json};
+
+/// A record of the work a long batch has finished, one JSON object per
+/// line, appended as each piece finishes so that a run interrupted part way
+/// picks up where it left off instead of starting again. The first line
+/// names the job it belongs to, so a manifest isn't resumed by a different
+/// one.
+pub struct Manifest {
+    path: String,
+    file: File,
+    entries: Vec<Json>,
+}
+
+impl Manifest {
+    /// Open the manifest at `path` for `job`, reading back what an earlier
+    /// run recorded, or start it if there's nothing there yet. A last line
+    /// cut off by the interruption is dropped.
+    pub fn open(path: &str, job: &str) -> Result<Manifest, String> {
+        let fail = |err: std::io::Error| format!("{path}: {err}");
+        let header = format!("# lightsout manifest for {job}");
+        let mut text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(fail(err)),
+        };
+        if !text.ends_with('\n') {
+            let complete = text.rfind('\n').map_or(0, |end| end + 1);
+            text.truncate(complete);
+            fs::write(path, &text).map_err(fail)?;
+        }
+        let mut lines = text.lines();
+        let entries = match lines.next() {
+            None => {
+                fs::write(path, format!("{header}\n")).map_err(fail)?;
+                Vec::new()
+            }
+            Some(first) if first == header => lines
+                .enumerate()
+                .map(|(number, line)| {
+                    json::parse(line).map_err(|err| format!("{path}: line {}: {err}", number + 2))
+                })
+                .collect::<Result<_, _>>()?,
+            Some(first) => {
+                let other = first.strip_prefix("# lightsout manifest for ");
+                return Err(match other {
+                    Some(other) => format!("{path} is the manifest for {other}, not {job}"),
+                    None => format!("{path} isn't a lightsout manifest"),
+                });
+            }
+        };
+        let file = OpenOptions::new().append(true).open(path).map_err(fail)?;
+        Ok(Manifest {
+            path: path.to_string(),
+            file,
+            entries,
+        })
+    }
+
+    /// What earlier runs finished, in the order they finished it.
+    pub fn entries(&self) -> &[Json] {
+        &self.entries
+    }
+
+    /// Record a finished piece of work, in one write so an interruption
+    /// can only cut off the line it's writing.
+    pub fn record(&mut self, entry: Json) -> Result<(), String> {
+        let line = format!("{entry}\n");
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|()| self.file.flush())
+            .map_err(|err| format!("{}: {err}", self.path))?;
+        self.entries.push(entry);
+        Ok(())
+    }
+}
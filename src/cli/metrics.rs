@@ -0,0 +1,158 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use lightsout::context::CacheStats;
+
+use super::solve::{context, outcome_name, Solved};
+
+/// Paths metrics are kept for; anything else counts as `other`, so clients
+/// can't grow the label set without bound.
+const PATHS: &[&str] = &["/solve", "/live", "/health", "/metrics"];
+
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0];
+const NODE_BUCKETS: &[f64] = &[10.0, 100.0, 1e3, 1e4, 1e5, 1e6];
+
+struct Histogram {
+    bounds: &'static [f64],
+    /// Observations at most each bound, not yet cumulative.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(bucket) = self.bounds.iter().position(|&bound| value <= bound) {
+            self.counts[bucket] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} histogram");
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum {}\n{name}_count {}", self.sum, self.count);
+    }
+}
+
+struct Counts {
+    requests: BTreeMap<(&'static str, u16), u64>,
+    rejected: u64,
+    solves: BTreeMap<(String, &'static str), u64>,
+    latency: Histogram,
+    nodes: Histogram,
+}
+
+/// What `serve` has done since it started, for `GET /metrics`.
+pub struct Metrics(Mutex<Counts>);
+
+impl Metrics {
+    /// A request to `path` answered with `status` after `elapsed`.
+    pub fn request(&self, path: &str, status: u16, elapsed: Duration) {
+        let path = PATHS.iter().find(|&&known| known == path).unwrap_or(&"other");
+        let mut counts = self.0.lock().unwrap();
+        *counts.requests.entry((path, status)).or_default() += 1;
+        counts.latency.observe(elapsed.as_secs_f64());
+    }
+
+    /// A connection turned away because the queue was full.
+    pub fn rejected(&self) {
+        self.0.lock().unwrap().rejected += 1;
+    }
+
+    pub fn solved(&self, solved: &Solved) {
+        let mut counts = self.0.lock().unwrap();
+        let key = (solved.algorithm.to_string(), outcome_name(solved.outcome));
+        *counts.solves.entry(key).or_default() += 1;
+        if let Some(report) = &solved.report {
+            counts.nodes.observe(report.explored as f64);
+        }
+    }
+
+    /// Everything in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let counts = self.0.lock().unwrap();
+        let mut out = String::new();
+        out += "# HELP lightsout_requests_total HTTP requests answered, by path and status.\n";
+        out += "# TYPE lightsout_requests_total counter\n";
+        for ((path, status), count) in &counts.requests {
+            let _ = writeln!(
+                out,
+                "lightsout_requests_total{{path=\"{path}\",status=\"{status}\"}} {count}"
+            );
+        }
+        out += "# HELP lightsout_rejected_total Connections turned away with a full queue.\n";
+        out += "# TYPE lightsout_rejected_total counter\n";
+        let _ = writeln!(out, "lightsout_rejected_total {}", counts.rejected);
+        out += "# HELP lightsout_solves_total Boards solved, by algorithm and outcome.\n";
+        out += "# TYPE lightsout_solves_total counter\n";
+        for ((algorithm, outcome), count) in &counts.solves {
+            let _ = writeln!(
+                out,
+                "lightsout_solves_total{{algorithm=\"{algorithm}\",outcome=\"{outcome}\"}} {count}"
+            );
+        }
+        counts.latency.render(
+            &mut out,
+            "lightsout_request_duration_seconds",
+            "Time to answer a request.",
+        );
+        counts.nodes.render(&mut out, "lightsout_search_nodes", "States explored per A* solve.");
+
+        let caches = [
+            ("state_table", context().state_table_stats()),
+            ("batch_solver", context().batch_solver_stats()),
+            ("linear_system", context().linear_system_stats()),
+        ];
+        let mut cache_counter = |name: &str, help: &str, value: fn(&CacheStats) -> u64| {
+            let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} counter");
+            for (cache, stats) in &caches {
+                let _ = writeln!(out, "{name}{{cache=\"{cache}\"}} {}", value(stats));
+            }
+        };
+        cache_counter(
+            "lightsout_cache_hits_total",
+            "Lookup table requests answered by a table already built.",
+            |stats| stats.hits,
+        );
+        cache_counter(
+            "lightsout_cache_misses_total",
+            "Lookup table requests that had to build the table.",
+            |stats| stats.misses,
+        );
+        out
+    }
+}
+
+/// The metrics shared by every worker.
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        Metrics(Mutex::new(Counts {
+            requests: BTreeMap::new(),
+            rejected: 0,
+            solves: BTreeMap::new(),
+            latency: Histogram::new(LATENCY_BUCKETS),
+            nodes: Histogram::new(NODE_BUCKETS),
+        }))
+    })
+}
@@ -0,0 +1,66 @@
+pub mod analyze;
+mod args;
+pub mod cache;
+#[cfg(feature = "image")]
+pub mod card;
+mod clipboard;
+pub mod completions;
+mod config;
+#[cfg(feature = "server")]
+pub mod coop;
+pub mod crosscheck;
+pub mod daemon;
+pub mod debug;
+pub mod demo;
+pub mod dedupe;
+pub mod diff;
+pub mod explain;
+pub mod feed;
+#[cfg(feature = "image")]
+pub mod from_image;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod mutate;
+mod input;
+mod manifest;
+#[cfg(feature = "server")]
+pub mod metrics;
+pub mod pack;
+pub mod play;
+pub mod print;
+pub mod profile;
+pub mod reduce;
+pub mod render;
+pub mod replay;
+pub mod rpc;
+pub mod selftest;
+#[cfg(feature = "server")]
+pub mod serve;
+pub mod solve;
+pub mod stream;
+pub mod tutorial;
+#[cfg(feature = "server")]
+pub mod websocket;
+pub mod wire;
+
+pub use args::{parse_args, usage, Algorithm, Args, Command, Output, Render, Verbosity};
+
+/// How a run ended, reported to the shell as the exit code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    Unsolvable,
+    LimitReached,
+    InputError,
+}
+
+impl Outcome {
+    pub fn code(self) -> i32 {
+        match self {
+            Outcome::Solved => 0,
+            Outcome::Unsolvable => 1,
+            Outcome::LimitReached => 2,
+            Outcome::InputError => 3,
+        }
+    }
+}
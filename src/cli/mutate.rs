@@ -0,0 +1,69 @@
+use lightsout::{
+    cell_name, core::LinearSystem, generate::SEEDS_PER_BOARD, json::Json, mutate::mutate,
+    pack::lookup, Board,
+};
+use rand::prelude::*;
+
+use super::{Args, Outcome, Output, Verbosity};
+
+/// The first solvable random board from seeds upwards of `seed`.
+fn random_solvable(args: &Args, seed: u64) -> Result<Board, String> {
+    for i in 0..SEEDS_PER_BOARD {
+        let board = args.random_board(seed.wrapping_add(i))?;
+        if LinearSystem::new(board.width(), board.height(), board.ruleset()).solvable(&board) {
+            return Ok(board);
+        }
+    }
+    Err(format!("no solvable board in {SEEDS_PER_BOARD} seeds"))
+}
+
+/// Print `--count` boards made from a pack/puzzle, or a random solvable
+/// board, by `--steps` of the `--mutations` that keep it solvable, each
+/// from the next seed up from `--seed`.
+pub fn run(args: &Args) -> Outcome {
+    let first_seed = args.seed.unwrap_or_else(random);
+    let board = match args.positional.first() {
+        Some(reference) => lookup(reference).map(|puzzle| puzzle.board),
+        None => random_solvable(args, first_seed),
+    };
+    let board = match board {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    if args.mutations.is_empty() {
+        eprintln!("error: mutate needs at least one of --mutations quiet, press or pair");
+        return Outcome::InputError;
+    }
+    for i in 0..args.count as u64 {
+        let seed = first_seed.wrapping_add(i);
+        let Some(mutant) = mutate(&board, &args.mutations, args.steps, seed) else {
+            eprintln!("error: the board is not solvable");
+            return Outcome::Unsolvable;
+        };
+        if mutant.mutations.is_empty() && args.steps > 0 {
+            eprintln!("error: none of the mutations change the board and keep it solvable");
+            return Outcome::InputError;
+        }
+        if !args.shows(Verbosity::Normal) {
+            continue;
+        }
+        let mutations = mutant.mutations.iter().map(ToString::to_string);
+        if args.output == Output::Json {
+            let presses = mutant.presses().positions().into_iter();
+            let json = Json::object([
+                ("seed", mutant.seed.into()),
+                ("code", mutant.board.to_code().into()),
+                ("mutations", mutations.collect::<Vec<_>>().into()),
+                ("presses", presses.map(|(x, y)| cell_name(x, y)).collect::<Vec<_>>().into()),
+            ]);
+            println!("{json}");
+        } else {
+            println!("Seed: {} ({})", mutant.seed, mutations.collect::<Vec<_>>().join(", "));
+            println!("{}", mutant.board);
+        }
+    }
+    Outcome::Solved
+}
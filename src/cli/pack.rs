@@ -0,0 +1,84 @@
+use lightsout::pack::{Pack, Puzzle};
+
+use super::{
+    play::play,
+    solve::{print_text, solve_board},
+    Args, Outcome, Verbosity,
+};
+
+const USAGE: &str = "usage: lightsout pack <list|solve|verify|play> <pack.json or built-in name> [puzzle]";
+
+fn find<'a>(pack: &'a Pack, name: Option<&String>) -> Result<&'a Puzzle, String> {
+    let name = name.ok_or(format!("puzzle name required\n{USAGE}"))?;
+    pack.get(name)
+        .ok_or(format!("no puzzle named {name} in pack {}", pack.name))
+}
+
+/// Check a puzzle is solvable and, if the pack records an optimal length,
+/// that it matches the linear solver's.
+fn verify(puzzle: &Puzzle) -> Result<(), String> {
+    let solution = puzzle.board.solve_linear().ok_or("unsolvable")?;
+    match puzzle.optimal {
+        Some(optimal) if optimal != solution.count() => Err(format!(
+            "recorded optimal length {optimal}, actual {}",
+            solution.count()
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let (Some(action), Some(path)) = (args.positional.first(), args.positional.get(1)) else {
+        eprintln!("error: {USAGE}");
+        return Outcome::InputError;
+    };
+    let pack = match Pack::open(path) {
+        Ok(pack) => pack,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let name = args.positional.get(2);
+    let result = match action.as_str() {
+        "list" => {
+            for puzzle in &pack.puzzles {
+                let optimal = puzzle.optimal.map_or(String::new(), |n| format!(" ({n} presses)"));
+                println!("{}/{}{optimal}", pack.name, puzzle.name);
+            }
+            Ok(Outcome::Solved)
+        }
+        "solve" => find(&pack, name).map(|puzzle| {
+            let solved = solve_board(args, puzzle.board.clone(), None);
+            print_text(args, &solved);
+            solved.outcome
+        }),
+        "play" => find(&pack, name).map(|puzzle| play(args, puzzle.board.clone())),
+        "verify" => {
+            let puzzles: Vec<&Puzzle> = match name {
+                Some(_) => find(&pack, name).map(|puzzle| vec![puzzle]),
+                None => Ok(pack.puzzles.iter().collect()),
+            }
+            .unwrap_or_default();
+            let mut outcome = Outcome::Solved;
+            for puzzle in puzzles {
+                let result = verify(puzzle);
+                if args.shows(Verbosity::Normal) {
+                    match &result {
+                        Ok(()) => println!("ok    {}", puzzle.name),
+                        Err(err) => println!("FAIL  {}: {err}", puzzle.name),
+                    }
+                }
+                if result.is_err() {
+                    outcome = Outcome::Unsolvable;
+                }
+            }
+            Ok(outcome)
+        }
+        _ => Err(format!("unknown pack action {action}\n{USAGE}")),
+    };
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        Outcome::InputError
+    })
+}
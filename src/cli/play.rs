@@ -0,0 +1,410 @@
+use std::io::{stdin, stdout, BufRead, Write};
+
+#[cfg(feature = "sound")]
+use lightsout::sound::{Cue, Speaker};
+use lightsout::{
+    cell_name,
+    game::{Engine, Evaluation, Game, Player},
+    parse_cell,
+    search::OpeningBook,
+    theme::Theme,
+    BitMask, Board,
+};
+
+use super::{
+    clipboard,
+    input::parse_board,
+    render,
+    replay::{Move, Recorder},
+    Args, Outcome, Render, Verbosity,
+};
+
+/// The board with column letters across the top and row numbers down the
+/// side, matching the cell names players type.
+pub fn render_with_labels(board: &Board, theme: &Theme) -> String {
+    label_rows(board, theme.draw(board, None).lines())
+}
+
+/// Rows of `board` already drawn, labelled as in [`render_with_labels`].
+pub fn label_rows<S: AsRef<str>>(board: &Board, rows: impl IntoIterator<Item = S>) -> String {
+    let mut out = String::from("    ");
+    for x in 0..board.width() {
+        let name = cell_name(x, 0);
+        out += &format!("{:<2}", &name[..name.len() - 1]);
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    for (y, line) in rows.into_iter().enumerate() {
+        out += &format!("{:>3} {}\n", y + 1, line.as_ref());
+    }
+    out
+}
+
+const HELP: &str = "\
+Enter a cell like b3 to press it, or: hint, undo, reset, look, quit.
+copy puts the board's code on the clipboard, copy grid the board itself,
+and paste plays the board on the clipboard instead.";
+
+const FOG_HELP: &str = "\
+Remember the board: from your first press on, you only see cells near your
+last press. Enter a cell like b3 to press it, or: undo, reset, look, quit.
+Quitting reveals the board, and paste plays the board on the clipboard
+instead.";
+
+/// `board` as a grid of `#` for lit cells and `.` for unlit ones, which
+/// pastes anywhere and parses back. Rules other than the classic ones only
+/// survive in the board's code.
+fn grid(board: &Board) -> String {
+    board
+        .rows()
+        .map(|row| row.map(|lit| if lit { '#' } else { '.' }).collect::<String>() + "\n")
+        .collect()
+}
+
+/// Cells within `radius` of `cursor` either way, which `--fog` shows.
+fn visible(board: &Board, cursor: (usize, usize), radius: usize) -> BitMask {
+    let mut visible = BitMask::new(board.width(), board.height());
+    for y in cursor.1.saturating_sub(radius)..(cursor.1 + radius + 1).min(board.height()) {
+        for x in cursor.0.saturating_sub(radius)..(cursor.0 + radius + 1).min(board.width()) {
+            visible.set(x, y, true);
+        }
+    }
+    visible
+}
+
+/// The whole board at the end of a game in `--fog`, and how the presses
+/// measured up against the fewest that clear it. `blind` counts presses on
+/// cells the player couldn't see at the time.
+fn reveal(
+    args: &Args,
+    start: &Board,
+    board: &Board,
+    history: &[(usize, usize)],
+    blind: usize,
+) -> String {
+    let mut out = format!("\nThe fog lifts:\n{}", render::labelled(args, board));
+    let (Some(optimal), Some(guidance)) =
+        (start.optimal_length(), start.solve_from_history(history))
+    else {
+        out += "This board can't be cleared\n";
+        return out;
+    };
+    let left = guidance.remaining.count();
+    if board.is_solved() {
+        out += &format!(
+            "Cleared in {} presses; the fewest that clear it are {optimal}\n",
+            history.len()
+        );
+    } else {
+        out += &format!(
+            "Not cleared: {left} more presses would have done it; the fewest from the start are {optimal}\n"
+        );
+    }
+    if !guidance.wasted.is_empty() {
+        out += &format!(
+            "{} of your {} presses end up undone\n",
+            guidance.wasted.len(),
+            history.len()
+        );
+    }
+    out += &format!(
+        "{blind} of your {} presses were on cells you couldn't see\n",
+        history.len()
+    );
+    // Presses made, plus any still needed, against the fewest possible.
+    let spent = history.len() + left;
+    let efficiency = (optimal * 100).checked_div(spent).unwrap_or(100);
+    out += &format!("Efficiency: {efficiency}%\n");
+    out
+}
+
+/// Interactive play on stdin, recorded to `--record` if given. Returns
+/// `Solved` if the player clears the board, `Unsolvable` if they give up.
+///
+/// With `--render plain` the board is read out only at the start, after
+/// undo and reset, and on `look`; each press says what it changed instead.
+///
+/// With `--fog` the board is shown whole only until the first press, and
+/// after that only around the last one, so the player has to remember the
+/// rest. Hints are off, and clearing the board or quitting reveals it with
+/// a rating of the play.
+pub fn play(args: &Args, mut start: Board) -> Outcome {
+    if args.vs_ai {
+        if args.fog.is_some() {
+            eprintln!("error: --fog only works playing alone");
+            return Outcome::InputError;
+        }
+        return play_vs_ai(args, start);
+    }
+    let mut recorder = match Recorder::create(args.record.as_deref(), &start) {
+        Ok(recorder) => recorder,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let mut board = start.clone();
+    let mut history: Vec<(usize, usize)> = Vec::new();
+    let quiet = !args.shows(Verbosity::Normal);
+    #[cfg(feature = "sound")]
+    let speaker = (!args.mute && args.volume > 0.0).then(|| Speaker::new(args.volume));
+    let help = if args.fog.is_some() { FOG_HELP } else { HELP };
+    // Where the player last pressed, and presses made outside what they
+    // could see then, with `--fog`.
+    let mut cursor = None;
+    let mut blind = 0;
+    if !quiet {
+        println!("{help}");
+    }
+    let mut lines = stdin().lock().lines();
+    let mut redraw = true;
+    loop {
+        if !quiet && (redraw || args.render == Render::Blocks) {
+            let view = match (args.fog, cursor) {
+                (Some(radius), Some(cursor)) => {
+                    render::fogged(args, &board, &visible(&board, cursor, radius))
+                }
+                _ => render::labelled(args, &board),
+            };
+            print!("\n{view}");
+        }
+        redraw = false;
+        if board.is_solved() {
+            if !quiet && args.fog.is_some() {
+                print!("{}", reveal(args, &start, &board, &history, blind));
+            } else if !quiet {
+                println!("Solved in {} presses!", history.len());
+            }
+            #[cfg(feature = "sound")]
+            if let Some(speaker) = &speaker {
+                speaker.play(Cue::Win);
+            }
+            return Outcome::Solved;
+        }
+        if !quiet {
+            print!("> ");
+            stdout().flush().ok();
+        }
+        // Running out of input is quitting, revealing a fogged board too.
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => "quit".to_string(),
+        };
+        match line.trim() {
+            "" => (),
+            "quit" | "q" => {
+                if !quiet && args.fog.is_some() {
+                    print!("{}", reveal(args, &start, &board, &history, blind));
+                }
+                return Outcome::Unsolvable;
+            }
+            "help" | "?" => println!("{help}"),
+            "hint" | "h" if args.fog.is_some() => println!("No hints in the fog"),
+            "copy" | "copy code" | "copy grid" if args.fog.is_some() => {
+                println!("No copying in the fog")
+            }
+            "copy" | "copy code" | "copy grid" => {
+                let (text, what) = match line.trim() {
+                    "copy grid" => {
+                        let size = format!("the {}x{} grid", board.width(), board.height());
+                        (grid(&board), size)
+                    }
+                    _ => (board.to_code(), board.to_code()),
+                };
+                match clipboard::copy(&text) {
+                    Ok(how) => println!("Copied {what} to the clipboard via {how}"),
+                    Err(err) => println!("Couldn't copy the board: {err}"),
+                }
+            }
+            "paste" if recorder.is_recording() => {
+                println!("Can't switch boards while recording with --record")
+            }
+            "paste" => match clipboard::paste().and_then(|text| parse_board(&text)) {
+                Ok(pasted) => {
+                    start = pasted;
+                    board = start.clone();
+                    history.clear();
+                    (cursor, blind) = (None, 0);
+                    redraw = true;
+                }
+                Err(err) => println!("Couldn't paste a board: {err}"),
+            },
+            "look" | "l" => redraw = true,
+            "reset" => {
+                board = start.clone();
+                history.clear();
+                recorder.record(Move::Reset);
+                redraw = true;
+            }
+            "undo" | "u" => match history.pop() {
+                Some((x, y)) => {
+                    board.toggle(x, y);
+                    recorder.record(Move::Undo);
+                    redraw = true;
+                }
+                None => println!("Nothing to undo"),
+            },
+            "hint" | "h" => match start.solve_from_history(&history) {
+                Some(guidance) => {
+                    recorder.record(Move::Hint);
+                    let presses = guidance.remaining.positions();
+                    // Before the first press, suggest the opening book's
+                    // move when it's part of the best finish.
+                    let opening = OpeningBook::embedded(start.width(), start.height())
+                        .filter(|_| history.is_empty())
+                        .and_then(|book| book.first_press(&start))
+                        .filter(|cell| presses.contains(cell));
+                    let (x, y) = opening.as_ref().unwrap_or_else(|| {
+                        presses
+                            .iter()
+                            .find(|cell| !guidance.repressed.contains(cell))
+                            .unwrap_or(&presses[0])
+                    });
+                    println!(
+                        "Try {} ({} presses left at best)",
+                        cell_name(*x, *y),
+                        presses.len()
+                    );
+                    if !guidance.wasted.is_empty() {
+                        println!(
+                            "{} of your {} presses so far end up undone",
+                            guidance.wasted.len(),
+                            history.len()
+                        );
+                    }
+                }
+                None => println!("This board can't be solved from here"),
+            },
+            input => match parse_cell(input) {
+                Some((x, y)) if x < board.width() && y < board.height() => {
+                    if let Some(radius) = args.fog {
+                        if cursor.is_some_and(|cursor| !visible(&board, cursor, radius).get(x, y)) {
+                            blind += 1;
+                        }
+                        cursor = Some((x, y));
+                        redraw = true;
+                    } else if !quiet && args.render == Render::Plain {
+                        println!("{}", render::describe_press(&board, x, y));
+                    }
+                    board.toggle(x, y);
+                    history.push((x, y));
+                    #[cfg(feature = "sound")]
+                    if let Some(speaker) = &speaker {
+                        speaker.play(Cue::Press);
+                    }
+                    recorder.record(Move::Press(x, y));
+                }
+                Some(_) => println!("{input} is off the board"),
+                None => println!("Unrecognized input {input:?}. {help}"),
+            },
+        }
+    }
+}
+
+const VS_AI_HELP: &str = "\
+Take turns with the computer; whoever clears the board wins.
+Enter a cell like b3 to press it, or: look, quit";
+
+/// Presses ahead the computer looks with `--vs-ai`.
+const AI_DEPTH: usize = 4;
+
+/// Play against an [`Engine`], the player moving first. Returns `Solved` if
+/// the player clears the board, `Unsolvable` if the computer does or they
+/// give up.
+fn play_vs_ai(args: &Args, start: Board) -> Outcome {
+    if args.record.is_some() {
+        eprintln!("error: --record only records playing alone");
+        return Outcome::InputError;
+    }
+    let mut game = Game::new(start);
+    let mut engine = Engine::new(AI_DEPTH);
+    let quiet = !args.shows(Verbosity::Normal);
+    #[cfg(feature = "sound")]
+    let speaker = (!args.mute && args.volume > 0.0).then(|| Speaker::new(args.volume));
+    if !quiet {
+        println!("{VS_AI_HELP}");
+    }
+    let mut lines = stdin().lock().lines();
+    let mut redraw = true;
+    loop {
+        if !quiet && redraw {
+            print!("\n{}", render::labelled(args, game.board()));
+        }
+        redraw = false;
+        match game.winner() {
+            Some(Player::First) => {
+                if !quiet {
+                    println!("You cleared the board. You win!");
+                }
+                #[cfg(feature = "sound")]
+                if let Some(speaker) = &speaker {
+                    speaker.play(Cue::Win);
+                }
+                return Outcome::Solved;
+            }
+            Some(Player::Second) => {
+                if !quiet {
+                    println!("The computer cleared the board. You lose");
+                }
+                return Outcome::Unsolvable;
+            }
+            None if game.is_over() => {
+                println!("The board is already clear");
+                return Outcome::Solved;
+            }
+            None => (),
+        }
+        if !quiet {
+            print!("> ");
+            stdout().flush().ok();
+        }
+        let Some(Ok(line)) = lines.next() else {
+            return Outcome::Unsolvable;
+        };
+        let (x, y) = match line.trim() {
+            "" => continue,
+            "quit" | "q" => return Outcome::Unsolvable,
+            "help" | "?" => {
+                println!("{VS_AI_HELP}");
+                continue;
+            }
+            "look" | "l" => {
+                redraw = true;
+                continue;
+            }
+            input => match parse_cell(input) {
+                Some((x, y)) if game.moves().contains(&(x, y)) => (x, y),
+                Some(_) => {
+                    println!("{input} can't be pressed");
+                    continue;
+                }
+                None => {
+                    println!("Unrecognized input {input:?}. {VS_AI_HELP}");
+                    continue;
+                }
+            },
+        };
+        game.press(x, y);
+        #[cfg(feature = "sound")]
+        if let Some(speaker) = &speaker {
+            speaker.play(Cue::Press);
+        }
+        redraw = true;
+        if game.is_over() {
+            continue;
+        }
+        if !quiet {
+            print!("\n{}", render::labelled(args, game.board()));
+        }
+        let Some(((x, y), value)) = engine.best_move(&game) else {
+            continue;
+        };
+        game.press(x, y);
+        if !quiet {
+            println!("The computer presses {}", cell_name(x, y));
+            if let Evaluation::Win { plies: 2.. } = value {
+                println!("The computer can force a win from here");
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use lightsout::pack::Pack;
+
+use super::{Args, Outcome, Verbosity};
+
+/// Lay out `--pack` as a printable PDF at `--out`, solutions last with
+/// `--solutions`.
+pub fn run(args: &Args) -> Outcome {
+    let (Some(pack), Some(out)) = (&args.pack, &args.out) else {
+        eprintln!("error: usage: lightsout print --pack <pack.json> --out <sheet.pdf>");
+        return Outcome::InputError;
+    };
+    let pack = match Pack::open(pack) {
+        Ok(pack) => pack,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let sheet = pack.sheet(args.solutions);
+    if let Err(err) = std::fs::write(out, sheet.to_bytes()) {
+        eprintln!("error: {out}: {err}");
+        return Outcome::InputError;
+    }
+    if args.shows(Verbosity::Normal) {
+        println!(
+            "Wrote {} puzzles on {} pages to {out}",
+            pack.puzzles.len(),
+            sheet.page_count()
+        );
+    }
+    Outcome::Solved
+}
@@ -0,0 +1,100 @@
+//! `lightsout profile OLD NEW`: compare two search profiles written by
+//! `solve --profile`, total by total and then depth by depth and score by
+//! score, so a change that makes the search slower shows where the extra
+//! work went.
+
+use std::{collections::BTreeSet, fs};
+
+use lightsout::search::Profile;
+
+use super::{Args, Outcome, Verbosity};
+
+fn read(path: &str) -> Result<Profile, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("couldn't read {path}: {err}"))?;
+    text.parse().map_err(|err| format!("{path}: {err}"))
+}
+
+/// `new` against `old` as a signed percentage, or nothing when there's
+/// nothing to compare against.
+fn change(old: usize, new: usize) -> String {
+    match (old, new) {
+        _ if old == new => String::new(),
+        (0, _) => "new".to_string(),
+        _ => format!("{:+.1}%", (new as f64 - old as f64) * 100.0 / old as f64),
+    }
+}
+
+/// One row of the comparison, left out when the counts are the same unless
+/// every row is wanted.
+fn row(args: &Args, label: &str, old: usize, new: usize) -> Option<String> {
+    (old != new || args.shows(Verbosity::Verbose))
+        .then(|| format!("{label:<16}{old:>12}{new:>12}{:>10}", change(old, new)))
+}
+
+/// `rows` under `heading`, unless there are none.
+fn section(heading: &str, rows: impl IntoIterator<Item = Option<String>>) {
+    let rows: Vec<String> = rows.into_iter().flatten().collect();
+    if !rows.is_empty() {
+        println!("{heading}");
+        for row in rows {
+            println!("  {row}");
+        }
+    }
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let [old, new] = match &args.positional[..] {
+        [old, new] => [old, new].map(|path| read(path)),
+        _ => {
+            eprintln!("error: profile needs the two profile files to compare");
+            return Outcome::InputError;
+        }
+    };
+    let (old, new) = match (old, new) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    if old == new {
+        println!("The profiles are the same");
+        return Outcome::Solved;
+    }
+
+    println!("{:<18}{:>12}{:>12}{:>10}", "", "old", "new", "change");
+    let (before, after) = (&old.report, &new.report);
+    section(
+        "totals",
+        [
+            row(args, "explored", before.explored, after.explored),
+            row(args, "pushed", old.pushed, new.pushed),
+            row(args, "duplicates", before.duplicates, after.duplicates),
+            row(args, "depth cutoffs", before.depth_cutoffs, after.depth_cutoffs),
+            row(args, "peak frontier", before.peak_frontier, after.peak_frontier),
+            row(args, "peak memory", before.peak_memory, after.peak_memory),
+        ],
+    );
+    if before.cancelled != after.cancelled {
+        println!("cancelled: {} before, {} after", before.cancelled, after.cancelled);
+    }
+
+    let depths = old.expanded.len().max(new.expanded.len());
+    section(
+        "expanded at depth",
+        (0..depths).map(|depth| {
+            let count = |profile: &Profile| profile.expanded.get(depth).copied().unwrap_or(0);
+            row(args, &depth.to_string(), count(&old), count(&new))
+        }),
+    );
+
+    let scores: BTreeSet<usize> = old.scores.keys().chain(new.scores.keys()).copied().collect();
+    section(
+        "expanded with score",
+        scores.into_iter().rev().map(|score| {
+            let count = |profile: &Profile| profile.scores.get(&score).copied().unwrap_or(0);
+            row(args, &score.to_string(), count(&old), count(&new))
+        }),
+    );
+    Outcome::Solved
+}
@@ -0,0 +1,77 @@
+use lightsout::{cell_name, json::Json, parse_cell, BitMask};
+
+use super::{Args, Output, Outcome, Verbosity};
+
+/// Presses like `a1 b2 a1 c3`, separated by spaces or commas.
+fn parse_presses(text: &str, width: usize, height: usize) -> Result<Vec<(usize, usize)>, String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|name| !name.is_empty())
+        .map(|name| match parse_cell(name) {
+            Some((x, y)) if x < width && y < height => Ok((x, y)),
+            _ => Err(format!("{name:?} is not a cell on a {width}x{height} board")),
+        })
+        .collect()
+}
+
+fn cell_names(mask: &BitMask) -> Vec<String> {
+    mask.positions()
+        .into_iter()
+        .map(|(x, y)| cell_name(x, y))
+        .collect()
+}
+
+fn describe(mask: &BitMask) -> String {
+    match mask.count() {
+        0 => "nothing".to_string(),
+        1 => format!("{} (1 press)", cell_names(mask).join(" ")),
+        count => format!("{} ({count} presses)", cell_names(mask).join(" ")),
+    }
+}
+
+/// Cut `--presses` down to the fewest presses with the same effect on a
+/// board of the configured size and rules, and count how many were wasted.
+pub fn run(args: &Args) -> Outcome {
+    let Some(text) = &args.presses else {
+        eprintln!("error: reduce needs --presses, e.g. --presses \"a1 b2 a1 c3\"");
+        return Outcome::InputError;
+    };
+    let board = match args.random_board(0) {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let presses = match parse_presses(text, board.width(), board.height()) {
+        Ok(presses) => presses,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let reduction = board.reduce_presses(&presses);
+    let wasted = presses.len() - reduction.minimal.count();
+    if !args.shows(Verbosity::Normal) {
+        return Outcome::Solved;
+    }
+    if args.output == Output::Json {
+        let json = Json::object([
+            ("presses", presses.len().into()),
+            ("cancelled", cell_names(&reduction.cancelled).into()),
+            ("minimal", cell_names(&reduction.minimal).into()),
+            ("wasted", wasted.into()),
+        ]);
+        println!("{json}");
+        return Outcome::Solved;
+    }
+    println!("Pressed {} times", presses.len());
+    println!("Repeats cancelled: {}", describe(&reduction.cancelled));
+    println!("Fewest with the same effect: {}", describe(&reduction.minimal));
+    if !presses.is_empty() {
+        println!(
+            "{wasted} presses wasted ({:.0}%)",
+            wasted as f64 / presses.len() as f64 * 100.0
+        );
+    }
+    Outcome::Solved
+}
@@ -0,0 +1,126 @@
+//! Boards and moves as text: block characters by default, or with
+//! `--render plain`, words a screen reader can speak, like
+//! "row 1: on off off on on".
+
+use lightsout::{cell_name, BitMask, Board};
+
+use super::{
+    play::{label_rows, render_with_labels},
+    Args, Render,
+};
+
+/// Drawn in place of cells hidden by `--fog`.
+const FOG: &str = "??";
+
+/// The board, drawn the way `--render` asks.
+pub fn board(args: &Args, board: &Board) -> String {
+    match args.render {
+        Render::Blocks => args.theme.draw(board, None),
+        Render::Plain => spoken(board, None),
+    }
+}
+
+/// [`board`], with the labels players need to name cells.
+pub fn labelled(args: &Args, board: &Board) -> String {
+    match args.render {
+        Render::Blocks => render_with_labels(board, args.theme),
+        Render::Plain => spoken(board, None),
+    }
+}
+
+/// [`labelled`], showing only the cells in `visible`. Obstacles are part
+/// of the board's shape, so they're drawn either way.
+pub fn fogged(args: &Args, board: &Board, visible: &BitMask) -> String {
+    if args.render == Render::Plain {
+        return spoken(board, Some(visible));
+    }
+    let rules = board.ruleset();
+    let rows = (0..board.height()).map(|y| {
+        let mut row = String::new();
+        for x in 0..board.width() {
+            if rules.is_obstacle(x, y) {
+                row.push_str("  ");
+            } else if visible.get(x, y) {
+                row += &args.theme.paint(board.get(x, y), false);
+            } else {
+                row.push_str(FOG);
+            }
+        }
+        row
+    });
+    label_rows(board, rows)
+}
+
+/// The board in words, cells outside `visible` read as hidden and the
+/// lights left uncounted.
+fn spoken(board: &Board, visible: Option<&BitMask>) -> String {
+    let lit = match visible {
+        Some(_) => "in fog".to_string(),
+        None => lights(board.lit_count()),
+    };
+    let mut out = format!("{} by {} board, {lit}\n", board.width(), board.height());
+    let rules = board.ruleset();
+    for (y, row) in board.rows().enumerate() {
+        let words: Vec<&str> = row
+            .enumerate()
+            .map(|(x, lit)| {
+                let hidden = visible.is_some_and(|visible| !visible.get(x, y));
+                match (rules.is_obstacle(x, y), hidden, lit) {
+                    (true, _, _) => "gap",
+                    (false, true, _) => "hidden",
+                    (false, false, true) => "on",
+                    (false, false, false) => "off",
+                }
+            })
+            .collect();
+        out += &format!("row {}: {}\n", y + 1, words.join(" "));
+    }
+    out
+}
+
+fn lights(count: usize) -> String {
+    match count {
+        1 => "1 light on".to_string(),
+        count => format!("{count} lights on"),
+    }
+}
+
+/// Names joined for reading aloud: "a1, b2 and c3".
+fn list(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+/// What pressing `(x, y)` on `before` does, in words: the press, the lights
+/// it turns on and off, and how many are left on.
+pub fn describe_press(before: &Board, x: usize, y: usize) -> String {
+    let after = before.clone_toggle(x, y);
+    let (mut on, mut off) = (Vec::new(), Vec::new());
+    for (cx, cy) in before.diff(&after).positions() {
+        let name = cell_name(cx, cy);
+        if after.get(cx, cy) {
+            on.push(name);
+        } else {
+            off.push(name);
+        }
+    }
+    let mut changes = Vec::new();
+    if !on.is_empty() {
+        changes.push(format!("turns on {}", list(&on)));
+    }
+    if !off.is_empty() {
+        changes.push(format!("turns off {}", list(&off)));
+    }
+    if changes.is_empty() {
+        changes.push("changes nothing".to_string());
+    }
+    format!(
+        "Press {}: {}. {}.",
+        cell_name(x, y),
+        changes.join(", "),
+        lights(after.lit_count())
+    )
+}
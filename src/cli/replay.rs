@@ -0,0 +1,263 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    time::Instant,
+};
+
+use lightsout::{calibrate::Session, cell_name, parse_cell, Board};
+
+use super::{play::render_with_labels, Args, Verbosity};
+
+/// One command from a `play` session that changed something.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    Press(usize, usize),
+    Undo,
+    Reset,
+    Hint,
+}
+
+impl Move {
+    /// The command as `play` takes it and replay files store it.
+    fn command(self) -> String {
+        match self {
+            Move::Press(x, y) => cell_name(x, y),
+            Move::Undo => "undo".to_string(),
+            Move::Reset => "reset".to_string(),
+            Move::Hint => "hint".to_string(),
+        }
+    }
+}
+
+/// Writes a `play` session to `--record`: the puzzle code on the first line,
+/// then each move on a line of its own, in the words `play` accepts, and
+/// once play ends a `# seconds: N` comment with how long it took.
+pub struct Recorder(Option<(File, Instant)>);
+
+impl Recorder {
+    pub fn create(path: Option<&str>, board: &Board) -> Result<Recorder, String> {
+        let Some(path) = path else {
+            return Ok(Recorder(None));
+        };
+        let mut file = File::create(path).map_err(|err| format!("{path}: {err}"))?;
+        writeln!(file, "{}", board.to_code()).map_err(|err| format!("{path}: {err}"))?;
+        Ok(Recorder(Some((file, Instant::now()))))
+    }
+
+    /// Whether moves are being written to a file at all.
+    pub fn is_recording(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Append a move. A replay that stops writing only loses its tail, so
+    /// errors are ignored rather than interrupting play.
+    pub fn record(&mut self, step: Move) {
+        if let Some((file, _)) = &mut self.0 {
+            let _ = writeln!(file, "{}", step.command());
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some((file, start)) = &mut self.0 {
+            let _ = writeln!(file, "# seconds: {:.1}", start.elapsed().as_secs_f64());
+        }
+    }
+}
+
+/// A `play` session read back from a replay file.
+pub struct Replay {
+    pub board: Board,
+    pub moves: Vec<Move>,
+    /// From a `# player: name` comment, which files can be given by hand
+    /// to tell players apart.
+    pub player: Option<String>,
+    /// From the `# seconds: N` comment [`Recorder`] ends with.
+    pub seconds: Option<f64>,
+}
+
+impl Replay {
+    /// The session as [`lightsout::calibrate`] takes it, playing the moves
+    /// out to see whether they cleared the board.
+    pub fn session(&self) -> Session {
+        let mut board = self.board.clone();
+        let mut history = Vec::new();
+        let (mut presses, mut hints) = (0, 0);
+        for &step in &self.moves {
+            match step {
+                Move::Press(x, y) => {
+                    board.toggle(x, y);
+                    history.push((x, y));
+                    presses += 1;
+                }
+                Move::Undo => {
+                    if let Some((x, y)) = history.pop() {
+                        board.toggle(x, y);
+                    }
+                }
+                Move::Reset => {
+                    board = self.board.clone();
+                    history.clear();
+                }
+                Move::Hint => hints += 1,
+            }
+        }
+        Session {
+            board: self.board.clone(),
+            player: self.player.clone(),
+            presses,
+            hints,
+            solved: board.is_solved(),
+            seconds: self.seconds,
+        }
+    }
+}
+
+/// The value of a `# key: value` comment line.
+fn comment<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let (name, value) = line.strip_prefix('#')?.split_once(':')?;
+    (name.trim() == key).then(|| value.trim())
+}
+
+/// A session read back from a file [`Recorder`] wrote. Blank lines and
+/// `#` comments are skipped, and so is `quit`.
+pub fn read(path: &str) -> Result<Replay, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    let (mut player, mut seconds) = (None, None);
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = comment(line, "player") {
+            player = Some(name.to_string());
+        }
+        if let Some(value) = comment(line, "seconds") {
+            seconds = value.parse().ok();
+        }
+    }
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+    let (_, code) = lines.next().ok_or(format!("{path}: replay is empty"))?;
+    let board = Board::from_code(code).map_err(|err| format!("{path}: line 1: {err}"))?;
+    let mut moves = Vec::new();
+    for (number, line) in lines {
+        let step = match line {
+            "undo" | "u" => Move::Undo,
+            "reset" => Move::Reset,
+            "hint" | "h" => Move::Hint,
+            "quit" | "q" => continue,
+            cell => match parse_cell(cell) {
+                Some((x, y)) if x < board.width() && y < board.height() => Move::Press(x, y),
+                _ => return Err(format!("{path}: line {number}: unrecognized move {cell:?}")),
+            },
+        };
+        moves.push(step);
+    }
+    Ok(Replay {
+        board,
+        moves,
+        player,
+        seconds,
+    })
+}
+
+fn optimal(board: &Board) -> Option<usize> {
+    board.optimal_length()
+}
+
+fn show_optimal(optimal: Option<usize>) -> String {
+    optimal.map_or("-".to_string(), |optimal| optimal.to_string())
+}
+
+/// Replay a session and report the fewest presses left after every move,
+/// where the player strayed from an optimal line, how many presses were
+/// wasted and the share of presses that brought the board closer to solved.
+/// Verbose output steps through the board after each move.
+pub fn analyze(args: &Args, path: &str) -> Result<(), String> {
+    let Replay {
+        board: start,
+        moves,
+        ..
+    } = read(path)?;
+    let mut board = start.clone();
+    let mut history: Vec<(usize, usize)> = Vec::new();
+    let start_optimal = optimal(&start);
+    let (mut presses, mut closer, mut hints) = (0, 0, 0);
+    let mut deviations = Vec::new();
+    let mut rows = Vec::new();
+    for (i, &step) in moves.iter().enumerate() {
+        let before = optimal(&board);
+        let verdict = match step {
+            Move::Press(x, y) => {
+                board.toggle(x, y);
+                history.push((x, y));
+                presses += 1;
+                let after = optimal(&board);
+                if before.zip(after).is_some_and(|(before, after)| after < before) {
+                    closer += 1;
+                    "on track"
+                } else {
+                    deviations.push(i + 1);
+                    "deviated"
+                }
+            }
+            Move::Undo => {
+                if let Some((x, y)) = history.pop() {
+                    board.toggle(x, y);
+                }
+                "undo"
+            }
+            Move::Reset => {
+                board = start.clone();
+                history.clear();
+                "reset"
+            }
+            Move::Hint => {
+                hints += 1;
+                "hint"
+            }
+        };
+        rows.push((step.command(), before, optimal(&board), verdict));
+        if args.shows(Verbosity::Verbose) {
+            println!("{:>4}. {} ({verdict})", i + 1, step.command());
+            print!("{}", render_with_labels(&board, args.theme));
+            println!();
+        }
+    }
+    if !args.shows(Verbosity::Normal) {
+        return Ok(());
+    }
+
+    println!("{:>4}  {:<6} {:>7}  verdict", "move", "input", "optimal");
+    for (i, (input, before, after, verdict)) in rows.iter().enumerate() {
+        let optimal = format!("{} -> {}", show_optimal(*before), show_optimal(*after));
+        println!("{:>4}  {input:<6} {optimal:>7}  {verdict}", i + 1);
+    }
+    println!();
+    let end_optimal = optimal(&board);
+    match (start_optimal, end_optimal) {
+        (None, _) => println!("The board can't be solved"),
+        (Some(_), Some(0)) => println!("Solved in {presses} presses"),
+        (Some(_), remaining) => println!(
+            "Stopped with {} presses left at best",
+            show_optimal(remaining)
+        ),
+    }
+    if let (Some(start), Some(end)) = (start_optimal, end_optimal) {
+        // Every press that didn't go towards the progress made was wasted.
+        let wasted = presses - start.saturating_sub(end).min(presses);
+        println!("Optimal from the start: {start} presses, {wasted} wasted");
+    }
+    if !deviations.is_empty() {
+        let moves: Vec<String> = deviations.iter().map(usize::to_string).collect();
+        println!("Deviated at move {}", moves.join(", "));
+    }
+    if hints > 0 {
+        println!("Asked for {hints} hint{}", if hints == 1 { "" } else { "s" });
+    }
+    if presses > 0 {
+        println!("Accuracy {:.0}%", closer as f64 / presses as f64 * 100.0);
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::{Arc, Mutex},
+};
+
+use lightsout::json::{self, Json};
+
+use super::{
+    daemon::{apply_settings, solve_request},
+    input::parse_item,
+    Args, Outcome,
+};
+
+/// JSON-RPC error codes, as the spec defines them.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+const METHODS: &[&str] = &["initialize", "solve", "hint", "rate", "shutdown"];
+
+/// How messages are delimited, picked from the first one a client sends:
+/// `Content-Length` headers like the language server protocol, or one
+/// message per line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Headers,
+    Lines,
+}
+
+/// Where replies and notifications go, shared with solves reporting
+/// progress.
+#[derive(Clone)]
+struct Writer {
+    framing: Framing,
+    stdout: Arc<Mutex<io::Stdout>>,
+}
+
+impl Writer {
+    fn send(&self, message: &Json) {
+        let body = message.to_string();
+        let mut stdout = self.stdout.lock().unwrap();
+        let _ = match self.framing {
+            Framing::Headers => write!(stdout, "Content-Length: {}\r\n\r\n{body}", body.len()),
+            Framing::Lines => writeln!(stdout, "{body}"),
+        };
+        let _ = stdout.flush();
+    }
+
+    fn notify(&self, method: &str, params: Json) {
+        self.send(&Json::object([
+            ("jsonrpc", "2.0".into()),
+            ("method", method.into()),
+            ("params", params),
+        ]));
+    }
+}
+
+/// The next message body, or `None` at the end of input.
+fn read_message(input: &mut impl BufRead, framing: &mut Option<Framing>) -> Option<String> {
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let Some(length) = line.strip_prefix("Content-Length:") else {
+            if line.trim().is_empty() {
+                continue;
+            }
+            framing.get_or_insert(Framing::Lines);
+            return Some(line);
+        };
+        framing.get_or_insert(Framing::Headers);
+        let length: usize = length.trim().parse().ok()?;
+        // Skip any other headers up to the blank line before the body.
+        loop {
+            line.clear();
+            if input.read_line(&mut line).ok()? == 0 {
+                return None;
+            }
+            if line.trim().is_empty() {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        input.read_exact(&mut body).ok()?;
+        return Some(String::from_utf8_lossy(&body).into_owned());
+    }
+}
+
+/// Why a request failed, as a JSON-RPC error code and message.
+type Failure = (i32, String);
+
+fn invalid(err: impl Into<String>) -> Failure {
+    (INVALID_PARAMS, err.into())
+}
+
+/// The next press towards solving `params.board` after the player's
+/// `history` of `[x, y]` presses, and how many presses are left.
+fn hint(params: &Json) -> Result<Json, Failure> {
+    let (board, _) = parse_item(params).map_err(invalid)?;
+    let history = match params.get("history") {
+        None => Vec::new(),
+        Some(history) => history
+            .as_array()
+            .ok_or_else(|| invalid("history must be an array of [x, y] presses"))?
+            .iter()
+            .map(|press| match press.as_array() {
+                Some([x, y]) => x
+                    .as_u64()
+                    .zip(y.as_u64())
+                    .map(|(x, y)| (x as usize, y as usize))
+                    .filter(|&(x, y)| x < board.width() && y < board.height()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("history must be an array of [x, y] presses"))?,
+    };
+    Ok(match board.solve_from_history(&history) {
+        None => Json::object([("solvable", false.into()), ("press", Json::Null)]),
+        Some(guidance) => {
+            let positions = guidance.remaining.positions();
+            let press = positions
+                .first()
+                .map_or(Json::Null, |&(x, y)| Json::Array(vec![x.into(), y.into()]));
+            Json::object([
+                ("solvable", true.into()),
+                ("press", press),
+                ("remaining", positions.len().into()),
+                ("wasted", guidance.wasted.len().into()),
+            ])
+        }
+    })
+}
+
+/// How hard `params.board` is: the optimal solution length, how many ways
+/// there are to reach it, and how many solutions there are in all.
+fn rate(params: &Json) -> Result<Json, Failure> {
+    let (board, _) = parse_item(params).map_err(invalid)?;
+    let minimal = board.minimal_solutions();
+    let Some(optimal) = minimal.first().map(|solution| solution.count()) else {
+        return Ok(Json::object([("solvable", false.into())]));
+    };
+    let solutions = 2f64.powi(board.kernel_dimension() as i32);
+    Ok(Json::object([
+        ("solvable", true.into()),
+        ("optimal", optimal.into()),
+        ("minimal_solutions", minimal.len().into()),
+        ("solutions", solutions.into()),
+    ]))
+}
+
+/// Serve JSON-RPC 2.0 on stdin and stdout until `shutdown` or the end of
+/// input. `initialize` takes settings to use for the rest of the session in
+/// the same form as daemon requests, `solve` takes a daemon request, and
+/// `hint` and `rate` take a `board`. While A* runs, `$/progress`
+/// notifications carry the request's `id`, the states explored so far and
+/// the best score yet, the most cells matching the goal on a board reached.
+pub fn run(args: &Args) -> Outcome {
+    let mut session = args.clone();
+    let mut framing = None;
+    let mut input = io::stdin().lock();
+    let stdout = Arc::new(Mutex::new(io::stdout()));
+    while let Some(body) = read_message(&mut input, &mut framing) {
+        let writer = Writer {
+            framing: framing.unwrap_or(Framing::Lines),
+            stdout: Arc::clone(&stdout),
+        };
+        let message = match json::parse(&body) {
+            Ok(message) => message,
+            Err(err) => {
+                writer.send(&reply(Json::Null, Err((PARSE_ERROR, err))));
+                continue;
+            }
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Json::as_str) else {
+            let failure = (INVALID_REQUEST, "expected a method".to_string());
+            writer.send(&reply(id.unwrap_or(Json::Null), Err(failure)));
+            continue;
+        };
+        let params = message.get("params").cloned().unwrap_or(Json::object::<&str>([]));
+        let result = match method {
+            "initialize" => initialize(&mut session, &params),
+            "solve" => {
+                let mut args = session.clone();
+                let (writer, id) = (writer.clone(), id.clone().unwrap_or(Json::Null));
+                args.on_progress = Some(Arc::new(move |report, best| {
+                    writer.notify(
+                        "$/progress",
+                        Json::object([
+                            ("id", id.clone()),
+                            ("explored", report.explored.into()),
+                            ("peak_frontier", report.peak_frontier.into()),
+                            ("best_score", best.into()),
+                        ]),
+                    )
+                }));
+                solve_request(&args, &params).map_err(invalid)
+            }
+            "hint" => hint(&params),
+            "rate" => rate(&params),
+            "shutdown" => Ok(Json::Null),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {method}"))),
+        };
+        // Notifications get no reply, even when they fail.
+        if let Some(id) = id {
+            writer.send(&reply(id, result));
+        }
+        if method == "shutdown" {
+            break;
+        }
+    }
+    Outcome::Solved
+}
+
+fn initialize(session: &mut Args, params: &Json) -> Result<Json, Failure> {
+    let Json::Object(settings) = params else {
+        return Err(invalid("expected settings as an object"));
+    };
+    let mut configured = session.clone();
+    apply_settings(&mut configured, settings).map_err(invalid)?;
+    *session = configured;
+    Ok(Json::object([
+        ("name", "lightsout".into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("methods", Json::Array(METHODS.iter().map(|&method| method.into()).collect())),
+    ]))
+}
+
+fn reply(id: Json, result: Result<Json, Failure>) -> Json {
+    let outcome = match result {
+        Ok(result) => ("result", result),
+        Err((code, message)) => (
+            "error",
+            Json::object([("code", f64::from(code).into()), ("message", message.into())]),
+        ),
+    };
+    Json::object([("jsonrpc", "2.0".into()), ("id", id), outcome])
+}
@@ -0,0 +1,339 @@
+//! `lightsout selftest`: known boards through every algorithm and every
+//! format through a round trip, for checking a build on a new platform
+//! without the test suite. Exits 1 if any check fails. `--regressions`
+//! adds the boards from bug reports kept in `regressions.toml`.
+
+use std::time::Duration;
+
+use lightsout::{
+    json::{self, Json},
+    pack::Puzzle,
+    search::StateTable,
+    Board,
+};
+
+use super::{config, solve::solve_board, wire::Format, Algorithm, Args, Outcome, Verbosity};
+
+/// Boards with the length of their optimal solutions, or `None` where
+/// there is no solution, across sizes and rules.
+const BATTERY: &[(&str, Option<usize>)] = &[
+    ("5x5-00008e0", Some(1)),
+    ("5x5-c408f38", Some(3)),
+    ("5x5-32fc420", Some(5)),
+    ("5x5-2e99a38", Some(8)),
+    ("5x5-e8cc620", Some(15)),
+    ("5x5-8000000", None),
+    ("6x5-c247bd4c", Some(4)),
+    ("7x7-c51c5182cf498", Some(6)),
+    ("3x3-ce8:wrap", Some(2)),
+    ("4x3-a53:diagonal", Some(6)),
+    ("4x4-a5f0:square", Some(9)),
+    ("4x4-48d6:holes=8001", Some(3)),
+    ("5x5-cddcec8:wrap+square", Some(2)),
+];
+
+/// The regression registry, built in so checks don't depend on where the
+/// binary is run from.
+const REGRESSIONS: &str = include_str!("../../regressions.toml");
+
+const ALGORITHMS: &[Algorithm] = &[
+    Algorithm::AStar,
+    Algorithm::Linear,
+    Algorithm::Exhaustive,
+    Algorithm::Bitsliced,
+    Algorithm::Chase,
+    Algorithm::Hybrid,
+    Algorithm::Auto,
+];
+
+/// Search budget per board, so slow searches are skipped rather than
+/// holding the whole run up.
+const MAX_NODES: usize = 50_000;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+enum Verdict {
+    Pass(String),
+    Skip(String),
+    Fail(String),
+}
+
+fn presses(count: usize) -> String {
+    match count {
+        1 => "1 press".to_string(),
+        count => format!("{count} presses"),
+    }
+}
+
+/// Solve `board` with `algorithm` and hold the answer to `expected`.
+fn check_solve(
+    args: &Args,
+    algorithm: Algorithm,
+    board: &Board,
+    expected: Option<usize>,
+) -> Verdict {
+    if algorithm == Algorithm::Exhaustive && board.width() * board.height() > StateTable::MAX_CELLS
+    {
+        return Verdict::Skip(format!("tables only cover {} cells", StateTable::MAX_CELLS));
+    }
+    let args = Args {
+        algorithm,
+        max_nodes: Some(MAX_NODES),
+        timeout: Some(TIMEOUT),
+        route: false,
+        constraints: None,
+        ..args.clone()
+    };
+    let solved = solve_board(&args, board.clone(), None);
+    match (solved.outcome, &solved.presses, expected) {
+        (Outcome::LimitReached, _, _) => Verdict::Skip("search budget ran out".to_string()),
+        (_, None, None) => Verdict::Pass("no solution".to_string()),
+        (_, Some(found), None) => Verdict::Fail(format!(
+            "expected no solution, got {}",
+            presses(found.len())
+        )),
+        (_, None, Some(expected)) => {
+            Verdict::Fail(format!("expected {}, got no solution", presses(expected)))
+        }
+        (_, Some(found), Some(expected)) => {
+            let mut after = board.clone();
+            for &(x, y) in found {
+                after.toggle(x, y);
+            }
+            // Chasing takes the first solution it finds, not the shortest.
+            let optimal = solved.algorithm != Algorithm::Chase;
+            if !after.is_solved() {
+                Verdict::Fail(format!("{} don't clear the board", presses(found.len())))
+            } else if found.len() < expected || optimal && found.len() != expected {
+                Verdict::Fail(format!(
+                    "expected {}, got {}",
+                    presses(expected),
+                    presses(found.len())
+                ))
+            } else {
+                Verdict::Pass(presses(found.len()))
+            }
+        }
+    }
+}
+
+fn same<T: PartialEq>(what: &str, a: T, b: T) -> Result<(), String> {
+    if a == b {
+        Ok(())
+    } else {
+        Err(format!("{what} came back different"))
+    }
+}
+
+type Check = fn(&Board) -> Result<(), String>;
+
+/// Every format boards and results are written in, written and read back.
+fn round_trips(boards: &[Board]) -> Vec<(&'static str, Result<(), String>)> {
+    let checks: Vec<(&str, Check)> = vec![
+        ("code", |board| {
+            same(
+                "puzzle code",
+                &Board::from_code(&board.to_code()).map_err(|e| e.to_string())?,
+                board,
+            )
+        }),
+        ("text", |board| {
+            // Drawings show neither rules nor holes, so only the lights count.
+            if board.ruleset().obstacles.is_some() {
+                return Ok(());
+            }
+            let drawn: Board = board
+                .to_string()
+                .parse()
+                .map_err(|e: lightsout::ParseError| e.to_string())?;
+            same(
+                "drawing",
+                &drawn.with_ruleset(board.ruleset().clone()),
+                board,
+            )
+        }),
+        ("json", |board| {
+            let puzzle = Puzzle::new("selftest", board.clone());
+            let parsed = json::parse(&puzzle.to_json().to_string())?;
+            same("pack entry", &Puzzle::from_json(&parsed)?, &puzzle)
+        }),
+        ("cbor", |board| wire(Format::Cbor, board)),
+        ("msgpack", |board| wire(Format::MessagePack, board)),
+    ];
+    #[cfg(feature = "image")]
+    let checks = {
+        let mut checks = checks;
+        checks.push(("png", |board| {
+            let pixels: Vec<[u8; 3]> = board
+                .cells()
+                .map(|(_, lit)| if lit { [255, 214, 10] } else { [48, 48, 48] })
+                .collect();
+            let png = lightsout::png::encode_rgb(board.width(), board.height(), &pixels);
+            let image = lightsout::png::decode(&png)?;
+            let decoded: Vec<[u8; 3]> =
+                image.pixels.iter().map(|&[r, g, b, _]| [r, g, b]).collect();
+            same("image", decoded, pixels)
+        }));
+        checks.push(("card", |board| {
+            same(
+                "card",
+                &lightsout::card::decode(&lightsout::card::encode(board))?,
+                board,
+            )
+        }));
+        checks
+    };
+    checks
+        .into_iter()
+        .map(|(name, check)| (name, boards.iter().try_for_each(check)))
+        .collect()
+}
+
+/// A result as the daemon and server send it, through `format` and back.
+fn wire(format: Format, board: &Board) -> Result<(), String> {
+    let value = Json::object([
+        ("code", board.to_code().into()),
+        ("width", board.width().into()),
+        ("lit", board.lit_count().into()),
+        (
+            "presses",
+            Json::Array(vec![Json::Array(vec![0usize.into(), 1usize.into()])]),
+        ),
+        ("seconds", 0.25.into()),
+        ("solved", board.is_solved().into()),
+        ("seed", Json::Null),
+    ]);
+    same("reply", format.decode(&format.encode(&value))?, value)
+}
+
+/// A board from a bug report, named after its section.
+struct Regression {
+    name: String,
+    board: Board,
+    optimal: Option<usize>,
+}
+
+/// Read the registry: a section per case, each a seeded random board with
+/// its expected answer.
+fn regressions(args: &Args, text: &str) -> Result<Vec<Regression>, String> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for (key, value) in config::parse(text)? {
+        let Some((name, key)) = key.split_once('.') else {
+            return Err(format!("{key} is outside a case's section"));
+        };
+        match sections.last_mut() {
+            Some((last, entries)) if last == name => entries.push((key.to_string(), value)),
+            _ => sections.push((name.to_string(), vec![(key.to_string(), value)])),
+        }
+    }
+    sections
+        .into_iter()
+        .map(|(name, entries)| {
+            let mut case = Args {
+                seed: None,
+                width: 5,
+                height: 5,
+                rules: None,
+                density: None,
+                ..args.clone()
+            };
+            let (mut solvable, mut optimal) = (None, None);
+            for (key, value) in entries {
+                let invalid = |err: &dyn std::fmt::Display| format!("{name}.{key}: {err}");
+                match key.as_str() {
+                    "seed" => case.seed = Some(value.parse().map_err(|err| invalid(&err))?),
+                    "width" => case.width = value.parse().map_err(|err| invalid(&err))?,
+                    "height" => case.height = value.parse().map_err(|err| invalid(&err))?,
+                    "density" => case.density = Some(value.parse().map_err(|err| invalid(&err))?),
+                    "rules" => case.rules = Some(value),
+                    "solvable" => solvable = Some(value.parse().map_err(|err| invalid(&err))?),
+                    "optimal" => optimal = Some(value.parse().map_err(|err| invalid(&err))?),
+                    _ => return Err(format!("{name}: unknown key {key}")),
+                }
+            }
+            let seed = case.seed.ok_or(format!("{name}: missing seed"))?;
+            match (solvable, optimal) {
+                (None, _) => return Err(format!("{name}: missing solvable")),
+                (Some(true), None) => return Err(format!("{name}: solvable but missing optimal")),
+                (Some(false), Some(_)) => {
+                    return Err(format!("{name}: unsolvable but has optimal"))
+                }
+                _ => (),
+            }
+            let board = case
+                .random_board(seed)
+                .map_err(|err| format!("{name}: {err}"))?;
+            Ok(Regression {
+                name,
+                board,
+                optimal,
+            })
+        })
+        .collect()
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let mut cases: Vec<(String, Board, Option<usize>)> = BATTERY
+        .iter()
+        .map(|&(code, optimal)| {
+            let board = Board::from_code(code).expect("battery boards are valid codes");
+            (code.to_string(), board, optimal)
+        })
+        .collect();
+    if args.regressions {
+        match regressions(args, REGRESSIONS) {
+            Ok(regressions) => cases.extend(
+                regressions
+                    .into_iter()
+                    .map(|case| (case.name, case.board, case.optimal)),
+            ),
+            Err(err) => {
+                eprintln!("error: regressions.toml: {err}");
+                return Outcome::InputError;
+            }
+        }
+    }
+    let boards: Vec<Board> = cases.iter().map(|(_, board, _)| board.clone()).collect();
+    let (mut passed, mut skipped, mut failed) = (0, 0, 0);
+    let mut report = |verdict: Verdict, check: &str, subject: &str| {
+        let (status, detail) = match verdict {
+            Verdict::Pass(detail) => {
+                passed += 1;
+                ("pass", detail)
+            }
+            Verdict::Skip(detail) => {
+                skipped += 1;
+                ("skip", detail)
+            }
+            Verdict::Fail(detail) => {
+                failed += 1;
+                ("FAIL", detail)
+            }
+        };
+        if args.shows(Verbosity::Normal) {
+            println!("{status}  {check:<11} {subject:<24} {detail}");
+        }
+    };
+
+    for &algorithm in ALGORITHMS {
+        for (name, board, expected) in &cases {
+            let verdict = check_solve(args, algorithm, board, *expected);
+            report(verdict, &algorithm.to_string(), name);
+        }
+    }
+    for (format, result) in round_trips(&boards) {
+        let verdict = match result {
+            Ok(()) => Verdict::Pass(format!("{} boards", boards.len())),
+            Err(err) => Verdict::Fail(err),
+        };
+        report(verdict, "round trip", format);
+    }
+
+    if args.shows(Verbosity::Normal) {
+        println!("{passed} passed, {skipped} skipped, {failed} failed");
+    }
+    if failed > 0 {
+        Outcome::Unsolvable
+    } else {
+        Outcome::Solved
+    }
+}
@@ -0,0 +1,485 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use lightsout::{
+    json::{self, Json},
+    Board, CancellationToken,
+};
+
+use super::{
+    coop,
+    daemon::{request_args, request_board},
+    metrics::metrics,
+    solve::{solve_boards, to_json, Progress},
+    websocket,
+    wire::Format,
+    Args, Outcome,
+};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// The budget for each request unless `--timeout` gives another, covering
+/// every board in it.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// States A* may explore per board unless `--max-nodes` says otherwise.
+const DEFAULT_MAX_NODES: usize = 100_000;
+
+/// How long a client gets to send its request, so slow ones can't hold a
+/// worker.
+pub(super) const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest request body accepted, enough for a few thousand boards.
+const MAX_BODY: usize = 8 << 20;
+const MAX_HEADER_LINE: u64 = 8 << 10;
+const MAX_HEADERS: usize = 64;
+
+pub(super) struct Request {
+    pub method: String,
+    pub path: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub(super) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(super) struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub(super) fn new(status: u16, format: Format, body: &Json) -> Response {
+        Response {
+            status,
+            content_type: format.content_type(),
+            body: format.encode(body),
+        }
+    }
+
+    fn text(body: String) -> Response {
+        Response {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body: body.into_bytes(),
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub(super) fn html(body: &str) -> Response {
+        Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    pub(super) fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::new(status, Format::Json, &Json::object([("error", message.into().into())]))
+    }
+
+    pub(super) fn write(&self, stream: &mut impl Write) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Content Too Large",
+            415 => "Unsupported Media Type",
+            426 => "Upgrade Required",
+            429 => "Too Many Requests",
+            _ => "Error",
+        };
+        write!(stream, "HTTP/1.1 {} {reason}\r\n", self.status)?;
+        write!(stream, "Content-Type: {}\r\n", self.content_type)?;
+        write!(stream, "Content-Length: {}\r\n", self.body.len())?;
+        write!(stream, "Connection: close\r\n")?;
+        if self.status == 429 {
+            write!(stream, "Retry-After: 1\r\n")?;
+        }
+        write!(stream, "\r\n")?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+/// The board as `gui`'s page and `/coop` players draw it: a string per
+/// row of `#` for lit, `.` for unlit and a space for obstacles.
+pub(super) fn view(board: &Board) -> Json {
+    let rules = board.ruleset();
+    let rows: Vec<Json> = board
+        .rows()
+        .enumerate()
+        .map(|(y, row)| {
+            row.enumerate()
+                .map(|(x, lit)| match (rules.is_obstacle(x, y), lit) {
+                    (true, _) => ' ',
+                    (false, true) => '#',
+                    (false, false) => '.',
+                })
+                .collect::<String>()
+                .into()
+        })
+        .collect();
+    Json::object([
+        ("code", board.to_code().into()),
+        ("rows", Json::Array(rows)),
+        ("solved", board.is_solved().into()),
+    ])
+}
+
+/// One line of the request head, without its line ending.
+fn read_line(reader: &mut impl BufRead) -> Result<String, Response> {
+    let mut line = String::new();
+    match reader.take(MAX_HEADER_LINE).read_line(&mut line) {
+        Ok(_) if line.ends_with('\n') => Ok(line.trim_end().to_string()),
+        Ok(_) => Err(Response::error(400, "request head cut short or too long")),
+        Err(_) => Err(Response::error(408, "timed out reading the request")),
+    }
+}
+
+pub(super) fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let line = read_line(reader)?;
+    let mut parts = line.split(' ');
+    let (Some(method), Some(path), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(Response::error(400, "malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(Response::error(400, "too many headers"));
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(Response::error(400, "malformed header"));
+        };
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    let mut request = Request {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+
+    let length = match request.header("Content-Length") {
+        None => 0,
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| Response::error(400, "invalid Content-Length"))?,
+    };
+    if length > MAX_BODY {
+        return Err(Response::error(413, format!("bodies are limited to {MAX_BODY} bytes")));
+    }
+    request.body = vec![0; length];
+    reader
+        .read_exact(&mut request.body)
+        .map_err(|_| Response::error(408, "timed out reading the body"))?;
+    Ok(request)
+}
+
+/// Solve one request object within `cancel`, or explain why not.
+fn solve_one(args: &Args, request: &Json, cancel: &CancellationToken) -> Json {
+    solve_watched(args, request, cancel, None)
+}
+
+/// [`solve_one`], passing A* progress to `on_progress` if given.
+fn solve_watched(
+    args: &Args,
+    request: &Json,
+    cancel: &CancellationToken,
+    on_progress: Option<Progress>,
+) -> Json {
+    let solved = request_args(args, request).and_then(|requested| {
+        // Without a board, check the size before a random one is built.
+        if request.get("board").is_none() {
+            within_max_cells(args, requested.width, requested.height)?;
+        }
+        let task = request_board(&requested, request)?;
+        within_max_cells(args, task.board.width(), task.board.height())?;
+        // Requests may tighten the node budget but never loosen it.
+        let max_nodes = match requested.max_nodes {
+            Some(requested) => requested.min(args.max_nodes.unwrap_or(DEFAULT_MAX_NODES)),
+            None => args.max_nodes.unwrap_or(DEFAULT_MAX_NODES),
+        };
+        let requested = Args {
+            threads: args.threads,
+            timeout: None,
+            max_nodes: Some(max_nodes),
+            cancel: Some(cancel.clone()),
+            on_progress,
+            ..requested
+        };
+        let solved = solve_boards(&requested, vec![task]);
+        metrics().solved(&solved[0]);
+        Ok(to_json(&solved[0]))
+    });
+    solved.unwrap_or_else(|err| Json::object([("error", err.into())]))
+}
+
+/// Refuse boards larger than `--max-cells`, without overflowing on the way.
+fn within_max_cells(args: &Args, width: usize, height: usize) -> Result<(), String> {
+    let fits = width.checked_mul(height).is_some_and(|cells| cells <= args.max_cells);
+    if !fits || width.max(height) > args.max_cells {
+        return Err(format!("boards are limited to {} cells", args.max_cells));
+    }
+    Ok(())
+}
+
+/// Run `work` with a token cancelled once `--timeout` has passed.
+fn within_timeout<R>(args: &Args, work: impl FnOnce(&CancellationToken) -> R) -> R {
+    let timeout = args.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let cancel = CancellationToken::new();
+    let (done, finished) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        let timer = cancel.clone();
+        scope.spawn(move || {
+            if finished.recv_timeout(timeout).is_err() {
+                timer.cancel();
+            }
+        });
+        let result = work(&cancel);
+        let _ = done.send(());
+        result
+    })
+}
+
+/// Answer `POST /solve`, whose body is a daemon request or an array of them,
+/// in whichever format its `Content-Type` names. The reply comes back in the
+/// format `Accept` names, or else the request's.
+fn solve(args: &Args, request: &Request) -> Response {
+    let format = match request.header("Content-Type") {
+        None => Format::Json,
+        Some(content_type) => match Format::from_content_type(content_type) {
+            Some(format) => format,
+            None => return Response::error(415, format!("unsupported content type {content_type}")),
+        },
+    };
+    let reply_format = request
+        .header("Accept")
+        .and_then(|accept| accept.split(',').find_map(Format::from_content_type))
+        .unwrap_or(format);
+    let body = match format.decode(&request.body) {
+        Ok(body) => body,
+        Err(err) => return Response::error(400, err),
+    };
+
+    let reply = within_timeout(args, |cancel| match &body {
+        Json::Array(requests) => Json::Array(
+            requests
+                .iter()
+                .map(|request| solve_one(args, request, cancel))
+                .collect(),
+        ),
+        request => solve_one(args, request, cancel),
+    });
+    let status = if reply.get("error").is_some() { 400 } else { 200 };
+    Response::new(status, reply_format, &reply)
+}
+
+/// Check that `request` asks for a WebSocket and accept it, after which
+/// `stream` speaks frames.
+pub(super) fn upgrade(request: &Request, stream: &mut TcpStream) -> Result<(), Response> {
+    let upgrade = request.header("Upgrade").unwrap_or_default();
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        let message = format!("{} needs a WebSocket upgrade", request.path);
+        return Err(Response::error(426, message));
+    }
+    let connection = request.header("Connection").unwrap_or_default();
+    if !connection.split(',').any(|token| token.trim().eq_ignore_ascii_case("upgrade")) {
+        return Err(Response::error(400, "Connection must include upgrade"));
+    }
+    if request.header("Sec-WebSocket-Version") != Some("13") {
+        return Err(Response::error(400, "only WebSocket version 13 is supported"));
+    }
+    let Some(key) = request.header("Sec-WebSocket-Key") else {
+        return Err(Response::error(400, "missing Sec-WebSocket-Key"));
+    };
+    let _ = websocket::handshake(stream, key);
+    Ok(())
+}
+
+/// Answer `GET /live`, a WebSocket on which the client sends one daemon
+/// request as a text message. While A* runs, the server sends
+/// `{"event": "progress"}` messages with the states explored so far, the
+/// largest frontier and the best score yet, then one `{"event": "solved"}`
+/// message with the result, and closes. There is no anytime search to
+/// report improved solutions from, so only the final one is sent.
+fn live(
+    args: &Args,
+    request: &Request,
+    reader: &mut impl BufRead,
+    stream: &mut TcpStream,
+) -> Result<(), Response> {
+    upgrade(request, stream)?;
+    let send = |stream: &mut TcpStream, message: &Json| {
+        websocket::write_frame(stream, websocket::TEXT, message.to_string().as_bytes())
+    };
+    let reply = match websocket::read_message(reader, stream, MAX_BODY) {
+        Ok((websocket::TEXT, payload)) => std::str::from_utf8(&payload)
+            .map_err(|_| "message is not UTF-8".to_string())
+            .and_then(json::parse),
+        Ok((websocket::CLOSE, _)) => return Ok(()),
+        Ok(_) => Err("requests must be text messages".to_string()),
+        Err(err) => Err(err),
+    };
+    let reply = match reply {
+        Err(err) => Json::object([("event", "error".into()), ("error", err.into())]),
+        Ok(request) => {
+            let Ok(writer) = stream.try_clone() else {
+                return Ok(());
+            };
+            let writer = Arc::new(Mutex::new(writer));
+            within_timeout(args, |cancel| {
+                let (writer, gone) = (Arc::clone(&writer), cancel.clone());
+                let on_progress: Progress = Arc::new(move |report, best| {
+                    let event = Json::object([
+                        ("event", "progress".into()),
+                        ("explored", report.explored.into()),
+                        ("peak_frontier", report.peak_frontier.into()),
+                        ("best_score", best.into()),
+                    ]);
+                    // Nobody is watching any more, so stop searching.
+                    if send(&mut writer.lock().unwrap(), &event).is_err() {
+                        gone.cancel();
+                    }
+                });
+                let solved = solve_watched(args, &request, cancel, Some(on_progress));
+                let event = if solved.get("error").is_some() { "error" } else { "solved" };
+                match solved {
+                    Json::Object(mut entries) => {
+                        entries.insert(0, ("event".to_string(), event.into()));
+                        Json::Object(entries)
+                    }
+                    other => other,
+                }
+            })
+        }
+    };
+    let _ = send(stream, &reply);
+    let _ = websocket::write_frame(stream, websocket::CLOSE, &1000u16.to_be_bytes());
+    // Wait briefly for the client's close, as in `reject`, so anything it
+    // sent meanwhile doesn't reset the connection.
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = stream.set_read_timeout(Some(Duration::from_millis(100)));
+    let _ = std::io::copy(&mut reader.take(64 << 10), &mut std::io::sink());
+    Ok(())
+}
+
+fn handle(args: &Args, mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(READ_TIMEOUT));
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(reader);
+    let start = Instant::now();
+    let (path, response) = match read_request(&mut reader) {
+        Err(response) => (String::new(), response),
+        Ok(request) => {
+            let response = match (request.method.as_str(), request.path.as_str()) {
+                ("POST", "/solve") => solve(args, &request),
+                ("GET", "/live") => match live(args, &request, &mut reader, &mut stream) {
+                    Ok(()) => return metrics().request("/live", 101, start.elapsed()),
+                    Err(response) => response,
+                },
+                // Players stay for as long as they like, so each gets a
+                // thread of their own rather than holding up a worker.
+                ("GET", "/coop") => match coop::admit(&request, &mut stream) {
+                    Ok(()) => {
+                        metrics().request("/coop", 101, start.elapsed());
+                        let args = args.clone();
+                        thread::spawn(move || coop::session(&args, reader, stream));
+                        return;
+                    }
+                    Err(response) => response,
+                },
+                ("GET", "/health") => {
+                    Response::new(200, Format::Json, &Json::object([("status", "ok".into())]))
+                }
+                ("GET", "/metrics") => Response::text(metrics().render()),
+                (_, "/solve" | "/live" | "/coop" | "/health" | "/metrics") => {
+                    Response::error(405, "method not allowed")
+                }
+                (_, path) => Response::error(404, format!("no route for {path}")),
+            };
+            (request.path, response)
+        }
+    };
+    let _ = response.write(&mut stream);
+    metrics().request(&path, response.status, start.elapsed());
+}
+
+/// Turn a connection away when every worker is busy and the queue is full.
+/// The request is drained briefly first, since closing a socket with unread
+/// data resets it and the client might never see the 429.
+fn reject(mut stream: TcpStream) {
+    metrics().rejected();
+    let _ = Response::error(429, "server busy, try again shortly").write(&mut stream);
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = stream.set_read_timeout(Some(Duration::from_millis(100)));
+    let _ = std::io::copy(&mut (&stream).take(64 << 10), &mut std::io::sink());
+}
+
+/// Serve HTTP on the address given, or [`DEFAULT_ADDRESS`]. Accepted
+/// connections wait in a queue of `--queue` for one of `--workers` workers;
+/// beyond that they get a 429. Each request gets `--timeout` in all, and each
+/// A* search in it `--max-nodes` states or fewer if the request asks.
+/// Cooperative players on `/coop` have threads of their own, see
+/// [`coop`].
+pub fn run(args: &Args) -> Outcome {
+    let address = args.positional.first().map_or(DEFAULT_ADDRESS, String::as_str);
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("error: {address}: {err}");
+            return Outcome::InputError;
+        }
+    };
+    match listener.local_addr() {
+        Ok(local) => eprintln!("listening on http://{local}"),
+        Err(_) => eprintln!("listening on http://{address}"),
+    }
+
+    let (queue, jobs) = mpsc::sync_channel::<TcpStream>(args.queue);
+    let jobs = Arc::new(Mutex::new(jobs));
+    for _ in 0..args.workers.max(1) {
+        let (args, jobs) = (args.clone(), Arc::clone(&jobs));
+        thread::spawn(move || loop {
+            let job = jobs.lock().unwrap().recv();
+            match job {
+                Ok(stream) => handle(&args, stream),
+                Err(_) => break,
+            }
+        });
+    }
+    for stream in listener.incoming() {
+        match stream.map(|stream| queue.try_send(stream)) {
+            Ok(Err(TrySendError::Full(stream))) => reject(stream),
+            Ok(Err(TrySendError::Disconnected(_))) => break,
+            _ => (),
+        }
+    }
+    Outcome::Solved
+}
@@ -0,0 +1,1035 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::OpenOptions,
+    io::Write,
+    sync::{mpsc, Arc, Mutex, OnceLock},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use lightsout::{
+    cache::SolutionCache,
+    context::SolverContext,
+    json::Json,
+    pack::{self, lookup},
+    search::{
+        a_star, a_star_opening_filtered, a_star_warm_filtered, estimate, search_cancellable,
+        ConstrainedBoard, LifoFrontier, MoveOrder, MultiGoalBoard, OpeningBook, OrderedBoard,
+        OrderingBoard, Profile, Search, SearchReport,
+        SearchState, StateTable, PROGRESS_INTERVAL,
+    },
+    route_length, shortest_route, BatchSolver, BitMask, Board, Goals, Preference, PressConstraints,
+};
+use rand::prelude::*;
+
+use super::{
+    explain::explain, input::read_boards, manifest::Manifest, render, Algorithm, Args, Outcome,
+    Output, Render, Verbosity,
+};
+
+/// The result of solving one board, independent of how it gets printed.
+pub struct Solved {
+    pub board: Board,
+    pub seed: Option<u64>,
+    /// The algorithm that actually solved the board, which `--auto-algorithm`
+    /// may have switched away from the one asked for.
+    pub algorithm: Algorithm,
+    /// Presses the solution was pinned to make or avoid, if any.
+    pub constraints: Option<PressConstraints>,
+    pub presses: Option<Vec<(usize, usize)>>,
+    pub report: Option<SearchReport>,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Called during long A* searches with the work done so far and the best
+/// score yet: the most cells matching the goal on any board reached.
+pub type Progress = Arc<dyn Fn(&SearchReport, usize) + Send + Sync>;
+
+/// What an A* search tries before anything else.
+#[derive(Clone, Copy)]
+enum Start<'a> {
+    /// Moves from the root, tried first.
+    Opening(&'a [usize]),
+    /// Moves to seed the frontier along, as `--warm-start` hands over.
+    Warm(&'a [usize]),
+}
+
+/// [`a_star`] from `start`, reporting to [`Args::on_progress`] if it is
+/// set, and giving up once [`Args::cancel`] is cancelled or `--max-nodes`
+/// states are explored, which is checked every
+/// [`lightsout::search::PROGRESS_INTERVAL`] states. With `--profile`, the
+/// search's [`Profile`] is written to that file, and with `--bloom`, a bloom
+/// filter is checked before the explored set.
+fn a_star_watched<T: Search<Score = usize>>(
+    args: &Args,
+    init_state: T,
+    start: Start,
+    max_depth: usize,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let watched = args.on_progress.is_some() || args.max_nodes.is_some() || args.cancel.is_some();
+    let plain = matches!(start, Start::Opening([]));
+    if !watched && plain && args.profile.is_none() && !args.bloom {
+        return a_star(init_state, max_depth);
+    }
+    let cancel = args.cancel.clone().unwrap_or_default();
+    let mut profile = Profile::default();
+    let on_progress = |report: &SearchReport, best| {
+        if let Some(progress) = &args.on_progress {
+            progress(report, best);
+        }
+        if args.max_nodes.is_some_and(|max_nodes| report.explored >= max_nodes) {
+            cancel.cancel();
+        }
+    };
+    // The search overshoots --max-nodes by up to a progress interval.
+    let filter = args.bloom.then(|| {
+        args.max_nodes
+            .map_or(BLOOM_STATES, |max_nodes| max_nodes + PROGRESS_INTERVAL)
+    });
+    let (result, report) = match start {
+        Start::Opening(opening) => a_star_opening_filtered(
+            init_state,
+            opening,
+            max_depth,
+            filter,
+            &cancel,
+            on_progress,
+            &mut profile,
+        ),
+        Start::Warm(candidate) => a_star_warm_filtered(
+            init_state,
+            candidate,
+            max_depth,
+            filter,
+            &cancel,
+            on_progress,
+            &mut profile,
+        ),
+    };
+    if let Some(path) = &args.profile {
+        profile.finish(&report);
+        if let Err(err) = std::fs::write(path, profile.to_string()) {
+            eprintln!("error: couldn't write the profile to {path}: {err}");
+        }
+    }
+    (result, report)
+}
+
+/// States `--bloom` sizes its filter for without `--max-nodes`, a little
+/// over a megabyte of it.
+const BLOOM_STATES: usize = 1 << 20;
+
+/// The move indices of an A* solution from `init_state`, the state to
+/// search `board` as, warm-started from `candidate` presses if there are
+/// any and otherwise opening with the book's first press.
+fn a_star_board<T: Search<Score = usize>>(
+    args: &Args,
+    init_state: T,
+    board: &Board,
+    candidate: Option<Vec<(usize, usize)>>,
+    max_depth: usize,
+) -> (Option<Vec<usize>>, SearchReport) {
+    let (result, report) = match candidate {
+        Some(presses) => {
+            let indices: Vec<usize> = presses.iter().map(|(x, y)| y * board.width() + x).collect();
+            a_star_watched(args, init_state, Start::Warm(&indices), max_depth)
+        }
+        None => {
+            let opening: Vec<usize> = OpeningBook::embedded(board.width(), board.height())
+                .and_then(|book| book.first_press(board))
+                .map(|(x, y)| y * board.width() + x)
+                .into_iter()
+                .collect();
+            a_star_watched(args, init_state, Start::Opening(&opening), max_depth)
+        }
+    };
+    let indices = result.map(|soln| {
+        let mut indices = soln.history;
+        indices.extend(soln.latest_move_index);
+        indices
+    });
+    (indices, report)
+}
+
+/// How an A* search that found nothing ended.
+fn search_outcome(report: &SearchReport) -> Outcome {
+    if report.cancelled || report.depth_cutoffs > 0 {
+        Outcome::LimitReached
+    } else {
+        Outcome::Unsolvable
+    }
+}
+
+/// Tables shared by every solve in this process, so a batch of same-sized
+/// boards only pays for building them once.
+pub fn context() -> &'static SolverContext {
+    static CONTEXT: OnceLock<SolverContext> = OnceLock::new();
+    CONTEXT.get_or_init(SolverContext::new)
+}
+
+/// How long `--auto-algorithm` probes a search to measure its speed.
+const AUTO_PROBE: Duration = Duration::from_millis(100);
+/// Projected search time beyond which `--auto-algorithm` switches to linear.
+const AUTO_BUDGET: Duration = Duration::from_secs(5);
+
+/// Boards up to this many cells with non-classic presses are answered from
+/// a state table by `--algorithm auto`; it builds in milliseconds.
+const AUTO_TABLE_CELLS: usize = 16;
+/// Kernel dimension beyond which trying every solution for the shortest is
+/// too slow, so `--algorithm auto` settles for the first one found.
+const AUTO_MAX_KERNEL: usize = Board::MAX_SEARCHED_KERNEL;
+
+/// Presses beyond which `--algorithm hybrid` orders a solution greedily
+/// instead of searching every order for the best.
+const HYBRID_SEARCH_PRESSES: usize = 20;
+
+/// The algorithm `--algorithm auto` uses for `board`. Linear algebra is
+/// exact for every ruleset, so it's the default, with a state table taking
+/// over for tiny variant boards and plain chasing for boards with too many
+/// solutions to compare.
+pub fn choose_algorithm(board: &Board) -> Algorithm {
+    if board.kernel_dimension() > AUTO_MAX_KERNEL {
+        Algorithm::Chase
+    } else if board.ruleset().presses_classically() {
+        Algorithm::Linear
+    } else if board.width() * board.height() <= AUTO_TABLE_CELLS {
+        Algorithm::Exhaustive
+    } else {
+        Algorithm::Linear
+    }
+}
+
+/// A board queued for solving, with the seed it came from and any press
+/// constraints its input gave it, which win over `--constraints`.
+pub struct Task {
+    pub board: Board,
+    pub seed: Option<u64>,
+    pub constraints: Option<PressConstraints>,
+}
+
+/// Solve one board, honoring `--constraints` and `--goals` if set.
+pub fn solve_board(args: &Args, board: Board, seed: Option<u64>) -> Solved {
+    let task = Task {
+        board,
+        seed,
+        constraints: None,
+    };
+    solve_task(args, task)
+}
+
+/// Solve one task, cancelling it once `--timeout` runs out.
+fn solve_task(args: &Args, task: Task) -> Solved {
+    let Some(timeout) = args.timeout else {
+        return solve_untimed(args, task);
+    };
+    let cancel = args.cancel.clone().unwrap_or_default();
+    let args = Args {
+        timeout: None,
+        cancel: Some(cancel.clone()),
+        ..args.clone()
+    };
+    let (done, finished) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            if finished.recv_timeout(timeout).is_err() {
+                cancel.cancel();
+            }
+        });
+        let solved = solve_untimed(&args, task);
+        // Stop the timer early; it has already gone if it cancelled us.
+        let _ = done.send(());
+        solved
+    })
+}
+
+fn solve_untimed(args: &Args, task: Task) -> Solved {
+    let rejected = |err: String| {
+        eprintln!("error: {err}");
+        Solved {
+            board: task.board.clone(),
+            seed: task.seed,
+            algorithm: args.algorithm,
+            constraints: None,
+            presses: None,
+            report: None,
+            outcome: Outcome::InputError,
+            elapsed: Duration::ZERO,
+        }
+    };
+    let constraints = match task.constraints.clone() {
+        Some(constraints) => Some(constraints),
+        None => match args.press_constraints(&task.board) {
+            Ok(constraints) => constraints,
+            Err(err) => return rejected(err),
+        },
+    };
+    let goals = match args.goals(&task.board) {
+        Ok(goals) => goals,
+        Err(err) => return rejected(err),
+    };
+    match (constraints.filter(|constraints| !constraints.is_empty()), goals) {
+        (Some(_), Some(_)) => rejected("--goals can't be combined with press constraints".into()),
+        (Some(constraints), None) => solve_constrained(args, task.board, task.seed, constraints),
+        (None, Some(goals)) => solve_goals(args, task.board, task.seed, &goals),
+        (None, None) => solve_cached(args, task.board, task.seed),
+    }
+}
+
+/// Solve a board towards whichever of `goals` is cheapest to reach. A*
+/// searches for any of them; every other algorithm is answered by
+/// [`Board::solve_goals`], and the result reports linear as the algorithm
+/// used. A solved board comes back aiming for the pattern its solution
+/// reaches, so it's drawn and checked against that one.
+fn solve_goals(args: &Args, board: Board, seed: Option<u64>, goals: &Goals) -> Solved {
+    let start = SystemTime::now();
+    let cheapest = board.solve_goals(goals);
+    let (algorithm, presses, report) = if args.algorithm == Algorithm::AStar {
+        let (result, report) = match &cheapest {
+            None => (None, SearchReport::default()),
+            Some((presses, _)) => {
+                let searched = MultiGoalBoard::new(&board, goals);
+                a_star_watched(args, searched, Start::Opening(&[]), presses.count())
+            }
+        };
+        let presses = result.map(|soln| {
+            soln.history
+                .iter()
+                .chain(soln.latest_move_index.as_ref())
+                .map(|id| (id % board.width(), id / board.width()))
+                .collect::<Vec<_>>()
+        });
+        (Algorithm::AStar, presses, Some(report))
+    } else {
+        let presses = cheapest.map(|(presses, _)| presses.positions());
+        (Algorithm::Linear, presses, None)
+    };
+    let outcome = match (&presses, &report) {
+        (Some(_), _) => Outcome::Solved,
+        (None, Some(report)) => search_outcome(report),
+        (None, None) => Outcome::Unsolvable,
+    };
+    let reached = presses.as_ref().and_then(|presses| {
+        let mut pressed = board.clone();
+        presses.iter().for_each(|&(x, y)| pressed.toggle(x, y));
+        goals.reached(&pressed)
+    });
+    Solved {
+        board: match reached {
+            Some(index) => goals.retarget(&board, index),
+            None => board,
+        },
+        seed,
+        algorithm,
+        constraints: None,
+        presses,
+        report,
+        outcome,
+        elapsed: SystemTime::now().duration_since(start).unwrap(),
+    }
+}
+
+/// Solve a board with some presses pinned. A* searches around them; every
+/// other algorithm is answered by [`Board::solve_constrained`], and the
+/// result reports linear as the algorithm used.
+fn solve_constrained(
+    args: &Args,
+    board: Board,
+    seed: Option<u64>,
+    constraints: PressConstraints,
+) -> Solved {
+    let start = SystemTime::now();
+    let (algorithm, presses, report, outcome) = if args.algorithm == Algorithm::AStar {
+        let width = board.width();
+        // The search starts with the required presses made, so it only
+        // needs to go as deep as the rest of the shortest solution.
+        let max_depth = board
+            .solve_constrained(&constraints)
+            .map(|solution| solution.count() - constraints.required.count());
+        let constrained = ConstrainedBoard::new(&board, &constraints);
+        let (result, report) = match max_depth {
+            None => (None, SearchReport::default()),
+            Some(max_depth) => a_star_watched(args, constrained, Start::Opening(&[]), max_depth),
+        };
+        let outcome = match result {
+            Some(_) => Outcome::Solved,
+            None => search_outcome(&report),
+        };
+        let presses = result.map(|soln| {
+            let searched = soln.history.iter().chain(soln.latest_move_index.as_ref());
+            constraints
+                .required
+                .positions()
+                .into_iter()
+                .chain(searched.map(|id| (id % width, id / width)))
+                .collect()
+        });
+        (Algorithm::AStar, presses, Some(report), outcome)
+    } else {
+        match board.solve_constrained(&constraints) {
+            Some(presses) => (
+                Algorithm::Linear,
+                Some(presses.positions()),
+                None,
+                Outcome::Solved,
+            ),
+            None => (Algorithm::Linear, None, None, Outcome::Unsolvable),
+        }
+    };
+    Solved {
+        board,
+        seed,
+        algorithm,
+        constraints: Some(constraints),
+        presses,
+        report,
+        outcome,
+        elapsed: SystemTime::now().duration_since(start).unwrap(),
+    }
+}
+
+/// The `--cache` file shared by every solve in this process, opened on
+/// first use. One that can't be read is warned about once and left alone.
+fn disk_cache(args: &Args) -> Option<&'static Mutex<SolutionCache>> {
+    static CACHE: OnceLock<Option<Mutex<SolutionCache>>> = OnceLock::new();
+    let path = args.cache.as_ref()?;
+    let cache = CACHE.get_or_init(|| match SolutionCache::open(path, args.cache_size) {
+        Ok(cache) => Some(Mutex::new(cache)),
+        Err(err) => {
+            eprintln!("warning: couldn't read the cache {path}: {err}");
+            None
+        }
+    });
+    cache.as_ref()
+}
+
+/// The puzzle with the id `id`, or ids starting with it, from the built-in
+/// packs or, failing those, the `--cache`. An abbreviated id has to pick
+/// out one puzzle.
+pub fn find_id(args: &Args, id: &str) -> Result<Board, String> {
+    let mut found: Vec<(String, Board)> = pack::find_id(id)
+        .into_iter()
+        .map(|(reference, puzzle)| (reference, puzzle.board))
+        .collect();
+    if let (true, Some(cache)) = (found.is_empty(), disk_cache(args)) {
+        let cached = cache.lock().unwrap().find_id(id);
+        found = cached.into_iter().map(|board| (board.to_code(), board)).collect();
+    }
+    let ids: BTreeSet<String> = found.iter().map(|(_, board)| board.puzzle_id()).collect();
+    match ids.len() {
+        0 if args.cache.is_some() => Err(format!("no puzzle with id {id} in the packs or the cache")),
+        0 => Err(format!("no puzzle with id {id} in the built-in packs")),
+        1 => Ok(found.swap_remove(0).1),
+        _ => {
+            let references: Vec<&str> = found.iter().map(|(reference, _)| reference.as_str()).collect();
+            Err(format!("id {id} is ambiguous between {}", references.join(", ")))
+        }
+    }
+}
+
+/// [`solve_free`], answered from `--cache` if the board, or a rotation or
+/// reflection of it, was solved before with the same algorithm and
+/// preference. Only solves that finished are stored, and a stored solution
+/// that doesn't clear the board is solved again.
+fn solve_cached(args: &Args, board: Board, seed: Option<u64>) -> Solved {
+    let Some(cache) = disk_cache(args) else {
+        return solve_free(args, board, seed);
+    };
+    let tag = format!("{}/{}", args.algorithm, args.prefer);
+    let start = SystemTime::now();
+    let cached = cache.lock().unwrap().get(&tag, &board);
+    match cached {
+        Some(presses) if presses.as_ref().is_none_or(|presses| board.verify(presses)) => {
+            let outcome = match presses {
+                Some(_) => Outcome::Solved,
+                None => Outcome::Unsolvable,
+            };
+            return Solved {
+                board,
+                seed,
+                algorithm: args.algorithm,
+                constraints: None,
+                presses,
+                report: None,
+                outcome,
+                elapsed: SystemTime::now().duration_since(start).unwrap(),
+            };
+        }
+        _ => {}
+    }
+    let solved = solve_free(args, board, seed);
+    if matches!(solved.outcome, Outcome::Solved | Outcome::Unsolvable) {
+        let stored = cache
+            .lock()
+            .unwrap()
+            .insert(&tag, &solved.board, solved.presses.as_deref());
+        if let Err(err) = stored {
+            eprintln!("warning: couldn't write to the cache: {err}");
+        }
+    }
+    solved
+}
+
+/// `args` for another algorithm to take a solve over with, keeping the
+/// preference, budgets and every other setting. The solve handing over
+/// already started the timer and stores the result in the cache under its
+/// own algorithm, so the new one does neither.
+fn handing_over(args: &Args, algorithm: Algorithm) -> Args {
+    Args {
+        algorithm,
+        cache: None,
+        timeout: None,
+        ..args.clone()
+    }
+}
+
+fn solve_free(args: &Args, board: Board, seed: Option<u64>) -> Solved {
+    if args.algorithm == Algorithm::Auto {
+        let chosen = handing_over(args, choose_algorithm(&board));
+        return solve_board(&chosen, board, seed);
+    }
+    if args.algorithm == Algorithm::AStar && args.auto_algorithm {
+        let estimate = estimate(&board, AUTO_PROBE);
+        if estimate.duration() > AUTO_BUDGET {
+            eprintln!(
+                "warning: astar projected to explore {:.2e} states (about {:.0}s); \
+                 solving with linear instead",
+                estimate.nodes,
+                estimate.duration().as_secs_f64()
+            );
+            let fallback = handing_over(args, Algorithm::Linear);
+            return solve_board(&fallback, board, seed);
+        }
+    }
+    let start = SystemTime::now();
+    let (presses, report, outcome) = match args.algorithm {
+        Algorithm::AStar => {
+            let max_depth = board.depth_bound();
+            let candidate = args
+                .warm_start
+                .filter(|&algorithm| algorithm != Algorithm::AStar && max_depth.is_some())
+                .and_then(|algorithm| {
+                    let warm = handing_over(args, algorithm);
+                    solve_board(&warm, board.clone(), seed).presses
+                });
+            let (result, report) = match max_depth {
+                // Unsolvable, so there's nothing to search for.
+                None => (None, SearchReport::default()),
+                Some(max_depth) => match args.order {
+                    MoveOrder::Chase => {
+                        a_star_board(args, board.clone(), &board, candidate, max_depth)
+                    }
+                    order => {
+                        let ordered = OrderedBoard::new(board.clone(), order);
+                        a_star_board(args, ordered, &board, candidate, max_depth)
+                    }
+                },
+            };
+            let outcome = match result {
+                Some(_) => Outcome::Solved,
+                None => search_outcome(&report),
+            };
+            let presses = result.map(|indices| {
+                indices
+                    .iter()
+                    .map(|id| (id % board.width(), id / board.width()))
+                    .collect()
+            });
+            (presses, Some(report), outcome)
+        }
+        Algorithm::Bitsliced => {
+            let solver = context().batch_solver(board.width(), board.height());
+            let solution = match args.prefer {
+                Preference::Fewest => solver.solve(std::slice::from_ref(&board)).pop().flatten(),
+                preference => board.preferred_solution(preference),
+            };
+            match solution {
+                Some(presses) => (Some(presses.positions()), None, Outcome::Solved),
+                None => (None, None, Outcome::Unsolvable),
+            }
+        }
+        Algorithm::Linear => {
+            let solution = match (&args.cancel, args.prefer) {
+                // Variants eliminate the whole board, so reuse the work
+                // across every board of the same size and rules.
+                (cancel, Preference::Fewest) if !board.ruleset().presses_classically() => {
+                    let cancel = cancel.clone().unwrap_or_default();
+                    context().linear_system(&board).solve_cancellable(&board, &cancel)
+                }
+                (Some(cancel), Preference::Fewest) => board.solve_linear_cancellable(cancel),
+                _ => Ok(board.preferred_solution(args.prefer)),
+            };
+            match solution {
+                Ok(Some(presses)) => (Some(presses.positions()), None, Outcome::Solved),
+                Ok(None) => (None, None, Outcome::Unsolvable),
+                Err(_) => (None, None, Outcome::LimitReached),
+            }
+        }
+        Algorithm::Hybrid if !board.ruleset().presses_classically() => {
+            let search = handing_over(args, Algorithm::AStar);
+            return solve_board(&search, board, seed);
+        }
+        Algorithm::Hybrid => {
+            let cancel = args.cancel.clone().unwrap_or_default();
+            match board.solve_linear_cancellable(&cancel) {
+                Ok(Some(solution)) => {
+                    // Every order of the presses solves the board, so the
+                    // search is only after the one that looks best.
+                    let count = solution.count();
+                    let ordering = OrderingBoard::new(board.clone(), solution);
+                    let (result, report) = if count <= HYBRID_SEARCH_PRESSES {
+                        a_star_watched(args, ordering, Start::Opening(&[]), count)
+                    } else {
+                        search_cancellable(ordering, count, LifoFrontier::default(), &cancel)
+                    };
+                    let outcome = match result {
+                        Some(_) => Outcome::Solved,
+                        None => search_outcome(&report),
+                    };
+                    let presses = result.map(|soln| {
+                        soln.history
+                            .iter()
+                            .chain(soln.latest_move_index.as_ref())
+                            .map(|id| (id % board.width(), id / board.width()))
+                            .collect()
+                    });
+                    (presses, Some(report), outcome)
+                }
+                Ok(None) => (None, None, Outcome::Unsolvable),
+                Err(_) => (None, None, Outcome::LimitReached),
+            }
+        }
+        Algorithm::Chase => match board.solve_chase() {
+            Some(presses) => (Some(presses.positions()), None, Outcome::Solved),
+            None => (None, None, Outcome::Unsolvable),
+        },
+        Algorithm::Exhaustive => match context().state_table(&board) {
+            Some(table) => match table.solve(&board) {
+                Some(_) if args.prefer != Preference::Fewest => {
+                    let minimal = table.minimal_solutions(&board);
+                    let best = minimal.iter().min_by_key(|solution| args.prefer.cost(solution));
+                    (best.map(BitMask::positions), None, Outcome::Solved)
+                }
+                Some(presses) => (Some(presses), None, Outcome::Solved),
+                None => (None, None, Outcome::Unsolvable),
+            },
+            None => {
+                eprintln!(
+                    "error: exhaustive tables only cover boards up to {} cells",
+                    StateTable::MAX_CELLS
+                );
+                (None, None, Outcome::InputError)
+            }
+        },
+        Algorithm::Auto => unreachable!("auto is resolved to another algorithm first"),
+    };
+    Solved {
+        board,
+        seed,
+        algorithm: args.algorithm,
+        constraints: None,
+        presses,
+        report,
+        outcome,
+        elapsed: SystemTime::now().duration_since(start).unwrap(),
+    }
+}
+
+/// Solve a batch of boards. `--algorithm bitsliced` solves each run of
+/// same-sized boards together, splitting the time evenly between them;
+/// everything else, and any batch with press constraints, a `--prefer`
+/// ranking or a `--cache`, goes one board at a time. With `--route`, each
+/// solution's presses come back in the order that keeps hand travel short.
+pub fn solve_boards(args: &Args, tasks: Vec<Task>) -> Vec<Solved> {
+    let mut results = solve_batch(args, tasks);
+    if args.route {
+        for presses in results.iter_mut().filter_map(|solved| solved.presses.as_mut()) {
+            *presses = shortest_route(presses);
+        }
+    }
+    results
+}
+
+/// Boards [`solve_resumable`] hands to [`solve_boards`] together: enough
+/// for sixteen full bit-sliced passes, and few enough that an interruption
+/// only loses a moment's work.
+const SOLVE_CHUNK: usize = 16 * BatchSolver::LANES;
+
+/// [`solve_boards`] a chunk of boards at a time, recording each result in
+/// the manifest at `path` once its chunk is done, and taking the results
+/// an earlier run recorded there instead of solving those boards again.
+/// Results are kept whatever their outcome, limits reached included. The
+/// manifest names the algorithm and preference, since a result found by
+/// one doesn't stand in for another's.
+fn solve_resumable(args: &Args, tasks: Vec<Task>, path: &str) -> Result<Vec<Solved>, String> {
+    let job = format!("solve with --algorithm {} --prefer {}", args.algorithm, args.prefer);
+    let mut manifest = Manifest::open(path, &job)?;
+    let mut done: HashMap<u64, Json> = manifest
+        .entries()
+        .iter()
+        .filter_map(|entry| Some((entry.get("index")?.as_u64()?, entry.clone())))
+        .collect();
+    let mut results: Vec<Option<Solved>> = Vec::with_capacity(tasks.len());
+    let mut unfinished = Vec::new();
+    for (index, task) in (0..).zip(tasks) {
+        match done.remove(&index) {
+            Some(entry) => {
+                let solved = restore(task, &entry)
+                    .map_err(|err| format!("{path}: board {}: {err}", index + 1))?;
+                results.push(Some(solved));
+            }
+            None => {
+                results.push(None);
+                unfinished.push((index, task));
+            }
+        }
+    }
+    while !unfinished.is_empty() {
+        let rest = unfinished.split_off(unfinished.len().min(SOLVE_CHUNK));
+        let (indices, chunk): (Vec<u64>, Vec<Task>) =
+            std::mem::replace(&mut unfinished, rest).into_iter().unzip();
+        for (index, solved) in indices.into_iter().zip(solve_boards(args, chunk)) {
+            let mut entry = to_json(&solved);
+            if let Json::Object(fields) = &mut entry {
+                fields.insert(0, ("index".to_string(), index.into()));
+            }
+            manifest.record(entry)?;
+            results[index as usize] = Some(solved);
+        }
+    }
+    Ok(results
+        .into_iter()
+        .map(|solved| solved.expect("every board solved or restored"))
+        .collect())
+}
+
+/// The result of `task` a manifest recorded as `entry`, from [`to_json`].
+fn restore(task: Task, entry: &Json) -> Result<Solved, String> {
+    let field = |key: &str| entry.get(key).ok_or_else(|| format!("no {key} recorded"));
+    let id = task.board.puzzle_id();
+    let recorded = field("id")?.as_str().ok_or("the id must be a string")?;
+    if recorded != id {
+        return Err(format!(
+            "the manifest has board {recorded} here, but the input has {id}"
+        ));
+    }
+    let algorithm = field("algorithm")?.as_str().ok_or("the algorithm must be a string")?;
+    let outcome = field("outcome")?.as_str().ok_or("the outcome must be a string")?;
+    let outcome = [
+        Outcome::Solved,
+        Outcome::Unsolvable,
+        Outcome::LimitReached,
+        Outcome::InputError,
+    ]
+    .into_iter()
+    .find(|&known| outcome_name(known) == outcome)
+    .ok_or_else(|| format!("unknown outcome {outcome:?}"))?;
+    let presses = match field("presses")? {
+        Json::Null => None,
+        presses => {
+            let press = |press: &Json| match press.as_array()? {
+                [x, y] => Some((x.as_u64()? as usize, y.as_u64()? as usize)),
+                _ => None,
+            };
+            let presses = presses.as_array().ok_or("presses must be an array")?;
+            let presses: Option<Vec<_>> = presses.iter().map(press).collect();
+            Some(presses.ok_or("presses must be [x, y] pairs")?)
+        }
+    };
+    let count = |key: &str| entry.get(key).and_then(Json::as_u64).map(|count| count as usize);
+    let report = count("explored").map(|explored| SearchReport {
+        explored,
+        peak_memory: count("peak_memory").unwrap_or(0),
+        ..SearchReport::default()
+    });
+    let seconds = field("seconds")?.as_f64().ok_or("seconds must be a number")?;
+    Ok(Solved {
+        board: task.board,
+        seed: task.seed,
+        algorithm: algorithm.parse()?,
+        constraints: task.constraints,
+        presses,
+        report,
+        outcome,
+        elapsed: Duration::from_secs_f64(seconds.max(0.0)),
+    })
+}
+
+fn solve_batch(args: &Args, tasks: Vec<Task>) -> Vec<Solved> {
+    let constrained = args.constraints.is_some()
+        || args.goals.is_some()
+        || args.cache.is_some()
+        || tasks.iter().any(|task| task.constraints.is_some());
+    if args.algorithm != Algorithm::Bitsliced || constrained || args.prefer != Preference::Fewest {
+        return tasks.into_iter().map(|task| solve_task(args, task)).collect();
+    }
+    let mut results = Vec::with_capacity(tasks.len());
+    let mut rest = tasks.as_slice();
+    while let Some(first) = rest.first() {
+        let size = (first.board.width(), first.board.height());
+        let run = rest
+            .iter()
+            .position(|task| (task.board.width(), task.board.height()) != size)
+            .unwrap_or(rest.len());
+        let (group, tail) = rest.split_at(run);
+        rest = tail;
+
+        let start = SystemTime::now();
+        let solver = context().batch_solver(size.0, size.1);
+        let group_boards: Vec<Board> = group.iter().map(|task| task.board.clone()).collect();
+        let solutions = solver.solve_parallel(&group_boards, args.threads);
+        let elapsed = SystemTime::now().duration_since(start).unwrap() / run as u32;
+        for (task, solution) in group.iter().zip(solutions) {
+            let outcome = match solution {
+                Some(_) => Outcome::Solved,
+                None => Outcome::Unsolvable,
+            };
+            results.push(Solved {
+                board: task.board.clone(),
+                seed: task.seed,
+                algorithm: Algorithm::Bitsliced,
+                constraints: None,
+                presses: solution.map(|solution| solution.positions()),
+                report: None,
+                outcome,
+                elapsed,
+            });
+        }
+    }
+    results
+}
+
+pub fn print_text(args: &Args, solved: &Solved) {
+    if !args.shows(Verbosity::Normal) {
+        return;
+    }
+    if let Some(seed) = solved.seed {
+        println!("Seed: {seed}");
+    }
+    println!("Id: {}", solved.board.puzzle_id());
+    println!("{}", render::board(args, &solved.board));
+    if args.algorithm == Algorithm::Auto {
+        println!("Algorithm: {}", solved.algorithm);
+    }
+    if let Some(constraints) = &solved.constraints {
+        println!("Constraints: {constraints}");
+    }
+    let cut_off = solved.report.as_ref().is_some_and(|report| !report.cancelled);
+    match &solved.presses {
+        None if solved.outcome == Outcome::LimitReached && cut_off => println!(
+            "No solution within {} moves",
+            solved.board.width() * solved.board.height()
+        ),
+        None if solved.outcome == Outcome::LimitReached => println!("Gave up before finishing"),
+        None => {
+            println!("No solution :(");
+            if let Some(invariant) = solved.board.invariant() {
+                println!("Why: {invariant}");
+            }
+        }
+        Some(presses) => {
+            println!("Solution:");
+            let mut board = solved.board.clone();
+            for (i, &(x, y)) in presses.iter().enumerate() {
+                if args.render == Render::Plain {
+                    println!("Move {}. {}", i + 1, render::describe_press(&board, x, y));
+                    board.toggle(x, y);
+                    continue;
+                }
+                board.toggle(x, y);
+                if args.shows(Verbosity::Debug) {
+                    println!("Press ({x}, {y})");
+                }
+                // Themes mark the press; the classic look stays free of
+                // escape codes.
+                let mut pressed = BitMask::new(board.width(), board.height());
+                pressed.set(x, y, true);
+                let pressed = args.theme.shapes.then_some(&pressed);
+                println!("{}", args.theme.draw(&board, pressed));
+            }
+            println!("{} moves", presses.len());
+            if args.route {
+                println!("Hand travel {} cells", route_length(presses));
+            }
+        }
+    }
+    if let Some(report) = &solved.report {
+        println!("Explored {} states", report.explored);
+        if args.shows(Verbosity::Verbose) {
+            println!(
+                "Peak memory {:.1} KiB ({} frontier states)",
+                report.peak_memory as f64 / 1024.0,
+                report.peak_frontier
+            );
+            println!("{} states cut off at the depth limit", report.depth_cutoffs);
+            if args.bloom {
+                println!(
+                    "Bloom filter skipped {} lookups, with {} false positives",
+                    report.bloom_skips, report.bloom_false_positives
+                );
+            }
+        }
+    }
+    println!("Took {:.4}s", solved.elapsed.as_secs_f64());
+    if args.explain {
+        println!();
+        print!("{}", explain(&solved.board));
+    }
+}
+
+pub fn to_json(solved: &Solved) -> Json {
+    let presses = solved.presses.as_ref().map(|presses| {
+        presses
+            .iter()
+            .map(|&(x, y)| Json::Array(vec![x.into(), y.into()]))
+            .collect::<Vec<_>>()
+    });
+    let mut entries = vec![
+        ("width", solved.board.width().into()),
+        ("height", solved.board.height().into()),
+        ("code", solved.board.to_code().into()),
+        ("id", solved.board.puzzle_id().into()),
+        ("seed", solved.seed.into()),
+        ("algorithm", solved.algorithm.to_string().into()),
+        ("outcome", outcome_name(solved.outcome).into()),
+        ("moves", presses.as_ref().map(Vec::len).into()),
+        ("presses", presses.into()),
+        ("seconds", solved.elapsed.as_secs_f64().into()),
+    ];
+    if let Some(constraints) = &solved.constraints {
+        entries.push(("constraints", constraints.to_string().into()));
+    }
+    if let Some(report) = &solved.report {
+        entries.push(("explored", report.explored.into()));
+        entries.push(("peak_memory", report.peak_memory.into()));
+    }
+    if solved.outcome == Outcome::Unsolvable {
+        if let Some(invariant) = solved.board.invariant() {
+            entries.push(("invariant", invariant.to_string().into()));
+        }
+    }
+    Json::object(entries)
+}
+
+const CSV_HEADER: &str = "seed,id,width,height,variant,algorithm,outcome,moves,nodes,seconds";
+
+pub fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Solved => "solved",
+        Outcome::Unsolvable => "unsolvable",
+        Outcome::LimitReached => "limit_reached",
+        Outcome::InputError => "input_error",
+    }
+}
+
+pub fn to_csv(solved: &Solved) -> String {
+    let optional = |value: Option<usize>| value.map_or(String::new(), |value| value.to_string());
+    format!(
+        "{},{},{},{},{},{},{},{},{},{:.6}",
+        solved.seed.map_or(String::new(), |seed| seed.to_string()),
+        solved.board.puzzle_id(),
+        solved.board.width(),
+        solved.board.height(),
+        solved.board.ruleset(),
+        solved.algorithm,
+        outcome_name(solved.outcome),
+        optional(solved.presses.as_ref().map(Vec::len)),
+        optional(solved.report.as_ref().map(|report| report.explored)),
+        solved.elapsed.as_secs_f64(),
+    )
+}
+
+/// Write csv rows to stdout, or append them to `--append`, adding the header
+/// only when the file is new or empty.
+fn write_csv(args: &Args, results: &[Solved]) -> std::io::Result<()> {
+    let rows: Vec<String> = results.iter().map(to_csv).collect();
+    match &args.append {
+        None => {
+            println!("{CSV_HEADER}");
+            rows.iter().for_each(|row| println!("{row}"));
+        }
+        Some(path) => {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            if file.metadata()?.len() == 0 {
+                writeln!(file, "{CSV_HEADER}")?;
+            }
+            for row in rows {
+                writeln!(file, "{row}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let boards = match (args.positional.first(), &args.id, &args.input) {
+        (Some(reference), _, _) => lookup(reference).map(|puzzle| {
+            vec![Task {
+                board: puzzle.board,
+                seed: None,
+                constraints: None,
+            }]
+        }),
+        (None, Some(id), _) => find_id(args, id).map(|board| {
+            vec![Task {
+                board,
+                seed: None,
+                constraints: None,
+            }]
+        }),
+        (None, None, Some(path)) => read_boards(path).map(|boards| {
+            boards
+                .into_iter()
+                .map(|(board, constraints)| Task {
+                    board,
+                    seed: None,
+                    constraints,
+                })
+                .collect()
+        }),
+        (None, None, None) => {
+            let seed = args.seed.unwrap_or_else(random);
+            args.random_board(seed).map(|board| {
+                vec![Task {
+                    board,
+                    seed: Some(seed),
+                    constraints: None,
+                }]
+            })
+        }
+    };
+    let results = match (boards, &args.manifest) {
+        (Ok(boards), None) => Ok(solve_boards(args, boards)),
+        (Ok(boards), Some(path)) => solve_resumable(args, boards, path),
+        (Err(err), _) => Err(err),
+    };
+    let results: Vec<Solved> = match results {
+        Ok(results) => results,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+
+    match args.output {
+        Output::Text => results.iter().for_each(|solved| print_text(args, solved)),
+        Output::Json if !args.shows(Verbosity::Normal) => (),
+        Output::Json if args.input.is_some() && args.positional.is_empty() && args.id.is_none() => {
+            println!("{}", Json::Array(results.iter().map(to_json).collect()));
+        }
+        Output::Json => println!("{}", to_json(&results[0])),
+        Output::Csv => {
+            if let Err(err) = write_csv(args, &results) {
+                eprintln!("error: {}: {err}", args.append.as_deref().unwrap_or("stdout"));
+                return Outcome::InputError;
+            }
+        }
+    }
+
+    // Report the worst outcome across the batch.
+    results
+        .iter()
+        .map(|solved| solved.outcome)
+        .max_by_key(|outcome| outcome.code())
+        .unwrap_or(Outcome::Solved)
+}
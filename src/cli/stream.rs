@@ -0,0 +1,93 @@
+//! `lightsout stream`: solvable boards written to stdout one per line at
+//! `--rate`, forever or for `--rounds`, for load-testing services that take
+//! puzzles or feeding a bot. Lines are puzzle codes, JSON objects or CSV
+//! rows as `--output` says.
+//!
+//! A reader that falls behind holds the stream up rather than having lines
+//! pile up for it: each line is flushed before the next is made, a full pipe
+//! blocks the write, and the generator only works a few boards ahead. Time
+//! lost to a blocked write isn't made up with a burst afterwards. A reader
+//! that goes away ends the stream quietly.
+
+use std::{
+    io::{stdout, ErrorKind, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use lightsout::{
+    generate::{generate_boards, Generated, Target},
+    json::Json,
+};
+use rand::prelude::*;
+
+use super::{Args, Outcome, Output};
+
+fn line(args: &Args, generated: &Generated) -> String {
+    let code = generated.board.to_code();
+    match args.output {
+        Output::Text => code,
+        Output::Json => Json::object([
+            ("seed", generated.seed.into()),
+            ("code", code.into()),
+            ("id", generated.board.puzzle_id().into()),
+            ("optimal", generated.optimal.into()),
+        ])
+        .to_string(),
+        Output::Csv => format!(
+            "{},{code},{},{}",
+            generated.seed,
+            generated.board.puzzle_id(),
+            generated.optimal
+        ),
+    }
+}
+
+pub fn run(args: &Args) -> Outcome {
+    let shape = match args.generation_shape() {
+        Ok(shape) => shape,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return Outcome::InputError;
+        }
+    };
+    let target = Target {
+        optimal: args.target_optimal,
+        unique: args.unique,
+        shape,
+    };
+    let first_seed = args.seed.unwrap_or_else(random);
+    let count = args.rounds.unwrap_or(usize::MAX);
+    let boards = generate_boards(args.width, args.height, first_seed, count, args.threads, target);
+    let interval = Duration::try_from_secs_f64(1.0 / args.rate).unwrap_or(Duration::ZERO);
+
+    let mut out = stdout().lock();
+    let mut written = 0;
+    let mut header = (args.output == Output::Csv).then_some("seed,code,id,optimal".to_string());
+    let mut due = Instant::now();
+    for generated in boards {
+        let now = Instant::now();
+        if due > now {
+            thread::sleep(due - now);
+        }
+        let mut text = header.take().into_iter().chain([line(args, &generated)]);
+        let result = text
+            .try_for_each(|text| writeln!(out, "{text}"))
+            .and_then(|_| out.flush());
+        match result {
+            Ok(()) => written += 1,
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => return Outcome::Solved,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return Outcome::InputError;
+            }
+        }
+        // Behind schedule, as after a blocked write: start again from now.
+        due = (due + interval).max(Instant::now());
+    }
+    if written < count && args.rounds.is_some() {
+        eprintln!("warning: only found {written} of {count} matching boards");
+        return Outcome::LimitReached;
+    }
+    Outcome::Solved
+}
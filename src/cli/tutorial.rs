@@ -0,0 +1,186 @@
+//! `lightsout tutorial`: a guided run through the light-chasing strategy on
+//! progressively harder boards. Presses that don't follow the strategy are
+//! refused with an explanation of what it calls for instead.
+
+use std::io::{stdin, stdout, BufRead, Write};
+
+use lightsout::{cell_name, parse_cell, theme::CLASSIC, Board, Rowtype};
+
+use super::{play::render_with_labels, Outcome};
+
+struct Lesson {
+    title: &'static str,
+    intro: &'static str,
+    size: (usize, usize),
+    /// Presses that build the lesson board from an empty one.
+    presses: &'static [&'static str],
+}
+
+const LESSONS: &[Lesson] = &[
+    Lesson {
+        title: "Pressing a cell",
+        intro: "Pressing a cell toggles it and its four neighbours. The goal is to turn\n\
+                every light off. Here one press in the middle will do it.",
+        size: (3, 3),
+        presses: &["b2"],
+    },
+    Lesson {
+        title: "Chasing the lights",
+        intro: "The chasing strategy: look at the top-most row with lights on, and for each\n\
+                lit cell press the cell directly below it. That clears the row without\n\
+                touching the rows above. Repeat row by row.",
+        size: (3, 3),
+        presses: &["a2", "c3"],
+    },
+    Lesson {
+        title: "Chasing a full board",
+        intro: "The same strategy works on bigger boards. Chase every row down to the bottom.",
+        size: (5, 5),
+        presses: &["b2", "d2", "c3", "a4", "e5"],
+    },
+    Lesson {
+        title: "Fixing the bottom row",
+        intro: "Sometimes chasing leaves lights on the bottom row, with nowhere below to press.\n\
+                The fix is to press certain cells on the top row, which depend only on the\n\
+                pattern left below, and then chase again. When you reach that point the\n\
+                tutorial will tell you which top-row cells to press.",
+        size: (5, 5),
+        presses: &["a1", "c3", "e4"],
+    },
+    Lesson {
+        title: "Putting it together",
+        intro: "Chase down, fix the top row, chase again. You're on your own now, though\n\
+                the tutorial will still catch strategy mistakes.",
+        size: (5, 5),
+        presses: &["b1", "d1", "a2", "c2", "e3", "b4", "c5"],
+    },
+];
+
+fn lesson_board(lesson: &Lesson) -> Board {
+    let mut board = Board::new(lesson.size.0, lesson.size.1);
+    for press in lesson.presses {
+        let (x, y) = parse_cell(press).expect("lesson presses are valid cells");
+        board.toggle(x, y);
+    }
+    board
+}
+
+fn names(row: Rowtype, y: usize, width: usize) -> String {
+    (0..width)
+        .filter(|x| row & (1 << x) != 0)
+        .map(|x| cell_name(x, y))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What the strategy calls for on the current board: the row to press in and
+/// which cells of it, plus why.
+fn expected(board: &Board, fixing: Rowtype) -> Option<(usize, Rowtype, String)> {
+    if fixing != 0 {
+        return Some((
+            0,
+            fixing,
+            format!(
+                "fix the bottom row by pressing {} on the top row",
+                names(fixing, 0, board.width())
+            ),
+        ));
+    }
+    let top = board.rows().position(|mut row| row.any(|lit| lit))?;
+    let row: Rowtype = board
+        .cells()
+        .filter(|&((_, y), lit)| y == top && lit)
+        .fold(0, |row, ((x, _), _)| row | (1 << x));
+    if top + 1 < board.height() {
+        Some((
+            top + 1,
+            row,
+            format!(
+                "row {} is the top-most lit row, so press below its lights: {}",
+                top + 1,
+                names(row, top + 1, board.width())
+            ),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Run one lesson; `Ok(true)` if completed, `Ok(false)` if skipped, `Err` on
+/// quit or end of input.
+fn run_lesson(
+    lesson: &Lesson,
+    lines: &mut impl Iterator<Item = std::io::Result<String>>,
+) -> Result<bool, ()> {
+    let mut board = lesson_board(lesson);
+    let mut fixing: Rowtype = 0;
+    println!("\n== {} ==\n{}", lesson.title, lesson.intro);
+    loop {
+        print!("\n{}", render_with_labels(&board, &CLASSIC));
+        if board.is_clear() {
+            println!("Well done!");
+            return Ok(true);
+        }
+        let bottom_only = board
+            .rows()
+            .take(board.height() - 1)
+            .all(|mut row| !row.any(|lit| lit));
+        if fixing == 0 && bottom_only && board.height() > 1 {
+            let explanation = board.explain_linear();
+            fixing = explanation.chosen.unwrap_or(0);
+            println!(
+                "Only the bottom row is left. For this pattern, press {} on the top row, then chase again.",
+                names(fixing, 0, board.width())
+            );
+        }
+        print!("> ");
+        stdout().flush().ok();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return Err(()),
+        };
+        match line.trim() {
+            "" => continue,
+            "quit" | "q" => return Err(()),
+            "skip" => return Ok(false),
+            "hint" | "h" => {
+                if let Some((_, _, why)) = expected(&board, fixing) {
+                    println!("Hint: {why}.");
+                }
+                continue;
+            }
+            _ => (),
+        }
+        let Some((x, y)) = parse_cell(&line).filter(|&(x, y)| x < board.width() && y < board.height())
+        else {
+            println!("Enter a cell like b2, or hint, skip, quit.");
+            continue;
+        };
+        match expected(&board, fixing) {
+            Some((row, cells, _)) if y == row && cells & (1 << x) != 0 => {
+                board.toggle(x, y);
+                if fixing != 0 {
+                    fixing &= !(1 << x);
+                }
+            }
+            Some((_, _, why)) => println!("Not quite: {why}."),
+            None => board.toggle(x, y),
+        }
+    }
+}
+
+pub fn run() -> Outcome {
+    println!("Lights Out tutorial. Enter cells like b2 to press them; type hint for help,");
+    println!("skip to move on to the next lesson, or quit to stop.");
+    let mut lines = stdin().lock().lines();
+    let mut completed = 0;
+    for lesson in LESSONS {
+        match run_lesson(lesson, &mut lines) {
+            Ok(true) => completed += 1,
+            Ok(false) => (),
+            Err(()) => return Outcome::Unsolvable,
+        }
+    }
+    println!("\nTutorial finished: {completed} of {} lessons completed.", LESSONS.len());
+    Outcome::Solved
+}
@@ -0,0 +1,139 @@
+use std::io::{self, Read, Write};
+
+use super::wire::base64;
+
+/// Appended to a client's key before hashing, as RFC 6455 specifies.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+pub const TEXT: u8 = 0x1;
+pub const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xa;
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64 * 8).to_be_bytes());
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, temp);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+    let mut digest = [0; 20];
+    for (bytes, word) in digest.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// The `Sec-WebSocket-Accept` value answering a client's
+/// `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{ACCEPT_GUID}", key.trim()).as_bytes()))
+}
+
+/// Accept an upgrade, after which the connection speaks frames.
+pub fn handshake(stream: &mut impl Write, key: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    )?;
+    stream.flush()
+}
+
+/// Send one unfragmented frame; servers never mask theirs.
+pub fn write_frame(stream: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut head = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => head.push(len as u8),
+        len @ 126..=0xffff => {
+            head.push(126);
+            head.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            head.push(127);
+            head.extend((len as u64).to_be_bytes());
+        }
+    }
+    stream.write_all(&head)?;
+    stream.write_all(payload)?;
+    stream.flush()
+}
+
+/// The next data message from a client as its opcode and unmasked payload,
+/// answering pings along the way. Fragmented messages and payloads over
+/// `limit` bytes are refused.
+pub fn read_message(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    limit: usize,
+) -> Result<(u8, Vec<u8>), String> {
+    let io_error = |err: io::Error| err.to_string();
+    loop {
+        let mut head = [0; 2];
+        reader.read_exact(&mut head).map_err(io_error)?;
+        let (fin, opcode) = (head[0] & 0x80 != 0, head[0] & 0x0f);
+        if !fin {
+            return Err("fragmented messages aren't supported".to_string());
+        }
+        let len = match head[1] & 0x7f {
+            126 => {
+                let mut len = [0; 2];
+                reader.read_exact(&mut len).map_err(io_error)?;
+                u16::from_be_bytes(len) as u64
+            }
+            127 => {
+                let mut len = [0; 8];
+                reader.read_exact(&mut len).map_err(io_error)?;
+                u64::from_be_bytes(len)
+            }
+            len => len as u64,
+        };
+        if len > limit as u64 {
+            return Err(format!("messages are limited to {limit} bytes"));
+        }
+        let mut mask = [0; 4];
+        if head[1] & 0x80 != 0 {
+            reader.read_exact(&mut mask).map_err(io_error)?;
+        }
+        let mut payload = vec![0; len as usize];
+        reader.read_exact(&mut payload).map_err(io_error)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+        match opcode {
+            PING => write_frame(writer, PONG, &payload).map_err(io_error)?,
+            PONG => (),
+            _ => return Ok((opcode, payload)),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::io::BufRead;
+
+use lightsout::{
+    cbor,
+    json::{self, Json},
+    msgpack,
+};
+
+/// How requests and replies are encoded, negotiated by content type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl Format {
+    /// The format for a `Content-Type` or `Accept` value, ignoring any
+    /// parameters like `charset`.
+    pub fn from_content_type(content_type: &str) -> Option<Format> {
+        let media_type = content_type.split(';').next()?.trim().to_ascii_lowercase();
+        match media_type.as_str() {
+            "application/json" => Some(Format::Json),
+            "application/cbor" => Some(Format::Cbor),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Format::MessagePack)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Cbor => "application/cbor",
+            Format::MessagePack => "application/msgpack",
+        }
+    }
+
+    pub fn encode(self, value: &Json) -> Vec<u8> {
+        match self {
+            Format::Json => value.to_string().into_bytes(),
+            Format::Cbor => cbor::encode(value),
+            Format::MessagePack => msgpack::encode(value),
+        }
+    }
+
+    /// Decode a whole message body.
+    pub fn decode(self, body: &[u8]) -> Result<Json, String> {
+        let mut reader = body;
+        let value = match self {
+            Format::Json => {
+                return json::parse(std::str::from_utf8(body).map_err(|_| "body is not UTF-8")?)
+            }
+            Format::Cbor => cbor::decode(&mut reader)?,
+            Format::MessagePack => msgpack::decode(&mut reader)?,
+        };
+        if !reader.is_empty() {
+            return Err("trailing bytes after the message".to_string());
+        }
+        Ok(value)
+    }
+
+    /// Read the next message from a stream: a line of JSON, or one binary
+    /// value, which needs no delimiter. `None` at the end of the stream.
+    /// Blank lines between JSON messages are skipped.
+    pub fn read(self, reader: &mut impl BufRead) -> Result<Option<Json>, String> {
+        if reader.fill_buf().map_err(|err| err.to_string())?.is_empty() {
+            return Ok(None);
+        }
+        match self {
+            Format::Json => {
+                let mut line = String::new();
+                while line.trim().is_empty() {
+                    line.clear();
+                    if reader.read_line(&mut line).map_err(|err| err.to_string())? == 0 {
+                        return Ok(None);
+                    }
+                }
+                json::parse(&line).map(Some)
+            }
+            Format::Cbor => cbor::decode(&mut reader.by_ref()).map(Some),
+            Format::MessagePack => msgpack::decode(&mut reader.by_ref()).map(Some),
+        }
+    }
+}
+
+/// Standard base64, with padding.
+pub fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,128 @@
+//! Lookup tables shared between solves.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use crate::{
+    core::{BatchSolver, Board, LinearSystem, Ruleset},
+    search::StateTable,
+};
+
+/// Tables keyed by board size, each built at most once. The map lock is only
+/// held to find or insert a slot; building happens outside it, so threads
+/// after different sizes don't wait on each other.
+struct Cache<K, V> {
+    slots: Mutex<HashMap<K, Arc<OnceLock<V>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+    fn get_or_init(&self, key: K, init: impl FnOnce() -> V) -> V {
+        let slot = Arc::clone(self.slots.lock().unwrap().entry(key).or_default());
+        if let Some(value) = slot.get() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return value.clone();
+        }
+        // Threads racing to build the same table all count as misses.
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        slot.get_or_init(init).clone()
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<K, V> Default for Cache<K, V> {
+    fn default() -> Self {
+        Cache {
+            slots: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+}
+
+/// How often lookups found a table already built, and how often they had
+/// to build one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Default)]
+struct Caches {
+    state_tables: Cache<(usize, usize, Ruleset), Option<Arc<StateTable>>>,
+    batch_solvers: Cache<(usize, usize), Arc<BatchSolver>>,
+    linear_systems: Cache<(usize, usize, Ruleset), Arc<LinearSystem>>,
+}
+
+/// Owns the per-size tables solvers reuse across requests: exhaustive state
+/// tables, the eliminated top-row systems behind [`BatchSolver`] and the
+/// whole-board ones in [`LinearSystem`] for variant rules. Each is
+/// built lazily on first use, then shared behind an `Arc`. Clones share the
+/// same caches, so one context can be handed to every worker of a server.
+#[derive(Clone, Default)]
+pub struct SolverContext {
+    caches: Arc<Caches>,
+}
+
+impl SolverContext {
+    pub fn new() -> SolverContext {
+        SolverContext::default()
+    }
+
+    /// The state table covering `board`'s size and rules, or `None` if it
+    /// has more than [`StateTable::MAX_CELLS`] cells.
+    pub fn state_table(&self, board: &Board) -> Option<Arc<StateTable>> {
+        let (width, height) = (board.width(), board.height());
+        let ruleset = board.ruleset().without_goal();
+        self.caches
+            .state_tables
+            .get_or_init((width, height, ruleset.clone()), || {
+                StateTable::with_ruleset(width, height, &ruleset).map(Arc::new)
+            })
+    }
+
+    /// The batch solver for `width` x `height` boards.
+    pub fn batch_solver(&self, width: usize, height: usize) -> Arc<BatchSolver> {
+        self.caches.batch_solvers.get_or_init((width, height), || {
+            Arc::new(BatchSolver::new(width, height))
+        })
+    }
+
+    /// The eliminated system for `board`'s size and rules, whatever its
+    /// goal.
+    pub fn linear_system(&self, board: &Board) -> Arc<LinearSystem> {
+        let (width, height) = (board.width(), board.height());
+        let ruleset = board.ruleset().without_goal();
+        self.caches
+            .linear_systems
+            .get_or_init((width, height, ruleset.clone()), || {
+                Arc::new(LinearSystem::new(width, height, &ruleset))
+            })
+    }
+
+    pub fn state_table_stats(&self) -> CacheStats {
+        self.caches.state_tables.stats()
+    }
+
+    pub fn batch_solver_stats(&self) -> CacheStats {
+        self.caches.batch_solvers.stats()
+    }
+
+    pub fn linear_system_stats(&self) -> CacheStats {
+        self.caches.linear_systems.stats()
+    }
+}
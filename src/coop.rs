@@ -0,0 +1,154 @@
+//! Lights Out for a crowd: many players sharing one board, all pressing
+//! whenever they like, for classrooms and parties.
+//!
+//! A [`SharedBoard`] takes presses one at a time in the order they arrive
+//! and numbers the board's states, so each player can say which state they
+//! were looking at when they pressed. Presses commute, so most that were
+//! made on an out-of-date view still mean what the player meant and are
+//! applied anyway. The ones that don't are refused as a [`Conflict`]: a
+//! press of a cell someone else pressed since, which would undo theirs
+//! when both were surely after the same light, and any press made before
+//! the board was reset or replaced.
+//!
+//! ```
+//! use lightsout::{coop::{Conflict, SharedBoard}, Board};
+//!
+//! let mut shared = SharedBoard::new(Board::from_code("3x3-5d0").unwrap());
+//! // Two players both see version 0 and go for the middle.
+//! assert_eq!(shared.press(1, 1, 1, Some(0)), Ok(1));
+//! assert_eq!(shared.press(2, 1, 1, Some(0)), Err(Conflict::Pressed { player: 1 }));
+//! assert!(shared.board().is_solved());
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::core::Board;
+
+/// Why a press was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// The cell is off the board or an obstacle.
+    NotPressable,
+    /// The board is already clear; it has to be reset or replaced first.
+    Cleared,
+    /// `player` pressed the same cell after the version the press was made
+    /// on, and this one would undo theirs.
+    Pressed { player: u64 },
+    /// The board was reset or replaced after the version the press was
+    /// made on.
+    Replaced,
+    /// The press claims a version the board hasn't reached.
+    Unknown,
+}
+
+/// One step between versions of the board.
+#[derive(Clone, Debug)]
+enum Change {
+    Press { player: u64, cell: (usize, usize) },
+    Replaced,
+}
+
+/// A board many players press at once. Each accepted change, a press or a
+/// new board, moves it on a version.
+#[derive(Clone, Debug)]
+pub struct SharedBoard {
+    start: Board,
+    board: Board,
+    /// Every change so far; the version is how many there have been.
+    log: Vec<Change>,
+    /// Where in `log` the current board was dealt or reset.
+    since: usize,
+}
+
+impl SharedBoard {
+    pub fn new(board: Board) -> SharedBoard {
+        SharedBoard {
+            start: board.clone(),
+            board,
+            log: Vec::new(),
+            since: 0,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The board as dealt, before anyone pressed it.
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    pub fn version(&self) -> u64 {
+        self.log.len() as u64
+    }
+
+    /// Press `(x, y)` for `player`, who last saw version `seen`, or the
+    /// current one if `None`. Returns the new version.
+    pub fn press(
+        &mut self,
+        player: u64,
+        x: usize,
+        y: usize,
+        seen: Option<u64>,
+    ) -> Result<u64, Conflict> {
+        let (width, height) = (self.board.width(), self.board.height());
+        if x >= width || y >= height || self.board.ruleset().is_obstacle(x, y) {
+            return Err(Conflict::NotPressable);
+        }
+        let seen = seen.unwrap_or(self.version());
+        if seen > self.version() {
+            return Err(Conflict::Unknown);
+        }
+        if (seen as usize) < self.since {
+            return Err(Conflict::Replaced);
+        }
+        for change in &self.log[seen as usize..] {
+            if let Change::Press {
+                player: other,
+                cell,
+            } = *change
+            {
+                if cell == (x, y) && other != player {
+                    return Err(Conflict::Pressed { player: other });
+                }
+            }
+        }
+        if self.board.is_solved() {
+            return Err(Conflict::Cleared);
+        }
+        self.board.toggle(x, y);
+        self.log.push(Change::Press {
+            player,
+            cell: (x, y),
+        });
+        Ok(self.version())
+    }
+
+    /// Put the board back as it was dealt. Returns the new version.
+    pub fn reset(&mut self) -> u64 {
+        let start = self.start.clone();
+        self.replace(start)
+    }
+
+    /// Deal a different board. Returns the new version.
+    pub fn replace(&mut self, board: Board) -> u64 {
+        self.start = board.clone();
+        self.board = board;
+        self.log.push(Change::Replaced);
+        self.since = self.log.len();
+        self.version()
+    }
+
+    /// Presses each player has made on the current board since it was
+    /// dealt or reset, for sharing out the credit once it's clear.
+    pub fn tally(&self) -> BTreeMap<u64, usize> {
+        let mut tally = BTreeMap::new();
+        for change in &self.log[self.since..] {
+            if let Change::Press { player, .. } = change {
+                *tally.entry(*player).or_insert(0) += 1;
+            }
+        }
+        tally
+    }
+}
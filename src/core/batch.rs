@@ -0,0 +1,316 @@
+use alloc::vec::Vec;
+
+use super::{
+    board::{BitMask, Board, Rowtype},
+    linear::{searched, LinearSystem},
+};
+
+/// One bit per board being solved side by side.
+type Lanes = u64;
+
+/// Solves many same-sized boards at once by bit-slicing them: each cell is
+/// held as a word whose bit `k` belongs to board `k`, so one chase over the
+/// grid advances [`BatchSolver::LANES`] boards together.
+///
+/// The top-row system only depends on the board size, so it's eliminated
+/// once up front with the residual left symbolic. Each batch then finds its
+/// particular top rows and solvability as parities of the residual bits.
+/// Results match [`Board::solve_linear`] exactly, ties included. Boards
+/// whose rules don't press classically are solved one by one, sharing a
+/// [`LinearSystem`] between those with the same rules.
+#[derive(Clone, Debug)]
+pub struct BatchSolver {
+    width: usize,
+    height: usize,
+    /// For each top-row cell, the residual bits whose parity presses it.
+    particular: Vec<Rowtype>,
+    /// Residual bit sets that must have even parity for a board to be solvable.
+    checks: Vec<Rowtype>,
+    kernel: Vec<Rowtype>,
+}
+
+impl BatchSolver {
+    /// Boards solved per pass.
+    pub const LANES: usize = Lanes::BITS as usize;
+
+    pub fn new(width: usize, height: usize) -> BatchSolver {
+        let mut solver = BatchSolver {
+            width,
+            height,
+            particular: alloc::vec![0; width],
+            checks: Vec::new(),
+            kernel: Vec::new(),
+        };
+        if width == 0 || height == 0 {
+            return solver;
+        }
+        let empty = Board::new(width, height);
+        let columns: Vec<Rowtype> = (0..width).map(|x| empty.chase(1 << x).1).collect();
+
+        // The same elimination as `solve_gf2`, except each equation's
+        // right-hand side is the set of residual bits it has absorbed.
+        let mut rows: Vec<(Rowtype, Rowtype)> = (0..width)
+            .map(|y| {
+                let coefficients = columns
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (x, col)| acc | (((col >> y) & 1) << x));
+                (coefficients, 1 << y)
+            })
+            .collect();
+        let mut pivots: Vec<usize> = Vec::new();
+        for col in 0..width {
+            let rank = pivots.len();
+            let Some(found) = (rank..rows.len()).find(|&r| rows[r].0 & (1 << col) != 0) else {
+                continue;
+            };
+            rows.swap(rank, found);
+            let pivot = rows[rank];
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r != rank && row.0 & (1 << col) != 0 {
+                    row.0 ^= pivot.0;
+                    row.1 ^= pivot.1;
+                }
+            }
+            pivots.push(col);
+        }
+        for (r, &col) in pivots.iter().enumerate() {
+            solver.particular[col] = rows[r].1;
+        }
+        solver.checks = rows[pivots.len()..].iter().map(|&(_, rhs)| rhs).collect();
+        solver.kernel = (0..width)
+            .filter(|col| !pivots.contains(col))
+            .map(|free| {
+                let mut vector: Rowtype = 1 << free;
+                for (r, &col) in pivots.iter().enumerate() {
+                    if rows[r].0 & (1 << free) != 0 {
+                        vector |= 1 << col;
+                    }
+                }
+                vector
+            })
+            .collect();
+        solver
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The smallest solution of each board, or `None` for unsolvable ones,
+    /// in the same order as `boards`.
+    ///
+    /// Panics if any board's size differs from the solver's.
+    pub fn solve(&self, boards: &[Board]) -> Vec<Option<BitMask>> {
+        assert!(
+            boards
+                .iter()
+                .all(|board| board.width() == self.width && board.height() == self.height),
+            "every board must match the solver's size"
+        );
+        if self.width == 0 || self.height == 0 {
+            return boards
+                .iter()
+                .map(|_| Some(BitMask::new(self.width, self.height)))
+                .collect();
+        }
+        // Bit-slicing relies on light-chasing, so boards with other presses
+        // are solved one at a time and merged back in order.
+        let (classic, other): (Vec<usize>, Vec<usize>) =
+            (0..boards.len()).partition(|&i| boards[i].presses_classically());
+        let classic_boards: Vec<&Board> = classic.iter().map(|&i| &boards[i]).collect();
+        let mut results: Vec<Option<BitMask>> = alloc::vec![None; boards.len()];
+        let solved = classic_boards
+            .chunks(Self::LANES)
+            .flat_map(|chunk| self.solve_chunk(chunk));
+        for (index, solution) in classic.iter().zip(solved) {
+            results[*index] = solution;
+        }
+        // Eliminated once per set of rules rather than once per board.
+        let mut systems: Vec<LinearSystem> = Vec::new();
+        for index in other {
+            let board = &boards[index];
+            let system = match systems.iter().position(|system| system.covers(board)) {
+                Some(found) => &systems[found],
+                None => {
+                    systems.push(LinearSystem::new(self.width, self.height, board.ruleset()));
+                    systems.last().unwrap()
+                }
+            };
+            results[index] = system.solve(board);
+        }
+        results
+    }
+
+    /// [`BatchSolver::solve`] for large sweeps, split into runs of whole
+    /// passes across `threads` worker threads. Every solution is checked by
+    /// pressing it on its board, and any that fails is replaced by
+    /// [`Board::solve_linear`]'s answer, as is every board a lane calls
+    /// unsolvable, so a fault in the bit-sliced path costs time rather than
+    /// correctness. There's no GPU path; threads are the only parallelism.
+    ///
+    /// Panics if any board's size differs from the solver's.
+    #[cfg(feature = "std")]
+    pub fn solve_parallel(&self, boards: &[Board], threads: usize) -> Vec<Option<BitMask>> {
+        let passes = boards.len().div_ceil(Self::LANES);
+        let run = passes.div_ceil(threads.max(1)).max(1) * Self::LANES;
+        let solved: Vec<Option<BitMask>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = boards
+                .chunks(run)
+                .map(|chunk| scope.spawn(|| self.solve(chunk)))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+        boards
+            .iter()
+            .zip(solved)
+            .map(|(board, solution)| {
+                let Some(presses) = &solution else {
+                    return board.solve_linear();
+                };
+                let mut pressed = board.clone();
+                pressed.apply_press_set(presses);
+                if pressed.is_solved() {
+                    solution
+                } else {
+                    board.solve_linear()
+                }
+            })
+            .collect()
+    }
+
+    fn solve_chunk(&self, boards: &[&Board]) -> Vec<Option<BitMask>> {
+        let (width, height) = (self.width, self.height);
+        let mut lit: Vec<Lanes> = alloc::vec![0; width * height];
+        for (lane, board) in boards.iter().enumerate() {
+            let rules = board.ruleset();
+            for y in 0..height {
+                for x in 0..width {
+                    let goal = rules.goal_row(y) & (1 << x) != 0;
+                    if board.get(x, y) != goal {
+                        lit[y * width + x] |= 1 << lane;
+                    }
+                }
+            }
+        }
+
+        let (_, residual) = self.chase(&lit, &alloc::vec![0; width]);
+        let parity = |bits: Rowtype| {
+            (0..width)
+                .filter(|j| bits & (1 << j) != 0)
+                .fold(0, |acc, j| acc ^ residual[j])
+        };
+        let solvable = self.checks.iter().fold(Lanes::MAX, |acc, &check| acc & !parity(check));
+        let particular: Vec<Lanes> = self.particular.iter().map(|&bits| parity(bits)).collect();
+
+        // Try every kernel combination in the same order as `solve_linear`,
+        // keeping each lane's best press set with a bit-sliced counter.
+        let planes = (usize::BITS - (width * height).leading_zeros()) as usize;
+        let mut best_presses: Vec<Lanes> = Vec::new();
+        let mut best_count: Vec<Lanes> = Vec::new();
+        let kernel = searched(&self.kernel);
+        for combination in 0..(1u128 << kernel.len()) {
+            let first: Vec<Lanes> = (0..width)
+                .map(|x| {
+                    let flip = kernel
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, vector)| combination & (1 << i) != 0 && *vector & (1 << x) != 0)
+                        .count()
+                        % 2;
+                    particular[x] ^ if flip == 1 { Lanes::MAX } else { 0 }
+                })
+                .collect();
+            let (presses, _) = self.chase(&lit, &first);
+            let count = count_lanes(&presses, planes);
+            if best_presses.is_empty() {
+                (best_presses, best_count) = (presses, count);
+                continue;
+            }
+            let better = less_than(&count, &best_count);
+            for (best, current) in best_presses.iter_mut().zip(&presses) {
+                *best = (*best & !better) | (current & better);
+            }
+            for (best, current) in best_count.iter_mut().zip(&count) {
+                *best = (*best & !better) | (current & better);
+            }
+        }
+
+        (0..boards.len())
+            .map(|lane| {
+                (solvable & (1 << lane) != 0).then(|| {
+                    let mut solution = BitMask::new(width, height);
+                    for (index, presses) in best_presses.iter().enumerate() {
+                        if presses & (1 << lane) != 0 {
+                            solution.set(index % width, index / width, true);
+                        }
+                    }
+                    solution
+                })
+            })
+            .collect()
+    }
+
+    /// Bit-sliced [`Board::chase`]: the presses made in every cell and the
+    /// lanes left lit on the bottom row.
+    fn chase(&self, lit: &[Lanes], first: &[Lanes]) -> (Vec<Lanes>, Vec<Lanes>) {
+        let (width, height) = (self.width, self.height);
+        let mut lit = lit.to_vec();
+        let mut presses = Vec::with_capacity(width * height);
+        let mut press = first.to_vec();
+        for y in 0..height {
+            for x in 0..width {
+                let here = press[x];
+                if y > 0 {
+                    lit[(y - 1) * width + x] ^= here;
+                }
+                if y + 1 < height {
+                    lit[(y + 1) * width + x] ^= here;
+                }
+                let left = if x > 0 { press[x - 1] } else { 0 };
+                let right = if x + 1 < width { press[x + 1] } else { 0 };
+                lit[y * width + x] ^= left ^ here ^ right;
+            }
+            presses.extend_from_slice(&press);
+            press.copy_from_slice(&lit[y * width..(y + 1) * width]);
+        }
+        let bottom = lit[(height - 1) * width..].to_vec();
+        (presses, bottom)
+    }
+}
+
+/// Per-lane population counts of `words`, as `planes` bit planes, least
+/// significant first.
+fn count_lanes(words: &[Lanes], planes: usize) -> Vec<Lanes> {
+    let mut count = alloc::vec![0; planes];
+    for &word in words {
+        let mut carry = word;
+        for plane in count.iter_mut() {
+            let sum = *plane ^ carry;
+            carry &= *plane;
+            *plane = sum;
+            if carry == 0 {
+                break;
+            }
+        }
+    }
+    count
+}
+
+/// Lanes where bit-sliced number `a` is strictly below `b`.
+fn less_than(a: &[Lanes], b: &[Lanes]) -> Lanes {
+    let mut less = 0;
+    let mut equal = Lanes::MAX;
+    for (a, b) in a.iter().zip(b).rev() {
+        less |= equal & !a & b;
+        equal &= !(a ^ b);
+    }
+    less
+}
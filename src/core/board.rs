@@ -0,0 +1,385 @@
+use alloc::{sync::Arc, vec::Vec};
+use ::core::fmt::Display;
+
+use super::{
+    oracle,
+    rules::{Ruleset, CLASSIC},
+};
+
+pub type Rowtype = u64;
+
+/// Mask with the lowest `width` bits set.
+pub fn full_row(width: usize) -> Rowtype {
+    if width >= Rowtype::BITS as usize {
+        Rowtype::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+/// A grid of lights, each on or off, and the rules pressing them follows.
+///
+/// ```
+/// use lightsout::Board;
+///
+/// let mut board = Board::new(3, 3);
+/// board.toggle(1, 1);
+/// // Pressing a cell flips it and its orthogonal neighbours.
+/// assert_eq!(board.lit_count(), 5);
+/// assert!(board.get(1, 0) && !board.get(0, 0));
+///
+/// let code = board.to_code();
+/// assert_eq!(Board::from_code(&code), Ok(board.clone()));
+///
+/// board.toggle(1, 1);
+/// assert!(board.is_clear());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Board {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) rows: Vec<Rowtype>,
+    /// `None` for the classic rules, which keeps the common case cheap to
+    /// clone and lets presses take the row-at-a-time fast path.
+    pub(crate) rules: Option<Arc<Ruleset>>,
+}
+
+impl Board {
+    pub const MAX_WIDTH: usize = Rowtype::BITS as usize;
+
+    pub fn new(width: usize, height: usize) -> Board {
+        Board {
+            width,
+            height,
+            rows: (0..height).map(|_| 0).collect(),
+            rules: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The rules this board is played by.
+    pub fn ruleset(&self) -> &Ruleset {
+        self.rules.as_deref().unwrap_or(&CLASSIC)
+    }
+
+    /// Switch the board to `ruleset`, turning off any lights on obstacles.
+    /// Check the rules fit first with [`Ruleset::check`]; masks of the wrong
+    /// size panic here or later.
+    pub fn set_ruleset(&mut self, ruleset: Ruleset) {
+        if let Some(obstacles) = &ruleset.obstacles {
+            for (row, holes) in self.rows.iter_mut().zip(&obstacles.rows) {
+                *row &= !holes;
+            }
+        }
+        self.rules = (!ruleset.is_classic()).then(|| Arc::new(ruleset));
+    }
+
+    /// [`Board::set_ruleset`], by value.
+    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Board {
+        self.set_ruleset(ruleset);
+        self
+    }
+
+    /// Whether presses take the classic row-at-a-time path.
+    pub(crate) fn presses_classically(&self) -> bool {
+        self.rules.as_ref().is_none_or(|rules| rules.presses_classically())
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & (1 << x) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.rows[y] = (self.rows[y] & !(1 << x)) | (Into::<Rowtype>::into(value) << x);
+    }
+
+    /// Each row from top to bottom, as whether its cells are lit from left
+    /// to right.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = bool>> + '_ {
+        let width = self.width;
+        self.rows
+            .iter()
+            .map(move |&row| (0..width).map(move |x| row & (1 << x) != 0))
+    }
+
+    /// Every cell's position and whether it's lit, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), bool)> + '_ {
+        self.rows()
+            .enumerate()
+            .flat_map(|(y, row)| row.enumerate().map(move |(x, lit)| ((x, y), lit)))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn randomize(&mut self, seed: u64) {
+        use rand::prelude::*;
+        let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+        for y in 0..self.height {
+            self.rows[y] = rng.gen::<Rowtype>() & full_row(self.width);
+        }
+        if let Some(obstacles) = &self.ruleset().obstacles {
+            let holes = obstacles.rows.clone();
+            for (row, holes) in self.rows.iter_mut().zip(holes) {
+                *row &= !holes;
+            }
+        }
+    }
+
+    /// Press every cell of row `y` set in `mask` at once, by the classic
+    /// rules whatever the board's own.
+    pub(crate) fn press_row(&self, rows: &mut [Rowtype], y: usize, mask: Rowtype) {
+        if y > 0 {
+            rows[y - 1] ^= mask;
+        }
+        rows[y] ^= (mask ^ (mask << 1) ^ (mask >> 1)) & full_row(self.width);
+        if y < self.height - 1 {
+            rows[y + 1] ^= mask;
+        }
+    }
+
+    /// Press every cell of row `y` set in `mask`, a whole row's worth of
+    /// toggles in a handful of word operations.
+    pub fn toggle_row(&mut self, y: usize, mask: Rowtype) {
+        let before = oracle::sampled().then(|| self.clone());
+        self.press_mask(y, mask);
+        if let Some(before) = before {
+            oracle::check_row_press(&before, y, mask);
+        }
+    }
+
+    /// [`Board::toggle_row`] without the [`oracle`] looking on.
+    pub(crate) fn press_mask(&mut self, y: usize, mask: Rowtype) {
+        let mask = mask & full_row(self.width);
+        if self.presses_classically() {
+            let mut rows = ::core::mem::take(&mut self.rows);
+            self.press_row(&mut rows, y, mask);
+            self.rows = rows;
+            return;
+        }
+        let Some(rules) = &self.rules else {
+            return;
+        };
+        for x in (0..self.width).filter(|x| mask & (1 << x) != 0) {
+            for (nx, ny) in rules.affected(x, y, self.width, self.height) {
+                self.rows[ny] ^= 1 << nx;
+            }
+        }
+    }
+
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        self.toggle_row(y, 1 << x);
+    }
+
+    pub fn clone_toggle(&self, x: usize, y: usize) -> Board {
+        let mut new_board = self.clone();
+        new_board.toggle(x, y);
+        new_board
+    }
+
+    /// Press every cell set in `presses` in one go, a row at a time; pressing
+    /// a solution this way leaves the board clear.
+    ///
+    /// Panics if the mask is a different size from the board.
+    pub fn apply_press_set(&mut self, presses: &BitMask) {
+        self.assert_same_size(presses.width, presses.height);
+        for (y, &mask) in presses.rows.iter().enumerate() {
+            self.toggle_row(y, mask);
+        }
+    }
+
+    /// The board lit wherever exactly one of `self` and `other` is, e.g. the
+    /// residual between a board and the one a player is aiming for.
+    ///
+    /// Panics if the boards are different sizes.
+    pub fn xor(&self, other: &Board) -> Board {
+        self.assert_same_size(other.width, other.height);
+        Board {
+            width: self.width,
+            height: self.height,
+            rows: self.rows.iter().zip(&other.rows).map(|(a, b)| a ^ b).collect(),
+            rules: self.rules.clone(),
+        }
+    }
+
+    /// The cells whose state differs between `self` and `other`.
+    ///
+    /// Panics if the boards are different sizes.
+    pub fn diff(&self, other: &Board) -> BitMask {
+        let xor = self.xor(other);
+        BitMask {
+            width: xor.width,
+            height: xor.height,
+            rows: xor.rows,
+        }
+    }
+
+    fn assert_same_size(&self, width: usize, height: usize) {
+        assert!(
+            self.width == width && self.height == height,
+            "size mismatch: {}x{} against {width}x{height}",
+            self.width,
+            self.height
+        );
+    }
+
+    /// Lit cell count after pressing each cell (indexed `y * width + x`),
+    /// worked out from popcount deltas on the three affected rows rather than
+    /// by building and recounting every child board.
+    pub fn child_lit_counts(&self) -> Vec<usize> {
+        self.child_lit_counts_from(self.lit_count())
+    }
+
+    /// [`Board::child_lit_counts`] for callers that already know the board's
+    /// own lit count.
+    pub fn child_lit_counts_from(&self, lit: usize) -> Vec<usize> {
+        let counts = self.child_lit_counts_unchecked(lit);
+        if oracle::sampled() {
+            oracle::check_child_lit_counts(self, lit, &counts);
+        }
+        counts
+    }
+
+    /// [`Board::child_lit_counts_from`] without the [`oracle`] looking on.
+    pub(crate) fn child_lit_counts_unchecked(&self, lit: usize) -> Vec<usize> {
+        if !self.presses_classically() {
+            return (0..self.width * self.height)
+                .map(|i| self.clone_toggle(i % self.width, i / self.width).lit_count())
+                .collect();
+        }
+        let lit = lit as isize;
+        let full = full_row(self.width);
+        let mut counts = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            let row = self.rows[y];
+            let above = if y > 0 { self.rows[y - 1] } else { 0 };
+            let below = if y < self.height - 1 { self.rows[y + 1] } else { 0 };
+            for x in 0..self.width {
+                let bit: Rowtype = 1 << x;
+                let mut delta = flip_delta(row, (bit | (bit << 1) | (bit >> 1)) & full);
+                if y > 0 {
+                    delta += flip_delta(above, bit);
+                }
+                if y < self.height - 1 {
+                    delta += flip_delta(below, bit);
+                }
+                counts.push((lit + delta) as usize);
+            }
+        }
+        counts
+    }
+
+    pub fn lit_count(&self) -> usize {
+        self.rows.iter().map(|row| row.count_ones() as usize).sum()
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.rows.iter().all(|row| *row == 0)
+    }
+
+    /// Cells that differ from the goal pattern; the lit count for boards
+    /// aiming to turn every light off.
+    pub fn mismatches(&self) -> usize {
+        let rules = self.ruleset();
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(y, row)| (row ^ rules.goal_row(y)).count_ones() as usize)
+            .sum()
+    }
+
+    /// Whether the board shows its goal pattern.
+    pub fn is_solved(&self) -> bool {
+        match &self.ruleset().goal {
+            None => self.is_clear(),
+            Some(goal) => self.rows == goal.rows,
+        }
+    }
+}
+
+/// Change in lit count from flipping the cells of `mask` in `row`.
+fn flip_delta(row: Rowtype, mask: Rowtype) -> isize {
+    (mask & !row).count_ones() as isize - (mask & row).count_ones() as isize
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let rules = self.ruleset();
+        for (y, row) in self.rows().enumerate() {
+            for (x, lit) in row.enumerate() {
+                f.write_str(match (rules.is_obstacle(x, y), lit) {
+                    (true, _) => "  ",
+                    (false, true) => "██",
+                    (false, false) => "░░",
+                })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl ::core::hash::Hash for Board {
+    fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        self.rows.hash(state);
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.ruleset() == other.ruleset()
+    }
+}
+
+impl Eq for Board {}
+
+/// A set of cells on a board, such as the presses making up a solution.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMask {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) rows: Vec<Rowtype>,
+}
+
+impl BitMask {
+    pub fn new(width: usize, height: usize) -> BitMask {
+        BitMask {
+            width,
+            height,
+            rows: (0..height).map(|_| 0).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & (1 << x) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.rows[y] = (self.rows[y] & !(1 << x)) | (Into::<Rowtype>::into(value) << x);
+    }
+
+    pub fn count(&self) -> usize {
+        self.rows.iter().map(|row| row.count_ones() as usize).sum()
+    }
+
+    /// Cells in the mask, in row-major order.
+    pub fn positions(&self) -> Vec<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|(x, y)| self.get(*x, *y))
+            .collect()
+    }
+}
@@ -0,0 +1,178 @@
+use ::core::fmt::Display;
+
+use super::{
+    board::{BitMask, Board},
+    rules::{Neighborhood, Ruleset, RulesError},
+};
+
+/// Why a [`BoardBuilder`] couldn't build its board.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildError {
+    ZeroWidth,
+    ZeroHeight,
+    TooWide(usize),
+    /// Densities are probabilities, so must lie in `0.0..=1.0`.
+    BadDensity(f64),
+    /// The rules don't fit the board.
+    Rules(RulesError),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            BuildError::ZeroWidth => write!(f, "board width must be at least 1"),
+            BuildError::ZeroHeight => write!(f, "board height must be at least 1"),
+            BuildError::TooWide(width) => write!(
+                f,
+                "board is {width} cells wide, the most supported is {}",
+                Board::MAX_WIDTH
+            ),
+            BuildError::BadDensity(density) => {
+                write!(f, "density {density} is not between 0 and 1")
+            }
+            BuildError::Rules(err) => err.fmt(f),
+        }
+    }
+}
+
+impl ::core::error::Error for BuildError {}
+
+/// Validated construction of boards, in place of a constructor per
+/// combination of options, e.g.
+/// `BoardBuilder::new().width(7).height(5).density(0.4).seed(1234).build()`.
+/// Without a seed the board starts dark.
+#[derive(Clone, Debug)]
+pub struct BoardBuilder {
+    width: usize,
+    height: usize,
+    ruleset: Ruleset,
+    #[cfg(feature = "std")]
+    density: Option<f64>,
+    #[cfg(feature = "std")]
+    seed: Option<u64>,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        BoardBuilder {
+            width: 5,
+            height: 5,
+            ruleset: Ruleset::CLASSIC,
+            #[cfg(feature = "std")]
+            density: None,
+            #[cfg(feature = "std")]
+            seed: None,
+        }
+    }
+}
+
+impl BoardBuilder {
+    pub fn new() -> BoardBuilder {
+        BoardBuilder::default()
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Play by `ruleset`, replacing any rules set so far.
+    pub fn ruleset(mut self, ruleset: Ruleset) -> Self {
+        self.ruleset = ruleset;
+        self
+    }
+
+    /// Which cells a press toggles.
+    pub fn neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.ruleset.neighborhood = neighborhood;
+        self
+    }
+
+    /// Whether presses on one edge wrap round to the opposite edge.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.ruleset.wrap = wrap;
+        self
+    }
+
+    /// How many states each cell cycles through; classic boards have 2.
+    pub fn states(mut self, states: u8) -> Self {
+        self.ruleset.states = states;
+        self
+    }
+
+    /// The pattern to finish on, instead of every light off.
+    pub fn goal(mut self, goal: BitMask) -> Self {
+        self.ruleset.goal = Some(goal);
+        self
+    }
+
+    /// Cells that are missing from the board altogether.
+    pub fn obstacles(mut self, obstacles: BitMask) -> Self {
+        self.ruleset.obstacles = Some(obstacles);
+        self
+    }
+
+    /// Chance of each cell starting lit when randomized with
+    /// [`BoardBuilder::seed`]. Leaving it unset keeps boards identical to
+    /// [`Board::randomize`] for the same seed.
+    #[cfg(feature = "std")]
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = Some(density);
+        self
+    }
+
+    /// Randomize the starting lights from `seed`.
+    #[cfg(feature = "std")]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Check the options, then build the board.
+    pub fn build(&self) -> Result<Board, BuildError> {
+        if self.width == 0 {
+            return Err(BuildError::ZeroWidth);
+        }
+        if self.height == 0 {
+            return Err(BuildError::ZeroHeight);
+        }
+        if self.width > Board::MAX_WIDTH {
+            return Err(BuildError::TooWide(self.width));
+        }
+        #[cfg(feature = "std")]
+        if let Some(density) = self.density.filter(|density| !(0.0..=1.0).contains(density)) {
+            return Err(BuildError::BadDensity(density));
+        }
+        self.ruleset
+            .check(self.width, self.height)
+            .map_err(BuildError::Rules)?;
+
+        let mut board = Board::new(self.width, self.height);
+        #[cfg(feature = "std")]
+        if let Some(seed) = self.seed {
+            self.fill(&mut board, seed);
+        }
+        board.set_ruleset(self.ruleset.clone());
+        Ok(board)
+    }
+
+    #[cfg(feature = "std")]
+    fn fill(&self, board: &mut Board, seed: u64) {
+        use rand::prelude::*;
+        let Some(density) = self.density else {
+            board.randomize(seed);
+            return;
+        };
+        let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                board.set(x, y, rng.gen_bool(density));
+            }
+        }
+    }
+}
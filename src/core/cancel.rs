@@ -0,0 +1,47 @@
+use alloc::sync::Arc;
+use ::core::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A flag shared between a running solver and whoever may want to stop it.
+/// Clones share the same flag; solvers check it once per step of their main
+/// loop and give up with [`Cancelled`] as soon as it's set.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Ask every solver holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(Cancelled)` once the token has been cancelled, for use with `?`.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A solver stopped early because its [`CancellationToken`] was cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str("solve was cancelled")
+    }
+}
+
+impl ::core::error::Error for Cancelled {}
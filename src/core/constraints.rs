@@ -0,0 +1,86 @@
+use alloc::{string::String, vec::Vec};
+use ::core::fmt::Display;
+
+use super::{
+    board::BitMask,
+    parse::{cell_name, parse_cell, ParseError},
+};
+
+/// Cells a solution has to press and cells it mustn't, for puzzle setters
+/// steering a board towards the solution they intended.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PressConstraints {
+    pub required: BitMask,
+    pub forbidden: BitMask,
+}
+
+impl PressConstraints {
+    /// No constraints on a `width` x `height` board.
+    pub fn new(width: usize, height: usize) -> PressConstraints {
+        PressConstraints {
+            required: BitMask::new(width, height),
+            forbidden: BitMask::new(width, height),
+        }
+    }
+
+    pub fn require(&mut self, x: usize, y: usize) {
+        self.required.set(x, y, true);
+        self.forbidden.set(x, y, false);
+    }
+
+    pub fn forbid(&mut self, x: usize, y: usize) {
+        self.forbidden.set(x, y, true);
+        self.required.set(x, y, false);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.required.count() == 0 && self.forbidden.count() == 0
+    }
+
+    /// Whether `presses` includes every required cell and no forbidden one.
+    pub fn allows(&self, presses: &BitMask) -> bool {
+        presses.rows.iter().enumerate().all(|(y, row)| {
+            row & self.required.rows[y] == self.required.rows[y]
+                && row & self.forbidden.rows[y] == 0
+        })
+    }
+
+    /// Parse the form written by `Display`: cell names separated by spaces
+    /// or commas, each one required unless prefixed with `!`, as in
+    /// `a1 c3 !b2`.
+    pub fn parse(text: &str, width: usize, height: usize) -> Result<PressConstraints, ParseError> {
+        let mut constraints = PressConstraints::new(width, height);
+        for item in text.split([' ', ',']).filter(|item| !item.is_empty()) {
+            let (forbid, name) = match item.strip_prefix('!') {
+                Some(name) => (true, name),
+                None => (false, item),
+            };
+            let (x, y) = parse_cell(name)
+                .filter(|&(x, y)| x < width && y < height)
+                .ok_or_else(|| ParseError::BadPress(String::from(item)))?;
+            if forbid {
+                constraints.forbid(x, y);
+            } else {
+                constraints.require(x, y);
+            }
+        }
+        Ok(constraints)
+    }
+}
+
+impl Display for PressConstraints {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let required = self
+            .required
+            .positions()
+            .into_iter()
+            .map(|(x, y)| cell_name(x, y));
+        let forbidden = self
+            .forbidden
+            .positions()
+            .into_iter()
+            .map(|(x, y)| alloc::format!("!{}", cell_name(x, y)));
+        let cells: Vec<String> = required.chain(forbidden).collect();
+        f.write_str(&cells.join(" "))
+    }
+}
@@ -0,0 +1,134 @@
+use alloc::vec::Vec;
+
+use super::board::{Board, Rowtype};
+
+/// Every solvable board of a size under the classic rules, one at a time,
+/// see [`Board::enumerate_solvable`].
+///
+/// The solvable boards are the combinations of a basis of what single
+/// presses do, visited in Gray code order so each board differs from the
+/// one before by a single basis vector. Only the basis and the current board are held,
+/// and [`Iterator::nth`] jumps straight to an index, so `skip` and `take`
+/// split the boards into chunks for separate workers.
+#[derive(Clone, Debug)]
+pub struct SolvableBoards {
+    width: usize,
+    height: usize,
+    basis: Vec<Vec<Rowtype>>,
+    current: Vec<Rowtype>,
+    index: u128,
+    end: u128,
+}
+
+impl SolvableBoards {
+    fn new(width: usize, height: usize) -> SolvableBoards {
+        let empty = Board::new(width, height);
+        let mut reduced: Vec<((usize, usize), Vec<Rowtype>)> = Vec::new();
+        for press in 0..width * height {
+            let mut rows = empty.clone_toggle(press % width, press / width).rows;
+            for (pivot, vector) in &reduced {
+                if rows[pivot.1] & (1 << pivot.0) != 0 {
+                    rows.iter_mut().zip(vector).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            let Some(y) = rows.iter().position(|&row| row != 0) else {
+                continue;
+            };
+            let pivot = (rows[y].trailing_zeros() as usize, y);
+            // Keep every vector clear of the new pivot, so each pivot is set
+            // in exactly one of them.
+            for (_, vector) in &mut reduced {
+                if vector[y] & (1 << pivot.0) != 0 {
+                    vector.iter_mut().zip(&rows).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            reduced.push((pivot, rows));
+        }
+        let basis: Vec<_> = reduced.into_iter().map(|(_, vector)| vector).collect();
+        SolvableBoards {
+            width,
+            height,
+            end: 1u128.checked_shl(basis.len() as u32).unwrap_or(u128::MAX),
+            basis,
+            current: alloc::vec![0; height],
+            index: 0,
+        }
+    }
+
+    /// How many solvable boards there are in total, `None` if it doesn't
+    /// fit in a `u128`.
+    pub fn total(&self) -> Option<u128> {
+        1u128.checked_shl(self.basis.len() as u32)
+    }
+
+    /// The board at `index` in the order the iterator yields them, for
+    /// sampling, or `None` past the end.
+    pub fn get(&self, index: u128) -> Option<Board> {
+        (index < self.end).then(|| Board {
+            width: self.width,
+            height: self.height,
+            rows: self.rows_at(index),
+            rules: None,
+        })
+    }
+
+    fn rows_at(&self, index: u128) -> Vec<Rowtype> {
+        let gray = index ^ (index >> 1);
+        let mut rows = alloc::vec![0; self.height];
+        let vectors = self.basis.iter().take(u128::BITS as usize).enumerate();
+        for (_, vector) in vectors.filter(|(i, _)| gray & (1 << i) != 0) {
+            rows.iter_mut().zip(vector).for_each(|(a, b)| *a ^= b);
+        }
+        rows
+    }
+}
+
+impl Iterator for SolvableBoards {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        if self.index >= self.end {
+            return None;
+        }
+        let board = Board {
+            width: self.width,
+            height: self.height,
+            rows: self.current.clone(),
+            rules: None,
+        };
+        self.index += 1;
+        if self.index < self.end {
+            let flip = &self.basis[self.index.trailing_zeros() as usize];
+            self.current.iter_mut().zip(flip).for_each(|(a, b)| *a ^= b);
+        }
+        Some(board)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Board> {
+        self.index = self.index.saturating_add(n as u128).min(self.end);
+        if self.index < self.end {
+            self.current = self.rows_at(self.index);
+        }
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.end - self.index) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl Board {
+    /// Every board of a size that the classic rules can solve, lazily, so
+    /// analyses and tables can run over all of them without holding them
+    /// in memory. That's every board up to 5x5 or so in reasonable time,
+    /// 2^(cells - kernel dimension) in all; past that use `skip` and `take`
+    /// to work through a chunk, or [`SolvableBoards::total`] to pick
+    /// indices to sample with [`SolvableBoards::get`]. Starts with the
+    /// blank board.
+    pub fn enumerate_solvable(width: usize, height: usize) -> SolvableBoards {
+        SolvableBoards::new(width, height)
+    }
+}
@@ -0,0 +1,185 @@
+//! Matrices over GF(2), the field of bits, where adding is XOR and
+//! multiplying is AND. Every press of a board adds its toggle pattern to the
+//! lights, so solving a board is solving the [`BitMatrix::toggles`] matrix
+//! against its difference from the goal; this is the elimination the solver
+//! itself runs, for looking at those matrices directly.
+//!
+//! Vectors are slices of bools, one per row or column.
+
+use alloc::vec::Vec;
+
+use super::rules::Ruleset;
+
+/// A `rows` x `cols` matrix of bits, each row packed into `u64` words.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    pub(crate) data: Vec<Vec<u64>>,
+}
+
+impl BitMatrix {
+    /// All zeros.
+    pub fn new(rows: usize, cols: usize) -> BitMatrix {
+        BitMatrix {
+            rows,
+            cols,
+            data: alloc::vec![alloc::vec![0; cols.div_ceil(64)]; rows],
+        }
+    }
+
+    pub fn identity(n: usize) -> BitMatrix {
+        let mut matrix = BitMatrix::new(n, n);
+        for i in 0..n {
+            matrix.set(i, i, true);
+        }
+        matrix
+    }
+
+    /// The toggle matrix of `width` x `height` boards under `rules`, with
+    /// cells and presses both numbered in row-major order: entry
+    /// `(cell, press)` is set when the press toggles the cell. Obstacles
+    /// make all-zero rows and columns, since nothing toggles them and
+    /// pressing them does nothing.
+    pub fn toggles(width: usize, height: usize, rules: &Ruleset) -> BitMatrix {
+        let mut matrix = BitMatrix::new(width * height, width * height);
+        for press in 0..width * height {
+            for (x, y) in rules.affected(press % width, press / width, width, height) {
+                let cell = y * width + x;
+                matrix.set(cell, press, !matrix.get(cell, press));
+            }
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.data[row][col / 64] & (1 << (col % 64)) != 0
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        let word = &mut self.data[row][col / 64];
+        *word = (*word & !(1 << (col % 64))) | (u64::from(value) << (col % 64));
+    }
+
+    /// Add a row of packed words to the bottom of the matrix.
+    pub(crate) fn push_row(&mut self, words: Vec<u64>) {
+        debug_assert_eq!(words.len(), self.cols.div_ceil(64));
+        self.data.push(words);
+        self.rows += 1;
+    }
+
+    /// The product `self * other`.
+    ///
+    /// Panics if `self` has a different number of columns than `other` has
+    /// rows.
+    pub fn multiply(&self, other: &BitMatrix) -> BitMatrix {
+        assert_eq!(self.cols, other.rows, "matrix sizes do not match");
+        let mut product = BitMatrix::new(self.rows, other.cols);
+        for (row, out) in self.data.iter().zip(&mut product.data) {
+            for (k, other_row) in other.data.iter().enumerate() {
+                if row[k / 64] & (1 << (k % 64)) != 0 {
+                    out.iter_mut().zip(other_row).for_each(|(a, b)| *a ^= b);
+                }
+            }
+        }
+        product
+    }
+
+    /// The product of the matrix and a column vector.
+    ///
+    /// Panics if `vector` doesn't have one entry per column.
+    pub fn apply(&self, vector: &[bool]) -> Vec<bool> {
+        assert_eq!(vector.len(), self.cols, "vector size does not match the matrix");
+        (0..self.rows)
+            .map(|row| (0..self.cols).filter(|&col| vector[col] && self.get(row, col)).count() % 2 == 1)
+            .collect()
+    }
+
+    /// Reduce the matrix to reduced row echelon form in place, making the
+    /// same row operations on `track`, and return the pivot columns: row
+    /// `i` of the result has its leading one in the `i`th pivot column,
+    /// and no other row has a one there. Rows past the pivots end up zero.
+    ///
+    /// Panics if `track` has a different number of rows.
+    pub(crate) fn reduce(&mut self, track: &mut BitMatrix) -> Vec<usize> {
+        assert_eq!(self.rows, track.rows, "tracked matrix has a different number of rows");
+        let mut pivots: Vec<usize> = Vec::new();
+        for col in 0..self.cols {
+            let rank = pivots.len();
+            let Some(found) = (rank..self.rows).find(|&r| self.get(r, col)) else {
+                continue;
+            };
+            self.data.swap(rank, found);
+            track.data.swap(rank, found);
+            let (pivot, tracked) = (self.data[rank].clone(), track.data[rank].clone());
+            for (r, (row, tracking)) in self.data.iter_mut().zip(&mut track.data).enumerate() {
+                if r != rank && row[col / 64] & (1 << (col % 64)) != 0 {
+                    row.iter_mut().zip(&pivot).for_each(|(a, b)| *a ^= b);
+                    tracking.iter_mut().zip(&tracked).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    /// The number of linearly independent rows.
+    pub fn rank(&self) -> usize {
+        let mut reduced = self.clone();
+        reduced.reduce(&mut BitMatrix::new(self.rows, 0)).len()
+    }
+
+    /// A vector `x` with `self * x == rhs`, or `None` if there isn't one.
+    /// Every other solution differs from it by a [`BitMatrix::nullspace`]
+    /// combination.
+    ///
+    /// Panics if `rhs` doesn't have one entry per row.
+    pub fn solve(&self, rhs: &[bool]) -> Option<Vec<bool>> {
+        assert_eq!(rhs.len(), self.rows, "right-hand side does not match the matrix");
+        let mut reduced = self.clone();
+        let mut column = BitMatrix::new(self.rows, 1);
+        for (row, &value) in rhs.iter().enumerate() {
+            column.set(row, 0, value);
+        }
+        let pivots = reduced.reduce(&mut column);
+        if (pivots.len()..self.rows).any(|row| column.get(row, 0)) {
+            return None;
+        }
+        let mut solution = alloc::vec![false; self.cols];
+        for (row, &col) in pivots.iter().enumerate() {
+            solution[col] = column.get(row, 0);
+        }
+        Some(solution)
+    }
+
+    /// A basis of the vectors `x` with `self * x == 0`, one per column
+    /// without a pivot.
+    pub fn nullspace(&self) -> Vec<Vec<bool>> {
+        let mut reduced = self.clone();
+        let pivots = reduced.reduce(&mut BitMatrix::new(self.rows, 0));
+        reduced.kernel(&pivots).collect()
+    }
+
+    /// The nullspace of a matrix already reduced, with pivot columns
+    /// `pivots`, in order of the free columns.
+    pub(crate) fn kernel<'a>(&'a self, pivots: &'a [usize]) -> impl Iterator<Item = Vec<bool>> + 'a {
+        (0..self.cols).filter(|col| !pivots.contains(col)).map(|free| {
+            let mut vector = alloc::vec![false; self.cols];
+            vector[free] = true;
+            for (row, &col) in pivots.iter().enumerate() {
+                if self.get(row, free) {
+                    vector[col] = true;
+                }
+            }
+            vector
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use alloc::{string::String, vec::Vec};
+use ::core::fmt::Display;
+
+use super::{
+    board::{full_row, BitMask, Board},
+    parse::{hex_cells, parse_hex_cells, ParseError},
+    rules::Ruleset,
+};
+
+/// Patterns any of which count as solving a board, for variants that win
+/// on something other than one fixed goal, such as every light off or
+/// every light on. Obstacles are left out of every pattern, so "every light
+/// on" means every cell the board has.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Goals {
+    pub patterns: Vec<BitMask>,
+}
+
+impl Goals {
+    pub fn new(patterns: Vec<BitMask>) -> Goals {
+        Goals { patterns }
+    }
+
+    /// Every light off, or every light on.
+    pub fn off_or_on(width: usize, height: usize) -> Goals {
+        let mut on = BitMask::new(width, height);
+        on.rows.iter_mut().for_each(|row| *row = full_row(width));
+        Goals::new(alloc::vec![BitMask::new(width, height), on])
+    }
+
+    /// Every light off but one full row, whichever row that is.
+    pub fn single_row(width: usize, height: usize) -> Goals {
+        let patterns = (0..height)
+            .map(|y| {
+                let mut pattern = BitMask::new(width, height);
+                pattern.rows[y] = full_row(width);
+                pattern
+            })
+            .collect();
+        Goals::new(patterns)
+    }
+
+    /// `pattern` as a goal under `rules`: without its obstacles, and `None`
+    /// for all lights off.
+    fn goal(pattern: &BitMask, rules: &Ruleset) -> Option<BitMask> {
+        let mut goal = pattern.clone();
+        if let Some(obstacles) = &rules.obstacles {
+            for (row, holes) in goal.rows.iter_mut().zip(&obstacles.rows) {
+                *row &= !holes;
+            }
+        }
+        (goal.count() > 0).then_some(goal)
+    }
+
+    /// `board` aiming for pattern `index` instead of its own goal.
+    ///
+    /// Panics if there's no such pattern or it's a different size from the
+    /// board.
+    pub fn retarget(&self, board: &Board, index: usize) -> Board {
+        let rules = board.ruleset();
+        let ruleset = Ruleset {
+            goal: Goals::goal(&self.patterns[index], rules),
+            ..rules.clone()
+        };
+        board.clone().with_ruleset(ruleset)
+    }
+
+    /// The first pattern `board` shows, if any.
+    pub fn reached(&self, board: &Board) -> Option<usize> {
+        (0..self.patterns.len()).find(|&index| self.retarget(board, index).is_solved())
+    }
+
+    /// Parse the form written by `Display`: patterns separated by spaces or
+    /// commas, each `off`, `on`, `rows` for [`Goals::single_row`]'s, or hex
+    /// digits encoding a `width` x `height` mask the same way as puzzle
+    /// codes.
+    pub fn parse(text: &str, width: usize, height: usize) -> Result<Goals, ParseError> {
+        let mut patterns = Vec::new();
+        for item in text.split([' ', ',']).filter(|item| !item.is_empty()) {
+            match item {
+                "off" => patterns.push(BitMask::new(width, height)),
+                "on" => patterns.extend(Goals::off_or_on(width, height).patterns.pop()),
+                "rows" => patterns.extend(Goals::single_row(width, height).patterns),
+                digits => patterns.push(BitMask {
+                    width,
+                    height,
+                    rows: parse_hex_cells(digits, width, height)
+                        .map_err(|_| ParseError::BadGoal(String::from(item)))?,
+                }),
+            }
+        }
+        if patterns.is_empty() {
+            return Err(ParseError::BadGoal(String::from(text)));
+        }
+        Ok(Goals::new(patterns))
+    }
+}
+
+impl Display for Goals {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let patterns: Vec<String> = self
+            .patterns
+            .iter()
+            .map(|pattern| match pattern.count() {
+                0 => String::from("off"),
+                count if count == pattern.width * pattern.height => String::from("on"),
+                _ => hex_cells(&pattern.rows, pattern.width),
+            })
+            .collect();
+        f.write_str(&patterns.join(" "))
+    }
+}
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+
+use super::board::{BitMask, Board};
+
+/// Advice for finishing a board the player has already started on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Guidance {
+    /// Presses that clear the board from where the player is now.
+    pub remaining: BitMask,
+    /// Cells in `remaining` the player has already pressed, which the best
+    /// finish has to press again to undo.
+    pub repressed: Vec<(usize, usize)>,
+    /// Entries of the player's history that didn't contribute to the final
+    /// solution: presses cancelled by pressing the same cell again, and the
+    /// presses that `repressed` undoes.
+    pub wasted: Vec<usize>,
+}
+
+/// A press sequence cut down to the fewest presses with the same effect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reduction {
+    /// Cells pressed an odd number of times, which is all that matters
+    /// since pressing a cell twice undoes it.
+    pub cancelled: BitMask,
+    /// The fewest presses changing the board the same way as `cancelled`,
+    /// which can be fewer still when some presses together change nothing.
+    pub minimal: BitMask,
+}
+
+impl Board {
+    /// Reduce `presses` to the fewest presses that change any board of this
+    /// size and rules the same way, first cancelling repeats and then
+    /// trading combinations of presses for cheaper ones with the same effect.
+    /// Only the board's size and rules matter, not its lights.
+    ///
+    /// Panics if a press is off the board.
+    pub fn reduce_presses(&self, presses: &[(usize, usize)]) -> Reduction {
+        let mut cancelled = BitMask::new(self.width, self.height);
+        for &(x, y) in presses {
+            cancelled.set(x, y, !cancelled.get(x, y));
+        }
+        // The effect of the presses on a blank board, which the shortest
+        // solution undoes with the same effect.
+        let mut effect =
+            Board::new(self.width, self.height).with_ruleset(self.ruleset().without_goal());
+        effect.apply_press_set(&cancelled);
+        let minimal = effect
+            .solve_linear()
+            .expect("pressed from blank, it can be pressed back");
+        Reduction { cancelled, minimal }
+    }
+
+    /// Solve the board reached by pressing `history` on `self`: the shortest
+    /// finish, and among equally short ones the one re-pressing the fewest
+    /// cells the player already pressed. `None` if the board can't be
+    /// cleared.
+    pub fn solve_from_history(&self, history: &[(usize, usize)]) -> Option<Guidance> {
+        let mut current = self.clone();
+        let mut pressed = BitMask::new(self.width, self.height);
+        for &(x, y) in history {
+            current.toggle(x, y);
+            pressed.set(x, y, !pressed.get(x, y));
+        }
+        let remaining = current.solutions().min_by_key(|solution| {
+            let overlap: usize = solution
+                .rows
+                .iter()
+                .zip(&pressed.rows)
+                .map(|(a, b)| (a & b).count_ones() as usize)
+                .sum();
+            (solution.count(), overlap)
+        })?;
+
+        let repressed: Vec<(usize, usize)> = remaining
+            .positions()
+            .into_iter()
+            .filter(|&(x, y)| pressed.get(x, y))
+            .collect();
+        // A history entry counts if it's the last press of a cell that stays
+        // pressed in the end; everything else was undone one way or another.
+        let wasted = (0..history.len())
+            .filter(|&i| {
+                let (x, y) = history[i];
+                let last = !history[i + 1..].contains(&(x, y));
+                !(last && pressed.get(x, y) && !remaining.get(x, y))
+            })
+            .collect();
+        Some(Guidance {
+            remaining,
+            repressed,
+            wasted,
+        })
+    }
+}
@@ -0,0 +1,287 @@
+//! Why a board can't be solved. Every press adds its pattern to the cells,
+//! so a weighting of the cells that every pattern totals zero under, mod
+//! the number of states, totals the same however the board is pressed. A
+//! board whose difference from the goal totals anything else under it can
+//! never reach the goal, and such a weighting exists for every board that
+//! can't.
+//!
+//! Two-state boards find theirs in the elimination the linear solver runs
+//! anyway, with obstacles taken out of the system, see
+//! [`Board::invariant`]. Rules with other numbers of states are a system
+//! over the integers mod `k`, which isn't a field unless `k` is prime, so
+//! [`Ruleset::invariant`] solves it one prime power of `k` at a time,
+//! diagonalising the matrix with pivots of the fewest factors of the prime.
+//!
+//! [`Board::invariant`]: super::Board::invariant
+
+use ::core::fmt::Display;
+use alloc::{format, string::String, vec::Vec};
+
+use super::{parse::cell_name, rules::Ruleset};
+
+/// A weighting of a board's cells that no press changes the total of, mod
+/// `modulus`, and that the board's difference from the goal breaks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invariant {
+    pub width: usize,
+    pub height: usize,
+    /// The number of states each cell cycles through.
+    pub modulus: u32,
+    /// Each cell's weight, row by row, below `modulus`.
+    pub weights: Vec<u32>,
+    /// What the differences from the goal total under the weights, mod
+    /// `modulus`; never zero, since every press keeps it where it is.
+    pub total: u32,
+}
+
+impl Invariant {
+    pub(crate) fn new(
+        width: usize,
+        height: usize,
+        modulus: u32,
+        weights: Vec<u32>,
+        differences: &[u32],
+    ) -> Invariant {
+        let total = weights
+            .iter()
+            .zip(differences)
+            .fold(0, |total, (weight, difference)| {
+                (total + weight * difference) % modulus
+            });
+        Invariant {
+            width,
+            height,
+            modulus,
+            weights,
+            total,
+        }
+    }
+
+    pub fn weight(&self, x: usize, y: usize) -> u32 {
+        self.weights[y * self.width + x]
+    }
+
+    /// The cells with a weight, in row-major order.
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        (0..self.weights.len())
+            .filter(|&cell| self.weights[cell] != 0)
+            .map(|cell| (cell % self.width, cell / self.width))
+            .collect()
+    }
+}
+
+impl Display for Invariant {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let cells = self.cells();
+        let names: Vec<String> = match self.modulus {
+            2 => cells.iter().map(|&(x, y)| cell_name(x, y)).collect(),
+            _ => cells
+                .iter()
+                .map(|&(x, y)| format!("{}x{}", self.weight(x, y), cell_name(x, y)))
+                .collect(),
+        };
+        match (self.modulus, &cells[..]) {
+            (2, [(x, y)]) => write!(
+                f,
+                "no press toggles {}, and it differs from the goal",
+                cell_name(*x, *y)
+            ),
+            (2, _) => write!(
+                f,
+                "every press toggles an even number of {}, but an odd number of them differ \
+                 from the goal",
+                names.join(" ")
+            ),
+            (modulus, _) => write!(
+                f,
+                "every press adds a multiple of {modulus} to {}, but the differences from the \
+                 goal add up to {} mod {modulus}",
+                names.join(" + "),
+                self.total
+            ),
+        }
+    }
+}
+
+/// `modulus` as powers of its prime factors, smallest prime first.
+fn prime_powers(mut modulus: u32) -> Vec<(u32, u32)> {
+    let mut powers = Vec::new();
+    let mut prime = 2;
+    while modulus > 1 {
+        if modulus.is_multiple_of(prime) {
+            let mut power = 1;
+            while modulus.is_multiple_of(prime) {
+                modulus /= prime;
+                power *= prime;
+            }
+            powers.push((prime, power));
+        }
+        prime += 1;
+    }
+    powers
+}
+
+/// How many times `prime` divides `value`, which isn't zero.
+fn valuation(mut value: u32, prime: u32) -> u32 {
+    let mut count = 0;
+    while value.is_multiple_of(prime) {
+        value /= prime;
+        count += 1;
+    }
+    count
+}
+
+/// A weighting of the rows of `matrix` that every column totals zero
+/// under, mod `power` of `prime`, and `rhs` doesn't, with the fewest
+/// weighted rows of the ones the elimination turns up. `None` if
+/// `matrix * x == rhs` has a solution mod `power`.
+fn prime_power_invariant(
+    mut matrix: Vec<Vec<u32>>,
+    rhs: &[u32],
+    prime: u32,
+    power: u32,
+) -> Option<Vec<u32>> {
+    let (rows, cols) = (matrix.len(), matrix.first().map_or(0, Vec::len));
+    // The row operations made so far, so `track * matrix` stays the matrix
+    // being reduced.
+    let mut track: Vec<Vec<u32>> = (0..rows)
+        .map(|row| (0..rows).map(|col| u32::from(row == col)).collect())
+        .collect();
+    // The power of `prime` on each diagonal entry, in order.
+    let mut pivots: Vec<u32> = Vec::new();
+    for rank in 0..rows.min(cols) {
+        // The entry with the fewest factors of `prime` divides every other
+        // one left, so it can clear its row and column.
+        let Some((_, row, col)) = (rank..rows)
+            .flat_map(|row| (rank..cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| matrix[row][col] != 0)
+            .map(|(row, col)| (valuation(matrix[row][col], prime), row, col))
+            .min()
+        else {
+            break;
+        };
+        matrix.swap(rank, row);
+        track.swap(rank, row);
+        for entries in &mut matrix {
+            entries.swap(rank, col);
+        }
+        let factor = prime.pow(valuation(matrix[rank][rank], prime));
+        let unit = matrix[rank][rank] / factor;
+        let inverse = (1..power)
+            .find(|inverse| unit * inverse % power == 1)
+            .expect("units have inverses");
+        for value in matrix[rank].iter_mut().chain(&mut track[rank]) {
+            *value = *value * inverse % power;
+        }
+        let (pivot, tracked) = (matrix[rank].clone(), track[rank].clone());
+        for row in rank + 1..rows {
+            let scale = matrix[row][rank] / factor;
+            if scale == 0 {
+                continue;
+            }
+            for (value, pivot) in matrix[row]
+                .iter_mut()
+                .chain(&mut track[row])
+                .zip(pivot.iter().chain(&tracked))
+            {
+                *value = (*value + (power - scale) * pivot) % power;
+            }
+        }
+        // Clearing the rest of the pivot row is a column operation, which
+        // only touches this row now nothing else is left in the column.
+        matrix[rank][rank + 1..].fill(0);
+        pivots.push(factor);
+    }
+    (0..rows)
+        .filter_map(|row| {
+            let reduced = track[row]
+                .iter()
+                .zip(rhs)
+                .fold(0, |total, (weight, value)| (total + weight * value) % power);
+            // Rows past the pivots are all zero, as if their pivot were
+            // `power` itself.
+            let factor = pivots.get(row).copied().unwrap_or(power);
+            (reduced % factor != 0).then(|| {
+                track[row]
+                    .iter()
+                    .map(|weight| weight * (power / factor) % power)
+                    .collect()
+            })
+        })
+        .min_by_key(|weights: &Vec<u32>| weights.iter().filter(|&&weight| weight != 0).count())
+}
+
+impl Ruleset {
+    /// Why a `width` x `height` board of these rules, with each cell in
+    /// the state `cells` gives row by row, can't reach the goal, or `None`
+    /// if it can. A press adds one to each cell it affects, wrapping round
+    /// after [`Ruleset::states`], and the goal is every cell at 0, or 1
+    /// where the goal pattern is lit. Works for any number of states,
+    /// though boards can only be played with two.
+    ///
+    /// ```
+    /// use lightsout::Ruleset;
+    ///
+    /// // On 2x2 each press adds one to three of the four cells, so with
+    /// // three states, the total never changes.
+    /// let rules = Ruleset::parse("states=3", 2, 2).unwrap();
+    /// let invariant = rules.invariant(2, 2, &[1, 0, 0, 0]).unwrap();
+    /// assert_eq!(invariant.cells().len(), 4);
+    /// assert_eq!(invariant.total, 2);
+    /// // With four states, every board can be cleared.
+    /// let rules = Ruleset::parse("states=4", 2, 2).unwrap();
+    /// assert_eq!(rules.invariant(2, 2, &[1, 0, 0, 0]), None);
+    /// ```
+    ///
+    /// Panics if `cells` doesn't have one state per cell.
+    pub fn invariant(&self, width: usize, height: usize, cells: &[u8]) -> Option<Invariant> {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "cells do not match the board size"
+        );
+        let modulus = u32::from(self.states.max(1));
+        let n = width * height;
+        let mut matrix = alloc::vec![alloc::vec![0; n]; n];
+        let toggles = (0..n).flat_map(|press| {
+            let affected = self.affected(press % width, press / width, width, height);
+            affected.map(move |(x, y)| (y * width + x, press))
+        });
+        for (cell, press) in toggles {
+            matrix[cell][press] = (matrix[cell][press] + 1) % modulus;
+        }
+        let differences: Vec<u32> = (0..n)
+            .map(|cell| {
+                let goal = u32::from(self.goal_row(cell / width) & (1 << (cell % width)) != 0);
+                // Obstacles are never anything but off.
+                let state = u32::from(cells[cell]) % modulus;
+                match self.is_obstacle(cell % width, cell / width) {
+                    true => 0,
+                    false => (goal + modulus - state) % modulus,
+                }
+            })
+            .collect();
+        prime_powers(modulus)
+            .into_iter()
+            .find_map(|(prime, power)| {
+                let reduced = matrix
+                    .iter()
+                    .map(|row| row.iter().map(|value| value % power).collect());
+                let rhs: Vec<u32> = differences.iter().map(|value| value % power).collect();
+                let weights = prime_power_invariant(reduced.collect(), &rhs, prime, power)?;
+                // Weights that work mod a prime power work mod `modulus` once
+                // scaled up by the rest of it.
+                let weights = weights
+                    .iter()
+                    .map(|weight| weight * (modulus / power))
+                    .collect();
+                Some(Invariant::new(
+                    width,
+                    height,
+                    modulus,
+                    weights,
+                    &differences,
+                ))
+            })
+    }
+}
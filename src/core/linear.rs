@@ -0,0 +1,697 @@
+use alloc::vec::Vec;
+
+use super::{
+    board::{BitMask, Board, Rowtype},
+    cancel::{CancellationToken, Cancelled},
+    constraints::PressConstraints,
+    gf2::BitMatrix,
+    goals::Goals,
+    invariant::Invariant,
+    rules::Ruleset,
+};
+
+/// Gaussian elimination over GF(2) on an `n`-variable system, where bit `i`
+/// of each row's coefficients is variable `i`. Returns a particular solution
+/// and a basis of the nullspace, or `None` if the system is inconsistent.
+pub(crate) fn solve_gf2(mut rows: Vec<(Rowtype, bool)>, n: usize) -> Option<(Rowtype, Vec<Rowtype>)> {
+    let mut pivots: Vec<usize> = Vec::new();
+    let mut rank = 0;
+    for col in 0..n {
+        let Some(found) = (rank..rows.len()).find(|&r| rows[r].0 & (1 << col) != 0) else {
+            continue;
+        };
+        rows.swap(rank, found);
+        let pivot = rows[rank];
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row.0 & (1 << col) != 0 {
+                row.0 ^= pivot.0;
+                row.1 ^= pivot.1;
+            }
+        }
+        pivots.push(col);
+        rank += 1;
+    }
+    if rows[rank..].iter().any(|(_, rhs)| *rhs) {
+        return None;
+    }
+
+    let mut solution: Rowtype = 0;
+    for (r, &col) in pivots.iter().enumerate() {
+        if rows[r].1 {
+            solution |= 1 << col;
+        }
+    }
+
+    let kernel = (0..n)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut vector: Rowtype = 1 << free;
+            for (r, &col) in pivots.iter().enumerate() {
+                if rows[r].0 & (1 << free) != 0 {
+                    vector |= 1 << col;
+                }
+            }
+            vector
+        })
+        .collect();
+    Some((solution, kernel))
+}
+
+/// Checks [`LinearSystem::invariant`] tries every combination of, rather
+/// than only each one alone.
+const INVARIANT_MAX_CHECKS: usize = 16;
+
+/// Whether an odd number of cells are in both bitsets.
+fn parity(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).map(|(a, b)| (a & b).count_ones()).sum::<u32>() % 2 == 1
+}
+
+/// The whole-board equations for one size and set of rules, eliminated
+/// once with the right-hand side left symbolic, the way [`BatchSolver`]
+/// does for the top-row system. They only depend on the size, presses and
+/// obstacles, so solving another board with the same ones only takes
+/// parities of its differences from the goal, rather than a fresh
+/// elimination. Gives exactly [`Board::solve_linear`]'s answers, ties
+/// included, for boards that don't press classically; classic boards get
+/// there quicker by light-chasing anyway.
+///
+/// [`BatchSolver`]: super::BatchSolver
+#[derive(Clone, Debug)]
+pub struct LinearSystem {
+    width: usize,
+    height: usize,
+    rules: Ruleset,
+    /// Each pivot's press and the cells whose differences add up to it.
+    pivots: Vec<(usize, Vec<u64>)>,
+    /// Cell sets whose differences must add up to zero on a solvable board.
+    checks: Vec<Vec<u64>>,
+    kernel: Vec<BitMask>,
+}
+
+impl LinearSystem {
+    /// The system for `width` x `height` boards with `rules`, whatever
+    /// their goal.
+    pub fn new(width: usize, height: usize, rules: &Ruleset) -> LinearSystem {
+        LinearSystem::eliminate(width, height, rules, None)
+    }
+
+    /// [`LinearSystem::new`], leaving the cells in `avoid` unpressed.
+    fn eliminate(
+        width: usize,
+        height: usize,
+        rules: &Ruleset,
+        avoid: Option<&BitMask>,
+    ) -> LinearSystem {
+        // One equation per cell: the presses that toggle it must add up to
+        // its difference from the goal. Obstacles get `press = 0` instead,
+        // since pressing them does nothing, and avoided cells get it too.
+        // Each right-hand side is tracked as the set of cell differences it
+        // has absorbed.
+        let n = width * height;
+        let words = n.div_ceil(64);
+        let single = |cell: usize| {
+            let mut vector = alloc::vec![0; words];
+            vector[cell / 64] = 1 << (cell % 64);
+            vector
+        };
+        let mut coefficients = BitMatrix::toggles(width, height, rules);
+        let mut rhs = BitMatrix::identity(n);
+        for cell in 0..n {
+            if rules.is_obstacle(cell % width, cell / width) {
+                coefficients.data[cell] = single(cell);
+                rhs.data[cell] = alloc::vec![0; words];
+            }
+        }
+        if let Some(avoid) = avoid {
+            for (x, y) in avoid.positions() {
+                coefficients.push_row(single(y * width + x));
+                rhs.push_row(alloc::vec![0; words]);
+            }
+        }
+        let pivots = coefficients.reduce(&mut rhs);
+
+        let kernel = coefficients
+            .kernel(&pivots)
+            .map(|vector| {
+                let mut mask = BitMask::new(width, height);
+                for cell in (0..n).filter(|&cell| vector[cell]) {
+                    mask.set(cell % width, cell / width, true);
+                }
+                mask
+            })
+            .collect();
+        let checks = rhs.data.split_off(pivots.len());
+        let pivots = pivots.into_iter().zip(rhs.data).collect();
+        LinearSystem {
+            width,
+            height,
+            rules: rules.without_goal(),
+            pivots,
+            checks,
+            kernel,
+        }
+    }
+
+    /// Whether the system is for `board`'s size and rules.
+    pub fn covers(&self, board: &Board) -> bool {
+        (board.width, board.height) == (self.width, self.height)
+            && board.ruleset().without_goal() == self.rules
+    }
+
+    /// Whether some presses solve `board`, which only takes parities
+    /// against the checks, without looking for the fewest.
+    ///
+    /// Panics if the system doesn't cover the board.
+    pub fn solvable(&self, board: &Board) -> bool {
+        assert!(self.covers(board), "board does not match the system's size and rules");
+        self.solution_space(board).is_some()
+    }
+
+    /// How many independent press sets leave a board unchanged, as for
+    /// [`Board::kernel_dimension`].
+    pub fn kernel_dimension(&self) -> usize {
+        self.kernel.len()
+    }
+
+    /// Why `board` can't be solved, as in [`Board::invariant`].
+    ///
+    /// Panics if the system doesn't cover the board.
+    pub fn invariant(&self, board: &Board) -> Option<Invariant> {
+        assert!(self.covers(board), "board does not match the system's size and rules");
+        let differences = self.differences(board);
+        let count = |check: &[u64]| check.iter().map(|word| word.count_ones()).sum::<u32>();
+        let mut best: Option<Vec<u64>> = None;
+        let mut consider = |check: &[u64]| {
+            let smaller = best.as_ref().is_none_or(|best| count(check) < count(best));
+            if smaller && parity(check, &differences) {
+                best = Some(check.to_vec());
+            }
+        };
+        if self.checks.len() <= INVARIANT_MAX_CHECKS {
+            // Every combination in Gray code order, one check added or
+            // taken away at each step.
+            let mut combined = alloc::vec![0; differences.len()];
+            for step in 1..1usize << self.checks.len() {
+                let check = &self.checks[step.trailing_zeros() as usize];
+                combined.iter_mut().zip(check).for_each(|(a, b)| *a ^= b);
+                consider(&combined);
+            }
+        } else {
+            self.checks.iter().for_each(|check| consider(check));
+        }
+        let best = best?;
+        let n = self.width * self.height;
+        let weights = (0..n).map(|cell| ((best[cell / 64] >> (cell % 64)) & 1) as u32).collect();
+        let differences: Vec<u32> = (0..n)
+            .map(|cell| ((differences[cell / 64] >> (cell % 64)) & 1) as u32)
+            .collect();
+        Some(Invariant::new(self.width, self.height, 2, weights, &differences))
+    }
+
+    /// The cells where `board` differs from its goal, as a bitset.
+    fn differences(&self, board: &Board) -> Vec<u64> {
+        let rules = board.ruleset();
+        let mut differences = alloc::vec![0; (self.width * self.height).div_ceil(64)];
+        for ((x, y), lit) in board.cells() {
+            if lit != (rules.goal_row(y) & (1 << x) != 0) {
+                let cell = y * self.width + x;
+                differences[cell / 64] |= 1 << (cell % 64);
+            }
+        }
+        differences
+    }
+
+    /// Every solution as a particular one plus any combination of the
+    /// kernel, as in [`Board::solution_space`].
+    fn solution_space(&self, board: &Board) -> Option<(BitMask, &[BitMask])> {
+        let differences = self.differences(board);
+        if self.checks.iter().any(|check| parity(check, &differences)) {
+            return None;
+        }
+        let mut particular = BitMask::new(self.width, self.height);
+        for (col, rhs) in &self.pivots {
+            if parity(rhs, &differences) {
+                particular.set(col % self.width, col / self.width, true);
+            }
+        }
+        Some((particular, &self.kernel))
+    }
+
+    /// [`Board::solve_linear`] with the elimination already done.
+    ///
+    /// Panics if the system doesn't cover the board.
+    pub fn solve(&self, board: &Board) -> Option<BitMask> {
+        self.solve_cancellable(board, &CancellationToken::new())
+            .unwrap_or_else(|Cancelled| unreachable!("nothing else holds the token"))
+    }
+
+    /// [`LinearSystem::solve`], checking `cancel` between kernel
+    /// combinations.
+    ///
+    /// Panics if the system doesn't cover the board.
+    pub fn solve_cancellable(
+        &self,
+        board: &Board,
+        cancel: &CancellationToken,
+    ) -> Result<Option<BitMask>, Cancelled> {
+        assert!(self.covers(board), "board does not match the system's size and rules");
+        if self.width == 0 || self.height == 0 {
+            return Ok(Some(BitMask::new(self.width, self.height)));
+        }
+        match self.solution_space(board) {
+            Some((particular, kernel)) => shortest(&particular, kernel, cancel).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Board {
+    /// Kernel dimension beyond which the linear solvers don't try every
+    /// solution for the fewest presses, and take the first one elimination
+    /// finds as the only one: past it there are over a million to compare,
+    /// and degenerate rules can have more than a `u128` can count.
+    pub const MAX_SEARCHED_KERNEL: usize = 20;
+
+    /// The board light-chasing works on: its difference from the goal,
+    /// under the classic rules. Only meaningful if presses are classic.
+    fn residual(&self) -> Board {
+        let rules = self.ruleset();
+        Board {
+            width: self.width,
+            height: self.height,
+            rows: (0..self.height).map(|y| self.rows[y] ^ rules.goal_row(y)).collect(),
+            rules: None,
+        }
+    }
+
+    /// Every solution as a particular one plus any combination of a kernel
+    /// basis, all as full press masks. Classic presses get there by
+    /// light-chasing; other rules by eliminating the whole board at once.
+    fn solution_space(&self) -> Option<(BitMask, Vec<BitMask>)> {
+        if !self.presses_classically() {
+            return self.eliminate(None);
+        }
+        let mask = |rows| BitMask {
+            width: self.width,
+            height: self.height,
+            rows,
+        };
+        let residual = self.residual();
+        let (particular, kernel) = residual.top_row_solutions()?;
+        let empty = Board::new(self.width, self.height);
+        Some((
+            mask(residual.chase(particular).0),
+            kernel.iter().map(|&top| mask(empty.chase(top).0)).collect(),
+        ))
+    }
+
+    /// [`Board::solution_space`] by eliminating the whole board at once,
+    /// which works for any rules, leaving the cells in `avoid` unpressed.
+    fn eliminate(&self, avoid: Option<&BitMask>) -> Option<(BitMask, Vec<BitMask>)> {
+        let system = LinearSystem::eliminate(self.width, self.height, self.ruleset(), avoid);
+        system
+            .solution_space(self)
+            .map(|(particular, kernel)| (particular, kernel.to_vec()))
+    }
+
+    /// Light-chase the board after pressing `first` on the top row, returning
+    /// the presses made on every row and the lights left on the bottom row.
+    /// Presses follow the classic rules whatever the board's own.
+    pub fn chase(&self, first: Rowtype) -> (Vec<Rowtype>, Rowtype) {
+        let mut rows = self.rows.clone();
+        let mut presses = Vec::with_capacity(self.height);
+        let mut press = first;
+        for y in 0..self.height {
+            self.press_row(&mut rows, y, press);
+            presses.push(press);
+            press = rows[y];
+        }
+        (presses, rows[self.height - 1])
+    }
+
+    /// The bottom row left over after chasing is an affine function of the
+    /// top row presses. Returns that map as the bottom-row effect of each
+    /// top-row cell, plus the bottom row left when nothing is pressed on top.
+    pub(crate) fn top_row_system(&self) -> (Vec<Rowtype>, Rowtype) {
+        let empty = Board::new(self.width, self.height);
+        let columns = (0..self.width).map(|x| empty.chase(1 << x).1).collect();
+        (columns, self.chase(0).1)
+    }
+
+    /// A top row that clears the board after chasing, and a basis of the top
+    /// rows that chase to nothing at all; every solution is the first plus a
+    /// combination of the rest.
+    pub(crate) fn top_row_solutions(&self) -> Option<(Rowtype, Vec<Rowtype>)> {
+        let (columns, residual) = self.top_row_system();
+        let equations = (0..self.width)
+            .map(|y| {
+                let coefficients = columns
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (x, col)| acc | (((col >> y) & 1) << x));
+                (coefficients, (residual >> y) & 1 == 1)
+            })
+            .collect();
+        solve_gf2(equations, self.width)
+    }
+
+    /// Solve the board exactly using linear algebra over GF(2), returning the
+    /// smallest set of presses that clears it, or `None` if it is unsolvable.
+    /// Boards with more solutions than [`Board::MAX_SEARCHED_KERNEL`] lets
+    /// it compare get the first one elimination finds.
+    ///
+    /// ```
+    /// use lightsout::Board;
+    ///
+    /// let board = Board::from_code("5x5-00008e0").unwrap();
+    /// let presses = board.solve_linear().unwrap();
+    /// assert_eq!(presses.positions(), [(1, 4)]);
+    /// assert!(board.verify(&presses.positions()));
+    ///
+    /// // A single light in the corner of a 5x5 board can't be cleared.
+    /// assert_eq!(Board::from_code("5x5-8000000").unwrap().solve_linear(), None);
+    /// ```
+    pub fn solve_linear(&self) -> Option<BitMask> {
+        self.solve_linear_cancellable(&CancellationToken::new())
+            .unwrap_or_else(|Cancelled| unreachable!("nothing else holds the token"))
+    }
+
+    /// [`Board::solve_linear`], checking `cancel` between kernel combinations.
+    pub fn solve_linear_cancellable(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<Option<BitMask>, Cancelled> {
+        if self.width == 0 || self.height == 0 {
+            return Ok(Some(BitMask::new(self.width, self.height)));
+        }
+        match self.solution_space() {
+            Some((particular, kernel)) => shortest(&particular, &kernel, cancel).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The smallest set of presses that solves the board while pressing
+    /// every cell `constraints` requires and none it forbids, or `None` if
+    /// no solution does. Obstacles can't be required, since solutions never
+    /// press them. Goes through whole-board elimination whatever the
+    /// rules, so it's slower than [`Board::solve_linear`].
+    ///
+    /// Panics if the constraints are a different size from the board.
+    pub fn solve_constrained(&self, constraints: &PressConstraints) -> Option<BitMask> {
+        if constraints.is_empty() {
+            return self.solve_linear();
+        }
+        let rules = self.ruleset();
+        let required = constraints.required.positions();
+        if !constraints.allows(&constraints.required)
+            || required.iter().any(|&(x, y)| rules.is_obstacle(x, y))
+        {
+            // A cell both required and forbidden, or required but missing.
+            return None;
+        }
+        let mut pressed = self.clone();
+        pressed.apply_press_set(&constraints.required);
+        let mut avoid = constraints.forbidden.clone();
+        for (row, required) in avoid.rows.iter_mut().zip(&constraints.required.rows) {
+            *row |= required;
+        }
+        let (particular, kernel) = pressed.eliminate(Some(&avoid))?;
+        let mut solution = shortest(&particular, &kernel, &CancellationToken::new())
+            .unwrap_or_else(|Cancelled| unreachable!("nothing else holds the token"));
+        for (row, required) in solution.rows.iter_mut().zip(&constraints.required.rows) {
+            *row |= required;
+        }
+        Some(solution)
+    }
+
+    /// The smallest set of presses that leaves the board showing any of
+    /// `goals`' patterns, with the index of the pattern it shows, or `None`
+    /// if no pattern can be reached. Ties go to the earlier pattern.
+    ///
+    /// ```
+    /// use lightsout::{Board, Goals};
+    ///
+    /// // One press from every light on, and many more from every light off.
+    /// let board = Board::from_code("5x5-ffffff8").unwrap().clone_toggle(2, 2);
+    /// let goals = Goals::off_or_on(5, 5);
+    /// let (presses, reached) = board.solve_goals(&goals).unwrap();
+    /// assert_eq!((presses.positions(), reached), (vec![(2, 2)], 1));
+    ///
+    /// let mut pressed = board.clone();
+    /// pressed.apply_press_set(&presses);
+    /// assert_eq!(goals.reached(&pressed), Some(1));
+    /// ```
+    ///
+    /// Panics if a pattern is a different size from the board.
+    pub fn solve_goals(&self, goals: &Goals) -> Option<(BitMask, usize)> {
+        (0..goals.patterns.len())
+            .filter_map(|index| Some((goals.retarget(self, index).solve_linear()?, index)))
+            .min_by_key(|(presses, _)| presses.count())
+    }
+
+    /// Light-chase with the first top row found to work, without searching
+    /// for a shorter one. Fast, but can need many more presses than
+    /// [`Board::solve_linear`] on boards with several solutions. Boards
+    /// with other rules take the first solution elimination finds instead.
+    pub fn solve_chase(&self) -> Option<BitMask> {
+        if self.width == 0 || self.height == 0 {
+            return Some(BitMask::new(self.width, self.height));
+        }
+        self.solution_space().map(|(particular, _)| particular)
+    }
+
+    /// Every set of presses that solves the board, one per kernel coset
+    /// member, in no particular order. Empty if the board is unsolvable.
+    /// Past [`Board::MAX_SEARCHED_KERNEL`], only the first solution.
+    pub fn solutions(&self) -> impl Iterator<Item = BitMask> + '_ {
+        let space = self.solution_space();
+        let count = space.as_ref().map_or(0, |(_, kernel)| 1u128 << searched(kernel).len());
+        (0..count).map(move |combination| {
+            let (particular, kernel) = space.as_ref().unwrap();
+            combine_masks(particular, searched(kernel), combination)
+        })
+    }
+
+    /// How many presses the shortest solution takes, or `None` if the board
+    /// is unsolvable: the length of [`Board::solve_linear`]'s answer, for
+    /// ratings and statistics that don't need the presses themselves. Walks
+    /// the kernel combinations in Gray code order, toggling one vector into
+    /// a single running solution each step instead of building each one.
+    pub fn optimal_length(&self) -> Option<usize> {
+        if self.width == 0 || self.height == 0 {
+            return Some(0);
+        }
+        let (mut solution, kernel) = self.solution_space()?;
+        let kernel = searched(&kernel);
+        let mut best = solution.count();
+        for step in 1..(1u128 << kernel.len()) {
+            let vector = &kernel[step.trailing_zeros() as usize];
+            solution.rows.iter_mut().zip(&vector.rows).for_each(|(a, b)| *a ^= b);
+            best = best.min(solution.count());
+        }
+        Some(best)
+    }
+
+    /// [`Board::solve_linear`] as an anytime solver: `improved` hears of
+    /// the first solution found, the same as [`Board::solve_chase`]'s, and
+    /// then of each shorter one as the kernel combinations are walked in
+    /// Gray code order, along with how many combinations had been tried.
+    /// Cancelling stops the walk but keeps the best found so far, which is
+    /// returned, and is the shortest if the walk ran to the end. `None` if
+    /// the board is unsolvable.
+    ///
+    /// ```
+    /// use lightsout::{Board, CancellationToken};
+    ///
+    /// let board = Board::from_code("5x5-c408f38").unwrap();
+    /// let mut lengths = Vec::new();
+    /// let best = board.solve_anytime(&CancellationToken::new(), |solution, _| {
+    ///     lengths.push(solution.count());
+    /// });
+    /// assert_eq!(best.unwrap().count(), 3);
+    /// assert_eq!(lengths.last(), Some(&3));
+    /// assert!(lengths.windows(2).all(|pair| pair[1] < pair[0]));
+    /// ```
+    pub fn solve_anytime(
+        &self,
+        cancel: &CancellationToken,
+        mut improved: impl FnMut(&BitMask, u128),
+    ) -> Option<BitMask> {
+        if self.width == 0 || self.height == 0 {
+            let solution = BitMask::new(self.width, self.height);
+            improved(&solution, 1);
+            return Some(solution);
+        }
+        let (mut solution, kernel) = self.solution_space()?;
+        let mut best = solution.clone();
+        improved(&best, 1);
+        // Cancelling is how a walk too long to finish ends, so this one
+        // isn't cut off at `MAX_SEARCHED_KERNEL`.
+        let steps = 1u128.checked_shl(kernel.len() as u32).unwrap_or(u128::MAX);
+        for step in 1..steps {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let vector = &kernel[step.trailing_zeros() as usize];
+            solution.rows.iter_mut().zip(&vector.rows).for_each(|(a, b)| *a ^= b);
+            if solution.count() < best.count() {
+                best = solution.clone();
+                improved(&best, step + 1);
+            }
+        }
+        Some(best)
+    }
+
+    /// Every solution with the fewest presses, in the same order as
+    /// [`Board::solutions`]. A hint can only point at the one way to solve
+    /// the board when there's exactly one. Empty if the board is unsolvable.
+    pub fn minimal_solutions(&self) -> Vec<BitMask> {
+        let Some(best) = self.solve_linear().map(|solution| solution.count()) else {
+            return Vec::new();
+        };
+        self.solutions()
+            .filter(|solution| solution.count() == best)
+            .collect()
+    }
+
+    /// Why the board can't be solved, or `None` if it can: a set of
+    /// cells every press toggles an even number of, so how many of them
+    /// differ from the goal is odd or even for good, of which an odd
+    /// number do. Obstacles are left out of the system rather than
+    /// searched around, and the set is the smallest found among the
+    /// combinations of the system's checks.
+    ///
+    /// ```
+    /// use lightsout::Board;
+    ///
+    /// let invariant = Board::from_code("5x5-8000000").unwrap().invariant().unwrap();
+    /// assert!(invariant.cells().contains(&(0, 0)));
+    /// assert!(Board::from_code("5x5-c408f38").unwrap().invariant().is_none());
+    /// ```
+    pub fn invariant(&self) -> Option<Invariant> {
+        LinearSystem::new(self.width, self.height, self.ruleset()).invariant(self)
+    }
+
+    /// How many independent press sets leave a board unchanged under its
+    /// rules. Depends only on the size and presses, not the lights: every
+    /// solvable board has `2^kernel_dimension` solutions, and only one board
+    /// in `2^kernel_dimension` is solvable.
+    pub fn kernel_dimension(&self) -> usize {
+        if self.width == 0 || self.height == 0 {
+            return 0;
+        }
+        let empty = Board::new(self.width, self.height).with_ruleset(self.ruleset().without_goal());
+        empty
+            .solution_space()
+            .map_or(0, |(_, kernel)| kernel.len())
+    }
+
+    /// Work through [`Board::solve_linear`] keeping every intermediate
+    /// result, for presenting the method step by step. Light-chasing only
+    /// applies to classic presses, so other rules get an empty explanation.
+    pub fn explain_linear(&self) -> LinearExplanation {
+        if !self.presses_classically() {
+            return LinearExplanation {
+                residual: 0,
+                columns: Vec::new(),
+                particular: None,
+                kernel: Vec::new(),
+                candidates: Vec::new(),
+                chosen: None,
+            };
+        }
+        let board = self.residual();
+        let (columns, residual) = board.top_row_system();
+        let mut explanation = LinearExplanation {
+            residual,
+            columns,
+            particular: None,
+            kernel: Vec::new(),
+            candidates: Vec::new(),
+            chosen: None,
+        };
+        if let Some((particular, kernel)) = board.top_row_solutions() {
+            explanation.candidates = (0..(1u128 << searched(&kernel).len()))
+                .map(|combination| {
+                    let first = combine(particular, searched(&kernel), combination);
+                    let (presses, _) = board.chase(first);
+                    (first, presses.iter().map(|row| row.count_ones() as usize).sum())
+                })
+                .collect();
+            explanation.chosen = explanation
+                .candidates
+                .iter()
+                .min_by_key(|(_, count)| *count)
+                .map(|(first, _)| *first);
+            explanation.particular = Some(particular);
+            explanation.kernel = kernel;
+        }
+        explanation
+    }
+}
+
+/// `particular` plus the kernel vectors selected by the bits of `combination`.
+fn combine(particular: Rowtype, kernel: &[Rowtype], combination: u128) -> Rowtype {
+    kernel
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| combination & (1 << i) != 0)
+        .fold(particular, |acc, (_, vector)| acc ^ vector)
+}
+
+/// The kernel vectors to combine in search of fewer presses: all of them,
+/// or none past [`Board::MAX_SEARCHED_KERNEL`].
+pub(crate) fn searched<T>(kernel: &[T]) -> &[T] {
+    if kernel.len() > Board::MAX_SEARCHED_KERNEL {
+        &[]
+    } else {
+        kernel
+    }
+}
+
+/// Whichever combination of kernel vectors with `particular` needs the
+/// fewest presses, the first found on ties.
+fn shortest(
+    particular: &BitMask,
+    kernel: &[BitMask],
+    cancel: &CancellationToken,
+) -> Result<BitMask, Cancelled> {
+    cancel.check()?;
+    let kernel = searched(kernel);
+    let mut best = particular.clone();
+    for combination in 1..(1u128 << kernel.len()) {
+        cancel.check()?;
+        let solution = combine_masks(particular, kernel, combination);
+        if solution.count() < best.count() {
+            best = solution;
+        }
+    }
+    Ok(best)
+}
+
+/// `particular` plus the kernel masks selected by the bits of `combination`.
+fn combine_masks(particular: &BitMask, kernel: &[BitMask], combination: u128) -> BitMask {
+    let mut solution = particular.clone();
+    for (_, vector) in kernel.iter().enumerate().filter(|(i, _)| combination & (1 << i) != 0) {
+        solution.rows.iter_mut().zip(&vector.rows).for_each(|(a, b)| *a ^= b);
+    }
+    solution
+}
+
+/// Intermediate results of the linear solver, see [`Board::explain_linear`].
+/// Row vectors use bit `x` for column `x`.
+#[derive(Clone, Debug)]
+pub struct LinearExplanation {
+    /// Bottom row left lit after chasing with nothing pressed on the top row.
+    pub residual: Rowtype,
+    /// Bottom row toggled by pressing each top-row cell then chasing.
+    pub columns: Vec<Rowtype>,
+    /// One top row that clears the board, if any does.
+    pub particular: Option<Rowtype>,
+    /// Top rows that chase down to a blank bottom row on an empty board.
+    pub kernel: Vec<Rowtype>,
+    /// Every solving top row with the total presses it leads to.
+    pub candidates: Vec<(Rowtype, usize)>,
+    /// The candidate with the fewest presses.
+    pub chosen: Option<Rowtype>,
+}
@@ -0,0 +1,37 @@
+//! Board representation, toggle logic and the linear GF(2) solver.
+//!
+//! Everything in here only needs `alloc`, so it builds under `no_std` for
+//! driving physical boards from a microcontroller.
+
+mod batch;
+mod board;
+mod builder;
+mod cancel;
+mod constraints;
+mod enumerate;
+mod goals;
+pub mod gf2;
+mod guide;
+mod invariant;
+mod linear;
+pub mod oracle;
+mod parse;
+mod rank;
+mod route;
+mod rules;
+mod symmetry;
+
+pub use batch::BatchSolver;
+pub use board::{full_row, BitMask, Board, Rowtype};
+pub use builder::{BoardBuilder, BuildError};
+pub use cancel::{CancellationToken, Cancelled};
+pub use constraints::PressConstraints;
+pub use enumerate::SolvableBoards;
+pub use goals::Goals;
+pub use guide::{Guidance, Reduction};
+pub use invariant::Invariant;
+pub use linear::{LinearExplanation, LinearSystem};
+pub use parse::{cell_name, parse_cell, ParseError};
+pub use rank::Preference;
+pub use route::{route_length, shortest_route, EXACT_ROUTE_PRESSES};
+pub use rules::{Neighborhood, Ruleset, RulesError};
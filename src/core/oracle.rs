@@ -0,0 +1,198 @@
+//! Checks of the algebra the fast paths rely on, against slow reference
+//! versions written straight from the rules: a press toggles what
+//! [`Ruleset::affected`] says, pressing a cell twice changes nothing,
+//! presses give the same board in any order, and a lit count worked out
+//! incrementally is the one a fresh count gives.
+//!
+//! Debug builds, tests included, run them on a random sample of the
+//! presses, [`Board::verify`] calls and child lit counts made anywhere,
+//! and on the scores searches give the children they expand, so a slip in
+//! the row-at-a-time arithmetic shows up in whatever test happens to hit
+//! it. A failed check panics with the smallest board, and
+//! fewest presses, it could shrink the failure to while it still fails.
+//! Release builds skip them entirely; the checks themselves are public for
+//! running on boards of your own.
+//!
+//! [`Ruleset::affected`]: super::Ruleset::affected
+
+use alloc::{string::String, vec::Vec};
+
+use super::{
+    board::{Board, Rowtype},
+    parse::cell_name,
+};
+
+/// One in how many chances to check one is taken.
+#[cfg(debug_assertions)]
+const SAMPLE: u32 = 16;
+
+/// Whether to check this time, by a xorshift shared across threads. Always
+/// no in release builds, and on targets without atomics.
+#[inline]
+pub(crate) fn sampled() -> bool {
+    #[cfg(all(debug_assertions, target_has_atomic = "32"))]
+    {
+        use ::core::sync::atomic::{AtomicU32, Ordering};
+
+        static STATE: AtomicU32 = AtomicU32::new(0x9e37_79b9);
+        let mut x = STATE.load(Ordering::Relaxed);
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        STATE.store(x, Ordering::Relaxed);
+        x.is_multiple_of(SAMPLE)
+    }
+    #[cfg(not(all(debug_assertions, target_has_atomic = "32")))]
+    false
+}
+
+/// The board after pressing `(x, y)`, from [`Ruleset::affected`] alone.
+///
+/// [`Ruleset::affected`]: super::Ruleset::affected
+pub fn reference_press(board: &Board, x: usize, y: usize) -> Board {
+    let mut pressed = board.clone();
+    for (nx, ny) in board.ruleset().affected(x, y, board.width, board.height) {
+        pressed.rows[ny] ^= 1 << nx;
+    }
+    pressed
+}
+
+/// Greedily turn off lights of `board` while `fails` still holds.
+pub fn minimize(board: &Board, mut fails: impl FnMut(&Board) -> bool) -> Board {
+    let mut smallest = board.clone();
+    for ((x, y), lit) in board.cells() {
+        if lit {
+            let mut smaller = smallest.clone();
+            smaller.set(x, y, false);
+            if fails(&smaller) {
+                smallest = smaller;
+            }
+        }
+    }
+    smallest
+}
+
+/// Greedily drop presses while `fails` still holds.
+pub fn minimize_presses(
+    presses: &[(usize, usize)],
+    mut fails: impl FnMut(&[(usize, usize)]) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut fewest = presses.to_vec();
+    let mut i = 0;
+    while i < fewest.len() {
+        let mut fewer = fewest.clone();
+        fewer.remove(i);
+        if fails(&fewer) {
+            fewest = fewer;
+        } else {
+            i += 1;
+        }
+    }
+    fewest
+}
+
+fn names(presses: &[(usize, usize)]) -> String {
+    let names: Vec<String> = presses.iter().map(|&(x, y)| cell_name(x, y)).collect();
+    names.join(" ")
+}
+
+/// Pressing every cell of row `y` in `mask` at once, with
+/// [`Board::toggle_row`], is pressing each one by the reference, and doing
+/// it twice gives the board back.
+///
+/// Panics, with the smallest board that still fails, if not.
+pub fn check_row_press(board: &Board, y: usize, mask: Rowtype) {
+    let fails = |board: &Board| {
+        let mut fast = board.clone();
+        fast.press_mask(y, mask);
+        let reference = (0..board.width)
+            .filter(|x| mask & (1 << x) != 0)
+            .fold(board.clone(), |pressed, x| reference_press(&pressed, x, y));
+        let mut twice = fast.clone();
+        twice.press_mask(y, mask);
+        fast != reference || twice != *board
+    };
+    if fails(board) {
+        let board = minimize(board, fails);
+        let cells: Vec<(usize, usize)> = (0..board.width)
+            .filter(|x| mask & (1 << x) != 0)
+            .map(|x| (x, y))
+            .collect();
+        panic!(
+            "oracle: pressing {} on {} either isn't what the rules say or doesn't undo itself",
+            names(&cells),
+            board.to_code()
+        );
+    }
+}
+
+/// `presses` clear the same lights in reverse as in order.
+///
+/// Panics, with the fewest presses and smallest board that still fail, if
+/// not.
+pub fn check_order(board: &Board, presses: &[(usize, usize)]) {
+    let fails = |board: &Board, presses: &[(usize, usize)]| {
+        let forward = presses.iter().fold(board.clone(), |pressed, &(x, y)| {
+            reference_press(&pressed, x, y)
+        });
+        let mut backward = board.clone();
+        for &(x, y) in presses.iter().rev() {
+            backward.press_mask(y, 1 << x);
+        }
+        forward != backward
+    };
+    if fails(board, presses) {
+        let presses = minimize_presses(presses, |presses| fails(board, presses));
+        let board = minimize(board, |board| fails(board, &presses));
+        panic!(
+            "oracle: pressing {} on {} gives a different board in reverse",
+            names(&presses),
+            board.to_code()
+        );
+    }
+}
+
+/// `counts`, from [`Board::child_lit_counts_from`] told the board has
+/// `lit` lights on, are every child's fresh lit count.
+///
+/// Panics, with the smallest board that still fails, if not.
+pub fn check_child_lit_counts(board: &Board, lit: usize, counts: &[usize]) {
+    if lit != board.lit_count() {
+        panic!(
+            "oracle: child lit counts of {} were worked out from {lit} lights, not its {}",
+            board.to_code(),
+            board.lit_count()
+        );
+    }
+    let wrong = |board: &Board, counts: &[usize]| {
+        (0..board.width * board.height).find(|&cell| {
+            let child = reference_press(board, cell % board.width, cell / board.width);
+            counts.get(cell) != Some(&child.lit_count())
+        })
+    };
+    let Some(cell) = wrong(board, counts) else {
+        return;
+    };
+    // Shrink the board if the fast path gets it wrong by itself, rather
+    // than only with the counts it was handed.
+    let reproduces = |board: &Board| {
+        let counts = board.child_lit_counts_unchecked(board.lit_count());
+        wrong(board, &counts).is_some()
+    };
+    let (board, counts) = if reproduces(board) {
+        let board = minimize(board, reproduces);
+        let counts = board.child_lit_counts_unchecked(board.lit_count());
+        (board, counts)
+    } else {
+        (board.clone(), counts.to_vec())
+    };
+    let cell = wrong(&board, &counts).unwrap_or(cell);
+    let (x, y) = (cell % board.width, cell / board.width);
+    panic!(
+        "oracle: the incremental lit count after pressing {} on {} is {:?}, not {}",
+        cell_name(x, y),
+        board.to_code(),
+        counts.get(cell),
+        reference_press(&board, x, y).lit_count()
+    );
+}
@@ -0,0 +1,255 @@
+use alloc::{string::String, vec::Vec};
+use ::core::{fmt::Display, str::FromStr};
+
+use super::{
+    board::{full_row, Board, Rowtype},
+    oracle,
+    rules::{Ruleset, RulesError},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    TooWide(usize),
+    RaggedRow { row: usize, expected: usize, found: usize },
+    BadCell { row: usize, found: char },
+    BadCode,
+    /// A ruleset rule that couldn't be understood.
+    BadRules(String),
+    /// Rules that were understood but can't be played on the board.
+    Rules(RulesError),
+    /// A press constraint naming no cell on the board.
+    BadPress(String),
+    /// A goal pattern that's neither a name nor a mask of the board's size.
+    BadGoal(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "board is empty"),
+            ParseError::TooWide(width) => write!(
+                f,
+                "board is {width} cells wide, at most {} are supported",
+                Rowtype::BITS
+            ),
+            ParseError::RaggedRow { row, expected, found } => write!(
+                f,
+                "row {} has {found} cells, expected {expected}",
+                row + 1
+            ),
+            ParseError::BadCell { row, found } => {
+                write!(f, "unexpected character {found:?} in row {}", row + 1)
+            }
+            ParseError::BadCode => write!(f, "malformed puzzle code"),
+            ParseError::BadRules(rule) => write!(f, "unrecognized rule {rule:?}"),
+            ParseError::Rules(err) => err.fmt(f),
+            ParseError::BadPress(press) => write!(f, "{press:?} is not a cell on the board"),
+            ParseError::BadGoal(goal) => write!(f, "{goal:?} is not a goal pattern for the board"),
+        }
+    }
+}
+
+impl ::core::error::Error for ParseError {}
+
+fn parse_row(line: &str, row: usize) -> Result<Vec<bool>, ParseError> {
+    let mut cells = Vec::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '#' | 'X' | 'x' | '1' | '*' => cells.push(true),
+            '.' | 'O' | 'o' | '0' | '-' | '_' => cells.push(false),
+            // The block glyphs come in pairs, as printed by `Display`.
+            '█' | '░' => {
+                if chars.next() != Some(c) {
+                    return Err(ParseError::BadCell { row, found: c });
+                }
+                cells.push(c == '█');
+            }
+            c if c.is_whitespace() => (),
+            found => return Err(ParseError::BadCell { row, found }),
+        }
+    }
+    Ok(cells)
+}
+
+impl FromStr for Board {
+    type Err = ParseError;
+
+    /// Parse a grid of cells, one row per line. Lit cells are any of `#X1*`
+    /// and unlit cells any of `.O0-_`; the block glyphs printed by `Display`
+    /// are accepted too, so a printed board parses back to itself.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .collect();
+        let end = lines
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .ok_or(ParseError::Empty)?;
+        let mut rows = Vec::new();
+        let mut width = None;
+        for (y, line) in lines[..=end].iter().enumerate() {
+            let cells = parse_row(line, y)?;
+            let expected = *width.get_or_insert(cells.len());
+            if cells.len() != expected {
+                return Err(ParseError::RaggedRow { row: y, expected, found: cells.len() });
+            }
+            if expected > Board::MAX_WIDTH {
+                return Err(ParseError::TooWide(expected));
+            }
+            rows.push(
+                cells
+                    .iter()
+                    .enumerate()
+                    .fold(0, |row, (x, lit)| row | (Into::<Rowtype>::into(*lit) << x)),
+            );
+        }
+        let width = width.unwrap_or(0);
+        if width == 0 {
+            return Err(ParseError::Empty);
+        }
+        Ok(Board {
+            width,
+            height: rows.len(),
+            rows,
+            rules: None,
+        })
+    }
+}
+
+/// Rows of cells as hex in row-major order, four cells per digit with the
+/// first cell in the high bit, padding the last digit with unlit cells.
+pub(crate) fn hex_cells(rows: &[Rowtype], width: usize) -> String {
+    let cells: Vec<bool> = rows
+        .iter()
+        .flat_map(|&row| (0..width).map(move |x| row & (1 << x) != 0))
+        .collect();
+    cells
+        .chunks(4)
+        .map(|chunk| {
+            let digit = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |digit, (i, lit)| digit | (Into::<u32>::into(*lit) << (3 - i)));
+            ::core::char::from_digit(digit, 16).unwrap()
+        })
+        .collect()
+}
+
+/// The inverse of [`hex_cells`], rejecting the wrong number of digits and
+/// lit padding.
+pub(crate) fn parse_hex_cells(
+    digits: &str,
+    width: usize,
+    height: usize,
+) -> Result<Vec<Rowtype>, ParseError> {
+    let cells = width.checked_mul(height).ok_or(ParseError::BadCode)?;
+    if digits.len() != cells.div_ceil(4) {
+        return Err(ParseError::BadCode);
+    }
+    let mut rows = alloc::vec![0; height];
+    for (i, c) in digits.chars().enumerate() {
+        let digit = c.to_digit(16).ok_or(ParseError::BadCode)?;
+        for bit in 0..4 {
+            let cell = i * 4 + bit;
+            let lit = digit & (1 << (3 - bit)) != 0;
+            if cell < cells {
+                rows[cell / width] |= Into::<Rowtype>::into(lit) << (cell % width);
+            } else if lit {
+                return Err(ParseError::BadCode);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+impl Board {
+    /// Compact shareable code for the board, like `5x5-1f0a3c0`: the size
+    /// followed by the cells in row-major order as hex, four cells per digit.
+    /// Boards with other rules add them after a colon, as in
+    /// `5x5-1f0a3c0:wrap+square`; see [`Ruleset::parse`].
+    pub fn to_code(&self) -> String {
+        let mut code = alloc::format!(
+            "{}x{}-{}",
+            self.width,
+            self.height,
+            hex_cells(&self.rows, self.width)
+        );
+        if !self.ruleset().is_classic() {
+            code += &alloc::format!(":{}", self.ruleset());
+        }
+        code
+    }
+
+    pub fn from_code(code: &str) -> Result<Board, ParseError> {
+        let (code, rules) = code.trim().split_once(':').unwrap_or((code.trim(), ""));
+        let (size, digits) = code.split_once('-').ok_or(ParseError::BadCode)?;
+        let (width, height) = size.split_once('x').ok_or(ParseError::BadCode)?;
+        let width: usize = width.parse().map_err(|_| ParseError::BadCode)?;
+        let height: usize = height.parse().map_err(|_| ParseError::BadCode)?;
+        if width == 0 || height == 0 {
+            return Err(ParseError::Empty);
+        }
+        if width > Board::MAX_WIDTH {
+            return Err(ParseError::TooWide(width));
+        }
+        let rows = parse_hex_cells(digits, width, height)?;
+        let ruleset = Ruleset::parse(rules, width, height)?;
+        ruleset.check(width, height).map_err(ParseError::Rules)?;
+        let mut board = Board {
+            width,
+            height,
+            rows,
+            rules: None,
+        };
+        debug_assert!(board.rows.iter().all(|row| row & !full_row(width) == 0));
+        board.set_ruleset(ruleset);
+        Ok(board)
+    }
+
+    /// Check that pressing `presses` in order solves the board. Presses
+    /// outside the board make the solution invalid rather than panicking.
+    pub fn verify(&self, presses: &[(usize, usize)]) -> bool {
+        let mut board = self.clone();
+        for &(x, y) in presses {
+            if x >= self.width || y >= self.height {
+                return false;
+            }
+            board.toggle(x, y);
+        }
+        if oracle::sampled() {
+            oracle::check_order(self, presses);
+        }
+        board.is_solved()
+    }
+}
+
+/// Spreadsheet-style name for a cell: column letters then 1-based row, so
+/// `(0, 0)` is `a1` and `(27, 4)` is `ab5`.
+pub fn cell_name(x: usize, y: usize) -> String {
+    let mut letters = Vec::new();
+    let mut column = x + 1;
+    while column > 0 {
+        letters.push(b'a' + ((column - 1) % 26) as u8);
+        column = (column - 1) / 26;
+    }
+    letters.reverse();
+    alloc::format!("{}{}", String::from_utf8(letters).unwrap(), y + 1)
+}
+
+/// Inverse of [`cell_name`], case-insensitive.
+pub fn parse_cell(name: &str) -> Option<(usize, usize)> {
+    let name = name.trim();
+    let split = name.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = name.split_at(split);
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+    let column = letters
+        .bytes()
+        .fold(0, |column, c| column * 26 + (c.to_ascii_lowercase() - b'a') as usize + 1);
+    let row: usize = digits.parse().ok()?;
+    Some((column - 1, row.checked_sub(1)?))
+}
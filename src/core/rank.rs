@@ -0,0 +1,82 @@
+use alloc::string::{String, ToString};
+use ::core::{fmt::Display, str::FromStr};
+
+use super::{
+    board::{BitMask, Board},
+    route::{route_length, shortest_route},
+};
+
+/// How to choose between a board's minimal solutions when it has several.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Preference {
+    /// Whichever [`Board::solve_linear`] finds first.
+    #[default]
+    Fewest,
+    /// Presses spread over the fewest rows.
+    Rows,
+    /// The fewest presses on the top row: the ones a player has to work out
+    /// for themselves, rather than follow the lights down to.
+    Chase,
+    /// The least hand movement between presses, made in the order
+    /// [`shortest_route`] picks.
+    Travel,
+}
+
+impl Preference {
+    pub const ALL: [Preference; 4] = [
+        Preference::Fewest,
+        Preference::Rows,
+        Preference::Chase,
+        Preference::Travel,
+    ];
+
+    /// How bad `solution` is by this preference; lower is better.
+    pub fn cost(&self, solution: &BitMask) -> usize {
+        match self {
+            Preference::Fewest => solution.count(),
+            Preference::Rows => solution.rows.iter().filter(|&&row| row != 0).count(),
+            Preference::Chase => solution
+                .rows
+                .first()
+                .map_or(0, |row| row.count_ones() as usize),
+            Preference::Travel => route_length(&shortest_route(&solution.positions())),
+        }
+    }
+}
+
+impl FromStr for Preference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Preference::ALL
+            .into_iter()
+            .find(|preference| preference.to_string() == s)
+            .ok_or_else(|| {
+                alloc::format!("unknown preference {s:?} (expected fewest, rows, chase or travel)")
+            })
+    }
+}
+
+impl Display for Preference {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Preference::Fewest => "fewest",
+            Preference::Rows => "rows",
+            Preference::Chase => "chase",
+            Preference::Travel => "travel",
+        })
+    }
+}
+
+impl Board {
+    /// The minimal solution `preference` ranks best, the first found on
+    /// ties, or `None` if the board is unsolvable.
+    pub fn preferred_solution(&self, preference: Preference) -> Option<BitMask> {
+        if preference == Preference::Fewest {
+            return self.solve_linear();
+        }
+        self.minimal_solutions()
+            .into_iter()
+            .min_by_key(|solution| preference.cost(solution))
+    }
+}
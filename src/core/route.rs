@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+/// Press sets up to this size are ordered exactly; bigger ones with the
+/// nearest-neighbour heuristic.
+pub const EXACT_ROUTE_PRESSES: usize = 12;
+
+/// Nearest-neighbour routes are tried from at most this many starting
+/// presses, which keeps whole-board solutions quick to order.
+const NEAREST_STARTS: usize = 16;
+
+/// Hand movement between two cells, in cells moved along rows and columns.
+pub(crate) fn travel(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Total movement pressing `route` in order.
+pub fn route_length(route: &[(usize, usize)]) -> usize {
+    route.windows(2).map(|pair| travel(pair[0], pair[1])).sum()
+}
+
+/// `presses` reordered to need as little hand movement as possible, starting
+/// from whichever press suits. Presses commute, so any order still solves
+/// the board, but on a physical handheld a short route is a fast one.
+///
+/// Routes through up to [`EXACT_ROUTE_PRESSES`] presses are shortest, by
+/// Held-Karp dynamic programming over subsets. Longer ones start from each
+/// of the first few presses and always move to the nearest press left,
+/// keeping the best; ties go to the earlier press in `presses`.
+pub fn shortest_route(presses: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    if presses.len() <= 2 {
+        return presses.to_vec();
+    }
+    if presses.len() <= EXACT_ROUTE_PRESSES {
+        held_karp(presses)
+    } else {
+        (0..presses.len().min(NEAREST_STARTS))
+            .map(|start| nearest_neighbour(presses, start))
+            .min_by_key(|route| route_length(route))
+            .unwrap()
+    }
+}
+
+fn nearest_neighbour(presses: &[(usize, usize)], start: usize) -> Vec<(usize, usize)> {
+    let mut left: Vec<(usize, usize)> = presses.to_vec();
+    let mut route = Vec::with_capacity(presses.len());
+    route.push(left.remove(start));
+    while !left.is_empty() {
+        let here = *route.last().unwrap();
+        let (next, _) = left
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &press)| travel(here, press))
+            .unwrap();
+        route.push(left.remove(next));
+    }
+    route
+}
+
+/// The shortest open path through every press, where `best[set][last]` is
+/// the shortest path visiting exactly `set` and ending at `last`.
+fn held_karp(presses: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let n = presses.len();
+    let full = (1 << n) - 1;
+    let mut best = alloc::vec![usize::MAX; (1 << n) * n];
+    let mut came_from = alloc::vec![usize::MAX; (1 << n) * n];
+    for last in 0..n {
+        best[(1 << last) * n + last] = 0;
+    }
+    for set in 1..=full {
+        for last in (0..n).filter(|last| set & (1 << last) != 0) {
+            let length = best[set * n + last];
+            if length == usize::MAX {
+                continue;
+            }
+            for next in (0..n).filter(|next| set & (1 << next) == 0) {
+                let grown = set | (1 << next);
+                let candidate = length + travel(presses[last], presses[next]);
+                if candidate < best[grown * n + next] {
+                    best[grown * n + next] = candidate;
+                    came_from[grown * n + next] = last;
+                }
+            }
+        }
+    }
+
+    let mut last = (0..n).min_by_key(|&last| best[full * n + last]).unwrap();
+    let mut set = full;
+    let mut route = Vec::with_capacity(n);
+    loop {
+        route.push(presses[last]);
+        let previous = came_from[set * n + last];
+        if previous == usize::MAX {
+            break;
+        }
+        set &= !(1 << last);
+        last = previous;
+    }
+    route.reverse();
+    route
+}
@@ -0,0 +1,304 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use ::core::fmt::Display;
+
+use super::{
+    board::{BitMask, Rowtype},
+    parse::{hex_cells, parse_hex_cells, ParseError},
+};
+
+/// Which cells a press toggles, as offsets from the pressed cell.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The cell and its four orthogonal neighbours, as in the original game.
+    Plus,
+    /// The cell and its four diagonal neighbours.
+    Diagonal,
+    /// The full 3x3 square around the cell.
+    Square,
+    /// Any other pattern of `(dx, dy)` offsets.
+    Custom(Vec<(isize, isize)>),
+}
+
+const PLUS: &[(isize, isize)] = &[(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)];
+const DIAGONAL: &[(isize, isize)] = &[(0, 0), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+const SQUARE: &[(isize, isize)] = &[
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (0, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+impl Neighborhood {
+    pub fn offsets(&self) -> &[(isize, isize)] {
+        match self {
+            Neighborhood::Plus => PLUS,
+            Neighborhood::Diagonal => DIAGONAL,
+            Neighborhood::Square => SQUARE,
+            Neighborhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// Everything that makes a variant a variant, kept together so boards,
+/// codes, packs and solvers all agree on the rules being played.
+///
+/// Boards only store two states per cell, so `states` other than 2 can be
+/// described (and rejected by [`Ruleset::check`]) but not played.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ruleset {
+    pub neighborhood: Neighborhood,
+    /// Whether presses on one edge wrap round to the opposite edge.
+    pub wrap: bool,
+    /// How many states each cell cycles through.
+    pub states: u8,
+    /// The pattern to finish on, or `None` to turn every light off.
+    pub goal: Option<BitMask>,
+    /// Cells missing from the board: never lit and never pressable.
+    pub obstacles: Option<BitMask>,
+}
+
+/// The rules of the original game, for handing out by reference.
+pub(crate) static CLASSIC: Ruleset = Ruleset::CLASSIC;
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset::CLASSIC
+    }
+}
+
+impl Ruleset {
+    pub const CLASSIC: Ruleset = Ruleset {
+        neighborhood: Neighborhood::Plus,
+        wrap: false,
+        states: 2,
+        goal: None,
+        obstacles: None,
+    };
+
+    pub fn is_classic(&self) -> bool {
+        *self == Ruleset::CLASSIC
+    }
+
+    /// Whether presses behave as in the original game, so that the
+    /// row-at-a-time press and light-chasing shortcuts apply. The goal
+    /// pattern doesn't matter: solving towards it is solving the difference.
+    pub fn presses_classically(&self) -> bool {
+        self.neighborhood == Neighborhood::Plus
+            && !self.wrap
+            && self.states == 2
+            && self.obstacles.as_ref().is_none_or(|mask| mask.count() == 0)
+    }
+
+    /// The same rules with the goal reset to all lights off.
+    pub fn without_goal(&self) -> Ruleset {
+        Ruleset {
+            goal: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_obstacle(&self, x: usize, y: usize) -> bool {
+        self.obstacles.as_ref().is_some_and(|mask| mask.get(x, y))
+    }
+
+    /// Row `y` of the goal pattern.
+    pub(crate) fn goal_row(&self, y: usize) -> Rowtype {
+        self.goal.as_ref().map_or(0, |goal| goal.rows[y])
+    }
+
+    /// Cells toggled by pressing `(x, y)` on a `width` x `height` board.
+    /// Pressing an obstacle toggles nothing, and obstacles are never toggled.
+    pub fn affected(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let pressable = !self.is_obstacle(x, y);
+        self.neighborhood
+            .offsets()
+            .iter()
+            .filter(move |_| pressable)
+            .filter_map(move |&(dx, dy)| {
+                let nx = (x as isize).checked_add(dx)?;
+                let ny = (y as isize).checked_add(dy)?;
+                if self.wrap {
+                    Some((
+                        nx.rem_euclid(width as isize) as usize,
+                        ny.rem_euclid(height as isize) as usize,
+                    ))
+                } else if (0..width as isize).contains(&nx) && (0..height as isize).contains(&ny) {
+                    Some((nx as usize, ny as usize))
+                } else {
+                    None
+                }
+            })
+            .filter(move |&(nx, ny)| !self.is_obstacle(nx, ny))
+    }
+
+    /// Check the rules can be played on a `width` x `height` board.
+    pub fn check(&self, width: usize, height: usize) -> Result<(), RulesError> {
+        if self.states != 2 {
+            return Err(RulesError::Unsupported("cells with other than 2 states"));
+        }
+        for (name, mask) in [("goal", &self.goal), ("obstacle", &self.obstacles)] {
+            if let Some(mask) = mask {
+                let found = (mask.width(), mask.height());
+                if found != (width, height) {
+                    return Err(RulesError::MaskSize {
+                        mask: name,
+                        expected: (width, height),
+                        found,
+                    });
+                }
+            }
+        }
+        if let (Some(goal), Some(obstacles)) = (&self.goal, &self.obstacles) {
+            if goal.rows.iter().zip(&obstacles.rows).any(|(g, o)| g & o != 0) {
+                return Err(RulesError::GoalOnObstacle);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the form written by `Display`: `classic`, or `+`-separated
+    /// rules from `wrap`, `diagonal`, `square`, `pattern=dx.dy_dx.dy...`,
+    /// `states=N`, `goal=HEX` and `holes=HEX`, where the hex digits encode a
+    /// `width` x `height` mask the same way as puzzle codes. Pattern offsets
+    /// must be smaller than the board.
+    pub fn parse(text: &str, width: usize, height: usize) -> Result<Ruleset, ParseError> {
+        let bad = |rule: &str| ParseError::BadRules(rule.to_string());
+        let mut ruleset = Ruleset::CLASSIC;
+        let mut pattern = None;
+        for rule in text.trim().split('+').filter(|rule| !rule.is_empty()) {
+            let (name, value) = rule.split_once('=').unwrap_or((rule, ""));
+            let mask = || -> Result<BitMask, ParseError> {
+                Ok(BitMask {
+                    width,
+                    height,
+                    rows: parse_hex_cells(value, width, height).map_err(|_| bad(rule))?,
+                })
+            };
+            match name {
+                "classic" => (),
+                "wrap" => ruleset.wrap = true,
+                "plus" => ruleset.neighborhood = Neighborhood::Plus,
+                "diagonal" => ruleset.neighborhood = Neighborhood::Diagonal,
+                "square" => ruleset.neighborhood = Neighborhood::Square,
+                "pattern" => {
+                    let offsets = value
+                        .split('_')
+                        .map(|pair| {
+                            let (dx, dy) = pair.split_once('.')?;
+                            Some((dx.parse().ok()?, dy.parse().ok()?))
+                        })
+                        .collect::<Option<Vec<(isize, isize)>>>()
+                        .ok_or_else(|| bad(rule))?;
+                    // An offset as far as the board is wide or tall reaches
+                    // no cell a nearer one wouldn't, and past that the
+                    // arithmetic overflows.
+                    if offsets
+                        .iter()
+                        .any(|&(dx, dy)| dx.unsigned_abs() >= width || dy.unsigned_abs() >= height)
+                    {
+                        return Err(bad(rule));
+                    }
+                    ruleset.neighborhood = Neighborhood::Custom(offsets);
+                    pattern = Some(rule);
+                }
+                "states" => ruleset.states = value.parse().map_err(|_| bad(rule))?,
+                "goal" => ruleset.goal = Some(mask()?),
+                "holes" => ruleset.obstacles = Some(mask()?),
+                _ => return Err(bad(rule)),
+            }
+        }
+        // Two offsets reaching the same cell toggle it twice, which is the
+        // same as not at all, and wrapping can bring different ones there.
+        if let (Some(rule), Neighborhood::Custom(offsets)) = (pattern, &ruleset.neighborhood) {
+            let (width, height) = (width as isize, height as isize);
+            let cells: Vec<(isize, isize)> = offsets
+                .iter()
+                .map(|&(dx, dy)| {
+                    if ruleset.wrap {
+                        (dx.rem_euclid(width), dy.rem_euclid(height))
+                    } else {
+                        (dx, dy)
+                    }
+                })
+                .collect();
+            if (1..cells.len()).any(|i| cells[..i].contains(&cells[i])) {
+                return Err(bad(rule));
+            }
+        }
+        Ok(ruleset)
+    }
+}
+
+impl Display for Ruleset {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let mut rules: Vec<String> = Vec::new();
+        if self.wrap {
+            rules.push("wrap".to_string());
+        }
+        match &self.neighborhood {
+            Neighborhood::Plus => (),
+            Neighborhood::Diagonal => rules.push("diagonal".to_string()),
+            Neighborhood::Square => rules.push("square".to_string()),
+            Neighborhood::Custom(offsets) => {
+                let pairs: Vec<String> =
+                    offsets.iter().map(|(dx, dy)| format!("{dx}.{dy}")).collect();
+                rules.push(format!("pattern={}", pairs.join("_")));
+            }
+        }
+        if self.states != 2 {
+            rules.push(format!("states={}", self.states));
+        }
+        for (name, mask) in [("goal", &self.goal), ("holes", &self.obstacles)] {
+            if let Some(mask) = mask {
+                rules.push(format!("{name}={}", hex_cells(&mask.rows, mask.width)));
+            }
+        }
+        if rules.is_empty() {
+            f.write_str("classic")
+        } else {
+            f.write_str(&rules.join("+"))
+        }
+    }
+}
+
+/// Why a [`Ruleset`] can't be played on a board.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RulesError {
+    /// A goal or obstacle mask is a different size from the board.
+    MaskSize { mask: &'static str, expected: (usize, usize), found: (usize, usize) },
+    /// The goal asks for a light on a cell that doesn't exist.
+    GoalOnObstacle,
+    /// A rule the board representation can't express.
+    Unsupported(&'static str),
+}
+
+impl Display for RulesError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            RulesError::MaskSize { mask, expected, found } => write!(
+                f,
+                "{mask} mask is {}x{} but the board is {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            RulesError::GoalOnObstacle => write!(f, "goal lights a cell that is an obstacle"),
+            RulesError::Unsupported(rule) => write!(f, "{rule} is not supported"),
+        }
+    }
+}
+
+impl ::core::error::Error for RulesError {}
@@ -0,0 +1,248 @@
+use alloc::{format, string::String, vec::Vec};
+
+use super::{
+    board::{BitMask, Board},
+    rules::{Neighborhood, Ruleset},
+};
+
+/// One of the eight rotations and reflections of a grid: an optional
+/// transpose, then optional flips along each axis, then on wrapping boards
+/// a shift, which moves nothing that matters there.
+#[derive(Clone, Copy)]
+struct Symmetry {
+    transpose: bool,
+    flip_x: bool,
+    flip_y: bool,
+    shift: (usize, usize),
+}
+
+impl Symmetry {
+    /// The size of a `width` x `height` grid after the transform.
+    fn size(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where cell `(x, y)` of a `width` x `height` grid lands.
+    fn cell(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (width, height) = self.size(width, height);
+        let (x, y) = if self.transpose { (y, x) } else { (x, y) };
+        let x = if self.flip_x { width - 1 - x } else { x };
+        let y = if self.flip_y { height - 1 - y } else { y };
+        ((x + self.shift.0) % width, (y + self.shift.1) % height)
+    }
+
+    /// A press offset after the transform, which shifts leave alone.
+    fn offset(&self, dx: isize, dy: isize) -> (isize, isize) {
+        let (dx, dy) = if self.transpose { (dy, dx) } else { (dx, dy) };
+        (
+            if self.flip_x { -dx } else { dx },
+            if self.flip_y { -dy } else { dy },
+        )
+    }
+
+    fn mask(&self, mask: &BitMask) -> BitMask {
+        let (width, height) = self.size(mask.width, mask.height);
+        let mut image = BitMask::new(width, height);
+        for (x, y) in mask.positions() {
+            let (x, y) = self.cell(x, y, mask.width, mask.height);
+            image.set(x, y, true);
+        }
+        image
+    }
+
+    fn ruleset(&self, rules: &Ruleset, width: usize, height: usize) -> Ruleset {
+        let neighborhood = match &rules.neighborhood {
+            Neighborhood::Custom(offsets) => {
+                let mut offsets: Vec<_> =
+                    offsets.iter().map(|&(dx, dy)| self.offset(dx, dy)).collect();
+                offsets.sort();
+                Neighborhood::Custom(offsets)
+            }
+            // The built in patterns look the same every way round.
+            neighborhood => neighborhood.clone(),
+        };
+        let mask = |mask: &Option<BitMask>| {
+            mask.as_ref().map(|mask| {
+                debug_assert_eq!((mask.width, mask.height), (width, height));
+                self.mask(mask)
+            })
+        };
+        Ruleset {
+            neighborhood,
+            goal: mask(&rules.goal),
+            obstacles: mask(&rules.obstacles),
+            ..rules.clone()
+        }
+    }
+}
+
+impl Board {
+    /// Every way of rotating and reflecting the board, and of shifting it
+    /// if its presses wrap, with its rules transformed to match. Rotations
+    /// of a board that isn't square come out the other way round, unless
+    /// it's too tall to lay on its side.
+    fn symmetries(&self) -> impl Iterator<Item = (Symmetry, Board)> + '_ {
+        let shifts = if self.ruleset().wrap {
+            self.width * self.height
+        } else {
+            1
+        };
+        let transposes: &[bool] = if self.height <= Board::MAX_WIDTH {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        transposes.iter().flat_map(move |&transpose| {
+            let width = if transpose { self.height } else { self.width };
+            (0..4 * shifts).map(move |i| Symmetry {
+                transpose,
+                flip_x: i & 1 != 0,
+                flip_y: i & 2 != 0,
+                shift: ((i / 4) % width, i / 4 / width),
+            })
+        })
+        .map(|symmetry| (symmetry, self.transformed(symmetry)))
+    }
+
+    fn transformed(&self, symmetry: Symmetry) -> Board {
+        let (width, height) = symmetry.size(self.width, self.height);
+        let mut image = Board::new(width, height);
+        for ((x, y), lit) in self.cells().filter(|&(_, lit)| lit) {
+            let (x, y) = symmetry.cell(x, y, self.width, self.height);
+            image.set(x, y, lit);
+        }
+        let rules = symmetry.ruleset(self.ruleset(), self.width, self.height);
+        image.with_ruleset(rules)
+    }
+
+    /// The same puzzle for every board it can be rotated or reflected into,
+    /// or on wrapping boards shifted into: whichever of those has the
+    /// smallest [`Board::to_code`]. Goals, obstacles and custom press
+    /// patterns turn with the lights. A board that isn't square may come
+    /// back lying on its side.
+    pub fn canonical_form(&self) -> Board {
+        self.canonical_symmetry().1
+    }
+
+    /// [`Board::canonical_form`], along with the cell of this board each
+    /// of its cells came from, in row-major order, so presses found for
+    /// the canonical form can be made here instead.
+    ///
+    /// ```
+    /// use lightsout::Board;
+    ///
+    /// let board = Board::from_code("3x3-200").unwrap();
+    /// let (canonical, cells) = board.canonical_form_mapped();
+    /// let (x, y) = canonical.cells().find(|&(_, lit)| lit).unwrap().0;
+    /// assert_eq!(cells[y * canonical.width() + x], (2, 0));
+    /// ```
+    pub fn canonical_form_mapped(&self) -> (Board, Vec<(usize, usize)>) {
+        let (symmetry, canonical) = self.canonical_symmetry();
+        let mut cells = alloc::vec![(0, 0); self.width * self.height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (to_x, to_y) = symmetry.cell(x, y, self.width, self.height);
+                cells[to_y * canonical.width + to_x] = (x, y);
+            }
+        }
+        (canonical, cells)
+    }
+
+    /// A short name for the puzzle that's the same wherever it turns up:
+    /// sixteen hex digits of a 64-bit FNV-1a hash of its
+    /// [`Board::canonical_form`]'s code, which carries the rules, so every
+    /// rotation and reflection of a puzzle shares an id and the same lights
+    /// under other rules don't. Ids only change if puzzle codes do.
+    ///
+    /// ```
+    /// use lightsout::Board;
+    ///
+    /// let board = Board::from_code("3x3-800").unwrap();
+    /// assert_eq!(board.puzzle_id(), Board::from_code("3x3-200").unwrap().puzzle_id());
+    /// assert_ne!(board.puzzle_id(), Board::from_code("3x3-800:wrap").unwrap().puzzle_id());
+    /// ```
+    pub fn puzzle_id(&self) -> String {
+        let hash = self
+            .canonical_form()
+            .to_code()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("{hash:016x}")
+    }
+
+    /// Whether `id` is this board's [`Board::puzzle_id`] or the start of
+    /// it, ignoring case, for finding puzzles from an abbreviated id.
+    pub fn matches_id(&self, id: &str) -> bool {
+        !id.is_empty() && self.puzzle_id().starts_with(&id.to_ascii_lowercase())
+    }
+
+    fn canonical_symmetry(&self) -> (Symmetry, Board) {
+        self.symmetries()
+            .min_by_key(|(_, board)| board.to_code())
+            .unwrap_or_else(|| unreachable!("the identity is always a symmetry"))
+    }
+
+    /// Whether the two boards are the same puzzle up to rotation,
+    /// reflection and, when presses wrap, shifting, so that solving one
+    /// solves the other with the presses moved to match.
+    pub fn equivalent_to(&self, other: &Board) -> bool {
+        self.width * self.height == other.width * other.height
+            && self.lit_count() == other.lit_count()
+            && self.canonical_form().to_code() == other.canonical_form().to_code()
+    }
+
+    /// Whether some rotation or reflection other than the identity maps the
+    /// board onto itself, lights and rules alike: a start with a mirror line
+    /// or turning symmetry.
+    pub fn is_symmetric(&self) -> bool {
+        let transposes: &[bool] =
+            if self.width == self.height { &[false, true] } else { &[false] };
+        transposes.iter().any(|&transpose| {
+            (0..4).filter(|&i| transpose || i != 0).any(|i| {
+                let symmetry = Symmetry {
+                    transpose,
+                    flip_x: i & 1 != 0,
+                    flip_y: i & 2 != 0,
+                    shift: (0, 0),
+                };
+                self.transformed(symmetry) == *self
+            })
+        })
+    }
+
+    /// Where cell `(x, y)` lands under each rotation and reflection that
+    /// maps the board's size and rules onto themselves, leaving out the
+    /// cell itself and repeats. Those are the cells that mirror it in the
+    /// puzzle's layout, whatever the lights.
+    #[cfg(feature = "std")]
+    pub(crate) fn mirrors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let rules = self.ruleset();
+        let transposes: &[bool] =
+            if self.width == self.height { &[false, true] } else { &[false] };
+        let mut mirrors = Vec::new();
+        for &transpose in transposes {
+            for i in 0..4 {
+                let symmetry = Symmetry {
+                    transpose,
+                    flip_x: i & 1 != 0,
+                    flip_y: i & 2 != 0,
+                    shift: (0, 0),
+                };
+                let cell = symmetry.cell(x, y, self.width, self.height);
+                if cell != (x, y)
+                    && !mirrors.contains(&cell)
+                    && symmetry.ruleset(rules, self.width, self.height) == *rules
+                {
+                    mirrors.push(cell);
+                }
+            }
+        }
+        mirrors
+    }
+}
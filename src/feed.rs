@@ -0,0 +1,334 @@
+//! A puzzle a day as static RSS, Atom and JSON feeds, for publishing from a
+//! cron job with nothing but this crate. Each day's board comes from its
+//! date, so the same day always gets the same puzzle wherever the feed is
+//! built, and entries embed the board as an SVG with a link to its solution.
+
+use std::{
+    fmt::{Display, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    cell_name,
+    core::{BitMask, Board},
+    generate::{generate_solvable, Generated, SEEDS_PER_BOARD},
+    json::Json,
+};
+
+/// A day in the proleptic Gregorian calendar, counted from 1970-01-01.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(pub i64);
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+impl Date {
+    /// Today in UTC.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Date((seconds / 86_400) as i64)
+    }
+
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Date {
+        // Howard Hinnant's days_from_civil, with years starting in March so
+        // leap days fall at the end.
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = month as i64;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + day as i64
+            - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Date(era * 146_097 + day_of_era - 719_468)
+    }
+
+    /// The year, month (1 to 12) and day of the month.
+    pub fn ymd(self) -> (i64, u32, u32) {
+        let days = self.0 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+
+    /// Parse `YYYY-MM-DD`, rejecting days the month doesn't have.
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        let date = Date::from_ymd(year, month, day);
+        (date.ymd() == (year, month, day)).then_some(date)
+    }
+
+    /// Midnight UTC as RFC 822 says, for RSS: `Wed, 14 Oct 2026 00:00:00 GMT`.
+    pub fn rfc822(self) -> String {
+        let (year, month, day) = self.ymd();
+        let weekday = WEEKDAYS[(self.0 + 4).rem_euclid(7) as usize];
+        let month = MONTHS[month as usize - 1];
+        format!("{weekday}, {day:02} {month} {year} 00:00:00 GMT")
+    }
+
+    /// Midnight UTC as RFC 3339 says, for Atom and JSON Feed.
+    pub fn rfc3339(self) -> String {
+        format!("{self}T00:00:00Z")
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// Pixels per cell in feed images.
+const CELL: usize = 24;
+
+/// The board as a standalone SVG, with a dot on each cell in `presses`.
+/// Obstacles are left out.
+pub fn svg(board: &Board, presses: Option<&BitMask>) -> String {
+    let rules = board.ruleset();
+    let (width, height) = (board.width() * CELL, board.height() * CELL);
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\"><rect width=\"{width}\" height=\"{height}\" \
+         fill=\"#101010\"/>"
+    );
+    for ((x, y), lit) in board.cells() {
+        if rules.is_obstacle(x, y) {
+            continue;
+        }
+        let fill = if lit { "#ffd60a" } else { "#303030" };
+        let _ = write!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"3\" fill=\"{fill}\"/>",
+            x * CELL + 1,
+            y * CELL + 1,
+            CELL - 2,
+            CELL - 2
+        );
+        if presses.is_some_and(|presses| presses.get(x, y)) {
+            let _ = write!(
+                out,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#dc2828\"/>",
+                x * CELL + CELL / 2,
+                y * CELL + CELL / 2,
+                CELL / 5
+            );
+        }
+    }
+    out += "</svg>";
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// One day's puzzle and its fewest-press solution.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub date: Date,
+    pub generated: Generated,
+    pub solution: BitMask,
+}
+
+impl Entry {
+    /// The day's puzzle on a `width` by `height` board. Days are
+    /// [`SEEDS_PER_BOARD`] seeds apart so neighbouring days never reach the
+    /// same solvable seed.
+    pub fn for_date(date: Date, width: usize, height: usize) -> Entry {
+        let first_seed = (date.0 as u64).wrapping_mul(SEEDS_PER_BOARD);
+        let generated = generate_solvable(width, height, first_seed, 1, 1)
+            .next()
+            .expect("every size has solvable boards");
+        let solution = generated
+            .board
+            .solve_linear()
+            .expect("generated boards are solvable");
+        Entry {
+            date,
+            generated,
+            solution,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        format!("Puzzle for {}", self.date)
+    }
+
+    /// The entry as HTML, with the board inline.
+    pub fn html(&self, solution_link: &str) -> String {
+        format!(
+            "<p>{}</p><p>Code <code>{}</code>, solvable in {} presses.</p>\
+             <p><a href=\"{}\">Solution</a></p>",
+            svg(&self.generated.board, None),
+            self.generated.board.to_code(),
+            self.generated.optimal,
+            escape(solution_link)
+        )
+    }
+
+    /// The solution's presses by cell name, in reading order.
+    pub fn presses(&self) -> Vec<String> {
+        self.solution
+            .positions()
+            .into_iter()
+            .map(|(x, y)| cell_name(x, y))
+            .collect()
+    }
+}
+
+/// A feed of daily puzzles, newest first.
+#[derive(Clone, Debug)]
+pub struct Feed {
+    pub title: String,
+    /// The URL the feed and its solution pages are published under, without
+    /// a trailing slash.
+    pub site: String,
+    pub entries: Vec<Entry>,
+}
+
+impl Feed {
+    /// The puzzles for the `days` days ending on `last` on boards of the
+    /// size given, published under `site`.
+    pub fn new(
+        title: &str,
+        site: &str,
+        last: Date,
+        days: usize,
+        width: usize,
+        height: usize,
+    ) -> Feed {
+        Feed {
+            title: title.to_string(),
+            site: site.trim_end_matches('/').to_string(),
+            entries: (0..days as i64)
+                .map(|back| Entry::for_date(Date(last.0 - back), width, height))
+                .collect(),
+        }
+    }
+
+    /// Where an entry's solution is published, relative to the site.
+    pub fn solution_path(entry: &Entry) -> String {
+        format!("solutions/{}.svg", entry.date)
+    }
+
+    fn link(&self, path: &str) -> String {
+        format!("{}/{path}", self.site)
+    }
+
+    fn entry_id(&self, entry: &Entry) -> String {
+        self.link(&format!("#{}", entry.date))
+    }
+
+    fn updated(&self) -> Date {
+        self.entries.first().map_or(Date::today(), |entry| entry.date)
+    }
+
+    pub fn rss(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out += "<rss version=\"2.0\"><channel>";
+        let _ = write!(
+            out,
+            "<title>{}</title><link>{}</link><description>{}</description>\
+             <lastBuildDate>{}</lastBuildDate>",
+            escape(&self.title),
+            escape(&self.link("")),
+            escape(&self.title),
+            self.updated().rfc822()
+        );
+        for entry in &self.entries {
+            let solution = self.link(&Feed::solution_path(entry));
+            let _ = write!(
+                out,
+                "<item><title>{}</title><link>{}</link><guid isPermaLink=\"false\">{}</guid>\
+                 <pubDate>{}</pubDate><description>{}</description></item>",
+                escape(&entry.title()),
+                escape(&solution),
+                escape(&self.entry_id(entry)),
+                entry.date.rfc822(),
+                escape(&entry.html(&solution))
+            );
+        }
+        out += "</channel></rss>\n";
+        out
+    }
+
+    pub fn atom(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out += "<feed xmlns=\"http://www.w3.org/2005/Atom\">";
+        let _ = write!(
+            out,
+            "<title>{}</title><id>{}</id><link href=\"{}\"/><link rel=\"self\" href=\"{}\"/>\
+             <updated>{}</updated><author><name>{}</name></author>",
+            escape(&self.title),
+            escape(&self.link("")),
+            escape(&self.link("")),
+            escape(&self.link("atom.xml")),
+            self.updated().rfc3339(),
+            escape(&self.title)
+        );
+        for entry in &self.entries {
+            let solution = self.link(&Feed::solution_path(entry));
+            let _ = write!(
+                out,
+                "<entry><title>{}</title><id>{}</id><link href=\"{}\"/><updated>{}</updated>\
+                 <content type=\"html\">{}</content></entry>",
+                escape(&entry.title()),
+                escape(&self.entry_id(entry)),
+                escape(&solution),
+                entry.date.rfc3339(),
+                escape(&entry.html(&solution))
+            );
+        }
+        out += "</feed>\n";
+        out
+    }
+
+    /// The feed as JSON Feed 1.1, with each entry's board and solution as
+    /// extension fields for clients that want to render them themselves.
+    pub fn json(&self) -> Json {
+        let items = self.entries.iter().map(|entry| {
+            let solution = self.link(&Feed::solution_path(entry));
+            Json::object([
+                ("id", self.entry_id(entry).into()),
+                ("url", solution.clone().into()),
+                ("title", entry.title().into()),
+                ("content_html", entry.html(&solution).into()),
+                ("date_published", entry.date.rfc3339().into()),
+                (
+                    "_lightsout",
+                    Json::object([
+                        ("code", entry.generated.board.to_code().into()),
+                        ("seed", entry.generated.seed.into()),
+                        ("optimal", entry.generated.optimal.into()),
+                        ("presses", entry.presses().into()),
+                    ]),
+                ),
+            ])
+        });
+        Json::object([
+            ("version", "https://jsonfeed.org/version/1.1".into()),
+            ("title", self.title.clone().into()),
+            ("home_page_url", self.link("").into()),
+            ("feed_url", self.link("feed.json").into()),
+            ("items", Json::Array(items.collect())),
+        ])
+    }
+}
@@ -0,0 +1,59 @@
+//! Entry points for the `cargo fuzz` targets in `fuzz/`. Each one takes raw
+//! fuzzer input and panics if an invariant of the untrusted-input paths is
+//! broken; plain errors are expected and ignored. Seed inputs for each
+//! target are kept in `fuzz/seeds/<target>`, and `tests/fuzz.rs` runs them.
+
+use alloc::vec::Vec;
+
+use crate::core::Board;
+
+/// Parsing never panics, and anything that parses survives a round trip
+/// through both the text rendering and the puzzle code.
+pub fn board_parser(data: &[u8]) {
+    let Ok(text) = ::core::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(board) = text.parse::<Board>() {
+        let printed = alloc::format!("{board}");
+        assert_eq!(printed.parse::<Board>().as_ref(), Ok(&board));
+        assert_eq!(Board::from_code(&board.to_code()).as_ref(), Ok(&board));
+    }
+}
+
+/// Decoding never panics, and a decoded board survives a round trip.
+pub fn code_decoder(data: &[u8]) {
+    let Ok(code) = ::core::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(board) = Board::from_code(code) {
+        assert_eq!(Board::from_code(&board.to_code()).as_ref(), Ok(&board));
+    }
+}
+
+/// The input is a puzzle code on the first line followed by one press per
+/// byte pair. Verification never panics, and agrees with the linear solver
+/// on whether the board can be cleared at all.
+pub fn solution_verifier(data: &[u8]) {
+    let Some(split) = data.iter().position(|&b| b == b'\n') else {
+        return;
+    };
+    let Ok(code) = ::core::str::from_utf8(&data[..split]) else {
+        return;
+    };
+    let Ok(board) = Board::from_code(code) else {
+        return;
+    };
+    let presses: Vec<(usize, usize)> = data[split + 1..]
+        .chunks_exact(2)
+        .map(|pair| (pair[0] as usize, pair[1] as usize))
+        .collect();
+    if board.verify(&presses) {
+        assert!(board.solve_linear().is_some());
+    }
+    if let Some(solution) = board.solve_linear() {
+        assert!(board.verify(&solution.positions()));
+        let mut pressed = board.clone();
+        pressed.apply_press_set(&solution);
+        assert!(pressed.is_solved());
+    }
+}
@@ -0,0 +1,202 @@
+//! Lights Out for two: players take turns pressing any cell, and whoever
+//! makes the press that clears the board wins.
+//!
+//! An [`Engine`] plays it by negamax a set number of presses ahead,
+//! remembering every board it has valued. Presses commute, so the same
+//! board turns up along many lines of play, and a search that deep visits
+//! far fewer boards than lines. Neither player has to let the other win, so
+//! under best play from both most boards are drawn; the engine wins by
+//! pouncing on a press that leaves it a clear in reach, and avoids leaving
+//! one itself.
+//!
+//! ```
+//! use lightsout::{game::{Engine, Evaluation, Game}, Board};
+//!
+//! // One press clears it, so whoever moves first wins straight away.
+//! let game = Game::new(Board::from_code("3x3-5d0").unwrap());
+//! let mut engine = Engine::new(4);
+//! assert_eq!(engine.best_move(&game), Some(((1, 1), Evaluation::Win { plies: 1 })));
+//! ```
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::core::Board;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Player {
+    First,
+    Second,
+}
+
+impl Player {
+    pub fn other(self) -> Player {
+        match self {
+            Player::First => Player::Second,
+            Player::Second => Player::First,
+        }
+    }
+}
+
+/// A game in progress: the board as it stands and whose turn it is.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Game {
+    board: Board,
+    to_move: Player,
+    presses: usize,
+}
+
+impl Game {
+    pub fn new(board: Board) -> Game {
+        Game {
+            board,
+            to_move: Player::First,
+            presses: 0,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// Presses made so far, by both players.
+    pub fn presses(&self) -> usize {
+        self.presses
+    }
+
+    /// The player who cleared the board, once someone has. A board that
+    /// starts clear has no winner.
+    pub fn winner(&self) -> Option<Player> {
+        (self.board.is_solved() && self.presses > 0).then(|| self.to_move.other())
+    }
+
+    /// Cells the player to move may press: every cell but obstacles, or
+    /// none once the board is clear.
+    pub fn moves(&self) -> Vec<(usize, usize)> {
+        if self.board.is_solved() {
+            return Vec::new();
+        }
+        let rules = self.board.ruleset();
+        (0..self.board.height())
+            .flat_map(|y| (0..self.board.width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| !rules.is_obstacle(x, y))
+            .collect()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    /// Press `(x, y)` for the player to move, or return `false` if it isn't
+    /// one of [`Game::moves`].
+    pub fn press(&mut self, x: usize, y: usize) -> bool {
+        if !self.moves().contains(&(x, y)) {
+            return false;
+        }
+        self.board.toggle(x, y);
+        self.to_move = self.to_move.other();
+        self.presses += 1;
+        true
+    }
+}
+
+/// What a game is worth to the player to move, under best play from
+/// both sides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Evaluation {
+    /// They clear the board within `plies` presses, counting both players'.
+    Win { plies: usize },
+    /// Their opponent clears it within `plies` presses.
+    Loss { plies: usize },
+    /// Neither side can force a clear within the engine's depth.
+    Even,
+}
+
+/// Values in plies from the end, so quicker wins score higher.
+const WIN: i32 = 1 << 20;
+
+impl Evaluation {
+    fn from_value(value: i32) -> Evaluation {
+        match value {
+            0 => Evaluation::Even,
+            value if value > 0 => Evaluation::Win { plies: (WIN - value) as usize },
+            value => Evaluation::Loss { plies: (WIN + value) as usize },
+        }
+    }
+}
+
+/// Negamax search of [`Game`] positions, looking `depth` presses ahead.
+pub struct Engine {
+    depth: usize,
+    /// Each board's value to the player to move, with the depth it was
+    /// searched to. Whose turn it is doesn't change the value.
+    table: HashMap<(Board, usize), i32>,
+}
+
+impl Engine {
+    pub fn new(depth: usize) -> Engine {
+        Engine {
+            depth,
+            table: HashMap::new(),
+        }
+    }
+
+    pub fn evaluate(&mut self, game: &Game) -> Evaluation {
+        Evaluation::from_value(self.negamax(game, self.depth))
+    }
+
+    /// The press the player to move should make and what it leaves them
+    /// with, or `None` if the game is over. Ties go to the press leaving
+    /// the fewest lights on, to keep the opponent near a mistake, and then
+    /// to the first cell in row-major order.
+    pub fn best_move(&mut self, game: &Game) -> Option<((usize, usize), Evaluation)> {
+        let depth = self.depth.max(1);
+        game.moves()
+            .into_iter()
+            .map(|(x, y)| {
+                let value = self.value_after(game, x, y, depth);
+                let lit = game.board.clone_toggle(x, y).lit_count();
+                ((x, y), value, Reverse(lit))
+            })
+            .rev()
+            .max_by_key(|&(_, value, lit)| (value, lit))
+            .map(|(cell, value, _)| (cell, Evaluation::from_value(value)))
+    }
+
+    /// Boards valued so far.
+    pub fn positions(&self) -> usize {
+        self.table.len()
+    }
+
+    /// The value to the player pressing `(x, y)` in `game`.
+    fn value_after(&mut self, game: &Game, x: usize, y: usize, depth: usize) -> i32 {
+        let mut next = game.clone();
+        next.press(x, y);
+        if next.board.is_solved() {
+            return WIN - 1;
+        }
+        // A win or loss further off is worth a ply less.
+        let value = -self.negamax(&next, depth - 1);
+        value - value.signum()
+    }
+
+    fn negamax(&mut self, game: &Game, depth: usize) -> i32 {
+        if depth == 0 {
+            return 0;
+        }
+        if let Some(&value) = self.table.get(&(game.board.clone(), depth)) {
+            return value;
+        }
+        let value = game
+            .moves()
+            .into_iter()
+            .map(|(x, y)| self.value_after(game, x, y, depth))
+            .max()
+            .unwrap_or(0);
+        self.table.insert((game.board.clone(), depth), value);
+        value
+    }
+}
@@ -0,0 +1,463 @@
+use std::{
+    collections::VecDeque,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use rand::prelude::*;
+
+use crate::core::{gf2::BitMatrix, BitMask, Board};
+
+/// A solvable board along with the seed that produced it and its optimal
+/// solution length.
+#[derive(Clone, Debug)]
+pub struct Generated {
+    pub seed: u64,
+    pub board: Board,
+    pub optimal: usize,
+}
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Generate `count` solvable boards on `threads` worker threads, trying seeds
+/// upwards from `first_seed`. Boards are yielded as soon as they're rated, so
+/// they arrive roughly but not exactly in seed order.
+pub fn generate_solvable(
+    width: usize,
+    height: usize,
+    first_seed: u64,
+    count: usize,
+    threads: usize,
+) -> impl Iterator<Item = Generated> {
+    generate_boards(width, height, first_seed, count, threads, Target::default())
+}
+
+/// Generate up to `count` boards whose optimal solution is exactly `optimal`
+/// presses, like [`generate_solvable`]; see [`Target::optimal`].
+pub fn generate_with_optimal(
+    width: usize,
+    height: usize,
+    first_seed: u64,
+    count: usize,
+    threads: usize,
+    optimal: usize,
+) -> impl Iterator<Item = Generated> {
+    let target = Target {
+        optimal: Some(optimal),
+        ..Target::default()
+    };
+    generate_boards(width, height, first_seed, count, threads, target)
+}
+
+/// Seeds tried per board asked for before giving up on a [`Target`] being
+/// reachable at all.
+pub const SEEDS_PER_BOARD: u64 = 10_000;
+
+/// What [`generate_boards`] looks for beyond a board being solvable.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Target {
+    /// Exactly this many presses in the optimal solution.
+    ///
+    /// Each seed starts from a random set of that many presses, which
+    /// solves the board it makes but may not be the shortest way to.
+    /// Swapping presses for unpressed cells, keeping swaps that don't
+    /// shorten the optimum the linear solver finds, climbs towards a board
+    /// needing all of them. Seeds that don't get there within a few swaps
+    /// per cell are skipped.
+    pub optimal: Option<usize>,
+    /// Only boards with a single minimal solution, so hints never have to
+    /// pick between equally short ones.
+    pub unique: bool,
+    /// Only boards with this look, drawn straight from the solvable boards
+    /// that have it rather than from random presses. With an
+    /// [`optimal`](Target::optimal) as well, seeds are drawn until one
+    /// happens to need that many presses instead of climbing to it.
+    pub shape: Option<Shape>,
+}
+
+/// A look for generated boards, see [`Target::shape`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// The same reflected left to right.
+    LeftRight,
+    /// The same reflected top to bottom.
+    TopBottom,
+    /// The same reflected either way.
+    Mirrored,
+    /// Lights only in the outermost rows and columns.
+    Border,
+    /// As few cells from these lights as a solvable board can be. Seeds
+    /// only make a difference where several boards are equally close.
+    Image(BitMask),
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    /// Every shape but [`Shape::Image`], which needs its lights.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left-right" => Ok(Shape::LeftRight),
+            "top-bottom" => Ok(Shape::TopBottom),
+            "mirrored" => Ok(Shape::Mirrored),
+            "border" => Ok(Shape::Border),
+            _ => Err(format!(
+                "unknown shape {s:?} (expected left-right, top-bottom, mirrored or border)"
+            )),
+        }
+    }
+}
+
+/// Kernel dimensions up to which [`Shape::Image`] finds the very closest
+/// solvable boards, with a search over all `2^dimension` ways of failing
+/// the solvability checks. Past it the repair changes at most `dimension`
+/// cells, but maybe more than it needs to.
+pub const EXACT_SHAPE_KERNEL: usize = 16;
+
+/// A [`Shape`] with the linear algebra done once per size, for every seed
+/// to share.
+enum Shaper {
+    /// Sets of cells to light together, of which every combination is a
+    /// solvable board with the shape.
+    Blocks(Vec<BitMask>),
+    /// The target lights, and for each cell the checks it fails when
+    /// toggled, one bit per quiet pattern of the classic rules: a board is
+    /// solvable when it meets all of them, and toggling a cell flips the
+    /// checks its quiet patterns press it in.
+    Image { target: BitMask, syndromes: Vec<u64> },
+}
+
+impl Shaper {
+    /// Panics if an image isn't `width` x `height`.
+    fn new(shape: &Shape, width: usize, height: usize) -> Shaper {
+        let cells = width * height;
+        // The classic toggle matrix is symmetric, so the presses that do
+        // nothing are also the checks a solvable board's lights pass.
+        let checks = BitMatrix::toggles(width, height, &Default::default()).nullspace();
+        let orbit = |x: usize, y: usize| {
+            let (flip_x, flip_y) = (width - 1 - x, height - 1 - y);
+            match shape {
+                Shape::LeftRight => vec![(x, y), (flip_x, y)],
+                Shape::TopBottom => vec![(x, y), (x, flip_y)],
+                Shape::Mirrored => vec![(x, y), (flip_x, y), (x, flip_y), (flip_x, flip_y)],
+                Shape::Border => vec![(x, y)],
+                Shape::Image(_) => unreachable!("images aren't made of blocks"),
+            }
+        };
+        let blocks: Vec<BitMask> = match shape {
+            Shape::Image(target) => {
+                let size = (target.width(), target.height());
+                assert_eq!(size, (width, height), "image is the wrong size");
+                let syndromes = (0..cells)
+                    .map(|cell| {
+                        let failed = checks.iter().enumerate().filter(|(_, check)| check[cell]);
+                        failed.fold(0, |syndrome, (i, _)| syndrome | 1 << i)
+                    })
+                    .collect();
+                return Shaper::Image {
+                    target: target.clone(),
+                    syndromes,
+                };
+            }
+            Shape::Border => (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .filter(|&(x, y)| x == 0 || y == 0 || x == width - 1 || y == height - 1)
+                .map(|(x, y)| orbit(x, y))
+                .map(|cells| mask(width, height, &cells))
+                .collect(),
+            _ => {
+                let mut blocks: Vec<BitMask> = Vec::new();
+                for (x, y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+                    let block = mask(width, height, &orbit(x, y));
+                    if !blocks.contains(&block) {
+                        blocks.push(block);
+                    }
+                }
+                blocks
+            }
+        };
+        // Which checks each block fails; the combinations failing none of
+        // them are the solvable boards with the shape.
+        let mut failed = BitMatrix::new(checks.len(), blocks.len());
+        for (i, check) in checks.iter().enumerate() {
+            for (j, block) in blocks.iter().enumerate() {
+                let overlap = block.positions().into_iter();
+                let overlap = overlap.filter(|&(x, y)| check[y * width + x]).count();
+                failed.set(i, j, overlap % 2 == 1);
+            }
+        }
+        let combined = failed.nullspace().into_iter().map(|combination| {
+            let chosen = blocks.iter().zip(combination).filter(|(_, chosen)| *chosen);
+            let cells: Vec<_> = chosen.flat_map(|(block, _)| block.positions()).collect();
+            // Blocks don't overlap, so listing every cell of each is enough.
+            mask(width, height, &cells)
+        });
+        Shaper::Blocks(combined.collect())
+    }
+
+    /// A solvable board with the shape from `seed`, or `None` if the seed
+    /// comes out blank.
+    fn board(&self, width: usize, height: usize, seed: u64) -> Option<Board> {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+        let mut board = Board::new(width, height);
+        match self {
+            Shaper::Blocks(blocks) => {
+                for block in blocks.iter().filter(|_| rng.gen()) {
+                    for (x, y) in block.positions() {
+                        board.set(x, y, !board.get(x, y));
+                    }
+                }
+            }
+            Shaper::Image { target, syndromes } => board = closest(target, syndromes, &mut rng),
+        }
+        (!board.is_clear()).then_some(board)
+    }
+}
+
+/// The solvable classic board as few cells from `image` as there can be,
+/// for turning pictures into puzzles: the [`Shape::Image`] repair, with
+/// ties always broken the same way.
+pub fn nearest_solvable(image: &BitMask) -> Board {
+    let (width, height) = (image.width(), image.height());
+    let Shaper::Image { target, syndromes } =
+        Shaper::new(&Shape::Image(image.clone()), width, height)
+    else {
+        unreachable!("images make image shapers");
+    };
+    closest(&target, &syndromes, &mut SeedableRng::seed_from_u64(0))
+}
+
+/// `target` repaired into a solvable board, see [`Shaper::Image`].
+fn closest(target: &BitMask, syndromes: &[u64], rng: &mut StdRng) -> Board {
+    let width = target.width();
+    let mut board = Board::new(width, target.height());
+    for (x, y) in target.positions() {
+        board.set(x, y, true);
+    }
+    let failing = target
+        .positions()
+        .into_iter()
+        .fold(0, |failing, (x, y)| failing ^ syndromes[y * width + x]);
+    for cell in repair(syndromes, failing, rng) {
+        board.set(cell % width, cell / width, !board.get(cell % width, cell / width));
+    }
+    board
+}
+
+fn mask(width: usize, height: usize, cells: &[(usize, usize)]) -> BitMask {
+    let mut mask = BitMask::new(width, height);
+    for &(x, y) in cells {
+        mask.set(x, y, true);
+    }
+    mask
+}
+
+/// Cells to toggle to flip exactly the `failing` checks, given the checks
+/// each cell flips: as few as there can be, ties broken by `rng`, when
+/// there are at most [`EXACT_SHAPE_KERNEL`] checks.
+fn repair(syndromes: &[u64], failing: u64, rng: &mut StdRng) -> Vec<usize> {
+    let checks = syndromes.iter().fold(0, |all, syndrome| all | syndrome);
+    let dimension = (u64::BITS - checks.leading_zeros()) as usize;
+    let mut order: Vec<usize> =
+        (0..syndromes.len()).filter(|&cell| syndromes[cell] != 0).collect();
+    order.shuffle(rng);
+    if dimension > EXACT_SHAPE_KERNEL {
+        // Any cells flipping the right checks, by elimination.
+        let mut matrix = BitMatrix::new(dimension, order.len());
+        for (j, &cell) in order.iter().enumerate() {
+            for i in 0..dimension {
+                matrix.set(i, j, syndromes[cell] & 1 << i != 0);
+            }
+        }
+        let rhs: Vec<bool> = (0..dimension).map(|i| failing & 1 << i != 0).collect();
+        let chosen = matrix
+            .solve(&rhs)
+            .unwrap_or_else(|| unreachable!("the cells flip every check between them"));
+        let cells = order.into_iter().zip(chosen);
+        return cells.filter(|(_, chosen)| *chosen).map(|(cell, _)| cell).collect();
+    }
+    // Breadth first over which checks are flipped, so the first time
+    // `failing` comes up is with the fewest cells.
+    let mut reached: Vec<Option<usize>> = vec![None; 1 << dimension];
+    let mut queue = VecDeque::from([0u64]);
+    while let Some(state) = queue.pop_front() {
+        if state == failing {
+            break;
+        }
+        for &cell in &order {
+            let next = state ^ syndromes[cell];
+            if next != 0 && reached[next as usize].is_none() {
+                reached[next as usize] = Some(cell);
+                queue.push_back(next);
+            }
+        }
+    }
+    let mut cells = Vec::new();
+    let mut state = failing;
+    while state != 0 {
+        let cell = reached[state as usize]
+            .unwrap_or_else(|| unreachable!("the cells flip every check between them"));
+        cells.push(cell);
+        state ^= syndromes[cell];
+    }
+    cells
+}
+
+/// Generate up to `count` boards meeting `target`, like
+/// [`generate_solvable`]. Unless the target is the default, generation
+/// stops early after [`SEEDS_PER_BOARD`] seeds per board, since some
+/// targets, like an optimum longer than the hardest board's, are never met.
+///
+/// Panics if the target's [`Shape::Image`] isn't `width` x `height`.
+///
+/// ```
+/// use lightsout::generate::{generate_boards, Target};
+///
+/// let target = Target { optimal: Some(6), unique: true, ..Target::default() };
+/// let boards: Vec<_> = generate_boards(5, 5, 1, 3, 2, target).collect();
+/// assert_eq!(boards.len(), 3);
+/// for generated in boards {
+///     assert_eq!(generated.optimal, 6);
+///     assert_eq!(generated.board.solve_linear().unwrap().count(), 6);
+/// }
+/// ```
+pub fn generate_boards(
+    width: usize,
+    height: usize,
+    first_seed: u64,
+    count: usize,
+    threads: usize,
+    target: Target,
+) -> impl Iterator<Item = Generated> {
+    let seeds = if target == Target::default() {
+        u64::MAX
+    } else {
+        (count as u64).saturating_mul(SEEDS_PER_BOARD)
+    };
+    let shaper = target.shape.as_ref().map(|shape| Shaper::new(shape, width, height));
+    generate(first_seed, count, threads, seeds, move |seed| {
+        let generated = match (&shaper, target.optimal) {
+            (Some(shaper), optimal) => {
+                let board = shaper.board(width, height, seed)?;
+                let length = board.optimal_length()?;
+                if optimal.is_some_and(|optimal| optimal != length) {
+                    return None;
+                }
+                Generated {
+                    seed,
+                    board,
+                    optimal: length,
+                }
+            }
+            (None, Some(optimal)) => climb_to_optimal(width, height, seed, optimal)?,
+            (None, None) => {
+                let mut board = Board::new(width, height);
+                board.randomize(seed);
+                let optimal = board.optimal_length()?;
+                Generated {
+                    seed,
+                    board,
+                    optimal,
+                }
+            }
+        };
+        if target.unique && generated.board.minimal_solutions().len() != 1 {
+            return None;
+        }
+        Some(generated)
+    })
+}
+
+/// A board needing exactly `optimal` presses from `seed`, as described
+/// for [`Target::optimal`].
+fn climb_to_optimal(width: usize, height: usize, seed: u64, optimal: usize) -> Option<Generated> {
+    let cells = width * height;
+    if optimal > cells {
+        return None;
+    }
+    let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+    let mut order: Vec<usize> = (0..cells).collect();
+    order.shuffle(&mut rng);
+    let (pressed, unpressed) = order.split_at_mut(optimal);
+    let board_for = |pressed: &[usize]| {
+        let mut board = Board::new(width, height);
+        for &index in pressed {
+            board.toggle(index % width, index / width);
+        }
+        board
+    };
+    let length = |board: &Board| board.optimal_length().unwrap_or(0);
+
+    let mut board = board_for(pressed);
+    let mut best = length(&board);
+    for _ in 0..cells * 4 {
+        if best == optimal || unpressed.is_empty() {
+            break;
+        }
+        let (i, j) = (rng.gen_range(0..optimal), rng.gen_range(0..unpressed.len()));
+        std::mem::swap(&mut pressed[i], &mut unpressed[j]);
+        let candidate = board_for(pressed);
+        let candidate_length = length(&candidate);
+        if candidate_length >= best {
+            (board, best) = (candidate, candidate_length);
+        } else {
+            std::mem::swap(&mut pressed[i], &mut unpressed[j]);
+        }
+    }
+    (best == optimal).then_some(Generated {
+        seed,
+        board,
+        optimal,
+    })
+}
+
+/// Run `make` over seeds upwards from `first_seed` on `threads` workers,
+/// yielding the first `count` boards it accepts, or fewer once `seeds`
+/// seeds have been tried. Workers only keep a few boards ahead of the
+/// consumer, so a slow one holds them up rather than filling memory.
+fn generate(
+    first_seed: u64,
+    count: usize,
+    threads: usize,
+    seeds: u64,
+    make: impl Fn(u64) -> Option<Generated> + Send + Sync + 'static,
+) -> impl Iterator<Item = Generated> {
+    let make = Arc::new(make);
+    let next_seed = Arc::new(AtomicU64::new(0));
+    let remaining = Arc::new(AtomicUsize::new(count));
+    let (sender, receiver) = mpsc::sync_channel(threads.max(1) * 2);
+    for _ in 0..threads.max(1) {
+        let make = Arc::clone(&make);
+        let next_seed = Arc::clone(&next_seed);
+        let remaining = Arc::clone(&remaining);
+        let sender = sender.clone();
+        thread::spawn(move || {
+            while remaining.load(Ordering::Relaxed) > 0 {
+                let tried = next_seed.fetch_add(1, Ordering::Relaxed);
+                if tried >= seeds {
+                    break;
+                }
+                let Some(generated) = make(first_seed.wrapping_add(tried)) else {
+                    continue;
+                };
+                // Claim a slot before sending so exactly `count` boards go out.
+                let claimed = remaining
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                    .is_ok();
+                if !claimed {
+                    break;
+                }
+                if sender.send(generated).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    receiver.into_iter()
+}
@@ -0,0 +1,126 @@
+//! Driving a physical Lights Out build: an LED matrix to show the board and
+//! a set of buttons to press cells, plus a "solve it for me" button that
+//! plays back the linear solver's presses one at a time.
+
+use alloc::{collections::VecDeque, vec::Vec};
+
+use crate::core::Board;
+
+/// Something the player did on the hardware.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    Press(usize, usize),
+    Solve,
+    Reset,
+}
+
+/// An LED matrix with one button per cell and a few extra control buttons.
+pub trait Hardware {
+    /// Size of the matrix as `(width, height)`.
+    fn dimensions(&self) -> (usize, usize);
+    /// Light up the LEDs to match the board.
+    fn show(&mut self, board: &Board);
+    /// Return the next button press, if one is waiting.
+    fn poll(&mut self) -> Option<Input>;
+}
+
+/// Runs a game on a piece of hardware. Call [`Driver::step`] from the main
+/// loop of the firmware; each call handles at most one input or one press of
+/// an in-progress auto-solve.
+pub struct Driver<H: Hardware> {
+    hardware: H,
+    start: Board,
+    board: Board,
+    pending: VecDeque<(usize, usize)>,
+}
+
+impl<H: Hardware> Driver<H> {
+    pub fn new(mut hardware: H, start: Board) -> Driver<H> {
+        debug_assert_eq!(hardware.dimensions(), (start.width(), start.height()));
+        let board = start.clone();
+        hardware.show(&board);
+        Driver {
+            hardware,
+            start,
+            board,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn hardware(&self) -> &H {
+        &self.hardware
+    }
+
+    /// Whether an auto-solve is still playing back.
+    pub fn solving(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn step(&mut self) {
+        if let Some((x, y)) = self.pending.pop_front() {
+            self.board.toggle(x, y);
+        } else {
+            match self.hardware.poll() {
+                None => return,
+                Some(Input::Press(x, y)) => {
+                    if x < self.board.width() && y < self.board.height() {
+                        self.board.toggle(x, y);
+                    }
+                }
+                Some(Input::Solve) => {
+                    if let Some(presses) = self.board.solve_linear() {
+                        self.pending.extend(presses.positions());
+                    }
+                }
+                Some(Input::Reset) => self.board = self.start.clone(),
+            }
+        }
+        self.hardware.show(&self.board);
+    }
+}
+
+/// In-memory stand-in for real hardware, fed from a script of inputs and
+/// recording every frame it was asked to show.
+pub struct Simulator {
+    width: usize,
+    height: usize,
+    inputs: VecDeque<Input>,
+    pub frames: Vec<Board>,
+}
+
+impl Simulator {
+    pub fn new(width: usize, height: usize) -> Simulator {
+        Simulator {
+            width,
+            height,
+            inputs: VecDeque::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn queue(&mut self, input: Input) {
+        self.inputs.push_back(input);
+    }
+
+    pub fn idle(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+impl Hardware for Simulator {
+    fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn show(&mut self, board: &Board) {
+        self.frames.push(board.clone());
+    }
+
+    fn poll(&mut self) -> Option<Input> {
+        self.inputs.pop_front()
+    }
+}
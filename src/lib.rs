@@ -0,0 +1,108 @@
+//! Lights Out boards, solvers and generators.
+//!
+//! A board is parsed from a drawing or a puzzle code, solved, and checked
+//! by pressing the solution:
+//!
+//! ```
+//! use lightsout::Board;
+//!
+//! let board: Board = "
+//!     XX...
+//!     X....
+//!     ..X..
+//!     .XXXX
+//!     ..XXX
+//! "
+//! .parse()
+//! .unwrap();
+//! assert_eq!(board.to_code(), "5x5-c408f38");
+//!
+//! let presses = board.solve_linear().expect("the board can be cleared");
+//! assert_eq!(presses.positions(), [(0, 0), (2, 3), (4, 4)]);
+//!
+//! let mut pressed = board.clone();
+//! pressed.apply_press_set(&presses);
+//! assert!(pressed.is_solved());
+//! ```
+//!
+//! Nothing here prints, and the only global state is the sampler debug
+//! builds use to pick which results to check against slower reference
+//! versions; the same calls back the `lightsout` command line tool.
+//!
+//! # Stability
+//!
+//! The [`prelude`] and the items re-exported here at the crate root follow
+//! semver: they only change incompatibly with the major version (the minor
+//! version, before 1.0). The modules behind them, [`search`] above all, are
+//! public for tools that need their internals, such as custom frontiers or
+//! search states, and may change in any release. Prefer a [`Solver`] to
+//! calling into them when one will do.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "bot")]
+pub mod bot;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod calibrate;
+#[cfg(feature = "image")]
+pub mod card;
+#[cfg(feature = "std")]
+pub mod cbor;
+#[cfg(feature = "std")]
+pub mod context;
+#[cfg(feature = "std")]
+pub mod coop;
+pub mod core;
+#[cfg(feature = "std")]
+pub mod feed;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+#[cfg(feature = "std")]
+pub mod game;
+#[cfg(feature = "std")]
+pub mod generate;
+pub mod hardware;
+#[cfg(feature = "std")]
+pub mod json;
+#[cfg(feature = "std")]
+pub mod msgpack;
+#[cfg(feature = "std")]
+pub mod mutate;
+#[cfg(feature = "std")]
+pub mod pack;
+#[cfg(feature = "std")]
+pub mod pdf;
+#[cfg(feature = "image")]
+pub mod png;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "sound")]
+pub mod sound;
+pub mod solver;
+pub mod theme;
+
+/// The types most programs need, for importing in one go with
+/// `use lightsout::prelude::*`.
+pub mod prelude {
+    pub use crate::{
+        core::{
+            BitMask, Board, BoardBuilder, BuildError, CancellationToken, Cancelled, ParseError,
+            Ruleset, RulesError,
+        },
+        solver::{Budget, Registry, Solution, SolveOutcome, Solver},
+    };
+}
+
+pub use crate::core::{
+    cell_name, parse_cell, route_length, shortest_route, BatchSolver, BitMask, Board,
+    BoardBuilder, BuildError, CancellationToken, Cancelled, Goals, Neighborhood, ParseError,
+    Preference,
+    PressConstraints, Rowtype, Ruleset, RulesError,
+};
+pub use crate::solver::{Budget, Registry, Solution, SolveOutcome, Solver};
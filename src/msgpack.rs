@@ -0,0 +1,192 @@
+//! MessagePack encoding of [`Json`] values, the other compact format the
+//! server and daemon accept. Binary and extension types are rejected when
+//! decoding, since no JSON value maps onto them.
+
+use std::io::Read;
+
+use crate::json::Json;
+
+/// Nesting limit, so hostile input can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+pub fn encode(value: &Json) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_value(&mut out, value);
+    out
+}
+
+/// A length prefix: the fix form's tag ORed with small lengths, else the 16
+/// or 32 bit form. `fix_max` is the largest length the fix form holds.
+fn write_length(out: &mut Vec<u8>, len: usize, fix: u8, fix_max: usize, tags: [u8; 2]) {
+    if len <= fix_max {
+        out.push(fix | len as u8);
+    } else if len <= 0xffff {
+        out.push(tags[0]);
+        out.extend((len as u16).to_be_bytes());
+    } else {
+        out.push(tags[1]);
+        out.extend((len as u32).to_be_bytes());
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    if (32..=0xff).contains(&s.len()) {
+        out.extend([0xd9, s.len() as u8]);
+    } else {
+        write_length(out, s.len(), 0xa0, 31, [0xda, 0xdb]);
+    }
+    out.extend(s.as_bytes());
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Json) {
+    match value {
+        Json::Null => out.push(0xc0),
+        Json::Bool(false) => out.push(0xc2),
+        Json::Bool(true) => out.push(0xc3),
+        // Whole numbers go out as integers, which is what they usually are.
+        Json::Number(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(63) => {
+            let n = *n as i64;
+            match n {
+                0..=0x7f => out.push(n as u8),
+                -32..=-1 => out.push(n as i8 as u8),
+                0x80..=0xff => out.extend([0xcc, n as u8]),
+                0x100..=0xffff => {
+                    out.push(0xcd);
+                    out.extend((n as u16).to_be_bytes());
+                }
+                0x1_0000..=0xffff_ffff => {
+                    out.push(0xce);
+                    out.extend((n as u32).to_be_bytes());
+                }
+                _ if n > 0 => {
+                    out.push(0xcf);
+                    out.extend((n as u64).to_be_bytes());
+                }
+                -0x80..=-33 => out.extend([0xd0, n as i8 as u8]),
+                -0x8000..=-0x81 => {
+                    out.push(0xd1);
+                    out.extend((n as i16).to_be_bytes());
+                }
+                -0x8000_0000..=-0x8001 => {
+                    out.push(0xd2);
+                    out.extend((n as i32).to_be_bytes());
+                }
+                _ => {
+                    out.push(0xd3);
+                    out.extend(n.to_be_bytes());
+                }
+            }
+        }
+        Json::Number(n) => {
+            out.push(0xcb);
+            out.extend(n.to_be_bytes());
+        }
+        Json::String(s) => write_string(out, s),
+        Json::Array(items) => {
+            write_length(out, items.len(), 0x90, 15, [0xdc, 0xdd]);
+            items.iter().for_each(|item| write_value(out, item));
+        }
+        Json::Object(entries) => {
+            write_length(out, entries.len(), 0x80, 15, [0xde, 0xdf]);
+            for (key, value) in entries {
+                write_string(out, key);
+                write_value(out, value);
+            }
+        }
+    }
+}
+
+/// Read one value from `reader`, leaving anything after it unread.
+pub fn decode(reader: &mut impl Read) -> Result<Json, String> {
+    read_value(reader, 0)
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], String> {
+    let mut bytes = [0; N];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|_| "unexpected end of input".to_string())?;
+    Ok(bytes)
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u64, String> {
+    Ok(u8::from_be_bytes(read_bytes(reader)?) as u64)
+}
+
+fn read_u16(reader: &mut impl Read) -> Result<u64, String> {
+    Ok(u16::from_be_bytes(read_bytes(reader)?) as u64)
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u64, String> {
+    Ok(u32::from_be_bytes(read_bytes(reader)?) as u64)
+}
+
+fn read_string(reader: &mut impl Read, len: u64) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(len)
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+    if bytes.len() as u64 != len {
+        return Err("unexpected end of input".to_string());
+    }
+    String::from_utf8(bytes).map_err(|_| "string is not UTF-8".to_string())
+}
+
+fn read_array(reader: &mut impl Read, len: u64, depth: usize) -> Result<Json, String> {
+    (0..len)
+        .map(|_| read_value(reader, depth + 1))
+        .collect::<Result<_, _>>()
+        .map(Json::Array)
+}
+
+fn read_map(reader: &mut impl Read, len: u64, depth: usize) -> Result<Json, String> {
+    (0..len)
+        .map(|_| match read_value(reader, depth + 1)? {
+            Json::String(key) => Ok((key, read_value(reader, depth + 1)?)),
+            _ => Err("map keys must be strings".to_string()),
+        })
+        .collect::<Result<_, _>>()
+        .map(Json::Object)
+}
+
+fn read_value(reader: &mut impl Read, depth: usize) -> Result<Json, String> {
+    if depth > MAX_DEPTH {
+        return Err("nested too deeply".to_string());
+    }
+    let [tag] = read_bytes(reader)?;
+    let number = |n: f64| Ok(Json::Number(n));
+    match tag {
+        0x00..=0x7f => number(tag as f64),
+        0x80..=0x8f => read_map(reader, (tag & 0x0f) as u64, depth),
+        0x90..=0x9f => read_array(reader, (tag & 0x0f) as u64, depth),
+        0xa0..=0xbf => Ok(Json::String(read_string(reader, (tag & 0x1f) as u64)?)),
+        0xc0 => Ok(Json::Null),
+        0xc2 => Ok(Json::Bool(false)),
+        0xc3 => Ok(Json::Bool(true)),
+        0xca => number(f32::from_be_bytes(read_bytes(reader)?) as f64),
+        0xcb => number(f64::from_be_bytes(read_bytes(reader)?)),
+        0xcc => number(read_u8(reader)? as f64),
+        0xcd => number(read_u16(reader)? as f64),
+        0xce => number(read_u32(reader)? as f64),
+        0xcf => number(u64::from_be_bytes(read_bytes(reader)?) as f64),
+        0xd0 => number(i8::from_be_bytes(read_bytes(reader)?) as f64),
+        0xd1 => number(i16::from_be_bytes(read_bytes(reader)?) as f64),
+        0xd2 => number(i32::from_be_bytes(read_bytes(reader)?) as f64),
+        0xd3 => number(i64::from_be_bytes(read_bytes(reader)?) as f64),
+        0xd9..=0xdf => {
+            let len = match tag {
+                0xd9 => read_u8(reader)?,
+                0xda | 0xdc | 0xde => read_u16(reader)?,
+                _ => read_u32(reader)?,
+            };
+            match tag {
+                0xd9..=0xdb => Ok(Json::String(read_string(reader, len)?)),
+                0xdc | 0xdd => read_array(reader, len, depth),
+                _ => read_map(reader, len, depth),
+            }
+        }
+        0xe0..=0xff => number(tag as i8 as f64),
+        _ => Err(format!("unsupported type 0x{tag:02x}")),
+    }
+}
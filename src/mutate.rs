@@ -0,0 +1,203 @@
+//! Perturbing a puzzle into related ones that are still solvable, for
+//! designing families of levels around one that works.
+
+use std::{fmt, str::FromStr};
+
+use rand::prelude::*;
+
+use crate::core::{cell_name, gf2::BitMatrix, BitMask, Board, LinearSystem};
+
+/// How many times an operator draws a change before giving up on finding
+/// one that keeps the board solvable.
+const ATTEMPTS: usize = 64;
+
+/// The ways [`mutate`] can change a board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// Toggle the lights of a quiet pattern, a set of presses that changes
+    /// nothing, when the lights it lands on keep the board solvable.
+    Quiet,
+    /// Press a cell, so the board is one press further from solved and
+    /// the old solution plus that press still solves it.
+    Press,
+    /// Toggle a cell and one that mirrors it, keeping symmetric layouts
+    /// symmetric, when that keeps the board solvable.
+    Pair,
+}
+
+/// Every operator, in the order their names are listed.
+pub const OPERATORS: &[Operator] = &[Operator::Quiet, Operator::Press, Operator::Pair];
+
+impl FromStr for Operator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quiet" => Ok(Operator::Quiet),
+            "press" => Ok(Operator::Press),
+            "pair" => Ok(Operator::Pair),
+            _ => Err(format!("unknown mutation {s:?} (expected quiet, press or pair)")),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::Quiet => "quiet",
+            Operator::Press => "press",
+            Operator::Pair => "pair",
+        })
+    }
+}
+
+/// One change [`mutate`] made.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    /// Toggled these lights, a quiet pattern.
+    Quiet(BitMask),
+    /// Pressed this cell.
+    Press(usize, usize),
+    /// Toggled these two lights, which mirror each other.
+    Pair((usize, usize), (usize, usize)),
+}
+
+impl Mutation {
+    pub fn operator(&self) -> Operator {
+        match self {
+            Mutation::Quiet(_) => Operator::Quiet,
+            Mutation::Press(..) => Operator::Press,
+            Mutation::Pair(..) => Operator::Pair,
+        }
+    }
+}
+
+/// Like `press c3` or `pair a1 e5`.
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operator())?;
+        match self {
+            Mutation::Quiet(lights) => {
+                for (x, y) in lights.positions() {
+                    write!(f, " {}", cell_name(x, y))?;
+                }
+                Ok(())
+            }
+            Mutation::Press(x, y) => write!(f, " {}", cell_name(*x, *y)),
+            Mutation::Pair((x1, y1), (x2, y2)) => {
+                write!(f, " {} {}", cell_name(*x1, *y1), cell_name(*x2, *y2))
+            }
+        }
+    }
+}
+
+/// A board [`mutate`] made, with the seed it came from and what it did.
+#[derive(Clone, Debug)]
+pub struct Mutant {
+    pub seed: u64,
+    pub board: Board,
+    pub mutations: Vec<Mutation>,
+}
+
+impl Mutant {
+    /// The recorded presses, with repeats cancelled: pressing them on the
+    /// mutant undoes them, though quiet patterns and pairs stay.
+    pub fn presses(&self) -> BitMask {
+        let mut presses = BitMask::new(self.board.width(), self.board.height());
+        for mutation in &self.mutations {
+            if let Mutation::Press(x, y) = *mutation {
+                presses.set(x, y, !presses.get(x, y));
+            }
+        }
+        presses
+    }
+}
+
+/// Make `steps` changes to `board` with operators drawn at random from
+/// `operators`, each leaving it solvable. Operators that can't find a
+/// change that does, like quiet patterns on a board that has none, are
+/// passed over for that step, so the mutant may have fewer changes than
+/// asked for, or none. `None` if `board` isn't solvable to begin with.
+pub fn mutate(board: &Board, operators: &[Operator], steps: usize, seed: u64) -> Option<Mutant> {
+    let (width, height) = (board.width(), board.height());
+    let rules = board.ruleset();
+    let system = LinearSystem::new(width, height, rules);
+    if !system.solvable(board) {
+        return None;
+    }
+    let open: Vec<(usize, usize)> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| !rules.is_obstacle(x, y))
+        .collect();
+    let quiet: Vec<BitMask> = BitMatrix::toggles(width, height, rules)
+        .nullspace()
+        .into_iter()
+        .map(|vector| {
+            let mut lights = BitMask::new(width, height);
+            for &(x, y) in &open {
+                lights.set(x, y, vector[y * width + x]);
+            }
+            lights
+        })
+        .filter(|lights| lights.count() > 0)
+        .collect();
+
+    let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+    let mut board = board.clone();
+    let mut mutations = Vec::new();
+    for _ in 0..steps {
+        let mut order = operators.to_vec();
+        order.shuffle(&mut rng);
+        let mutation = order.into_iter().find_map(|operator| {
+            (0..ATTEMPTS).find_map(|_| {
+                let mutation = match operator {
+                    Operator::Quiet => {
+                        let mut lights = BitMask::new(width, height);
+                        for pattern in quiet.iter().filter(|_| rng.gen()) {
+                            for (x, y) in pattern.positions() {
+                                lights.set(x, y, !lights.get(x, y));
+                            }
+                        }
+                        Mutation::Quiet(lights)
+                    }
+                    Operator::Press => {
+                        let (x, y) = *open.choose(&mut rng)?;
+                        Mutation::Press(x, y)
+                    }
+                    Operator::Pair => {
+                        let (x, y) = *open.choose(&mut rng)?;
+                        let mirror = *board.mirrors(x, y).choose(&mut rng)?;
+                        Mutation::Pair((x, y), mirror)
+                    }
+                };
+                let mutated = apply(&board, &mutation);
+                (mutated != board && system.solvable(&mutated)).then_some((mutation, mutated))
+            })
+        });
+        if let Some((mutation, mutated)) = mutation {
+            mutations.push(mutation);
+            board = mutated;
+        }
+    }
+    Some(Mutant {
+        seed,
+        board,
+        mutations,
+    })
+}
+
+fn apply(board: &Board, mutation: &Mutation) -> Board {
+    let mut board = board.clone();
+    let lights = match mutation {
+        Mutation::Quiet(lights) => lights.positions(),
+        Mutation::Press(x, y) => {
+            board.toggle(*x, *y);
+            Vec::new()
+        }
+        Mutation::Pair(a, b) => vec![*a, *b],
+    };
+    for (x, y) in lights {
+        board.set(x, y, !board.get(x, y));
+    }
+    board
+}
@@ -0,0 +1,347 @@
+//! Puzzle packs: JSON files of named boards with metadata, for distributing
+//! curated collections.
+//!
+//! ```json
+//! {
+//!   "name": "Starter",
+//!   "author": "someone",
+//!   "puzzles": [
+//!     { "name": "cross", "board": "5x5-011c400", "difficulty": 1, "optimal": 1 },
+//!     { "name": "corners", "board": ["#...#", ".....", ".....", ".....", "#...#"] }
+//!   ]
+//! }
+//! ```
+//!
+//! Boards are either puzzle codes or arrays of row strings. Puzzles played
+//! by other rules say so with a `variant` in the form [`Ruleset::parse`]
+//! takes, e.g. `"variant": "wrap+square"`, or in the code's `:` suffix.
+//!
+//! Packs embedded in the crate (see [`BUILTIN`]) can be opened by name
+//! anywhere a pack path is accepted, and single puzzles referenced as
+//! `pack/puzzle`, e.g. `starter/23`.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{
+    core::{BitMask, Board, Ruleset},
+    json::{self, Json},
+    pdf::{Document, Page, PAGE_HEIGHT, PAGE_WIDTH},
+};
+
+/// Puzzles to a printed page, across and down, and the paper left around
+/// them, in points.
+const SHEET_COLUMNS: usize = 2;
+const SHEET_ROWS: usize = 3;
+const SHEET_MARGIN: f64 = 40.0;
+
+/// Packs shipped with the crate, as `(name, json)`.
+///
+/// `starter` is fifty 5x5 levels generated from a fixed seed, numbered in
+/// order of optimal solution length (1 to 15 presses).
+pub const BUILTIN: &[(&str, &str)] = &[("starter", include_str!("packs/starter.json"))];
+
+pub fn builtin(name: &str) -> Option<Pack> {
+    BUILTIN
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, json)| Pack::parse(json).expect("built-in packs are valid"))
+}
+
+/// Resolve a `pack/puzzle` reference, where `pack` is a built-in pack name
+/// or a path to a pack file.
+pub fn lookup(reference: &str) -> Result<Puzzle, String> {
+    let (pack, name) = reference
+        .rsplit_once('/')
+        .ok_or(format!("expected pack/puzzle, got {reference:?}"))?;
+    let pack = Pack::open(pack)?;
+    pack.get(name)
+        .cloned()
+        .ok_or(format!("no puzzle named {name} in pack {}", pack.name))
+}
+
+/// Built-in puzzles whose [`Board::puzzle_id`] starts with `id`, with the
+/// `pack/puzzle` reference of each.
+pub fn find_id(id: &str) -> Vec<(String, Puzzle)> {
+    BUILTIN
+        .iter()
+        .filter_map(|(name, _)| Some((name, builtin(name)?)))
+        .flat_map(|(name, pack)| {
+            pack.puzzles
+                .into_iter()
+                .filter(|puzzle| puzzle.board.matches_id(id))
+                .map(move |puzzle| (format!("{name}/{}", puzzle.name), puzzle))
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    pub name: String,
+    pub board: Board,
+    pub author: Option<String>,
+    pub difficulty: Option<f64>,
+    /// Length of the optimal solution, if the pack records it.
+    pub optimal: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pack {
+    pub name: String,
+    pub author: Option<String>,
+    pub puzzles: Vec<Puzzle>,
+}
+
+fn board_from_json(value: &Json) -> Result<Board, String> {
+    match value {
+        Json::String(code) => Board::from_code(code).map_err(|err| err.to_string()),
+        Json::Array(rows) => rows
+            .iter()
+            .map(|row| row.as_str().ok_or("board rows must be strings"))
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n")
+            .parse()
+            .map_err(|err: crate::ParseError| err.to_string()),
+        _ => Err("board must be a puzzle code or an array of rows".to_string()),
+    }
+}
+
+impl Puzzle {
+    pub fn new(name: impl Into<String>, board: Board) -> Puzzle {
+        Puzzle {
+            name: name.into(),
+            board,
+            author: None,
+            difficulty: None,
+            optimal: None,
+        }
+    }
+
+    pub fn from_json(value: &Json) -> Result<Puzzle, String> {
+        let name = value
+            .get("name")
+            .and_then(Json::as_str)
+            .ok_or("puzzle is missing a name")?;
+        let board = value.get("board").ok_or(format!("puzzle {name} is missing a board"))?;
+        let mut board = board_from_json(board).map_err(|err| format!("puzzle {name}: {err}"))?;
+        // Rules may come with the board code or, for grids, alongside it.
+        if let Some(variant) = value.get("variant").and_then(Json::as_str) {
+            let ruleset = Ruleset::parse(variant, board.width(), board.height())
+                .map_err(|err| format!("puzzle {name}: {err}"))?;
+            ruleset
+                .check(board.width(), board.height())
+                .map_err(|err| format!("puzzle {name}: {err}"))?;
+            board.set_ruleset(ruleset);
+        }
+        Ok(Puzzle {
+            name: name.to_string(),
+            board,
+            author: value.get("author").and_then(Json::as_str).map(String::from),
+            difficulty: value.get("difficulty").and_then(Json::as_f64),
+            optimal: value.get("optimal").and_then(Json::as_u64).map(|n| n as usize),
+        })
+    }
+
+    pub fn to_json(&self) -> Json {
+        let mut entries = vec![
+            ("name", self.name.clone().into()),
+            ("board", self.board.to_code().into()),
+        ];
+        if let Some(author) = &self.author {
+            entries.push(("author", author.clone().into()));
+        }
+        if let Some(difficulty) = self.difficulty {
+            entries.push(("difficulty", difficulty.into()));
+        }
+        if let Some(optimal) = self.optimal {
+            entries.push(("optimal", optimal.into()));
+        }
+        Json::object(entries)
+    }
+}
+
+impl Pack {
+    pub fn from_json(value: &Json) -> Result<Pack, String> {
+        let puzzles = value
+            .get("puzzles")
+            .and_then(Json::as_array)
+            .ok_or("pack has no puzzles array")?
+            .iter()
+            .map(Puzzle::from_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Pack {
+            name: value
+                .get("name")
+                .and_then(Json::as_str)
+                .unwrap_or("unnamed")
+                .to_string(),
+            author: value.get("author").and_then(Json::as_str).map(String::from),
+            puzzles,
+        })
+    }
+
+    pub fn to_json(&self) -> Json {
+        let mut entries = vec![("name", self.name.clone().into())];
+        if let Some(author) = &self.author {
+            entries.push(("author", author.clone().into()));
+        }
+        entries.push((
+            "puzzles",
+            Json::Array(self.puzzles.iter().map(Puzzle::to_json).collect()),
+        ));
+        Json::object(entries)
+    }
+
+    pub fn parse(text: &str) -> Result<Pack, String> {
+        Pack::from_json(&json::parse(text)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Pack, String> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| Pack::parse(&text))
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    /// Load a pack file, falling back to a built-in pack of that name when
+    /// no such file exists.
+    pub fn open(path_or_name: &str) -> Result<Pack, String> {
+        match builtin(path_or_name) {
+            Some(pack) if !Path::new(path_or_name).exists() => Ok(pack),
+            _ => Pack::load(path_or_name),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        fs::write(path, format!("{}\n", self.to_json()))
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Puzzle> {
+        self.puzzles.iter().find(|puzzle| puzzle.name == name)
+    }
+
+    /// Remove every puzzle that is the same as an earlier one up to
+    /// rotation, reflection and wrapping shifts (see
+    /// [`Board::canonical_form`]), keeping the first of each. Returns the
+    /// removed puzzles in pack order, each with the name of the one kept.
+    pub fn dedupe(&mut self) -> Vec<(Puzzle, String)> {
+        let mut kept: HashMap<String, String> = HashMap::new();
+        let mut removed = Vec::new();
+        let puzzles = std::mem::take(&mut self.puzzles);
+        for puzzle in puzzles {
+            let canonical = puzzle.board.canonical_form().to_code();
+            match kept.get(&canonical) {
+                Some(original) => removed.push((puzzle, original.clone())),
+                None => {
+                    kept.insert(canonical, puzzle.name.clone());
+                    self.puzzles.push(puzzle);
+                }
+            }
+        }
+        removed
+    }
+
+    /// The pack as a printable document, six puzzles to an A4 page, lit
+    /// cells dark and cells the goal wants lit marked inside. With
+    /// `solutions`, pages after them show each puzzle's fewest presses.
+    pub fn sheet(&self, solutions: bool) -> Document {
+        let mut document = Document::new();
+        let heading = match &self.author {
+            Some(author) => format!("{} by {author}", self.name),
+            None => self.name.clone(),
+        };
+        let numbered: Vec<(usize, &Puzzle)> = self.puzzles.iter().enumerate().collect();
+        let per_page = SHEET_COLUMNS * SHEET_ROWS;
+        if numbered.is_empty() {
+            self.sheet_page(&mut document, &heading, &[], false);
+        }
+        for puzzles in numbered.chunks(per_page) {
+            self.sheet_page(&mut document, &heading, puzzles, false);
+        }
+        if solutions {
+            for puzzles in numbered.chunks(per_page) {
+                self.sheet_page(&mut document, &format!("{heading}: solutions"), puzzles, true);
+            }
+        }
+        document
+    }
+
+    fn sheet_page(
+        &self,
+        document: &mut Document,
+        heading: &str,
+        puzzles: &[(usize, &Puzzle)],
+        solutions: bool,
+    ) {
+        let number = document.page_count() + 1;
+        let page = document.page();
+        let top = PAGE_HEIGHT - SHEET_MARGIN;
+        page.text(SHEET_MARGIN, top - 14.0, 16.0, heading);
+        page.text(SHEET_MARGIN, SHEET_MARGIN / 2.0, 8.0, &format!("{} - page {number}", self.name));
+
+        let slot_width = (PAGE_WIDTH - 2.0 * SHEET_MARGIN) / SHEET_COLUMNS as f64;
+        let slot_height = (top - 30.0 - SHEET_MARGIN) / SHEET_ROWS as f64;
+        for (slot, &(index, puzzle)) in puzzles.iter().enumerate() {
+            let x = SHEET_MARGIN + (slot % SHEET_COLUMNS) as f64 * slot_width;
+            let y = top - 30.0 - (slot / SHEET_COLUMNS) as f64 * slot_height;
+            let title = format!("{}. {}", index + 1, puzzle.name);
+            let presses = solutions.then(|| puzzle.board.solve_linear());
+            let note = match &presses {
+                Some(Some(presses)) => match presses.count() {
+                    1 => "1 press".to_string(),
+                    count => format!("{count} presses"),
+                },
+                Some(None) => "no solution".to_string(),
+                None => match puzzle.board.to_code().split_once(':') {
+                    Some((_, rules)) => format!("rules: {rules}"),
+                    None => String::new(),
+                },
+            };
+            page.text(x, y - 12.0, 11.0, &title);
+            if !note.is_empty() {
+                page.text(x, y - 24.0, 8.0, &note);
+            }
+            let (width, height) = (slot_width - 16.0, slot_height - 44.0);
+            draw_board(page, &puzzle.board, presses.flatten().as_ref(), x, y - 32.0, width, height);
+        }
+    }
+}
+
+/// `board` fitted into the box hanging down from `(x, y)`, with a dot on
+/// each cell of `presses`.
+fn draw_board(
+    page: &mut Page,
+    board: &Board,
+    presses: Option<&BitMask>,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) {
+    let cell = (width / board.width() as f64)
+        .min(height / board.height() as f64)
+        .min(32.0);
+    let rules = board.ruleset();
+    for ((cx, cy), lit) in board.cells() {
+        if rules.is_obstacle(cx, cy) {
+            continue;
+        }
+        let (left, bottom) = (x + cx as f64 * cell, y - (cy + 1) as f64 * cell);
+        let (inset, size) = (cell * 0.06, cell * 0.88);
+        if lit {
+            page.fill_rect(left + inset, bottom + inset, size, size, 0.2);
+        } else {
+            page.stroke_rect(left + inset, bottom + inset, size, size, 0.4, 0.8);
+        }
+        if rules.goal.as_ref().is_some_and(|goal| goal.get(cx, cy)) {
+            let (inset, size) = (cell * 0.25, cell * 0.5);
+            page.stroke_rect(left + inset, bottom + inset, size, size, 0.6, 0.8);
+        }
+        if presses.is_some_and(|presses| presses.get(cx, cy)) {
+            let gray = if lit { 0.95 } else { 0.1 };
+            page.fill_circle(left + cell / 2.0, bottom + cell / 2.0, cell * 0.18, gray);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Just enough PDF to print puzzle sheets without a layout dependency:
+//! pages of grey rectangles, dots and Helvetica text, uncompressed.
+
+use std::fmt::Write;
+
+/// A4, in points.
+pub const PAGE_WIDTH: f64 = 595.28;
+pub const PAGE_HEIGHT: f64 = 841.89;
+
+/// A document being drawn, one page at a time.
+#[derive(Clone, Debug, Default)]
+pub struct Document {
+    pages: Vec<Page>,
+}
+
+/// One page's drawing, in points from the bottom left corner.
+#[derive(Clone, Debug, Default)]
+pub struct Page {
+    content: String,
+}
+
+impl Document {
+    pub fn new() -> Document {
+        Document::default()
+    }
+
+    /// Start a new page and draw on it.
+    pub fn page(&mut self) -> &mut Page {
+        self.pages.push(Page::default());
+        self.pages.last_mut().unwrap()
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The finished PDF file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut kids = Vec::new();
+        for page in &self.pages {
+            let contents = objects.len() + 2;
+            kids.push(format!("{} 0 R", objects.len() + 1));
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {contents} 0 R >>"
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+        objects[1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            self.pages.len()
+        );
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n{object}\nendobj\n", i + 1).bytes());
+        }
+        let xref = pdf.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        pdf.extend(table.bytes());
+        pdf
+    }
+}
+
+/// A number as PDF wants it, without needless digits.
+fn number(value: f64) -> String {
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+impl Page {
+    /// A rectangle filled with `gray`, from 0 for black to 1 for white.
+    pub fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, gray: f64) {
+        let [x, y, width, height, gray] = [x, y, width, height, gray].map(number);
+        let _ = writeln!(self.content, "{gray} g {x} {y} {width} {height} re f");
+    }
+
+    /// A rectangle's outline, `line` points thick.
+    pub fn stroke_rect(&mut self, x: f64, y: f64, width: f64, height: f64, gray: f64, line: f64) {
+        let [x, y, width, height, gray, line] = [x, y, width, height, gray, line].map(number);
+        let _ = writeln!(self.content, "{gray} G {line} w {x} {y} {width} {height} re S");
+    }
+
+    /// A filled circle around `(x, y)`.
+    pub fn fill_circle(&mut self, x: f64, y: f64, radius: f64, gray: f64) {
+        // Four Bézier quarters, with the usual control point distance.
+        let k = radius * 0.5523;
+        let _ = write!(self.content, "{} g {} {} m ", number(gray), number(x + radius), number(y));
+        let quarters = [
+            [(x + radius, y + k), (x + k, y + radius), (x, y + radius)],
+            [(x - k, y + radius), (x - radius, y + k), (x - radius, y)],
+            [(x - radius, y - k), (x - k, y - radius), (x, y - radius)],
+            [(x + k, y - radius), (x + radius, y - k), (x + radius, y)],
+        ];
+        for points in quarters {
+            for (px, py) in points {
+                let _ = write!(self.content, "{} {} ", number(px), number(py));
+            }
+            self.content.push_str("c ");
+        }
+        self.content.push_str("f\n");
+    }
+
+    /// Black text with its baseline starting at `(x, y)`. Characters
+    /// Helvetica can't show come out as `?`.
+    pub fn text(&mut self, x: f64, y: f64, size: f64, text: &str) {
+        let mut escaped = String::new();
+        for c in text.chars() {
+            match c {
+                '(' | ')' | '\\' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                ' '..='~' => escaped.push(c),
+                _ => escaped.push('?'),
+            }
+        }
+        let [x, y, size] = [x, y, size].map(number);
+        let _ = writeln!(self.content, "0 g BT /F1 {size} Tf {x} {y} Td ({escaped}) Tj ET");
+    }
+}
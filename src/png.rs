@@ -0,0 +1,436 @@
+//! Just enough PNG to write images without an imaging dependency: 8-bit RGB,
+//! uncompressed deflate blocks. Reading takes any PNG that isn't interlaced,
+//! for turning pictures into boards.
+
+use crate::core::BitMask;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// Encode `pixels` (row-major `[r, g, b]` triples) as a PNG file.
+pub fn encode_rgb(width: usize, height: usize, pixels: &[[u8; 3]]) -> Vec<u8> {
+    assert_eq!(pixels.len(), width * height);
+    let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+    for row in pixels.chunks(width.max(1)) {
+        raw.push(0);
+        raw.extend(row.iter().flatten());
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(block);
+    }
+    zlib.extend(adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::new();
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib);
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// A decoded image, row-major `[r, g, b, a]` pixels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    /// How light each pixel is from 0 to 255, over a white background
+    /// where it's see-through.
+    pub fn luminance(&self) -> Vec<u8> {
+        let blend = |channel: u8, alpha: u8| {
+            (channel as u32 * alpha as u32 + 255 * (255 - alpha as u32)) / 255
+        };
+        self.pixels
+            .iter()
+            .map(|&[r, g, b, a]| {
+                let (r, g, b) = (blend(r, a), blend(g, a), blend(b, a));
+                ((r * 299 + g * 587 + b * 114) / 1000) as u8
+            })
+            .collect()
+    }
+
+    /// The image shrunk to a `width` x `height` grid, each cell averaging
+    /// the pixels under it, with the cells darker than halfway between the
+    /// darkest and lightest lit: a dark logo on a light background comes
+    /// out as the logo. Nothing is lit if every cell is the same.
+    pub fn threshold(&self, width: usize, height: usize) -> BitMask {
+        let luminance = self.luminance();
+        // The pixels from `start` up to `end` along one axis, at least one.
+        let span = |cell: usize, cells: usize, pixels: usize| {
+            let start = (cell * pixels / cells).min(pixels.saturating_sub(1));
+            start..((cell + 1) * pixels / cells).max(start + 1)
+        };
+        let mut averages = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let (xs, ys) = (span(x, width, self.width), span(y, height, self.height));
+                let count = xs.len() * ys.len();
+                let total: usize = ys
+                    .flat_map(|py| xs.clone().map(move |px| (px, py)))
+                    .map(|(px, py)| luminance[py * self.width + px] as usize)
+                    .sum();
+                averages.push(total / count);
+            }
+        }
+        let darkest = averages.iter().copied().min().unwrap_or(0);
+        let lightest = averages.iter().copied().max().unwrap_or(0);
+        let mut mask = BitMask::new(width, height);
+        for (cell, &average) in averages.iter().enumerate() {
+            mask.set(cell % width, cell / width, average * 2 < darkest + lightest);
+        }
+        mask
+    }
+}
+
+/// Decode a PNG file of any colour type and bit depth, as long as it isn't
+/// interlaced. Sixteen-bit channels keep their high byte.
+pub fn decode(png: &[u8]) -> Result<Image, String> {
+    let mut rest = png.strip_prefix(&SIGNATURE).ok_or("not a PNG file")?;
+    let (mut header, mut palette, mut transparency, mut zlib) =
+        (None, Vec::new(), Vec::new(), Vec::new());
+    loop {
+        if rest.len() < 12 {
+            return Err("unexpected end of file".to_string());
+        }
+        let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind: [u8; 4] = rest[4..8].try_into().unwrap();
+        let data = rest.get(8..8 + len).ok_or("unexpected end of file")?;
+        let crc = rest
+            .get(8 + len..12 + len)
+            .ok_or("unexpected end of file")?;
+        if crc32(&rest[4..8 + len]).to_be_bytes() != crc {
+            return Err(format!(
+                "bad checksum on {} chunk",
+                String::from_utf8_lossy(&kind)
+            ));
+        }
+        rest = &rest[12 + len..];
+        match &kind {
+            b"IHDR" => header = Some(data.to_vec()),
+            b"PLTE" => {
+                palette = data
+                    .chunks_exact(3)
+                    .map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                    .collect()
+            }
+            b"tRNS" => transparency = data.to_vec(),
+            b"IDAT" => zlib.extend(data),
+            b"IEND" => break,
+            _ if kind[0].is_ascii_uppercase() => {
+                return Err(format!(
+                    "unsupported {} chunk",
+                    String::from_utf8_lossy(&kind)
+                ));
+            }
+            _ => {}
+        }
+    }
+    let header = header
+        .filter(|header| header.len() == 13)
+        .ok_or("missing IHDR chunk")?;
+    let width = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+    let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+    let (depth, color, interlace) = (header[8] as usize, header[9], header[12]);
+    if width == 0 || height == 0 {
+        return Err("the image is empty".to_string());
+    }
+    if interlace != 0 {
+        return Err("interlaced PNGs aren't supported".to_string());
+    }
+    let channels = match (color, depth) {
+        (0, 1 | 2 | 4 | 8 | 16) => 1,
+        (3, 1 | 2 | 4 | 8) => 1,
+        (4, 8 | 16) => 2,
+        (2, 8 | 16) => 3,
+        (6, 8 | 16) => 4,
+        _ => {
+            return Err(format!(
+                "invalid colour type {color} with bit depth {depth}"
+            ))
+        }
+    };
+    if color == 3 {
+        for (entry, &alpha) in palette.iter_mut().zip(&transparency) {
+            entry[3] = alpha;
+        }
+    }
+
+    let raw = inflate(zlib.get(2..).ok_or("unexpected end of image data")?)?;
+    let stride = (width * channels * depth).div_ceil(8);
+    let step = (channels * depth).div_ceil(8);
+    if raw.len() < height * (stride + 1) {
+        return Err("unexpected end of image data".to_string());
+    }
+    let mut previous = vec![0u8; stride];
+    let mut pixels = Vec::with_capacity(width * height);
+    for line in raw.chunks_exact(stride + 1).take(height) {
+        let mut row = line[1..].to_vec();
+        for i in 0..stride {
+            let left = if i >= step { row[i - step] } else { 0 };
+            let (up, corner) = (previous[i], if i >= step { previous[i - step] } else { 0 });
+            row[i] = row[i].wrapping_add(match line[0] {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, corner),
+                filter => return Err(format!("invalid filter type {filter}")),
+            });
+        }
+        // Each sample scaled up to a byte, high byte first for 16 bits.
+        let sample = |index: usize| -> u8 {
+            match depth {
+                8 => row[index],
+                16 => row[index * 2],
+                _ => {
+                    let bit = index * depth;
+                    let value = (row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1);
+                    if color == 3 {
+                        value
+                    } else {
+                        (value as u32 * 255 / ((1 << depth) - 1)) as u8
+                    }
+                }
+            }
+        };
+        for x in 0..width {
+            let i = x * channels;
+            pixels.push(match color {
+                0 => [sample(i), sample(i), sample(i), 255],
+                3 => *palette
+                    .get(sample(i) as usize)
+                    .ok_or("palette index out of range")?,
+                4 => [sample(i), sample(i), sample(i), sample(i + 1)],
+                2 => [sample(i), sample(i + 1), sample(i + 2), 255],
+                _ => [sample(i), sample(i + 1), sample(i + 2), sample(i + 3)],
+            });
+        }
+        previous = row;
+    }
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+fn paeth(left: u8, up: u8, corner: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - corner as i16;
+    let distance = |value: u8| (estimate - value as i16).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(corner) {
+        left
+    } else if distance(up) <= distance(corner) {
+        up
+    } else {
+        corner
+    }
+}
+
+/// Reads a deflate stream least significant bit first.
+struct Bits<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Bits<'_> {
+    fn take(&mut self, count: usize) -> Result<usize, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = self
+                .data
+                .get(self.position / 8)
+                .ok_or("unexpected end of image data")?;
+            value |= ((byte >> (self.position % 8)) as usize & 1) << i;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+/// A canonical Huffman code, as how many codes there are of each length
+/// and the symbols in code order.
+struct Huffman {
+    counts: [usize; 16],
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[usize]) -> Huffman {
+        let mut counts = [0; 16];
+        for &length in lengths {
+            counts[length] += 1;
+        }
+        counts[0] = 0;
+        let mut symbols: Vec<usize> = (0..lengths.len()).filter(|&s| lengths[s] != 0).collect();
+        symbols.sort_by_key(|&symbol| lengths[symbol]);
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<usize, String> {
+        let (mut code, mut first, mut index) = (0, 0, 0);
+        for &count in &self.counts[1..] {
+            code |= bits.take(1)?;
+            if code < first + count {
+                return Ok(self.symbols[index + code - first]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [usize; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [usize; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Decompress a raw deflate stream.
+fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bits = Bits { data, position: 0 };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.take(1)? == 1;
+        match bits.take(2)? {
+            0 => {
+                let start = bits.position.div_ceil(8);
+                let header = data
+                    .get(start..start + 4)
+                    .ok_or("unexpected end of image data")?;
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                let block = data
+                    .get(start + 4..start + 4 + len)
+                    .ok_or("unexpected end of image data")?;
+                out.extend(block);
+                bits.position = (start + 4 + len) * 8;
+            }
+            kind @ (1 | 2) => {
+                let (literals, distances) = if kind == 1 {
+                    let mut lengths = [8; 288];
+                    lengths[144..256].fill(9);
+                    lengths[256..280].fill(7);
+                    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+                } else {
+                    dynamic_codes(&mut bits)?
+                };
+                loop {
+                    let symbol = literals.decode(&mut bits)?;
+                    if symbol < 256 {
+                        out.push(symbol as u8);
+                        continue;
+                    } else if symbol == 256 {
+                        break;
+                    }
+                    let i = symbol - 257;
+                    let length = LENGTH_BASE.get(i).ok_or("invalid length code")?
+                        + bits.take(LENGTH_EXTRA[i])?;
+                    let d = distances.decode(&mut bits)?;
+                    let distance = DISTANCE_BASE.get(d).ok_or("invalid distance code")?
+                        + bits.take(DISTANCE_EXTRA[d])?;
+                    let start = out
+                        .len()
+                        .checked_sub(distance)
+                        .ok_or("distance too far back")?;
+                    for i in 0..length {
+                        out.push(out[start + i]);
+                    }
+                }
+            }
+            _ => return Err("invalid deflate block type".to_string()),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+/// The literal and distance codes at the start of a dynamic block.
+fn dynamic_codes(bits: &mut Bits) -> Result<(Huffman, Huffman), String> {
+    const ORDER: [usize; 19] = [
+        16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+    ];
+    let literals = bits.take(5)? + 257;
+    let distances = bits.take(5)? + 1;
+    let mut code_lengths = [0; 19];
+    for &i in ORDER.iter().take(bits.take(4)? + 4) {
+        code_lengths[i] = bits.take(3)?;
+    }
+    let code = Huffman::new(&code_lengths);
+    let mut lengths = Vec::with_capacity(literals + distances);
+    while lengths.len() < literals + distances {
+        let (value, repeat) = match code.decode(bits)? {
+            length @ 0..=15 => (length, 1),
+            16 => (
+                *lengths.last().ok_or("repeat with no length before it")?,
+                3 + bits.take(2)?,
+            ),
+            17 => (0, 3 + bits.take(3)?),
+            _ => (0, 11 + bits.take(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+    if lengths.len() != literals + distances {
+        return Err("code lengths overrun".to_string());
+    }
+    Ok((
+        Huffman::new(&lengths[..literals]),
+        Huffman::new(&lengths[literals..]),
+    ))
+}
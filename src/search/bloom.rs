@@ -0,0 +1,103 @@
+use std::hash::{Hash, Hasher};
+
+/// False positive rate a filter is sized for, at its expected number of
+/// states.
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// A compact, approximate set of states expanded, consulted before the
+/// explored set so that most children, which are new, skip the exact
+/// lookup. It never misses a state it was given, so only a state it might
+/// hold goes on to the explored set, and a wrong "might" costs a lookup,
+/// never a state.
+///
+/// ```
+/// use lightsout::search::BloomFilter;
+///
+/// let mut seen = BloomFilter::new(1000);
+/// seen.insert(&"5x5-c408f38");
+/// assert!(seen.may_contain(&"5x5-c408f38"));
+/// assert!(!seen.may_contain(&"5x5-00008e0"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    /// Bits set for each state.
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// A filter for about `expected` states, the optimum number of bits and
+    /// hashes for a 1% false positive rate once it holds that many.
+    pub fn new(expected: usize) -> BloomFilter {
+        let expected = expected.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-expected * FALSE_POSITIVE_RATE.ln() / (ln2 * ln2)).ceil() as usize;
+        let hashes = (bits as f64 / expected * ln2).round().max(1.0) as u32;
+        BloomFilter {
+            bits: vec![0; bits.div_ceil(64)],
+            hashes,
+        }
+    }
+
+    /// Size of the filter in bits.
+    pub fn bit_count(&self) -> usize {
+        self.bits.len() * 64
+    }
+
+    /// Bits set for each state.
+    pub fn hash_count(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Heap memory the filter owns.
+    pub fn heap_bytes(&self) -> usize {
+        self.bits.capacity() * std::mem::size_of::<u64>()
+    }
+
+    pub fn insert<T: Hash + ?Sized>(&mut self, value: &T) {
+        for bit in self.positions(value) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Whether `value` might have been inserted: always if it was, and
+    /// rarely if not.
+    pub fn may_contain<T: Hash + ?Sized>(&self, value: &T) -> bool {
+        self.positions(value)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// The bits `value` sets, by double hashing one cheap 64-bit hash.
+    fn positions<T: Hash + ?Sized>(&self, value: &T) -> impl Iterator<Item = usize> {
+        let mut hasher = FxHasher(0);
+        value.hash(&mut hasher);
+        let hash = hasher.0;
+        let (first, step) = (hash, hash.rotate_left(32) | 1);
+        let len = self.bit_count() as u64;
+        (0..u64::from(self.hashes))
+            .map(move |i| (first.wrapping_add(i.wrapping_mul(step)) % len) as usize)
+    }
+}
+
+/// The multiply-rotate hash rustc uses for its own tables: far quicker than
+/// the standard library's, and good enough when a collision only costs a
+/// lookup.
+struct FxHasher(u64);
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,149 @@
+use rand::prelude::*;
+
+use crate::core::{BitMask, Board};
+
+use super::book_data::BOOKS;
+
+/// Marks a quadrant no analysed board had, so the book has no advice.
+const NO_MOVE: u8 = u8::MAX;
+
+/// The press most often part of a minimal solution, for each lighting of a
+/// board's top-left quadrant, so a search can try it first and a hint can
+/// be given without solving anything.
+///
+/// Books for square classic boards from 4x4 to 6x6 are built into the
+/// crate by `lightsout analyze book`; [`OpeningBook::analyze`] builds one
+/// for any other size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpeningBook {
+    width: usize,
+    height: usize,
+    /// A cell index (`y * width + x`) per quadrant, or [`NO_MOVE`].
+    presses: Vec<u8>,
+}
+
+/// The quadrant's size: the top-left half of each side, rounding up.
+fn quadrant_size(width: usize, height: usize) -> (usize, usize) {
+    (width.div_ceil(2), height.div_ceil(2))
+}
+
+/// Boards of at most this many cells are analysed exhaustively.
+const EXHAUSTIVE_CELLS: usize = 16;
+/// Quadrants may have at most this many cells, so books stay small.
+const MAX_QUADRANT_CELLS: usize = 12;
+
+impl OpeningBook {
+    /// The book built into the crate for `width` x `height` classic boards,
+    /// if there is one.
+    pub fn embedded(width: usize, height: usize) -> Option<OpeningBook> {
+        BOOKS
+            .iter()
+            .find(|(w, h, _)| (*w, *h) == (width, height))
+            .map(|(width, height, presses)| OpeningBook {
+                width: *width,
+                height: *height,
+                presses: presses.to_vec(),
+            })
+    }
+
+    /// Build a book for classic `width` x `height` boards from the minimal
+    /// solutions of every solvable board, or for boards over 16 cells from
+    /// `samples` random solvable boards drawn from `seed`. `None` if the
+    /// quadrant would have more than 12 cells.
+    pub fn analyze(width: usize, height: usize, samples: usize, seed: u64) -> Option<OpeningBook> {
+        let (quadrant_width, quadrant_height) = quadrant_size(width, height);
+        let cells = width * height;
+        if quadrant_width * quadrant_height > MAX_QUADRANT_CELLS || cells > u8::MAX as usize {
+            return None;
+        }
+        let mut book = OpeningBook {
+            width,
+            height,
+            presses: vec![NO_MOVE; 1 << (quadrant_width * quadrant_height)],
+        };
+        let mut counts = vec![0.0f64; book.presses.len() * cells];
+        let mut add = |board: &Board| {
+            let minimal = board.minimal_solutions();
+            let quadrant = book.quadrant(board);
+            let weight = 1.0 / minimal.len() as f64;
+            for solution in &minimal {
+                for (x, y) in solution.positions() {
+                    counts[quadrant * cells + y * width + x] += weight;
+                }
+            }
+        };
+        if cells <= EXHAUSTIVE_CELLS {
+            for index in 0..1u32 << cells {
+                let mut board = Board::new(width, height);
+                for cell in (0..cells).filter(|cell| index & (1 << cell) != 0) {
+                    board.set(cell % width, cell / width, true);
+                }
+                if board.solve_linear().is_some() {
+                    add(&board);
+                }
+            }
+        } else {
+            // Random presses from a blank board reach every solvable board
+            // equally often.
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..samples {
+                let mut presses = BitMask::new(width, height);
+                for cell in 0..cells {
+                    presses.set(cell % width, cell / width, rng.gen());
+                }
+                let mut board = Board::new(width, height);
+                board.apply_press_set(&presses);
+                add(&board);
+            }
+        }
+        for (quadrant, press) in book.presses.iter_mut().enumerate() {
+            let counts = &counts[quadrant * cells..(quadrant + 1) * cells];
+            let mean = counts.iter().sum::<f64>() / cells as f64;
+            let best = (0..cells).max_by(|&a, &b| counts[a].total_cmp(&counts[b]).then(b.cmp(&a)));
+            // A quadrant that says nothing about the presses, as on boards
+            // like 3x3 where every cell is equally likely, gets no advice.
+            if let Some(best) = best.filter(|&best| counts[best] > mean * (1.0 + 1e-9)) {
+                *press = best as u8;
+            }
+        }
+        Some(book)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The entry for each lighting of the quadrant, indexed like
+    /// [`OpeningBook::quadrant`], with [`u8::MAX`] where there's no advice.
+    pub fn entries(&self) -> &[u8] {
+        &self.presses
+    }
+
+    /// Which lighting of its top-left quadrant `board` has: the quadrant's
+    /// cells in row-major order, one bit each from the lowest.
+    pub fn quadrant(&self, board: &Board) -> usize {
+        let (quadrant_width, quadrant_height) = quadrant_size(self.width, self.height);
+        (0..quadrant_height)
+            .flat_map(|y| (0..quadrant_width).map(move |x| (x, y)))
+            .enumerate()
+            .filter(|&(_, (x, y))| board.get(x, y))
+            .fold(0, |index, (bit, _)| index | 1 << bit)
+    }
+
+    /// The book's first press for `board`, as `(x, y)`. `None` for boards
+    /// of another size or with other rules, and for quadrants the book has
+    /// no advice on.
+    pub fn first_press(&self, board: &Board) -> Option<(usize, usize)> {
+        if (board.width(), board.height()) != (self.width, self.height)
+            || !board.ruleset().is_classic()
+        {
+            return None;
+        }
+        let press = self.presses[self.quadrant(board)];
+        (press != NO_MOVE).then(|| (press as usize % self.width, press as usize / self.width))
+    }
+}
@@ -0,0 +1,78 @@
+//! Opening books for square classic boards, one entry per lighting of the
+//! top-left quadrant. Regenerate an entry with
+//! `lightsout analyze book --width N --height N`: 4x4 from every solvable
+//! board, the larger sizes from 200,000 random ones drawn from seed 0.
+
+pub(super) const BOOKS: &[(usize, usize, &[u8])] = &[
+    (4, 4, &[
+        10, 5, 2, 1, 8, 4, 5, 0, 6, 5, 2, 1, 8, 4, 5, 0,
+    ]),
+    (5, 5, &[
+        18, 18, 6, 15, 11, 6, 16, 12, 16, 8, 7, 12, 16, 6, 16, 12,
+        11, 18, 8, 18, 8, 18, 15, 18, 8, 7, 12, 16, 8, 6, 16, 18,
+        12, 18, 8, 18, 11, 15, 16, 15, 8, 11, 18, 8, 12, 8, 12, 16,
+        11, 18, 7, 8, 8, 8, 6, 8, 18, 7, 12, 16, 6, 16, 18, 16,
+        7, 6, 7, 8, 8, 16, 18, 16, 6, 16, 12, 12, 11, 7, 18, 11,
+        6, 12, 6, 16, 12, 7, 8, 16, 18, 18, 7, 18, 7, 16, 7, 16,
+        7, 8, 8, 6, 18, 18, 7, 12, 12, 15, 3, 18, 6, 12, 6, 6,
+        3, 15, 6, 11, 16, 8, 12, 12, 16, 16, 6, 12, 12, 8, 7, 8,
+        8, 18, 6, 12, 12, 8, 6, 15, 15, 8, 7, 16, 7, 8, 16, 8,
+        18, 6, 7, 16, 12, 6, 11, 6, 3, 8, 12, 12, 6, 16, 8, 6,
+        18, 18, 6, 12, 18, 6, 16, 16, 15, 6, 16, 18, 12, 16, 8, 11,
+        15, 18, 7, 18, 15, 8, 16, 8, 18, 15, 16, 12, 8, 15, 8, 8,
+        7, 3, 16, 8, 7, 6, 15, 11, 12, 6, 12, 6, 8, 15, 8, 12,
+        3, 16, 18, 12, 12, 15, 8, 8, 3, 18, 18, 8, 6, 3, 11, 12,
+        12, 3, 11, 16, 15, 16, 18, 11, 6, 15, 12, 12, 16, 6, 16, 11,
+        3, 8, 8, 3, 8, 11, 6, 7, 8, 8, 8, 11, 8, 12, 18, 18,
+        6, 8, 11, 15, 11, 16, 6, 6, 7, 16, 11, 18, 3, 16, 16, 16,
+        8, 18, 6, 8, 8, 16, 16, 8, 18, 15, 6, 16, 18, 12, 6, 16,
+        16, 8, 12, 6, 6, 7, 16, 16, 8, 12, 7, 16, 16, 18, 18, 8,
+        8, 18, 6, 3, 15, 16, 7, 8, 11, 15, 6, 15, 6, 12, 16, 18,
+        7, 16, 6, 16, 18, 16, 7, 12, 12, 11, 3, 16, 3, 16, 12, 18,
+        18, 8, 18, 8, 12, 12, 8, 18, 7, 8, 7, 16, 12, 11, 15, 18,
+        16, 3, 7, 8, 18, 18, 8, 12, 18, 12, 3, 7, 18, 7, 8, 6,
+        3, 15, 18, 6, 6, 11, 3, 7, 11, 8, 6, 6, 8, 12, 11, 6,
+        6, 11, 11, 15, 12, 6, 16, 6, 16, 6, 18, 16, 7, 6, 15, 12,
+        18, 16, 12, 3, 6, 12, 15, 6, 11, 8, 12, 18, 8, 18, 11, 7,
+        8, 15, 11, 16, 6, 18, 6, 18, 15, 12, 6, 18, 8, 12, 6, 12,
+        12, 18, 16, 18, 7, 3, 11, 6, 12, 18, 18, 7, 6, 16, 12, 7,
+        7, 8, 12, 16, 3, 8, 8, 3, 8, 12, 18, 8, 18, 6, 8, 3,
+        3, 7, 8, 11, 8, 16, 16, 6, 16, 18, 7, 11, 15, 3, 8, 18,
+        6, 11, 12, 16, 3, 6, 15, 3, 12, 8, 16, 15, 12, 18, 12, 12,
+        8, 18, 16, 6, 18, 8, 11, 8, 7, 18, 7, 11, 16, 6, 18, 16,
+    ]),
+    (6, 6, &[
+        21, 25, 33, 19, 22, 6, 6, 1, 12, 10, 32, 32, 15, 31, 5, 12,
+        1, 27, 15, 12, 2, 0, 17, 26, 32, 23, 27, 13, 32, 11, 24, 15,
+        22, 6, 35, 11, 7, 31, 3, 30, 11, 22, 31, 31, 2, 22, 2, 24,
+        26, 19, 29, 32, 0, 11, 1, 26, 16, 5, 26, 21, 30, 32, 5, 24,
+        18, 7, 13, 20, 6, 33, 20, 0, 2, 21, 16, 9, 33, 20, 1, 20,
+        24, 20, 3, 33, 17, 18, 0, 7, 26, 11, 1, 32, 7, 8, 19, 25,
+        3, 21, 32, 24, 15, 23, 30, 33, 6, 19, 17, 33, 12, 10, 30, 4,
+        24, 0, 26, 22, 0, 9, 9, 6, 1, 34, 5, 28, 25, 14, 4, 21,
+        20, 23, 26, 4, 13, 11, 8, 16, 9, 26, 10, 4, 2, 17, 16, 2,
+        15, 12, 18, 23, 6, 20, 8, 0, 3, 5, 35, 7, 8, 32, 34, 32,
+        29, 12, 27, 23, 16, 30, 4, 6, 23, 10, 12, 15, 9, 27, 7, 32,
+        14, 28, 33, 26, 17, 31, 32, 5, 28, 5, 0, 29, 15, 25, 27, 8,
+        19, 14, 26, 0, 27, 22, 23, 24, 11, 7, 5, 28, 35, 26, 7, 31,
+        2, 16, 15, 14, 5, 35, 35, 6, 25, 21, 29, 1, 7, 17, 20, 35,
+        5, 19, 18, 14, 21, 10, 7, 16, 0, 16, 35, 0, 29, 30, 35, 1,
+        2, 12, 30, 32, 0, 34, 10, 27, 33, 12, 25, 14, 10, 10, 0, 23,
+        4, 15, 4, 22, 7, 33, 20, 19, 15, 22, 30, 25, 25, 25, 0, 9,
+        13, 6, 3, 0, 7, 30, 0, 5, 22, 26, 25, 15, 22, 3, 24, 28,
+        17, 3, 3, 7, 9, 9, 12, 11, 19, 30, 11, 28, 5, 8, 6, 8,
+        28, 26, 28, 4, 3, 24, 9, 32, 21, 8, 2, 2, 22, 33, 10, 1,
+        5, 10, 0, 9, 31, 29, 11, 11, 20, 24, 10, 30, 8, 16, 23, 9,
+        11, 0, 23, 29, 22, 31, 12, 1, 24, 26, 25, 13, 1, 2, 7, 20,
+        3, 35, 20, 3, 4, 26, 2, 20, 12, 1, 22, 27, 20, 0, 27, 8,
+        0, 8, 14, 11, 33, 32, 4, 11, 15, 20, 1, 17, 1, 22, 17, 34,
+        7, 19, 24, 33, 15, 34, 20, 16, 19, 0, 11, 17, 21, 8, 27, 23,
+        33, 11, 3, 13, 15, 28, 31, 25, 22, 12, 33, 11, 34, 13, 13, 33,
+        7, 20, 15, 27, 31, 16, 15, 24, 3, 27, 24, 21, 12, 35, 6, 0,
+        16, 7, 22, 30, 30, 3, 19, 33, 9, 12, 6, 14, 14, 7, 10, 30,
+        1, 32, 13, 35, 33, 32, 8, 1, 19, 11, 3, 18, 5, 30, 32, 1,
+        13, 2, 24, 17, 11, 15, 33, 6, 6, 34, 7, 32, 4, 15, 26, 4,
+        10, 20, 13, 21, 13, 19, 4, 1, 21, 17, 24, 28, 23, 20, 21, 8,
+        0, 9, 23, 10, 11, 35, 5, 9, 19, 29, 10, 0, 32, 31, 26, 30,
+    ]),
+];
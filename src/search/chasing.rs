@@ -0,0 +1,119 @@
+use std::fmt::Display;
+
+use crate::core::Board;
+
+use super::Search;
+
+/// A board to search with the light-chasing invariant as a pruning rule:
+/// once the top rows match the goal, no move may disturb them again, except
+/// for the presses on the top row that fix up a board chased down to its
+/// last row. That leaves only the presses below the first row still off its
+/// goal, which collapses the branching factor.
+///
+/// Every solvable board keeps a path to its goal. Chasing, pressing under
+/// each wrong cell of the first wrong row, is always allowed and ends with
+/// only the last row wrong. A solvable board there is solved by some set of
+/// top row presses followed by another chase. The first of those presses is
+/// allowed as a fix-up and leaves the top row wrong, which lifts the rule.
+/// Top row presses only clear the top row again once they cancel out on it,
+/// and at most one nonempty set of presses does that, so the rest can be
+/// made in an order that keeps it wrong until they are all done. The path
+/// can be longer than the best one, so search to
+/// [`ChasingBoard::max_depth`] rather than the cell count.
+///
+/// The rule only holds where presses work as in the original game; under
+/// other rules every move is kept.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ChasingBoard {
+    board: Board,
+}
+
+impl ChasingBoard {
+    pub fn new(board: Board) -> ChasingBoard {
+        ChasingBoard { board }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// A depth the path kept through any solvable board fits in: a chase,
+    /// a row of fix-up presses, and a second chase.
+    pub fn max_depth(&self) -> usize {
+        let (width, height) = (self.board.width(), self.board.height());
+        (2 * height).saturating_sub(1) * width
+    }
+
+    fn wrap(&self, moves: Vec<(Board, usize, usize)>) -> Vec<(Self, usize, usize)> {
+        moves
+            .into_iter()
+            .map(|(board, index, score)| (ChasingBoard { board }, index, score))
+            .collect()
+    }
+
+    /// How many rows from the top already match the goal.
+    fn cleared_rows(&self) -> usize {
+        let rules = self.board.ruleset();
+        (0..self.board.height)
+            .take_while(|&y| self.board.rows[y] == rules.goal_row(y))
+            .count()
+    }
+}
+
+impl Display for ChasingBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.board.fmt(f)
+    }
+}
+
+impl Search for ChasingBoard {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        self.board.score()
+    }
+
+    fn end(&self) -> bool {
+        self.board.end()
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        self.scored_moves(&self.score())
+            .into_iter()
+            .map(|(child, index, _)| (child, index))
+            .collect()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.board.heap_bytes()
+    }
+
+    fn scored_moves(&self, score: &usize) -> Vec<(Self, usize, usize)> {
+        let (width, height) = (self.board.width(), self.board.height());
+        let cleared = self.cleared_rows();
+        if !self.board.presses_classically() || cleared == height {
+            return self.wrap(self.board.scored_moves(score));
+        }
+        let mut moves: Vec<_> = if cleared == height - 1 {
+            Vec::new()
+        } else {
+            let moves = self.board.scored_moves(score);
+            moves
+                .into_iter()
+                .filter(|(_, index, _)| index / width > cleared)
+                .collect()
+        };
+        if cleared == 0 || cleared == height - 1 {
+            // The plain board leaves out presses that only light cells,
+            // which fix-up presses after the first can be, so add them back.
+            for x in 0..width {
+                if !moves.iter().any(|&(_, index, _)| index == x) {
+                    let board = self.board.clone_toggle(x, 0);
+                    let score = board.score();
+                    moves.push((board, x, score));
+                }
+            }
+        }
+        self.wrap(moves)
+    }
+}
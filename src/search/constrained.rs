@@ -0,0 +1,106 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::core::{full_row, BitMask, Board, PressConstraints};
+
+use super::Search;
+
+/// A board to search with some presses pinned by [`PressConstraints`]: the
+/// required presses are made up front, and neither they nor the forbidden
+/// ones are offered as moves, so any solution found completes the required
+/// presses into a solution that honors the constraints.
+#[derive(Clone, Debug)]
+pub struct ConstrainedBoard {
+    board: Board,
+    /// Shared by every state of a search, so left out of hashing and
+    /// equality.
+    pressable: Arc<BitMask>,
+}
+
+impl ConstrainedBoard {
+    /// Panics if the constraints are a different size from the board.
+    pub fn new(board: &Board, constraints: &PressConstraints) -> ConstrainedBoard {
+        let mut board = board.clone();
+        board.apply_press_set(&constraints.required);
+        let mut pressable = BitMask::new(board.width(), board.height());
+        for ((row, required), forbidden) in pressable
+            .rows
+            .iter_mut()
+            .zip(&constraints.required.rows)
+            .zip(&constraints.forbidden.rows)
+        {
+            *row = !(required | forbidden) & full_row(board.width());
+        }
+        ConstrainedBoard {
+            board,
+            pressable: Arc::new(pressable),
+        }
+    }
+
+    /// The board as it stands, required presses included.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    fn allows(&self, index: usize) -> bool {
+        let width = self.board.width();
+        self.pressable.get(index % width, index / width)
+    }
+}
+
+impl std::hash::Hash for ConstrainedBoard {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+    }
+}
+
+impl PartialEq for ConstrainedBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+    }
+}
+
+impl Eq for ConstrainedBoard {}
+
+impl Display for ConstrainedBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.board.fmt(f)
+    }
+}
+
+impl Search for ConstrainedBoard {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        self.board.score()
+    }
+
+    fn end(&self) -> bool {
+        self.board.end()
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        self.scored_moves(&self.score())
+            .into_iter()
+            .map(|(child, index, _)| (child, index))
+            .collect()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.board.heap_bytes()
+    }
+
+    fn scored_moves(&self, score: &usize) -> Vec<(Self, usize, usize)> {
+        self.board
+            .scored_moves(score)
+            .into_iter()
+            .filter(|(_, index, _)| self.allows(*index))
+            .map(|(board, index, score)| {
+                let child = ConstrainedBoard {
+                    board,
+                    pressable: Arc::clone(&self.pressable),
+                };
+                (child, index, score)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::core::{Board, CancellationToken};
+
+use super::a_star_cancellable;
+
+/// A projection of the work [`a_star`](super::a_star) would do on a board,
+/// from [`estimate`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Estimate {
+    /// Distinct boards reachable by pressing, which bounds the states any
+    /// search can explore: `2^(cells - kernel dimension)`.
+    pub states: f64,
+    /// Presses in the shortest solution, or `None` if the board is
+    /// unsolvable, in which case a search has to exhaust every state.
+    pub presses: Option<usize>,
+    /// Projected states explored before reaching a solution.
+    pub nodes: f64,
+    /// Search speed measured while probing.
+    pub nodes_per_second: f64,
+    /// Whether the probe finished the search, making `nodes` a count
+    /// rather than a projection.
+    pub exact: bool,
+}
+
+impl Estimate {
+    /// Projected search time.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.nodes / self.nodes_per_second.max(1.0))
+    }
+}
+
+/// Estimate how long an exact search of `board` would take without running
+/// it to completion.
+///
+/// The kernel rank gives the size of the state space, and the linear solver
+/// gives the depth of the shortest solution. A search is then probed for at
+/// most `probe` to measure its speed; if it finishes in that time the count
+/// is exact. Otherwise the projection is the smaller of the state space and
+/// the number of press sets up to the solution depth, a worst case for
+/// boards the heuristic leads astray on.
+pub fn estimate(board: &Board, probe: Duration) -> Estimate {
+    let cells = (0..board.height())
+        .flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| !board.ruleset().is_obstacle(x, y))
+        .count();
+    let states = 2f64.powi((cells - board.kernel_dimension()) as i32);
+    let presses = board.optimal_length();
+
+    let cancel = CancellationToken::new();
+    let (done, finished) = mpsc::channel::<()>();
+    let start = Instant::now();
+    let report = thread::scope(|scope| {
+        let timer = cancel.clone();
+        scope.spawn(move || {
+            if finished.recv_timeout(probe).is_err() {
+                timer.cancel();
+            }
+        });
+        let max_depth = presses.unwrap_or(0);
+        let (_, report) = a_star_cancellable(board.clone(), max_depth, &cancel);
+        // Stop the timer early; it has already gone if it cancelled us.
+        let _ = done.send(());
+        report
+    });
+    let elapsed = start.elapsed().as_secs_f64().max(f64::MIN_POSITIVE);
+    let nodes_per_second = report.explored as f64 / elapsed;
+
+    let nodes = if !report.cancelled {
+        report.explored as f64
+    } else {
+        let within_depth = presses.map_or(states, |depth| {
+            (0..=depth)
+                .scan(1f64, |choose, k| {
+                    let current = *choose;
+                    *choose = current * (cells - k) as f64 / (k + 1) as f64;
+                    Some(current)
+                })
+                .sum()
+        });
+        within_depth.min(states).max(report.explored as f64)
+    };
+    Estimate {
+        states,
+        presses,
+        nodes,
+        nodes_per_second,
+        exact: !report.cancelled,
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+
+use crate::core::{BitMask, Board, CancellationToken, Cancelled, Ruleset};
+
+/// Distance stored for states that can't reach the goal.
+const UNREACHABLE: u8 = u8::MAX;
+
+/// The distance from every state of a small board to the solved state,
+/// found once by breadth-first search outwards from the goal. After that,
+/// any board of the same size is answered by table lookups alone.
+///
+/// States are indexed by their cells' differences from the goal in
+/// row-major order, one bit each, so a table takes `2^(width * height)`
+/// bytes: 32 MiB at the 25-cell limit. The goal pattern doesn't change the
+/// table, so one serves every goal under otherwise equal rules.
+pub struct StateTable {
+    width: usize,
+    height: usize,
+    ruleset: Ruleset,
+    presses: Vec<u32>,
+    distances: Vec<u8>,
+}
+
+impl StateTable {
+    /// The largest board, in cells, a table will be built for.
+    pub const MAX_CELLS: usize = 25;
+
+    /// Build the table for `width` x `height` boards, or `None` if the board
+    /// has more than [`StateTable::MAX_CELLS`] cells.
+    pub fn new(width: usize, height: usize) -> Option<StateTable> {
+        Self::with_ruleset(width, height, &Ruleset::CLASSIC)
+    }
+
+    /// [`StateTable::new`] for boards played by `ruleset`.
+    pub fn with_ruleset(width: usize, height: usize, ruleset: &Ruleset) -> Option<StateTable> {
+        Self::new_cancellable(width, height, ruleset, &CancellationToken::new())
+            .unwrap_or_else(|Cancelled| unreachable!("nothing else holds the token"))
+    }
+
+    /// [`StateTable::with_ruleset`], checking `cancel` as the search goes.
+    pub fn new_cancellable(
+        width: usize,
+        height: usize,
+        ruleset: &Ruleset,
+        cancel: &CancellationToken,
+    ) -> Result<Option<StateTable>, Cancelled> {
+        let cells = width * height;
+        if cells == 0 || cells > Self::MAX_CELLS {
+            return Ok(None);
+        }
+        let ruleset = ruleset.without_goal();
+        let empty = Board::new(width, height).with_ruleset(ruleset.clone());
+        let presses: Vec<u32> = (0..cells)
+            .map(|index| encode(&empty.clone_toggle(index % width, index / width)))
+            .collect();
+        let mut distances = vec![UNREACHABLE; 1 << cells];
+        distances[0] = 0;
+        let mut queue = VecDeque::from([0u32]);
+        while let Some(state) = queue.pop_front() {
+            cancel.check()?;
+            let next = distances[state as usize] + 1;
+            for &press in &presses {
+                let child = (state ^ press) as usize;
+                if distances[child] == UNREACHABLE {
+                    distances[child] = next;
+                    queue.push_back(child as u32);
+                }
+            }
+        }
+        Ok(Some(StateTable {
+            width,
+            height,
+            ruleset,
+            presses,
+            distances,
+        }))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether `board` has the size and rules this table was built for.
+    pub fn covers(&self, board: &Board) -> bool {
+        board.width() == self.width
+            && board.height() == self.height
+            && board.ruleset().without_goal() == self.ruleset
+    }
+
+    /// Number of states that can be solved.
+    pub fn reachable(&self) -> usize {
+        self.distances.iter().filter(|&&d| d != UNREACHABLE).count()
+    }
+
+    /// Length of the shortest solution, or `None` if the board is unsolvable.
+    ///
+    /// Panics if `board` is not the size the table was built for.
+    pub fn distance(&self, board: &Board) -> Option<usize> {
+        assert!(self.covers(board), "board does not match the table's size and rules");
+        self.distance_of(encode(board))
+    }
+
+    fn distance_of(&self, state: u32) -> Option<usize> {
+        let distance = self.distances[state as usize];
+        (distance != UNREACHABLE).then_some(distance as usize)
+    }
+
+    /// A shortest solution, in row-major press order, found by repeatedly
+    /// pressing the first cell that brings the board one step closer.
+    pub fn solve(&self, board: &Board) -> Option<Vec<(usize, usize)>> {
+        let mut distance = self.distance(board)?;
+        let mut state = encode(board);
+        let mut presses = Vec::with_capacity(distance);
+        while distance > 0 {
+            let index = (0..self.presses.len())
+                .find(|&index| self.distance_of(state ^ self.presses[index]) == Some(distance - 1))
+                .expect("a solvable state always has a closer neighbour");
+            state ^= self.presses[index];
+            presses.push((index % self.width, index / self.width));
+            distance -= 1;
+        }
+        Some(presses)
+    }
+
+    /// Every shortest solution of `board`. Each one is found exactly once by
+    /// only ever pressing cells in increasing order.
+    pub fn minimal_solutions(&self, board: &Board) -> Vec<BitMask> {
+        let Some(distance) = self.distance(board) else {
+            return Vec::new();
+        };
+        let mut solutions = Vec::new();
+        let mut pressed = Vec::with_capacity(distance);
+        self.collect_minimal(encode(board), distance, 0, &mut pressed, &mut solutions);
+        solutions
+    }
+
+    fn collect_minimal(
+        &self,
+        state: u32,
+        distance: usize,
+        from: usize,
+        pressed: &mut Vec<usize>,
+        solutions: &mut Vec<BitMask>,
+    ) {
+        if distance == 0 {
+            let mut solution = BitMask::new(self.width, self.height);
+            for &index in pressed.iter() {
+                solution.set(index % self.width, index / self.width, true);
+            }
+            solutions.push(solution);
+            return;
+        }
+        for index in from..self.presses.len() {
+            let child = state ^ self.presses[index];
+            if self.distance_of(child) == Some(distance - 1) {
+                pressed.push(index);
+                self.collect_minimal(child, distance - 1, index + 1, pressed, solutions);
+                pressed.pop();
+            }
+        }
+    }
+}
+
+/// A board's differences from its goal packed row-major into the low bits
+/// of a `u32`.
+fn encode(board: &Board) -> u32 {
+    let rules = board.ruleset();
+    board.rows.iter().enumerate().fold(0, |state, (y, &row)| {
+        state | ((row ^ rules.goal_row(y)) as u32) << (y * board.width())
+    })
+}
@@ -0,0 +1,166 @@
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::{Search, SearchState};
+
+/// The set of discovered but not yet expanded states, and the order they
+/// come back out in.
+///
+/// Scores count dark cells, so higher is closer to solved: the
+/// score-ordered frontiers always hand back the *highest* scoring state.
+pub trait Frontier<T: Search> {
+    fn push(&mut self, state: SearchState<T>);
+    fn pop(&mut self) -> Option<SearchState<T>>;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Up to `count` of the states `pop` would hand back next, in order,
+    /// for inspecting a search as it runs. Frontiers that can't say return
+    /// none.
+    fn top(&self, _count: usize) -> Vec<&SearchState<T>> {
+        Vec::new()
+    }
+}
+
+/// Best-first: pops the highest scoring state, ties in no particular order.
+pub struct PriorityFrontier<T: Search>(BinaryHeap<SearchState<T>>);
+
+impl<T: Search> Default for PriorityFrontier<T> {
+    fn default() -> Self {
+        PriorityFrontier(BinaryHeap::new())
+    }
+}
+
+impl<T: Search> Frontier<T> for PriorityFrontier<T> {
+    fn push(&mut self, state: SearchState<T>) {
+        self.0.push(state);
+    }
+
+    fn pop(&mut self) -> Option<SearchState<T>> {
+        self.0.pop()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Ties come back in no particular order here either, so they may pop
+    /// in a different one.
+    fn top(&self, count: usize) -> Vec<&SearchState<T>> {
+        let mut states: Vec<&SearchState<T>> = self.0.iter().collect();
+        states.sort_by(|a, b| b.cmp(a));
+        states.truncate(count);
+        states
+    }
+}
+
+/// Breadth-first: pops states in the order they were pushed.
+pub struct FifoFrontier<T: Search>(VecDeque<SearchState<T>>);
+
+impl<T: Search> Default for FifoFrontier<T> {
+    fn default() -> Self {
+        FifoFrontier(VecDeque::new())
+    }
+}
+
+impl<T: Search> Frontier<T> for FifoFrontier<T> {
+    fn push(&mut self, state: SearchState<T>) {
+        self.0.push_back(state);
+    }
+
+    fn pop(&mut self) -> Option<SearchState<T>> {
+        self.0.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn top(&self, count: usize) -> Vec<&SearchState<T>> {
+        self.0.iter().take(count).collect()
+    }
+}
+
+/// Depth-first: pops the most recently pushed state.
+pub struct LifoFrontier<T: Search>(Vec<SearchState<T>>);
+
+impl<T: Search> Default for LifoFrontier<T> {
+    fn default() -> Self {
+        LifoFrontier(Vec::new())
+    }
+}
+
+impl<T: Search> Frontier<T> for LifoFrontier<T> {
+    fn push(&mut self, state: SearchState<T>) {
+        self.0.push(state);
+    }
+
+    fn pop(&mut self) -> Option<SearchState<T>> {
+        self.0.pop()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn top(&self, count: usize) -> Vec<&SearchState<T>> {
+        self.0.iter().rev().take(count).collect()
+    }
+}
+
+/// Best-first over small integer scores: one bucket per score, so pushes are
+/// O(1) and pops only ever scan down past emptied buckets. Ties pop most
+/// recently pushed first.
+pub struct BucketFrontier<T: Search<Score = usize>> {
+    buckets: Vec<Vec<SearchState<T>>>,
+    top: usize,
+    len: usize,
+}
+
+impl<T: Search<Score = usize>> Default for BucketFrontier<T> {
+    fn default() -> Self {
+        BucketFrontier {
+            buckets: Vec::new(),
+            top: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<T: Search<Score = usize>> Frontier<T> for BucketFrontier<T> {
+    fn push(&mut self, state: SearchState<T>) {
+        let score = state.score;
+        if score >= self.buckets.len() {
+            self.buckets.resize_with(score + 1, Vec::new);
+        }
+        self.buckets[score].push(state);
+        self.top = self.top.max(score);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<SearchState<T>> {
+        if self.len == 0 {
+            return None;
+        }
+        while self.buckets[self.top].is_empty() {
+            self.top -= 1;
+        }
+        self.len -= 1;
+        self.buckets[self.top].pop()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn top(&self, count: usize) -> Vec<&SearchState<T>> {
+        self.buckets
+            .iter()
+            .rev()
+            .flat_map(|bucket| bucket.iter().rev())
+            .take(count)
+            .collect()
+    }
+}
@@ -0,0 +1,101 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::core::{Board, Goals, Rowtype};
+
+use super::Search;
+
+/// A board to search that's solved once it shows any of several
+/// [`Goals`]' patterns. It scores by the pattern it's nearest, and offers
+/// every pressable cell as a move, since the board's own pruning only holds
+/// for its own goal.
+#[derive(Clone, Debug)]
+pub struct MultiGoalBoard {
+    board: Board,
+    /// Each pattern's rows, obstacles left out. Shared by every state of a
+    /// search, so left out of hashing and equality.
+    patterns: Arc<Vec<Vec<Rowtype>>>,
+}
+
+impl MultiGoalBoard {
+    /// Panics if a pattern is a different size from the board.
+    pub fn new(board: &Board, goals: &Goals) -> MultiGoalBoard {
+        let patterns = (0..goals.patterns.len())
+            .map(|index| {
+                let retargeted = goals.retarget(board, index);
+                let rules = retargeted.ruleset();
+                (0..board.height()).map(|y| rules.goal_row(y)).collect()
+            })
+            .collect();
+        MultiGoalBoard {
+            board: board.clone(),
+            patterns: Arc::new(patterns),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Cells differing from the nearest pattern.
+    fn mismatches(&self) -> usize {
+        self.patterns
+            .iter()
+            .map(|pattern| {
+                let rows = self.board.rows.iter().zip(pattern);
+                rows.map(|(row, goal)| (row ^ goal).count_ones() as usize).sum()
+            })
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+impl std::hash::Hash for MultiGoalBoard {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+    }
+}
+
+impl PartialEq for MultiGoalBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+    }
+}
+
+impl Eq for MultiGoalBoard {}
+
+impl Display for MultiGoalBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.board.fmt(f)
+    }
+}
+
+impl Search for MultiGoalBoard {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        self.board.width() * self.board.height() - self.mismatches()
+    }
+
+    fn end(&self) -> bool {
+        self.patterns.contains(&self.board.rows)
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        let (width, height) = (self.board.width(), self.board.height());
+        let rules = self.board.ruleset();
+        (0..width * height)
+            .filter(|&index| !rules.is_obstacle(index % width, index / width))
+            .map(|index| {
+                let child = MultiGoalBoard {
+                    board: self.board.clone_toggle(index % width, index / width),
+                    patterns: Arc::clone(&self.patterns),
+                };
+                (child, index)
+            })
+            .collect()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.board.heap_bytes()
+    }
+}
@@ -0,0 +1,601 @@
+mod bloom;
+mod book;
+mod book_data;
+mod chasing;
+mod constrained;
+mod estimate;
+mod exhaustive;
+mod frontier;
+mod goals;
+mod move_order;
+mod ordering;
+mod profile;
+mod step;
+mod task;
+
+use std::fmt::Display;
+
+use crate::core::{oracle, Board, CancellationToken};
+
+pub use bloom::BloomFilter;
+pub use book::OpeningBook;
+pub use chasing::ChasingBoard;
+pub use constrained::ConstrainedBoard;
+pub use estimate::{estimate, Estimate};
+pub use exhaustive::StateTable;
+pub use frontier::{BucketFrontier, FifoFrontier, Frontier, LifoFrontier, PriorityFrontier};
+pub use goals::MultiGoalBoard;
+pub use move_order::{MoveOrder, OrderedBoard};
+pub use ordering::OrderingBoard;
+pub use profile::Profile;
+pub use step::{Step, Stepper};
+pub use task::{solve_cancellable, SolveFuture};
+
+pub trait Search: Clone + std::hash::Hash + Eq + PartialEq + Display {
+    type Score: Ord + Clone + Display;
+
+    fn score(&self) -> Self::Score;
+    fn end(&self) -> bool;
+    fn moves(&self) -> Vec<(Self, usize)>;
+
+    /// Heap memory owned by the state, for memory accounting in searches.
+    fn heap_bytes(&self) -> usize {
+        0
+    }
+
+    /// `moves`, with each child's score alongside, given this state's own
+    /// score. Override it when children can be scored incrementally.
+    fn scored_moves(&self, _score: &Self::Score) -> Vec<(Self, usize, Self::Score)> {
+        self.moves()
+            .into_iter()
+            .map(|(child, index)| {
+                let score = child.score();
+                (child, index, score)
+            })
+            .collect()
+    }
+}
+
+impl Search for Board {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        self.width * self.height - self.mismatches()
+    }
+
+    fn end(&self) -> bool {
+        self.is_solved()
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        self.scored_moves(&self.score())
+            .into_iter()
+            .map(|(child, index, _)| (child, index))
+            .collect()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.rows.capacity() * std::mem::size_of::<crate::Rowtype>()
+    }
+
+    fn scored_moves(&self, score: &usize) -> Vec<(Self, usize, usize)> {
+        let mut moves = self.unordered_moves(score);
+        self.order_moves(&mut moves);
+        moves
+    }
+}
+
+/// Kernel dimension beyond which [`Board::depth_bound`] doesn't try every
+/// solution for the shortest.
+const DEPTH_BOUND_MAX_KERNEL: usize = Board::MAX_SEARCHED_KERNEL;
+
+impl Board {
+    /// How deep a search for a shortest solution needs to go, or `None` if
+    /// the board is unsolvable: the fewest presses that solve it, per
+    /// [`Board::optimal_length`]. Usually far below the cell count, so states
+    /// deeper than any shortest solution are cut off instead of expanded.
+    /// Boards with too many solutions to compare take the first one found
+    /// by elimination instead, which still bounds the shortest.
+    pub fn depth_bound(&self) -> Option<usize> {
+        if self.kernel_dimension() > DEPTH_BOUND_MAX_KERNEL {
+            self.solve_chase().map(|presses| presses.count())
+        } else {
+            self.optimal_length()
+        }
+    }
+
+    /// Every child worth searching with its move index and score.
+    fn unordered_moves(&self, score: &usize) -> Vec<(Board, usize, usize)> {
+        let cells = self.width * self.height;
+        if self.rules.is_some() {
+            // Lit-count deltas only work for the classic rules; score every
+            // pressable cell's child directly instead.
+            let rules = self.ruleset();
+            return (0..cells)
+                .filter(|&index| !rules.is_obstacle(index % self.width, index / self.width))
+                .map(|index| {
+                    let child = self.clone_toggle(index % self.width, index / self.width);
+                    let score = child.score();
+                    (child, index, score)
+                })
+                .collect();
+        }
+        let init_lit = cells - score;
+        let child_lit = &self.child_lit_counts_from(init_lit);
+        (0..self.width)
+            .flat_map(|x| {
+                (0..self.height)
+                    .filter(move |&y| {
+                        let mut target_score = 3;
+                        if x > 0 && x < self.width - 1 {
+                            target_score += 1;
+                        }
+                        if y > 0 && y < self.height - 1 {
+                            target_score += 1;
+                        }
+                        child_lit[y * self.width + x] != init_lit + target_score
+                    })
+                    .map(move |y| (x, y))
+            })
+            .map(|(x, y)| {
+                (
+                    self.clone_toggle(x, y),
+                    y * self.width + x,
+                    cells - child_lit[y * self.width + x],
+                )
+            })
+            .collect()
+    }
+
+    /// Put the moves that work on the boundary of the cleared region last,
+    /// since equally scored states pop most recently pushed first. Presses
+    /// on the topmost row still off its goal come first, those on its wrong
+    /// cells before the rest, then the rows below it nearest first, and
+    /// presses above it, which undo rows already right, come last. It only
+    /// changes which of equally scored states is tried first, so nothing the
+    /// search could find is lost. On random 4x5 boards it explores about a
+    /// tenth fewer states than taking presses column by column; on bigger
+    /// boards the lit count settles most of the order and it matters less.
+    fn order_moves(&self, moves: &mut [(Board, usize, usize)]) {
+        let rules = self.ruleset();
+        let Some((top, wrong)) = (0..self.height)
+            .map(|y| (y, self.rows[y] ^ rules.goal_row(y)))
+            .find(|&(_, wrong)| wrong != 0)
+        else {
+            return;
+        };
+        moves.sort_by_key(|&(_, index, _)| {
+            let (x, y) = (index % self.width, index / self.width);
+            let distance = if y >= top { y - top } else { self.height + top - y };
+            (std::cmp::Reverse(distance), y == top && wrong & (1 << x) != 0)
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchState<T: Search> {
+    pub history: Vec<usize>,
+    pub latest: T,
+    pub latest_move_index: Option<usize>,
+    pub score: T::Score,
+}
+
+impl<T: Search> SearchState<T> {
+    /// Bytes this state occupies, counting its history and board storage.
+    pub fn memory(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.history.capacity() * std::mem::size_of::<usize>()
+            + self.latest.heap_bytes()
+    }
+
+    /// The state reached by making the move with index `index`, if it's one
+    /// of this state's moves.
+    pub fn follow(&self, index: usize) -> Option<Self> {
+        let (latest, move_index, score) = self
+            .latest
+            .scored_moves(&self.score)
+            .into_iter()
+            .find(|(_, move_index, _)| *move_index == index)?;
+        let mut history = self.history.clone();
+        history.extend(self.latest_move_index);
+        Some(SearchState {
+            history,
+            latest,
+            latest_move_index: Some(move_index),
+            score,
+        })
+    }
+
+    fn moves(&self) -> Vec<Self> {
+        let mut new_history = self.history.clone();
+        if let Some(index) = self.latest_move_index {
+            new_history.push(index);
+        }
+        let moves = self.latest.scored_moves(&self.score);
+        if oracle::sampled() {
+            self.check_scores(&moves);
+        }
+        moves
+            .into_iter()
+            .map(|(new_move, move_index, score)| SearchState {
+                history: new_history.clone(),
+                latest: new_move,
+                latest_move_index: Some(move_index),
+                score,
+            })
+            .collect()
+    }
+
+    /// Every child's incremental score is the one it scores afresh, as the
+    /// [`oracle`] checks in debug builds.
+    fn check_scores(&self, moves: &[(T, usize, T::Score)]) {
+        for (child, index, score) in moves {
+            let fresh = child.score();
+            assert!(
+                *score == fresh,
+                "oracle: move {index} from\n{}\nscores {score} incrementally but {fresh} afresh",
+                self.latest
+            );
+        }
+    }
+}
+
+impl<T: Search> From<T> for SearchState<T> {
+    fn from(value: T) -> Self {
+        let score = value.score();
+        SearchState {
+            history: Vec::new(),
+            latest: value,
+            latest_move_index: None,
+            score,
+        }
+    }
+}
+
+impl<T: Search> PartialEq for SearchState<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+
+impl<T: Search> PartialOrd for SearchState<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Search> Ord for SearchState<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
+impl<T: Search> Eq for SearchState<T> {}
+
+/// Hooks into a running search, for progress bars, tracing, streaming and
+/// visualisations that would otherwise need calls of their own in the
+/// search loop. Every hook does nothing unless overridden, and `()` is the
+/// observer that watches nothing.
+pub trait Observer<T: Search> {
+    /// `state` is about to be expanded. `report` already counts it.
+    fn on_expand(&mut self, _state: &SearchState<T>, _report: &SearchReport) {}
+
+    /// `state`, about to be expanded, scores higher than any expanded
+    /// before it. Called just before [`Observer::on_expand`].
+    fn on_improvement(&mut self, _state: &SearchState<T>, _report: &SearchReport) {}
+
+    /// `state` has been pushed onto the frontier, as one of the initial
+    /// states or a child of one expanded.
+    fn on_push(&mut self, _state: &SearchState<T>) {}
+}
+
+impl<T: Search> Observer<T> for () {}
+
+/// Statistics about a finished search. Memory figures are an accounting of
+/// the frontier and explored set contents, not of allocator overhead.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchReport {
+    pub explored: usize,
+    pub peak_frontier: usize,
+    pub peak_memory: usize,
+    /// States left unexpanded because they were already at the depth limit.
+    pub depth_cutoffs: usize,
+    /// Children left off the frontier because their state had already been
+    /// expanded.
+    pub duplicates: usize,
+    /// Children a [`BloomFilter`] in front of the explored set showed were
+    /// new, without looking them up. Never a state already explored.
+    pub bloom_skips: usize,
+    /// Children the filter might have seen but the explored set hadn't, so
+    /// looked up for nothing.
+    pub bloom_false_positives: usize,
+    /// Whether the search stopped early because it was cancelled.
+    pub cancelled: bool,
+}
+
+impl SearchReport {
+    fn track(&mut self, frontier_len: usize, memory: usize) {
+        self.peak_frontier = self.peak_frontier.max(frontier_len);
+        self.peak_memory = self.peak_memory.max(memory);
+    }
+}
+
+/// Best-first search over integer scores, using a bucket queue for O(1)
+/// frontier operations. Use [`search`] with a [`PriorityFrontier`] for other
+/// score types.
+pub fn a_star<T: Search<Score = usize>>(
+    init_state: T,
+    max_depth: usize,
+) -> (Option<SearchState<T>>, SearchReport) {
+    search(init_state, max_depth, BucketFrontier::default())
+}
+
+/// [`a_star`], stopping with no result once `cancel` is cancelled.
+pub fn a_star_cancellable<T: Search<Score = usize>>(
+    init_state: T,
+    max_depth: usize,
+    cancel: &CancellationToken,
+) -> (Option<SearchState<T>>, SearchReport) {
+    search_cancellable(init_state, max_depth, BucketFrontier::default(), cancel)
+}
+
+/// States expanded between calls to a progress callback, see
+/// [`a_star_progress`].
+pub const PROGRESS_INTERVAL: usize = 10_000;
+
+/// [`a_star_cancellable`], calling `on_progress` every [`PROGRESS_INTERVAL`]
+/// expanded states with the work done so far and the highest score of any
+/// state expanded yet, for frontends that show a long search moving.
+pub fn a_star_progress<T: Search<Score = usize>>(
+    init_state: T,
+    max_depth: usize,
+    cancel: &CancellationToken,
+    on_progress: impl FnMut(&SearchReport, usize),
+) -> (Option<SearchState<T>>, SearchReport) {
+    a_star_opening(init_state, &[], max_depth, cancel, on_progress)
+}
+
+/// [`a_star_progress`], trying the moves in `opening` from the initial state
+/// before any others scoring the same, in the order given, such as the
+/// press an [`OpeningBook`] suggests.
+pub fn a_star_opening<T: Search<Score = usize>>(
+    init_state: T,
+    opening: &[usize],
+    max_depth: usize,
+    cancel: &CancellationToken,
+    on_progress: impl FnMut(&SearchReport, usize),
+) -> (Option<SearchState<T>>, SearchReport) {
+    a_star_opening_observed(init_state, opening, max_depth, cancel, on_progress, &mut ())
+}
+
+/// [`a_star_opening`], also calling `observer`'s hooks, such as a
+/// [`Profile`]'s.
+pub fn a_star_opening_observed<T: Search<Score = usize>>(
+    init_state: T,
+    opening: &[usize],
+    max_depth: usize,
+    cancel: &CancellationToken,
+    on_progress: impl FnMut(&SearchReport, usize),
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let filter = None;
+    a_star_opening_filtered(init_state, opening, max_depth, filter, cancel, on_progress, observer)
+}
+
+/// [`a_star_opening_observed`], with a [`BloomFilter`] sized for `filter`
+/// states, if given, checked before the explored set. On searches too big
+/// for the explored set to stay in cache, most children are new and the
+/// filter says so without a hash-set lookup.
+pub fn a_star_opening_filtered<T: Search<Score = usize>>(
+    init_state: T,
+    opening: &[usize],
+    max_depth: usize,
+    filter: Option<usize>,
+    cancel: &CancellationToken,
+    on_progress: impl FnMut(&SearchReport, usize),
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let root: SearchState<T> = init_state.into();
+    let seeds = if opening.is_empty() || root.latest.end() || max_depth == 0 {
+        vec![root]
+    } else {
+        // Seed with the root's children instead of the root itself. Ties
+        // pop most recently pushed first, so the opening goes in last.
+        let mut children = root.moves();
+        children.sort_by_key(|child| {
+            let index = child.latest_move_index;
+            let position = opening.iter().position(|&move_index| Some(move_index) == index);
+            position.map_or(0, |position| opening.len() - position)
+        });
+        children
+    };
+    a_star_seeded(seeds, max_depth, filter, cancel, on_progress, observer)
+}
+
+/// [`a_star_warm`], with progress, observer hooks and an optional
+/// [`BloomFilter`] as in [`a_star_opening_filtered`].
+pub fn a_star_warm_filtered<T: Search<Score = usize>>(
+    init_state: T,
+    candidate: &[usize],
+    max_depth: usize,
+    filter: Option<usize>,
+    cancel: &CancellationToken,
+    on_progress: impl FnMut(&SearchReport, usize),
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let seeds = warm_seeds(init_state, candidate, max_depth);
+    a_star_seeded(seeds, max_depth, filter, cancel, on_progress, observer)
+}
+
+fn a_star_seeded<T: Search<Score = usize>>(
+    seeds: Vec<SearchState<T>>,
+    max_depth: usize,
+    filter: Option<usize>,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(&SearchReport, usize),
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let mut progress = Progress {
+        on_progress: &mut on_progress,
+        best: 0,
+        reported: 0,
+        inner: observer,
+    };
+    let mut stepper = Stepper::seeded(seeds, max_depth, BucketFrontier::default(), &mut progress);
+    if let Some(expected) = filter {
+        stepper = stepper.with_bloom_filter(expected);
+    }
+    run(stepper, cancel, &mut progress)
+}
+
+/// The observer behind [`a_star_progress`], passing every hook on to
+/// `inner` as well.
+struct Progress<'a, T: Search, F> {
+    on_progress: F,
+    best: usize,
+    reported: usize,
+    inner: &'a mut dyn Observer<T>,
+}
+
+impl<T: Search<Score = usize>, F: FnMut(&SearchReport, usize)> Observer<T> for Progress<'_, T, F> {
+    fn on_expand(&mut self, state: &SearchState<T>, report: &SearchReport) {
+        self.inner.on_expand(state, report);
+        // A state reached twice is expanded twice without growing the count.
+        if report.explored.is_multiple_of(PROGRESS_INTERVAL) && report.explored != self.reported {
+            self.reported = report.explored;
+            (self.on_progress)(report, self.best);
+        }
+    }
+
+    fn on_improvement(&mut self, state: &SearchState<T>, report: &SearchReport) {
+        self.inner.on_improvement(state, report);
+        self.best = state.score;
+    }
+
+    fn on_push(&mut self, state: &SearchState<T>) {
+        self.inner.on_push(state);
+    }
+}
+
+/// Expand states in the order `fringe` hands them back until one is solved,
+/// returning it with a report on the work done.
+pub fn search<T: Search, F: Frontier<T>>(
+    init_state: T,
+    max_depth: usize,
+    fringe: F,
+) -> (Option<SearchState<T>>, SearchReport) {
+    search_cancellable(init_state, max_depth, fringe, &CancellationToken::new())
+}
+
+/// [`search`], checking `cancel` before expanding each state. A cancelled
+/// search returns no result and sets [`SearchReport::cancelled`].
+pub fn search_cancellable<T: Search, F: Frontier<T>>(
+    init_state: T,
+    max_depth: usize,
+    fringe: F,
+    cancel: &CancellationToken,
+) -> (Option<SearchState<T>>, SearchReport) {
+    search_observed(init_state, max_depth, fringe, cancel, &mut ())
+}
+
+/// [`search_cancellable`], calling `observer`'s hooks as the search goes.
+pub fn search_observed<T: Search, F: Frontier<T>>(
+    init_state: T,
+    max_depth: usize,
+    fringe: F,
+    cancel: &CancellationToken,
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    search_seeded(vec![init_state.into()], max_depth, fringe, cancel, observer)
+}
+
+/// [`a_star`] warm-started from a candidate sequence of move indices, such
+/// as a solution from a faster solver or from a similar board.
+pub fn a_star_warm<T: Search<Score = usize>>(
+    init_state: T,
+    candidate: &[usize],
+    max_depth: usize,
+) -> (Option<SearchState<T>>, SearchReport) {
+    search_warm(
+        init_state,
+        candidate,
+        max_depth,
+        BucketFrontier::default(),
+        &CancellationToken::new(),
+    )
+}
+
+/// [`search_cancellable`], with the frontier seeded by every state along
+/// `candidate` as well as the initial one, so the search can pick up from
+/// whichever looks most promising.
+///
+/// Candidate moves are taken in order where the state offers them. When it
+/// doesn't, the first later candidate move it does offer goes next, which
+/// suits puzzles like this one where moves commute. The path stops when no
+/// remaining move is offered or it reaches the depth limit.
+pub fn search_warm<T: Search, F: Frontier<T>>(
+    init_state: T,
+    candidate: &[usize],
+    max_depth: usize,
+    fringe: F,
+    cancel: &CancellationToken,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let seeds = warm_seeds(init_state, candidate, max_depth);
+    search_seeded(seeds, max_depth, fringe, cancel, &mut ())
+}
+
+/// The states along `candidate` from `init_state`, as [`search_warm`]
+/// follows it.
+fn warm_seeds<T: Search>(
+    init_state: T,
+    candidate: &[usize],
+    max_depth: usize,
+) -> Vec<SearchState<T>> {
+    let mut seeds: Vec<SearchState<T>> = vec![init_state.into()];
+    let mut remaining = candidate.to_vec();
+    while seeds.len() <= max_depth {
+        let last = seeds.last().unwrap();
+        let Some((position, next)) = remaining
+            .iter()
+            .enumerate()
+            .find_map(|(position, &index)| last.follow(index).map(|next| (position, next)))
+        else {
+            break;
+        };
+        remaining.remove(position);
+        seeds.push(next);
+    }
+    seeds
+}
+
+fn search_seeded<T: Search, F: Frontier<T>>(
+    seeds: Vec<SearchState<T>>,
+    max_depth: usize,
+    fringe: F,
+    cancel: &CancellationToken,
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let stepper = Stepper::seeded(seeds, max_depth, fringe, observer);
+    run(stepper, cancel, observer)
+}
+
+/// Step `stepper` until it finds a solution, runs out of states or is
+/// cancelled.
+fn run<T: Search, F: Frontier<T>>(
+    mut stepper: Stepper<T, F>,
+    cancel: &CancellationToken,
+    observer: &mut dyn Observer<T>,
+) -> (Option<SearchState<T>>, SearchReport) {
+    let result = loop {
+        if cancel.is_cancelled() {
+            stepper.cancel();
+            break None;
+        }
+        match stepper.step_observed(observer) {
+            Step::Solved(state) => break Some(state),
+            Step::Exhausted => break None,
+            Step::Expanded { .. } | Step::CutOff(_) => (),
+        }
+    };
+    (result, stepper.into_report())
+}
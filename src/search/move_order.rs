@@ -0,0 +1,178 @@
+use std::{
+    fmt::Display,
+    hash::{DefaultHasher, Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::core::Board;
+
+use super::Search;
+
+/// The order a board's children are pushed onto the frontier in. Equally
+/// scored states pop most recently pushed first, so under A* this decides
+/// which of the tied states is tried first, and under a [`LifoFrontier`]
+/// which child is tried first at all. It never changes what a search can
+/// find, only how much it explores on the way.
+///
+/// [`LifoFrontier`]: super::LifoFrontier
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum MoveOrder {
+    /// By move index, so the last cell pops first.
+    Lexical,
+    /// Fewest cells left off the goal last, so the press bringing the most
+    /// into line pops first, by move index among those.
+    LitReduction,
+    /// The order [`Board`] searches in itself: presses working on the
+    /// boundary of the cleared region last.
+    #[default]
+    Chase,
+    /// Shuffled, the same way each time a state is reached with the seed.
+    Random(u64),
+}
+
+impl MoveOrder {
+    /// Put `moves`, the children of `board` with their move indices and
+    /// scores, in this order.
+    pub fn sort(self, board: &Board, moves: &mut [(Board, usize, usize)]) {
+        match self {
+            MoveOrder::Lexical => moves.sort_by_key(|&(_, index, _)| index),
+            MoveOrder::LitReduction => moves
+                .sort_by_key(|(child, index, _)| (std::cmp::Reverse(child.mismatches()), *index)),
+            MoveOrder::Chase => board.order_moves(moves),
+            MoveOrder::Random(seed) => {
+                let mut hasher = DefaultHasher::new();
+                (seed, board).hash(&mut hasher);
+                let state = hasher.finish();
+                moves.sort_by_key(|&(_, index, _)| splitmix(state ^ index as u64));
+            }
+        }
+    }
+}
+
+/// One round of splitmix64, enough to scatter move indices.
+fn splitmix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl FromStr for MoveOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || {
+            format!(
+                "unknown move order {s:?} (expected lexical, lit-reduction, chase or random(SEED))"
+            )
+        };
+        match s {
+            "lexical" => Ok(MoveOrder::Lexical),
+            "lit-reduction" => Ok(MoveOrder::LitReduction),
+            "chase" => Ok(MoveOrder::Chase),
+            _ => {
+                let seed = s
+                    .strip_prefix("random(")
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .ok_or_else(unknown)?;
+                let seed = seed
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid seed {seed:?} in move order {s:?}"))?;
+                Ok(MoveOrder::Random(seed))
+            }
+        }
+    }
+}
+
+impl Display for MoveOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveOrder::Lexical => f.write_str("lexical"),
+            MoveOrder::LitReduction => f.write_str("lit-reduction"),
+            MoveOrder::Chase => f.write_str("chase"),
+            MoveOrder::Random(seed) => write!(f, "random({seed})"),
+        }
+    }
+}
+
+/// A board searched with its children in a [`MoveOrder`] other than its
+/// own. The order is the same for every state of a search, so it's left out
+/// of hashing and equality.
+#[derive(Clone, Debug)]
+pub struct OrderedBoard {
+    board: Board,
+    order: MoveOrder,
+}
+
+impl OrderedBoard {
+    pub fn new(board: Board, order: MoveOrder) -> OrderedBoard {
+        OrderedBoard { board, order }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+}
+
+impl Hash for OrderedBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+    }
+}
+
+impl PartialEq for OrderedBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+    }
+}
+
+impl Eq for OrderedBoard {}
+
+impl Display for OrderedBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.board.fmt(f)
+    }
+}
+
+impl Search for OrderedBoard {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        self.board.score()
+    }
+
+    fn end(&self) -> bool {
+        self.board.end()
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        self.scored_moves(&self.score())
+            .into_iter()
+            .map(|(child, index, _)| (child, index))
+            .collect()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.board.heap_bytes()
+    }
+
+    fn scored_moves(&self, score: &usize) -> Vec<(Self, usize, usize)> {
+        let mut moves = self.board.unordered_moves(score);
+        self.order.sort(&self.board, &mut moves);
+        moves
+            .into_iter()
+            .map(|(board, index, score)| {
+                let child = OrderedBoard {
+                    board,
+                    order: self.order,
+                };
+                (child, index, score)
+            })
+            .collect()
+    }
+}
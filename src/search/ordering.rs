@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+use crate::core::{BitMask, Board};
+
+use super::Search;
+
+/// A board to search for the order to make a known set of presses in, such
+/// as a solution from [`Board::solve_linear`]. Each move makes one of the
+/// presses still to go, so every path to the end is the whole set in some
+/// order and the search only picks which: lit counts steer it toward orders
+/// that turn lights off as early as they can.
+///
+/// Children come worst score first, so a [`LifoFrontier`] makes the best
+/// press at every step and reaches the end without backtracking, for sets
+/// too big to search through properly.
+///
+/// [`LifoFrontier`]: super::LifoFrontier
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct OrderingBoard {
+    board: Board,
+    remaining: BitMask,
+}
+
+impl OrderingBoard {
+    /// Panics if `presses` is a different size from the board.
+    pub fn new(board: Board, presses: BitMask) -> OrderingBoard {
+        assert!(
+            presses.width() == board.width() && presses.height() == board.height(),
+            "presses do not match the board's size"
+        );
+        OrderingBoard {
+            board,
+            remaining: presses,
+        }
+    }
+
+    /// The board with the presses made so far.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The presses still to make.
+    pub fn remaining(&self) -> &BitMask {
+        &self.remaining
+    }
+}
+
+impl Display for OrderingBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.board.fmt(f)
+    }
+}
+
+impl Search for OrderingBoard {
+    type Score = usize;
+
+    fn score(&self) -> usize {
+        self.board.score()
+    }
+
+    fn end(&self) -> bool {
+        self.remaining.count() == 0
+    }
+
+    fn moves(&self) -> Vec<(Self, usize)> {
+        self.scored_moves(&self.score())
+            .into_iter()
+            .map(|(child, index, _)| (child, index))
+            .collect()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        let remaining = self.remaining.rows.capacity() * std::mem::size_of::<crate::Rowtype>();
+        self.board.heap_bytes() + remaining
+    }
+
+    fn scored_moves(&self, _score: &usize) -> Vec<(Self, usize, usize)> {
+        let width = self.board.width();
+        let mut moves: Vec<_> = self
+            .remaining
+            .positions()
+            .into_iter()
+            .map(|(x, y)| {
+                let board = self.board.clone_toggle(x, y);
+                let score = board.score();
+                let mut remaining = self.remaining.clone();
+                remaining.set(x, y, false);
+                (OrderingBoard { board, remaining }, y * width + x, score)
+            })
+            .collect();
+        moves.sort_by_key(|&(_, _, score)| std::cmp::Reverse(score));
+        moves
+    }
+}
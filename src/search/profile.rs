@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use super::{Observer, Search, SearchReport, SearchState};
+
+/// Where a search spent its work, for comparing runs in more detail than
+/// their times: how many states were expanded at each depth and with each
+/// score, and how many were pruned. Collected as an [`Observer`], finished
+/// with the search's report, and written as a few lines of text that
+/// [`FromStr`] reads back.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// States expanded at each depth, the initial state's being 0.
+    pub expanded: Vec<usize>,
+    /// States expanded with each score: a histogram of the heuristic values
+    /// the search worked through.
+    pub scores: BTreeMap<usize, usize>,
+    /// States pushed onto the frontier, including the initial ones.
+    pub pushed: usize,
+    pub report: SearchReport,
+}
+
+impl Profile {
+    /// Take the totals from the finished search's `report`.
+    pub fn finish(&mut self, report: &SearchReport) {
+        self.report = report.clone();
+    }
+}
+
+impl<T: Search<Score = usize>> Observer<T> for Profile {
+    fn on_expand(&mut self, state: &SearchState<T>, _report: &SearchReport) {
+        let depth = state.history.len() + usize::from(state.latest_move_index.is_some());
+        if self.expanded.len() <= depth {
+            self.expanded.resize(depth + 1, 0);
+        }
+        self.expanded[depth] += 1;
+        *self.scores.entry(state.score).or_default() += 1;
+    }
+
+    fn on_push(&mut self, _state: &SearchState<T>) {
+        self.pushed += 1;
+    }
+}
+
+/// One `key value` line per total, then `depth d count` and `score s count`
+/// lines for the histograms.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = &self.report;
+        writeln!(f, "# lightsout search profile")?;
+        writeln!(f, "explored {}", report.explored)?;
+        writeln!(f, "pushed {}", self.pushed)?;
+        writeln!(f, "duplicates {}", report.duplicates)?;
+        writeln!(f, "depth_cutoffs {}", report.depth_cutoffs)?;
+        writeln!(f, "bloom_skips {}", report.bloom_skips)?;
+        writeln!(f, "bloom_false_positives {}", report.bloom_false_positives)?;
+        writeln!(f, "peak_frontier {}", report.peak_frontier)?;
+        writeln!(f, "peak_memory {}", report.peak_memory)?;
+        writeln!(f, "cancelled {}", report.cancelled)?;
+        for (depth, count) in self.expanded.iter().enumerate() {
+            writeln!(f, "depth {depth} {count}")?;
+        }
+        for (score, count) in &self.scores {
+            writeln!(f, "score {score} {count}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut profile = Profile::default();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fail = || format!("line {}: expected a profile entry, got {line:?}", number + 1);
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let number = |word: &str| word.parse::<usize>().map_err(|_| fail());
+            let report = &mut profile.report;
+            match words[..] {
+                ["explored", value] => report.explored = number(value)?,
+                ["pushed", value] => profile.pushed = number(value)?,
+                ["duplicates", value] => report.duplicates = number(value)?,
+                ["depth_cutoffs", value] => report.depth_cutoffs = number(value)?,
+                ["bloom_skips", value] => report.bloom_skips = number(value)?,
+                ["bloom_false_positives", value] => {
+                    report.bloom_false_positives = number(value)?
+                }
+                ["peak_frontier", value] => report.peak_frontier = number(value)?,
+                ["peak_memory", value] => report.peak_memory = number(value)?,
+                ["cancelled", value] => report.cancelled = value.parse().map_err(|_| fail())?,
+                ["depth", depth, count] => {
+                    let depth = number(depth)?;
+                    if profile.expanded.len() <= depth {
+                        profile.expanded.resize(depth + 1, 0);
+                    }
+                    profile.expanded[depth] = number(count)?;
+                }
+                ["score", score, count] => {
+                    profile.scores.insert(number(score)?, number(count)?);
+                }
+                _ => return Err(fail()),
+            }
+        }
+        Ok(profile)
+    }
+}
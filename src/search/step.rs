@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use super::{BloomFilter, Frontier, Observer, Search, SearchReport, SearchState};
+
+/// What one [`Stepper::step`] did with the state it popped.
+pub enum Step<T: Search> {
+    /// Expanded, pushing `pushed` children that hadn't been explored yet.
+    Expanded { state: SearchState<T>, pushed: usize },
+    /// Already at the depth limit, so dropped without expanding.
+    CutOff(SearchState<T>),
+    /// A solution, which ends the search.
+    Solved(SearchState<T>),
+    /// Nothing was left to pop: there's no solution within the depth limit.
+    Exhausted,
+}
+
+/// A search run a state at a time, for debuggers and visualisations that
+/// look at the frontier between expansions. [`search`](super::search) and
+/// friends run one of these to the end.
+pub struct Stepper<T: Search, F: Frontier<T>> {
+    fringe: F,
+    explored: HashSet<T>,
+    bloom: Option<BloomFilter>,
+    max_depth: usize,
+    report: SearchReport,
+    fringe_memory: usize,
+    explored_memory: usize,
+    best: Option<T::Score>,
+}
+
+impl<T: Search, F: Frontier<T>> Stepper<T, F> {
+    pub fn new(init_state: T, max_depth: usize, fringe: F) -> Self {
+        Stepper::seeded(vec![init_state.into()], max_depth, fringe, &mut ())
+    }
+
+    pub(super) fn seeded(
+        seeds: Vec<SearchState<T>>,
+        max_depth: usize,
+        mut fringe: F,
+        observer: &mut dyn Observer<T>,
+    ) -> Self {
+        let mut fringe_memory = 0;
+        for seed in seeds {
+            fringe_memory += seed.memory();
+            observer.on_push(&seed);
+            fringe.push(seed);
+        }
+        let mut report = SearchReport::default();
+        report.track(fringe.len(), fringe_memory);
+        Stepper {
+            fringe,
+            explored: HashSet::new(),
+            bloom: None,
+            max_depth,
+            report,
+            fringe_memory,
+            explored_memory: 0,
+            best: None,
+        }
+    }
+
+    /// Check a [`BloomFilter`] for about `expected` states before the
+    /// explored set, counting its memory with the explored set's. Call
+    /// before the first step.
+    pub fn with_bloom_filter(mut self, expected: usize) -> Self {
+        let bloom = BloomFilter::new(expected);
+        self.explored_memory += bloom.heap_bytes();
+        self.report
+            .track(self.fringe.len(), self.fringe_memory + self.explored_memory);
+        self.bloom = Some(bloom);
+        self
+    }
+
+    /// Pop the next state and expand it, unless it's a solution or at the
+    /// depth limit.
+    pub fn step(&mut self) -> Step<T> {
+        self.step_observed(&mut ())
+    }
+
+    /// [`Stepper::step`], calling `observer`'s hooks.
+    pub fn step_observed(&mut self, observer: &mut dyn Observer<T>) -> Step<T> {
+        let Some(state) = self.fringe.pop() else {
+            return Step::Exhausted;
+        };
+        self.fringe_memory -= state.memory();
+        if state.latest.end() {
+            return Step::Solved(state);
+        }
+        if state.history.len() >= self.max_depth {
+            self.report.depth_cutoffs += 1;
+            return Step::CutOff(state);
+        }
+        if let Some(bloom) = &mut self.bloom {
+            bloom.insert(&state.latest);
+        }
+        // States pushed twice before either was expanded are popped twice,
+        // but only stored once.
+        if self.explored.insert(state.latest.clone()) {
+            self.explored_memory += std::mem::size_of::<T>() + state.latest.heap_bytes();
+        }
+        self.report.explored = self.explored.len();
+        if self.best.as_ref().is_none_or(|best| state.score > *best) {
+            self.best = Some(state.score.clone());
+            observer.on_improvement(&state, &self.report);
+        }
+        observer.on_expand(&state, &self.report);
+        let mut pushed = 0;
+        for next_state in state.moves() {
+            let explored = match &self.bloom {
+                Some(bloom) if !bloom.may_contain(&next_state.latest) => {
+                    self.report.bloom_skips += 1;
+                    false
+                }
+                Some(_) => {
+                    let explored = self.explored.contains(&next_state.latest);
+                    self.report.bloom_false_positives += usize::from(!explored);
+                    explored
+                }
+                None => self.explored.contains(&next_state.latest),
+            };
+            if !explored {
+                self.fringe_memory += next_state.memory();
+                observer.on_push(&next_state);
+                self.fringe.push(next_state);
+                pushed += 1;
+            } else {
+                self.report.duplicates += 1;
+            }
+        }
+        self.report
+            .track(self.fringe.len(), self.fringe_memory + self.explored_memory);
+        Step::Expanded { state, pushed }
+    }
+
+    /// The states waiting to be expanded.
+    pub fn frontier(&self) -> &F {
+        &self.fringe
+    }
+
+    /// Work done so far.
+    pub fn report(&self) -> &SearchReport {
+        &self.report
+    }
+
+    pub(super) fn into_report(self) -> SearchReport {
+        self.report
+    }
+
+    pub(super) fn cancel(&mut self) {
+        self.report.cancelled = true;
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use crate::{
+    core::{Board, CancellationToken, Cancelled},
+    solver::{AStar, Budget, Solution, SolveOutcome, Solver},
+};
+
+#[derive(Default)]
+struct Shared {
+    result: Option<Result<Option<Solution>, Cancelled>>,
+    waker: Option<Waker>,
+}
+
+/// A board being solved on its own thread, see [`solve_cancellable`].
+/// Dropping the future cancels the solve.
+pub struct SolveFuture {
+    shared: Arc<Mutex<Shared>>,
+    cancel: CancellationToken,
+}
+
+impl Future for SolveFuture {
+    type Output = Result<Option<Solution>, Cancelled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for SolveFuture {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Find a shortest solution with A* on a background thread, resolving once
+/// it's found or `cancel` is cancelled. Doesn't depend on any particular
+/// executor: the worker wakes whichever task last polled the future.
+pub fn solve_cancellable(board: Board, cancel: CancellationToken) -> SolveFuture {
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let worker = (Arc::clone(&shared), cancel.clone());
+    thread::spawn(move || {
+        let (shared, cancel) = worker;
+        let budget = Budget {
+            cancel,
+            ..Budget::default()
+        };
+        let result = match AStar.solve(&board, &budget) {
+            SolveOutcome::Solved(solution) => Ok(Some(solution)),
+            SolveOutcome::Unsolvable => Ok(None),
+            SolveOutcome::BudgetExhausted => Err(Cancelled),
+        };
+        let mut shared = shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+    SolveFuture { shared, cancel }
+}
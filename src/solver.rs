@@ -0,0 +1,330 @@
+//! Solvers behind one interface. Every algorithm is a [`Solver`], answering
+//! within a [`Budget`] with a [`SolveOutcome`], and a [`Registry`] holds
+//! them by name so programs can pick one at run time, such as from a
+//! command line flag. Third-party solvers register the same way as the
+//! built-in ones.
+//!
+//! ```
+//! use lightsout::prelude::*;
+//!
+//! let solvers = Registry::default();
+//! let board = Board::from_code("5x5-c408f38").unwrap();
+//! let outcome = solvers.get("linear").unwrap().solve(&board, &Budget::default());
+//! let solution = outcome.solution().unwrap();
+//! assert_eq!(solution.len(), 3);
+//! assert!(board.verify(solution.presses()));
+//! ```
+
+use alloc::{boxed::Box, vec::Vec};
+use ::core::time::Duration;
+
+use crate::core::{BitMask, Board, CancellationToken};
+
+/// Presses that clear a board, in the order to make them. Any order works
+/// for the presses themselves; the order only matters for showing them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Solution {
+    presses: Vec<(usize, usize)>,
+}
+
+impl Solution {
+    pub fn new(presses: Vec<(usize, usize)>) -> Solution {
+        Solution { presses }
+    }
+
+    /// The presses as `(x, y)` cells.
+    pub fn presses(&self) -> &[(usize, usize)] {
+        &self.presses
+    }
+
+    pub fn len(&self) -> usize {
+        self.presses.len()
+    }
+
+    /// Whether the board was already clear.
+    pub fn is_empty(&self) -> bool {
+        self.presses.is_empty()
+    }
+
+    pub fn into_presses(self) -> Vec<(usize, usize)> {
+        self.presses
+    }
+}
+
+impl From<Vec<(usize, usize)>> for Solution {
+    fn from(presses: Vec<(usize, usize)>) -> Solution {
+        Solution::new(presses)
+    }
+}
+
+impl From<BitMask> for Solution {
+    /// The cells of the mask, in row-major order.
+    fn from(presses: BitMask) -> Solution {
+        Solution::new(presses.positions())
+    }
+}
+
+/// How much work a solve may do before giving up with
+/// [`SolveOutcome::BudgetExhausted`]. Every limit defaults to none.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    /// Time the solve may take. Only kept to with the `std` feature, which
+    /// has a clock to keep it by.
+    pub max_time: Option<Duration>,
+    /// States a search may explore. Solvers that don't search ignore it.
+    pub max_nodes: Option<usize>,
+    /// Bytes of states a search may hold at once, as
+    /// [`SearchReport::peak_memory`](crate::search::SearchReport::peak_memory)
+    /// counts them. Solvers that don't search ignore it.
+    pub max_memory: Option<usize>,
+    /// Presses a solution may take. Solvers that only find longer ones give
+    /// up instead.
+    pub max_depth: Option<usize>,
+    /// Stops the solve when cancelled, like any other limit running out.
+    pub cancel: CancellationToken,
+}
+
+impl Budget {
+    /// A budget of `max_nodes` explored states.
+    pub fn nodes(max_nodes: usize) -> Budget {
+        Budget {
+            max_nodes: Some(max_nodes),
+            ..Budget::default()
+        }
+    }
+
+    /// A budget of `max_time`.
+    pub fn time(max_time: Duration) -> Budget {
+        Budget {
+            max_time: Some(max_time),
+            ..Budget::default()
+        }
+    }
+
+    /// `solution`, unless it's longer than the budget allows.
+    fn fit(&self, solution: Option<Solution>) -> SolveOutcome {
+        match solution {
+            Some(solution) if self.max_depth.is_some_and(|depth| solution.len() > depth) => {
+                SolveOutcome::BudgetExhausted
+            }
+            solution => solution.into(),
+        }
+    }
+
+    /// Run `solve` with a token that's cancelled once `max_time` has gone by
+    /// or this budget's token is cancelled, whichever comes first.
+    #[cfg(feature = "std")]
+    fn timed<T>(&self, solve: impl FnOnce(&CancellationToken) -> T) -> T {
+        use std::{sync::mpsc, thread, time::Instant};
+
+        let Some(max_time) = self.max_time else {
+            return solve(&self.cancel);
+        };
+        // The budget's token may be shared, so time runs out on a private
+        // one that follows it.
+        let cancel = CancellationToken::new();
+        let deadline = Instant::now() + max_time;
+        let (done, finished) = mpsc::channel::<()>();
+        let (outer, timer) = (&self.cancel, cancel.clone());
+        thread::scope(|scope| {
+            scope.spawn(move || loop {
+                let left = deadline.saturating_duration_since(Instant::now());
+                let wait = left.min(Duration::from_millis(10));
+                match finished.recv_timeout(wait) {
+                    Err(mpsc::RecvTimeoutError::Timeout)
+                        if !left.is_zero() && !outer.is_cancelled() => {}
+                    Err(mpsc::RecvTimeoutError::Timeout) => break timer.cancel(),
+                    _ => break,
+                }
+            });
+            let result = solve(&cancel);
+            let _ = done.send(());
+            result
+        })
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn timed<T>(&self, solve: impl FnOnce(&CancellationToken) -> T) -> T {
+        solve(&self.cancel)
+    }
+}
+
+/// What a [`Solver`] made of a board.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveOutcome {
+    Solved(Solution),
+    /// No presses clear the board.
+    Unsolvable,
+    /// A limit ran out, or the solve was cancelled, before an answer.
+    BudgetExhausted,
+}
+
+impl SolveOutcome {
+    /// The solution, if one was found.
+    pub fn solution(self) -> Option<Solution> {
+        match self {
+            SolveOutcome::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+impl From<Option<Solution>> for SolveOutcome {
+    fn from(solution: Option<Solution>) -> SolveOutcome {
+        solution.map_or(SolveOutcome::Unsolvable, SolveOutcome::Solved)
+    }
+}
+
+/// An algorithm for solving boards.
+pub trait Solver: Send + Sync {
+    /// The name it's registered under, such as `linear`.
+    fn name(&self) -> &str;
+
+    fn solve(&self, board: &Board, budget: &Budget) -> SolveOutcome;
+
+    /// Whether every solution is as short as possible.
+    fn is_optimal(&self) -> bool {
+        true
+    }
+}
+
+/// Linear algebra over GF(2), see [`Board::solve_linear`]. Always shortest,
+/// and fast at any size.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Linear;
+
+impl Solver for Linear {
+    fn name(&self) -> &str {
+        "linear"
+    }
+
+    fn solve(&self, board: &Board, budget: &Budget) -> SolveOutcome {
+        match budget.timed(|cancel| board.solve_linear_cancellable(cancel)) {
+            Ok(solution) => budget.fit(solution.map(Solution::from)),
+            Err(_) => SolveOutcome::BudgetExhausted,
+        }
+    }
+}
+
+/// Light chasing, see [`Board::solve_chase`]. Faster still, but not always
+/// shortest, and quick enough that it only checks the budget's
+/// [`max_depth`](Budget::max_depth).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Chase;
+
+impl Solver for Chase {
+    fn name(&self) -> &str {
+        "chase"
+    }
+
+    fn solve(&self, board: &Board, budget: &Budget) -> SolveOutcome {
+        budget.fit(board.solve_chase().map(Solution::from))
+    }
+
+    fn is_optimal(&self) -> bool {
+        false
+    }
+}
+
+/// A* search from the board, see [`crate::search::a_star`]. Always
+/// shortest, with its presses in the order that clears the most lights
+/// soonest, but slow beyond ten or so presses.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AStar;
+
+#[cfg(feature = "std")]
+impl Solver for AStar {
+    fn name(&self) -> &str {
+        "astar"
+    }
+
+    fn solve(&self, board: &Board, budget: &Budget) -> SolveOutcome {
+        use crate::search::{a_star_cancellable, a_star_progress};
+
+        // Unsolvable, so there's nothing to search for.
+        let Some(shortest) = board.depth_bound() else {
+            return SolveOutcome::Unsolvable;
+        };
+        let max_depth = budget.max_depth.map_or(shortest, |depth| depth.min(shortest));
+        let (result, report) = budget.timed(|cancel| {
+            if budget.max_nodes.is_none() && budget.max_memory.is_none() {
+                return a_star_cancellable(board.clone(), max_depth, cancel);
+            }
+            // Node and memory use are checked as the search reports
+            // progress, on a token of its own, so running out doesn't cancel
+            // whatever else shares the budget's.
+            let limited = CancellationToken::new();
+            a_star_progress(board.clone(), max_depth, &limited, |report, _| {
+                let over = budget.max_nodes.is_some_and(|max| report.explored >= max)
+                    || budget.max_memory.is_some_and(|max| report.peak_memory >= max);
+                if over || cancel.is_cancelled() {
+                    limited.cancel();
+                }
+            })
+        });
+        match result {
+            Some(solved) => {
+                let width = board.width();
+                let presses: Vec<(usize, usize)> = solved
+                    .history
+                    .iter()
+                    .chain(solved.latest_move_index.as_ref())
+                    .map(|id| (id % width, id / width))
+                    .collect();
+                budget.fit(Some(presses.into()))
+            }
+            None if report.cancelled || report.depth_cutoffs > 0 => SolveOutcome::BudgetExhausted,
+            None => SolveOutcome::Unsolvable,
+        }
+    }
+}
+
+/// Solvers by name. The default holds the built-in ones; [`Registry::new`]
+/// starts empty.
+pub struct Registry {
+    solvers: Vec<Box<dyn Solver>>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            solvers: Vec::new(),
+        }
+    }
+
+    /// Add `solver` under its name, returning any solver it replaces.
+    pub fn register(&mut self, solver: impl Solver + 'static) -> Option<Box<dyn Solver>> {
+        let solver: Box<dyn Solver> = Box::new(solver);
+        match self.solvers.iter_mut().find(|old| old.name() == solver.name()) {
+            Some(old) => Some(::core::mem::replace(old, solver)),
+            None => {
+                self.solvers.push(solver);
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Solver> {
+        self.solvers
+            .iter()
+            .find(|solver| solver.name() == name)
+            .map(|solver| &**solver)
+    }
+
+    /// Registered names, in the order they were first registered.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.solvers.iter().map(|solver| solver.name())
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(Linear);
+        registry.register(Chase);
+        #[cfg(feature = "std")]
+        registry.register(AStar);
+        registry
+    }
+}
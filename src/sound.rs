@@ -0,0 +1,146 @@
+//! Sound cues for interactive play: a click for each press and a jingle
+//! for a win. Cues are synthesised as WAV, so there are no sound files to
+//! ship, cached in the temporary directory, and handed to whichever command
+//! line player the system has, so there's no audio stack to link either.
+//! Without one, play is silent.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+};
+
+const SAMPLE_RATE: u32 = 22_050;
+
+/// Players tried in order, with the arguments they need before a file.
+const PLAYERS: &[(&str, &[&str])] = &[
+    ("paplay", &[]),
+    ("pw-play", &[]),
+    ("aplay", &["-q"]),
+    ("afplay", &[]),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    Press,
+    Win,
+}
+
+impl Cue {
+    fn name(self) -> &'static str {
+        match self {
+            Cue::Press => "press",
+            Cue::Win => "win",
+        }
+    }
+
+    /// Notes as `(hertz, seconds)`.
+    fn notes(self) -> &'static [(f64, f64)] {
+        match self {
+            Cue::Press => &[(1400.0, 0.035)],
+            // C major arpeggio up to the octave.
+            Cue::Win => &[
+                (523.25, 0.11),
+                (659.25, 0.11),
+                (783.99, 0.11),
+                (1046.5, 0.35),
+            ],
+        }
+    }
+}
+
+/// The cue as a mono 16-bit WAV file at `volume`, from 0 for silence to 1.
+pub fn wav(cue: Cue, volume: f64) -> Vec<u8> {
+    let volume = volume.clamp(0.0, 1.0);
+    let mut samples = Vec::new();
+    for &(hertz, seconds) in cue.notes() {
+        let count = (seconds * SAMPLE_RATE as f64) as usize;
+        for i in 0..count {
+            let t = i as f64 / SAMPLE_RATE as f64;
+            // A sharp attack and an exponential decay, so notes don't click
+            // against each other.
+            let envelope = (t / 0.004).min(1.0) * (-5.0 * t / seconds).exp();
+            let wave = (2.0 * std::f64::consts::PI * hertz * t).sin();
+            samples.push((wave * envelope * volume * 0.8 * i16::MAX as f64) as i16);
+        }
+    }
+
+    let data = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data as usize);
+    wav.extend(b"RIFF");
+    wav.extend((36 + data).to_le_bytes());
+    wav.extend(b"WAVEfmt ");
+    wav.extend(16u32.to_le_bytes());
+    wav.extend(1u16.to_le_bytes()); // PCM
+    wav.extend(1u16.to_le_bytes()); // mono
+    wav.extend(SAMPLE_RATE.to_le_bytes());
+    wav.extend((SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend(2u16.to_le_bytes());
+    wav.extend(16u16.to_le_bytes());
+    wav.extend(b"data");
+    wav.extend(data.to_le_bytes());
+    for sample in samples {
+        wav.extend(sample.to_le_bytes());
+    }
+    wav
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Plays cues through the first installed player, without waiting for
+/// them to finish.
+#[derive(Clone, Debug)]
+pub struct Speaker {
+    player: Option<(&'static str, &'static [&'static str])>,
+    paths: [PathBuf; 2],
+}
+
+impl Speaker {
+    /// A speaker at `volume`, writing its cues to the cache unless an
+    /// earlier run already has.
+    pub fn new(volume: f64) -> Speaker {
+        let dir = env::temp_dir().join("lightsout-sound");
+        let percent = (volume.clamp(0.0, 1.0) * 100.0).round();
+        let paths =
+            [Cue::Press, Cue::Win].map(|cue| dir.join(format!("{}-{percent}.wav", cue.name())));
+        let cached = fs::create_dir_all(&dir).is_ok()
+            && [Cue::Press, Cue::Win]
+                .iter()
+                .zip(&paths)
+                .all(|(&cue, path)| {
+                    path.is_file() || fs::write(path, wav(cue, percent / 100.0)).is_ok()
+                });
+        let player = PLAYERS
+            .iter()
+            .find(|(program, _)| on_path(program))
+            .copied()
+            .filter(|_| cached);
+        Speaker { player, paths }
+    }
+
+    /// Whether there's a player to hear cues through.
+    pub fn is_audible(&self) -> bool {
+        self.player.is_some()
+    }
+
+    pub fn play(&self, cue: Cue) {
+        let Some((program, args)) = self.player else {
+            return;
+        };
+        let child = Command::new(program)
+            .args(args)
+            .arg(&self.paths[cue as usize])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Ok(mut child) = child {
+            // Reap it in the background; a cue is never worth waiting for.
+            thread::spawn(move || child.wait());
+        }
+    }
+}
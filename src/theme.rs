@@ -0,0 +1,132 @@
+//! Palettes for drawing boards in terminals and images.
+//!
+//! Every cell is one of four kinds: lit or unlit, and pressed (a solution's
+//! press, or a cell a diff highlights) or not. [`CLASSIC`] is the plain
+//! look, without colour in terminals. The others tell all four kinds apart
+//! by shape as well as colour, filled for lit, hollow for unlit and
+//! diamonds for presses, with colours from the Okabe-Ito set, which stays
+//! distinct under deuteranopia and protanopia.
+
+use alloc::{format, string::String};
+
+use crate::core::{BitMask, Board};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Two-column terminal glyphs for lit, unlit, lit and pressed, and
+    /// unlit and pressed cells.
+    pub glyphs: [&'static str; 4],
+    /// Colours for the same four kinds.
+    pub colors: [[u8; 3]; 4],
+    /// Behind cells in images, and where obstacles are.
+    pub background: [u8; 3],
+    /// Whether cells are told apart by shape as well as colour: coloured
+    /// glyphs in terminals, and in images hollow unlit cells and diamond
+    /// presses. Without it terminals get bare glyphs, presses picked out
+    /// with a red background, and images solid cells and round presses.
+    pub shapes: bool,
+}
+
+pub const CLASSIC: Theme = Theme {
+    name: "classic",
+    glyphs: ["██", "░░", "██", "░░"],
+    colors: [[255, 214, 10], [48, 48, 48], [220, 40, 40], [220, 40, 40]],
+    background: [16, 16, 16],
+    shapes: false,
+};
+
+/// Orange against sky blue and blue, for red-green colour blindness where
+/// greens are weak.
+pub const DEUTERANOPIA: Theme = Theme {
+    name: "deuteranopia",
+    glyphs: ["██", "··", "◆◆", "◇◇"],
+    colors: [[230, 159, 0], [90, 90, 90], [0, 114, 178], [86, 180, 233]],
+    background: [16, 16, 16],
+    shapes: true,
+};
+
+/// Yellow rather than orange for lit cells, since reds and oranges look dark
+/// where reds are weak.
+pub const PROTANOPIA: Theme = Theme {
+    name: "protanopia",
+    glyphs: ["██", "··", "◆◆", "◇◇"],
+    colors: [[240, 228, 66], [90, 90, 90], [0, 114, 178], [86, 180, 233]],
+    background: [16, 16, 16],
+    shapes: true,
+};
+
+/// White on black with yellow presses, for low vision and washed out
+/// screens.
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    glyphs: ["██", "··", "◆◆", "◇◇"],
+    colors: [[255, 255, 255], [128, 128, 128], [255, 255, 0], [255, 255, 0]],
+    background: [0, 0, 0],
+    shapes: true,
+};
+
+pub const THEMES: &[Theme] = &[CLASSIC, DEUTERANOPIA, PROTANOPIA, HIGH_CONTRAST];
+
+impl Theme {
+    pub fn named(name: &str) -> Option<&'static Theme> {
+        THEMES.iter().find(|theme| theme.name == name)
+    }
+
+    fn kind(lit: bool, pressed: bool) -> usize {
+        match (lit, pressed) {
+            (true, false) => 0,
+            (false, false) => 1,
+            (true, true) => 2,
+            (false, true) => 3,
+        }
+    }
+
+    pub fn glyph(&self, lit: bool, pressed: bool) -> &'static str {
+        self.glyphs[Theme::kind(lit, pressed)]
+    }
+
+    pub fn color(&self, lit: bool, pressed: bool) -> [u8; 3] {
+        self.colors[Theme::kind(lit, pressed)]
+    }
+
+    /// A cell for a terminal, with escape codes for its colour.
+    pub fn paint(&self, lit: bool, pressed: bool) -> String {
+        let glyph = self.glyph(lit, pressed);
+        if self.shapes {
+            let [r, g, b] = self.color(lit, pressed);
+            format!("\x1b[38;2;{r};{g};{b}m{glyph}\x1b[0m")
+        } else if pressed {
+            format!("\x1b[41m{glyph}\x1b[0m")
+        } else {
+            glyph.into()
+        }
+    }
+
+    /// One row of `board` for a terminal, with the cells in `presses`
+    /// marked. Obstacles are blank.
+    pub fn row(&self, board: &Board, y: usize, presses: Option<&BitMask>) -> String {
+        let rules = board.ruleset();
+        let mut row = String::new();
+        for x in 0..board.width() {
+            if rules.is_obstacle(x, y) {
+                row.push_str("  ");
+            } else {
+                let pressed = presses.is_some_and(|presses| presses.get(x, y));
+                row += &self.paint(board.get(x, y), pressed);
+            }
+        }
+        row
+    }
+
+    /// `board` for a terminal, a line a row; [`CLASSIC`] draws it just as
+    /// `Display` does.
+    pub fn draw(&self, board: &Board, presses: Option<&BitMask>) -> String {
+        let mut out = String::new();
+        for y in 0..board.height() {
+            out += &self.row(board, y, presses);
+            out.push('\n');
+        }
+        out
+    }
+}
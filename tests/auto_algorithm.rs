@@ -0,0 +1,32 @@
+use std::process::Command;
+
+/// The presses `lightsout solve` prints for seed 6 with `args`.
+fn presses(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .args(["solve", "--seed", "6", "--output", "json"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let start = stdout.find("\"presses\":").unwrap();
+    let end = start + stdout[start..].find("]]").unwrap();
+    stdout[start..end].to_string()
+}
+
+#[test]
+fn auto_keeps_the_preference_it_hands_over() {
+    for prefer in ["fewest", "travel"] {
+        let linear = presses(&["--algorithm", "linear", "--prefer", prefer]);
+        let auto = presses(&["--algorithm", "auto", "--prefer", prefer]);
+        assert_eq!(auto, linear, "--prefer {prefer}");
+    }
+    assert_ne!(
+        presses(&["--algorithm", "auto", "--prefer", "travel"]),
+        presses(&["--algorithm", "auto", "--prefer", "fewest"])
+    );
+}
@@ -0,0 +1,77 @@
+use lightsout::{BatchSolver, Board};
+
+/// `count` random boards of one size, about three quarters of them
+/// unsolvable at 5x5.
+fn random_boards(width: usize, height: usize, count: usize, seed: u64) -> Vec<Board> {
+    (0..count as u64)
+        .map(|i| {
+            let mut board = Board::new(width, height);
+            board.randomize(seed * 1_000_003 + i);
+            board
+        })
+        .collect()
+}
+
+#[test]
+fn parallel_batches_match_one_batch() {
+    let solver = BatchSolver::new(5, 5);
+    // Seven and a bit passes over three threads, so the last run is short
+    // and the last pass only partly full.
+    let boards = random_boards(5, 5, BatchSolver::LANES * 7 + 5, 1);
+    let solved = solver.solve_parallel(&boards, 3);
+    assert_eq!(solved, solver.solve(&boards));
+    assert!(solved.iter().any(Option::is_none));
+    for threads in [0, 1, 4, 64] {
+        let few = &boards[..BatchSolver::LANES + 9];
+        assert_eq!(
+            solver.solve_parallel(few, threads),
+            solver.solve(few),
+            "{threads} threads"
+        );
+    }
+}
+
+#[test]
+fn bit_sliced_solutions_match_the_scalar_solver() {
+    for (width, height) in [(1, 1), (2, 3), (3, 3), (4, 4), (5, 5), (7, 3)] {
+        let solver = BatchSolver::new(width, height);
+        for count in [
+            0,
+            1,
+            13,
+            BatchSolver::LANES - 1,
+            BatchSolver::LANES + 1,
+            150,
+        ] {
+            let boards = random_boards(width, height, count, (width * 31 + height) as u64);
+            let solved = solver.solve(&boards);
+            assert_eq!(solved.len(), boards.len());
+            for (board, solution) in boards.iter().zip(&solved) {
+                assert_eq!(
+                    *solution,
+                    board.solve_linear(),
+                    "{} in a batch of {count}",
+                    board.to_code()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn unsolvable_lanes_are_left_out_without_disturbing_their_neighbours() {
+    let solver = BatchSolver::new(5, 5);
+    let corner = Board::from_code("5x5-8000000").unwrap();
+    let mut boards = random_boards(5, 5, BatchSolver::LANES + 3, 2);
+    for index in (0..boards.len()).step_by(3) {
+        boards[index] = corner.clone();
+    }
+    let solved = solver.solve(&boards);
+    for (index, (board, solution)) in boards.iter().zip(&solved).enumerate() {
+        if index % 3 == 0 {
+            assert_eq!(*solution, None, "lane {index}");
+        }
+        assert_eq!(*solution, board.solve_linear(), "lane {index}");
+    }
+    assert!(solved.iter().any(Option::is_some));
+}
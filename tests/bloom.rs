@@ -0,0 +1,116 @@
+use lightsout::{
+    search::{a_star, a_star_opening_filtered, a_star_warm, a_star_warm_filtered, BloomFilter},
+    Board, CancellationToken,
+};
+
+#[test]
+fn the_filter_never_forgets_a_state() {
+    let mut filter = BloomFilter::new(2000);
+    let boards: Vec<Board> = (0..2000)
+        .map(|seed| {
+            let mut board = Board::new(5, 5);
+            board.randomize(seed);
+            board
+        })
+        .collect();
+    for board in &boards {
+        filter.insert(board);
+    }
+    assert!(boards.iter().all(|board| filter.may_contain(board)));
+
+    // Sized for 2000, it should be wrong about roughly one in a hundred
+    // others.
+    let wrong = (2000..12000)
+        .filter(|&seed| {
+            let mut board = Board::new(5, 5);
+            board.randomize(seed);
+            !boards.contains(&board) && filter.may_contain(&board)
+        })
+        .count();
+    assert!(wrong < 300, "{wrong} false positives in 10000");
+}
+
+#[test]
+fn filtered_searches_find_the_same_solution() {
+    for code in ["5x5-c408f38", "5x5-2e99a38"] {
+        let board = Board::from_code(code).unwrap();
+        let depth = board.depth_bound().unwrap();
+        let cancel = CancellationToken::new();
+        for expected in [1, 100, 100_000] {
+            let (found, report) = a_star_opening_filtered(
+                board.clone(),
+                &[],
+                depth,
+                Some(expected),
+                &cancel,
+                |_, _| {},
+                &mut (),
+            );
+            let (unfiltered, unfiltered_report) = a_star(board.clone(), depth);
+            assert_eq!(found.unwrap().history, unfiltered.unwrap().history);
+            assert_eq!(report.explored, unfiltered_report.explored);
+            assert_eq!(report.duplicates, unfiltered_report.duplicates);
+            assert!(report.peak_memory > unfiltered_report.peak_memory);
+            // Every new child was either skipped or looked up for nothing.
+            let children = report.bloom_skips + report.bloom_false_positives;
+            assert!(children > 0);
+            assert_eq!(unfiltered_report.bloom_skips, 0);
+        }
+    }
+}
+
+#[test]
+fn a_filter_too_small_only_costs_lookups() {
+    let board = Board::from_code("5x5-2e99a38").unwrap();
+    let depth = board.depth_bound().unwrap();
+    let cancel = CancellationToken::new();
+    let search = |expected| {
+        a_star_opening_filtered(
+            board.clone(),
+            &[],
+            depth,
+            Some(expected),
+            &cancel,
+            |_, _| {},
+            &mut (),
+        )
+        .1
+    };
+    let (small, large) = (search(1), search(100_000));
+    assert!(small.bloom_false_positives > large.bloom_false_positives);
+    assert_eq!(small.explored, large.explored);
+}
+
+#[test]
+fn warm_started_searches_can_be_filtered_too() {
+    let board = Board::from_code("5x5-2e99a38").unwrap();
+    let depth = board.depth_bound().unwrap();
+    let candidate: Vec<usize> = board
+        .solve_linear()
+        .unwrap()
+        .positions()
+        .iter()
+        .map(|(x, y)| y * 5 + x)
+        .collect();
+    // Half a solution, so there's searching left to do.
+    let candidate = &candidate[..candidate.len() / 2];
+    let cancel = CancellationToken::new();
+    let mut reports = 0;
+    let (found, report) = a_star_warm_filtered(
+        board.clone(),
+        candidate,
+        depth,
+        Some(1000),
+        &cancel,
+        |_, _| reports += 1,
+        &mut (),
+    );
+    let (unfiltered, unfiltered_report) = a_star_warm(board.clone(), candidate, depth);
+    assert_eq!(found.unwrap().history, unfiltered.unwrap().history);
+    assert_eq!(report.explored, unfiltered_report.explored);
+    assert!(report.bloom_skips > 0);
+    assert_eq!(
+        reports,
+        report.explored / lightsout::search::PROGRESS_INTERVAL
+    );
+}
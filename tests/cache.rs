@@ -0,0 +1,122 @@
+use std::{env, fs, path::PathBuf};
+
+use lightsout::{cache::SolutionCache, Board};
+
+/// A cache file of its own for each test, removed before it starts.
+fn path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("lightsout-cache-{}-{name}", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn board(rows: &[&str]) -> Board {
+    rows.join("\n").parse().unwrap()
+}
+
+#[test]
+fn solutions_come_back_turned_for_rotations() {
+    let path = path("rotations");
+    let corner = board(&["##...", "#....", ".....", ".....", "....."]);
+    let mut cache = SolutionCache::open(&path, 10).unwrap();
+    let presses = corner.solve_linear().unwrap().positions();
+    cache.insert("linear", &corner, Some(&presses)).unwrap();
+
+    let turned = board(&["...##", "....#", ".....", ".....", "....."]);
+    let found = cache.get("linear", &turned).unwrap().unwrap();
+    assert!(turned.verify(&found));
+    assert_eq!(found, vec![(4, 0)]);
+    assert_eq!(cache.get("astar", &turned), None);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn entries_survive_reopening() {
+    let path = path("reopen");
+    let solvable = Board::from_code("5x5-c408f38").unwrap();
+    let unsolvable = Board::from_code("5x5-8000000").unwrap();
+    let presses = solvable.solve_linear().unwrap().positions();
+    {
+        let mut cache = SolutionCache::open(&path, 10).unwrap();
+        cache.insert("linear", &solvable, Some(&presses)).unwrap();
+        cache.insert("linear", &unsolvable, None).unwrap();
+        cache
+            .insert("linear", &Board::new(5, 5), Some(&[]))
+            .unwrap();
+    }
+    let cache = SolutionCache::open(&path, 10).unwrap();
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.skipped, 0);
+    let mut found = cache.get("linear", &solvable).unwrap().unwrap();
+    found.sort();
+    assert_eq!(found, presses);
+    assert_eq!(cache.get("linear", &unsolvable), Some(None));
+    assert_eq!(
+        cache.get("linear", &Board::new(5, 5)),
+        Some(Some(Vec::new()))
+    );
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn the_oldest_entries_go_past_the_limit() {
+    let path = path("limit");
+    let mut cache = SolutionCache::open(&path, 3).unwrap();
+    let boards: Vec<Board> = Board::enumerate_solvable(3, 3).skip(1).take(20).collect();
+    let mut kept = Vec::new();
+    for board in &boards {
+        let canonical = board.canonical_form().to_code();
+        if kept.contains(&canonical) {
+            continue;
+        }
+        kept.push(canonical);
+        let presses = board.solve_linear().unwrap().positions();
+        cache.insert("linear", board, Some(&presses)).unwrap();
+    }
+    assert_eq!(cache.len(), 3);
+    let newest: Vec<String> = cache.entries().map(|entry| entry.code).collect();
+    assert_eq!(newest, kept[kept.len() - 3..]);
+
+    // Appending stops once the file holds twice the limit, and it's
+    // rewritten with only what's kept.
+    let lines = fs::read_to_string(&path).unwrap().lines().count();
+    assert!(lines <= 6);
+    let reopened = SolutionCache::open(&path, 3).unwrap();
+    let codes: Vec<String> = reopened.entries().map(|entry| entry.code).collect();
+    assert_eq!(codes, newest);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn unreadable_lines_are_skipped_and_clearing_empties_the_file() {
+    let path = path("clear");
+    fs::write(
+        &path,
+        "linear 3x3-ff8 a1,b2\nnot a cache line at all\nlinear 3x3-010 zz\n\
+         linear/fewest 3x3-000 z99\nlinear 3x3-000 c4\nlinear 3x3-1ff a1\n",
+    )
+    .unwrap();
+    let mut cache = SolutionCache::open(&path, 10).unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.skipped, 5);
+    assert_eq!(cache.get("linear/fewest", &Board::new(3, 3)), None);
+    cache.clear().unwrap();
+    assert!(cache.is_empty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn cached_boards_are_found_by_id() {
+    let path = path("ids");
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let mut cache = SolutionCache::open(&path, 10).unwrap();
+    let presses = board.solve_linear().unwrap().positions();
+    cache.insert("linear", &board, Some(&presses)).unwrap();
+    cache.insert("astar", &board, Some(&presses)).unwrap();
+
+    let found = cache.find_id(&board.puzzle_id()[..6]);
+    assert_eq!(found, vec![board.canonical_form()]);
+    assert_eq!(found[0].puzzle_id(), board.puzzle_id());
+    assert!(cache.find_id("not-an-id").is_empty());
+    fs::remove_file(path).unwrap();
+}
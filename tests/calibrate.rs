@@ -0,0 +1,109 @@
+use lightsout::{
+    calibrate::{Calibration, Model, Session},
+    pack::Pack,
+    Board,
+};
+
+fn starter() -> Vec<Board> {
+    let pack = Pack::open("starter").unwrap();
+    pack.puzzles
+        .into_iter()
+        .map(|puzzle| puzzle.board)
+        .collect()
+}
+
+#[test]
+fn model_fits_presses_to_board_features() {
+    // Everyone takes twice the optimum.
+    let sessions: Vec<_> = starter()
+        .into_iter()
+        .take(20)
+        .map(|board| {
+            let presses = 2 * board.optimal_length().unwrap();
+            Session::new(board, presses, true)
+        })
+        .collect();
+    let model = Model::fit(&sessions).unwrap();
+    assert_eq!(model.presses.samples, 20);
+    assert!(model.presses.r_squared > 0.99);
+    assert!(model.seconds.is_none());
+    for board in starter().into_iter().skip(20).take(5) {
+        let expected = 2.0 * board.optimal_length().unwrap() as f64;
+        assert!((model.presses(&board).unwrap() - expected).abs() < 0.5);
+    }
+}
+
+#[test]
+fn boards_players_struggle_with_rate_higher() {
+    let boards = starter();
+    let (easy, hard) = (boards[9].clone(), boards[10].clone());
+    let optimal = easy.optimal_length().unwrap();
+    assert_eq!(optimal, hard.optimal_length().unwrap());
+    let mut sessions = Vec::new();
+    for _ in 0..5 {
+        sessions.push(Session::new(easy.clone(), optimal, true));
+        sessions.push(Session::new(hard.clone(), optimal * 4, true));
+        sessions.push(Session::new(hard.clone(), optimal * 6, false));
+    }
+    let calibration = Calibration::fit(&sessions).unwrap();
+    let easy = calibration.board_rating(&easy).unwrap();
+    let hard = calibration.board_rating(&hard).unwrap();
+    assert_eq!((easy.sessions, hard.sessions), (5, 10));
+    assert!(hard.rating > easy.rating + 100.0);
+}
+
+#[test]
+fn unplayed_boards_get_the_prior() {
+    let boards = starter();
+    let sessions = [Session::new(boards[0].clone(), 1, true)];
+    let calibration = Calibration::fit(&sessions).unwrap();
+    let unplayed = calibration.board_rating(&boards[30]).unwrap();
+    assert_eq!(unplayed.sessions, 0);
+    assert_eq!(Some(unplayed.rating), calibration.model.prior(&boards[30]));
+    // A lone corner light can't be cleared on 5x5.
+    let mut corner = Board::new(5, 5);
+    corner.set(0, 0, true);
+    assert!(calibration.rating(&corner).is_none());
+}
+
+#[test]
+fn reflections_count_towards_the_same_rating() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let mut mirrored = Board::new(5, 5);
+    for ((x, y), lit) in board.cells() {
+        mirrored.set(4 - x, y, lit);
+    }
+    assert_ne!(board, mirrored);
+    let sessions = [
+        Session::new(board.clone(), 3, true),
+        Session::new(mirrored.clone(), 9, true),
+    ];
+    let calibration = Calibration::fit(&sessions).unwrap();
+    assert_eq!(calibration.boards.len(), 1);
+    assert_eq!(calibration.board_rating(&mirrored).unwrap().sessions, 2);
+    assert_eq!(calibration.rating(&board), calibration.rating(&mirrored));
+}
+
+#[test]
+fn named_players_are_rated() {
+    let boards = starter();
+    let mut sessions = Vec::new();
+    for board in &boards[..10] {
+        let optimal = board.optimal_length().unwrap();
+        let mut strong = Session::new(board.clone(), optimal, true);
+        strong.player = Some("ann".to_string());
+        let mut weak = Session::new(board.clone(), optimal * 3, false);
+        weak.player = Some("bob".to_string());
+        weak.hints = 2;
+        sessions.extend([strong, weak]);
+    }
+    let calibration = Calibration::fit(&sessions).unwrap();
+    assert!(calibration.players["ann"] > calibration.players["bob"]);
+}
+
+#[test]
+fn calibrating_needs_a_cleared_board() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    assert!(Calibration::fit(&[Session::new(board, 20, false)]).is_err());
+    assert!(Calibration::fit(&[]).is_err());
+}
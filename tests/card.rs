@@ -0,0 +1,65 @@
+#![cfg(feature = "image")]
+
+use lightsout::{
+    card,
+    png::{self, Image},
+    Board,
+};
+
+fn card_image(board: &Board) -> Image {
+    png::decode(&card::encode(board)).unwrap()
+}
+
+/// `image` resized by `scale` with nearest neighbour sampling, and every
+/// `noise`th pixel gone black or white.
+fn photographed(image: &Image, scale: f64, noise: usize) -> Image {
+    let width = (image.width as f64 * scale) as usize;
+    let height = (image.height as f64 * scale) as usize;
+    let pixels = (0..width * height)
+        .map(|i| {
+            if i % noise == 0 {
+                return if i % 2 == 0 { [0, 0, 0, 255] } else { [255, 255, 255, 255] };
+            }
+            let (x, y) = ((i % width) as f64 / scale, (i / width) as f64 / scale);
+            image.pixels[y as usize * image.width + x as usize]
+        })
+        .collect();
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+#[test]
+fn cards_scan_back_to_the_same_board() {
+    for code in ["5x5-5f66258", "7x6-f8fbf204d48:wrap", "3x3-000:holes=800", "12x2-abcdef"] {
+        let board = Board::from_code(code).unwrap();
+        assert_eq!(card::decode(&card::encode(&board)).unwrap(), board, "{code}");
+    }
+}
+
+#[test]
+fn scaled_and_speckled_cards_still_scan() {
+    let board = Board::from_code("6x6-123456789").unwrap();
+    for scale in [0.75, 1.4, 2.3] {
+        let image = photographed(&card_image(&board), scale, 97);
+        assert_eq!(card::read(&image).unwrap(), board, "scale {scale}");
+    }
+}
+
+#[test]
+fn damaged_strips_are_refused() {
+    let board = Board::from_code("5x5-5f66258").unwrap();
+    let mut image = card_image(&board);
+    // Invert the module holding the fifth bit of the length: the strip
+    // starts 31 modules down and 4 in, and its first payload row is the
+    // third.
+    for y in 33 * 6..34 * 6 {
+        for x in 9 * 6..10 * 6 {
+            let pixel = &mut image.pixels[y * image.width + x];
+            pixel[..3].iter_mut().for_each(|channel| *channel = 255 - *channel);
+        }
+    }
+    assert!(card::read(&image).is_err());
+}
@@ -0,0 +1,46 @@
+use lightsout::{
+    search::{a_star, ChasingBoard, Search},
+    Board,
+};
+
+fn board(rows: &[&str]) -> Board {
+    rows.join("\n").parse().unwrap()
+}
+
+#[test]
+fn cleared_rows_are_left_alone() {
+    let chasing = ChasingBoard::new(board(&["...", "#..", "##."]));
+    for (_, index) in chasing.moves() {
+        assert_eq!(index / 3, 2, "press {index} disturbs a cleared row");
+    }
+}
+
+#[test]
+fn chased_boards_can_fix_up_from_the_top() {
+    let chasing = ChasingBoard::new(board(&["...", "...", "#.#"]));
+    let mut presses: Vec<usize> = chasing
+        .moves()
+        .into_iter()
+        .map(|(_, index)| index)
+        .collect();
+    presses.sort();
+    assert_eq!(presses, [0, 1, 2]);
+}
+
+#[test]
+fn every_3x3_board_is_still_solved() {
+    for cells in 0..1u32 << 9 {
+        let mut board = Board::new(3, 3);
+        for index in 0..9 {
+            board.set(index % 3, index / 3, cells & (1 << index) != 0);
+        }
+        let chasing = ChasingBoard::new(board.clone());
+        let max_depth = chasing.max_depth();
+        let (result, _) = a_star(chasing, max_depth);
+        let solution = result.unwrap_or_else(|| panic!("no solution for\n{board}"));
+        for index in solution.history.iter().chain(&solution.latest_move_index) {
+            board.toggle(index % 3, index / 3);
+        }
+        assert!(board.is_clear());
+    }
+}
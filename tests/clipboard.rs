@@ -0,0 +1,81 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use lightsout::{pack::lookup, Board};
+
+/// `lightsout play` on `puzzle`, typing the lines of `input`, with no
+/// clipboard tools to be found, so copies go through the terminal.
+fn play(puzzle: &str, args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .args(["play", puzzle, "--render", "plain"])
+        .args(args)
+        .env("PATH", "")
+        .env_remove("DISPLAY")
+        .env_remove("WAYLAND_DISPLAY")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    write!(child.stdin.take().unwrap(), "{input}").unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// The text of every OSC 52 copy in `output`.
+fn copied(output: &str) -> Vec<String> {
+    output
+        .split("\x1b]52;c;")
+        .skip(1)
+        .map(|rest| decode(&rest[..rest.find('\x07').unwrap()]))
+        .collect()
+}
+
+fn decode(text: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let sextets: Vec<u32> = text
+        .bytes()
+        .filter(|&byte| byte != b'=')
+        .map(|byte| ALPHABET.iter().position(|&c| c == byte).unwrap() as u32)
+        .collect();
+    let mut bytes = Vec::new();
+    for chunk in sextets.chunks(4) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |n, (i, sextet)| n | sextet << (18 - 6 * i));
+        bytes.extend(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn copies_parse_back_to_the_board() {
+    let start = lookup("starter/3").unwrap().board;
+    let output = play("starter/3", &[], "copy\ncopy grid\nb2\ncopy\nq\n");
+    let copies = copied(&output);
+    assert_eq!(copies.len(), 3);
+    assert_eq!(copies[0], start.to_code());
+    assert_eq!(copies[1].parse::<Board>().unwrap(), start);
+    let mut pressed = start.clone();
+    pressed.toggle(1, 1);
+    assert_eq!(Board::from_code(&copies[2]).unwrap(), pressed);
+    assert!(output.contains(&format!(
+        "Copied {} to the clipboard via the terminal",
+        start.to_code()
+    )));
+}
+
+#[test]
+fn the_fog_keeps_its_secrets() {
+    let output = play("starter/3", &["--fog", "1"], "copy grid\nq\n");
+    assert!(copied(&output).is_empty());
+    assert!(output.contains("No copying in the fog"));
+}
+
+#[test]
+fn pasting_without_a_tool_says_what_to_install() {
+    let output = play("starter/3", &[], "paste\nq\n");
+    assert!(output.contains("Couldn't paste a board: no clipboard tool to paste with; install"));
+}
@@ -0,0 +1,106 @@
+use lightsout::{
+    search::{a_star, ConstrainedBoard},
+    BitMask, Board, PressConstraints, Ruleset,
+};
+
+/// The press set with `index`'s bits as its cells, row-major.
+fn press_set(width: usize, height: usize, index: usize) -> BitMask {
+    let mut presses = BitMask::new(width, height);
+    for cell in 0..width * height {
+        presses.set(cell % width, cell / width, index & (1 << cell) != 0);
+    }
+    presses
+}
+
+/// The fewest presses any allowed solution makes, trying every press set.
+fn fewest_allowed(board: &Board, constraints: &PressConstraints) -> Option<usize> {
+    let (width, height) = (board.width(), board.height());
+    (0..1usize << (width * height))
+        .map(|index| press_set(width, height, index))
+        .filter(|presses| constraints.allows(presses) && board.verify(&presses.positions()))
+        .map(|presses| presses.count())
+        .min()
+}
+
+#[test]
+fn constrained_solutions_are_the_smallest_allowed() {
+    for rules in ["classic", "wrap", "holes=100"] {
+        let rules = Ruleset::parse(rules, 3, 3).unwrap();
+        for seed in 0..12u64 {
+            let mut board = Board::new(3, 3).with_ruleset(rules.clone());
+            board.randomize(seed);
+            let text = ["a1", "!b2", "c3 !a1", "!a2,!b1", "b1 b3 !c2", "!a1 !a2 !a3"];
+            let constraints =
+                PressConstraints::parse(text[seed as usize % text.len()], 3, 3).unwrap();
+            let solution = board.solve_constrained(&constraints);
+            let context = format!("{rules} {} {constraints}", board.to_code());
+            assert_eq!(
+                solution.as_ref().map(BitMask::count),
+                fewest_allowed(&board, &constraints),
+                "{context}"
+            );
+            if let Some(solution) = solution {
+                assert!(constraints.allows(&solution), "{context}");
+                assert!(board.verify(&solution.positions()), "{context}");
+            }
+        }
+    }
+}
+
+#[test]
+fn searches_never_press_pinned_cells() {
+    let board = Board::from_code("3x3-ff8").unwrap();
+    let constraints = PressConstraints::parse("b2 !b1 !a2", 3, 3).unwrap();
+    let constrained = ConstrainedBoard::new(&board, &constraints);
+    let (found, _) = a_star(constrained, 9);
+    let mut presses = constraints.required.clone();
+    let found = found.unwrap();
+    for &index in found.history.iter().chain(&found.latest_move_index) {
+        let (x, y) = (index % 3, index / 3);
+        assert!(
+            !constraints.required.get(x, y) && !constraints.forbidden.get(x, y),
+            "{} is pinned",
+            lightsout::cell_name(x, y)
+        );
+        presses.set(x, y, !presses.get(x, y));
+    }
+    assert!(constraints.allows(&presses));
+    assert!(board.verify(&presses.positions()));
+}
+
+#[test]
+fn contradictory_constraints_are_unsolvable() {
+    // 3x3 boards have exactly one solution, so pinning any cell the other
+    // way leaves none.
+    let board = Board::from_code("3x3-d00").unwrap();
+    assert_eq!(board.solve_linear().unwrap().positions(), [(0, 0)]);
+    for text in ["!a1", "b2", "a1 c3"] {
+        let constraints = PressConstraints::parse(text, 3, 3).unwrap();
+        assert_eq!(board.solve_constrained(&constraints), None, "{text}");
+        assert_eq!(fewest_allowed(&board, &constraints), None, "{text}");
+    }
+
+    // A cell both required and forbidden.
+    let mut both = PressConstraints::new(3, 3);
+    both.required.set(0, 0, true);
+    both.forbidden.set(0, 0, true);
+    assert_eq!(board.solve_constrained(&both), None);
+
+    // A required obstacle, which pressing does nothing to.
+    let holes = Ruleset::parse("holes=800", 3, 3).unwrap();
+    let board = Board::new(3, 3).with_ruleset(holes);
+    let constraints = PressConstraints::parse("a1", 3, 3).unwrap();
+    assert_eq!(board.solve_constrained(&constraints), None);
+}
+
+#[test]
+fn later_pins_replace_earlier_ones_and_read_back() {
+    let constraints = PressConstraints::parse("a1 b2,!a1 !c3 c3", 3, 3).unwrap();
+    assert_eq!(constraints.to_string(), "b2 c3 !a1");
+    assert_eq!(
+        PressConstraints::parse(&constraints.to_string(), 3, 3),
+        Ok(constraints)
+    );
+    assert!(PressConstraints::parse("d1", 3, 3).is_err());
+    assert!(PressConstraints::parse("!", 3, 3).is_err());
+}
@@ -0,0 +1,53 @@
+use lightsout::{
+    coop::{Conflict, SharedBoard},
+    Board,
+};
+
+#[test]
+fn stale_presses_of_other_cells_still_count() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let mut shared = SharedBoard::new(board.clone());
+    // Both players saw the board as dealt; presses commute, so both land.
+    assert_eq!(shared.press(1, 0, 0, Some(0)), Ok(1));
+    assert_eq!(shared.press(2, 2, 3, Some(0)), Ok(2));
+    assert_eq!(shared.press(3, 4, 4, None), Ok(3));
+    assert!(shared.board().is_solved());
+    assert_eq!(shared.start(), &board);
+    let tally: Vec<_> = shared.tally().into_iter().collect();
+    assert_eq!(tally, [(1, 1), (2, 1), (3, 1)]);
+    assert_eq!(shared.press(1, 1, 1, None), Err(Conflict::Cleared));
+}
+
+#[test]
+fn clashing_presses_are_refused() {
+    let mut shared = SharedBoard::new(Board::from_code("3x3-5d0").unwrap());
+    assert_eq!(shared.press(1, 0, 0, Some(0)), Ok(1));
+    assert_eq!(
+        shared.press(2, 0, 0, Some(0)),
+        Err(Conflict::Pressed { player: 1 })
+    );
+    // Having seen the first press, pressing it again is deliberate.
+    assert_eq!(shared.press(2, 0, 0, Some(1)), Ok(2));
+    // So is pressing a cell again they pressed themselves.
+    assert_eq!(shared.press(2, 0, 0, Some(1)), Ok(3));
+    assert_eq!(shared.press(1, 3, 0, None), Err(Conflict::NotPressable));
+    assert_eq!(shared.press(1, 1, 1, Some(9)), Err(Conflict::Unknown));
+    assert_eq!(shared.version(), 3);
+}
+
+#[test]
+fn resets_and_new_boards_outdate_earlier_versions() {
+    let start = Board::from_code("3x3-5d0").unwrap();
+    let mut shared = SharedBoard::new(start.clone());
+    shared.press(1, 0, 0, None).unwrap();
+    assert_eq!(shared.reset(), 2);
+    assert_eq!(shared.board(), &start);
+    assert!(shared.tally().is_empty());
+    assert_eq!(shared.press(1, 1, 1, Some(1)), Err(Conflict::Replaced));
+    assert_eq!(shared.press(1, 1, 1, Some(2)), Ok(3));
+
+    let next = Board::from_code("3x3-800").unwrap();
+    assert_eq!(shared.replace(next.clone()), 4);
+    assert_eq!((shared.board(), shared.start()), (&next, &next));
+    assert_eq!(shared.press(2, 0, 0, Some(3)), Err(Conflict::Replaced));
+}
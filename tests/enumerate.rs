@@ -0,0 +1,26 @@
+use std::collections::HashSet;
+
+use lightsout::Board;
+
+#[test]
+fn every_solvable_board_comes_once() {
+    for (width, height) in [(3, 3), (4, 4), (2, 5)] {
+        let boards: Vec<Board> = Board::enumerate_solvable(width, height).collect();
+        let kernel = Board::new(width, height).kernel_dimension();
+        assert_eq!(boards.len(), 1 << (width * height - kernel));
+        assert!(boards.iter().all(|board| board.solve_linear().is_some()));
+        let distinct: HashSet<&Board> = boards.iter().collect();
+        assert_eq!(distinct.len(), boards.len());
+    }
+}
+
+#[test]
+fn chunks_match_the_full_sequence() {
+    let all: Vec<Board> = Board::enumerate_solvable(4, 4).collect();
+    let chunk: Vec<Board> = Board::enumerate_solvable(4, 4).skip(1000).take(50).collect();
+    assert_eq!(chunk, all[1000..1050]);
+    let boards = Board::enumerate_solvable(4, 4);
+    assert_eq!(boards.total(), Some(all.len() as u128));
+    assert_eq!(boards.get(1234).as_ref(), Some(&all[1234]));
+    assert_eq!(boards.get(all.len() as u128), None);
+}
@@ -0,0 +1,63 @@
+use lightsout::{search::StateTable, Board, Ruleset};
+
+/// Every board of one size, by its cells as the bits of an index.
+fn every_board(width: usize, height: usize) -> impl Iterator<Item = Board> {
+    (0..1usize << (width * height)).map(move |index| {
+        let mut board = Board::new(width, height);
+        for cell in 0..width * height {
+            board.set(cell % width, cell / width, index & (1 << cell) != 0);
+        }
+        board
+    })
+}
+
+#[test]
+fn table_distances_match_the_optimal_length() {
+    for (width, height) in [(3, 3), (2, 4)] {
+        let table = StateTable::new(width, height).unwrap();
+        let mut solvable = 0;
+        for board in every_board(width, height) {
+            let distance = table.distance(&board);
+            assert_eq!(distance, board.optimal_length(), "{}", board.to_code());
+            let Some(distance) = distance else {
+                assert_eq!(table.solve(&board), None);
+                assert!(table.minimal_solutions(&board).is_empty());
+                continue;
+            };
+            solvable += 1;
+            let presses = table.solve(&board).unwrap();
+            assert_eq!(presses.len(), distance, "{}", board.to_code());
+            assert!(board.verify(&presses), "{}", board.to_code());
+            let minimal = table.minimal_solutions(&board);
+            assert!(minimal.contains(&board.solve_linear().unwrap()));
+            for solution in minimal {
+                assert_eq!(solution.count(), distance);
+                assert!(board.verify(&solution.positions()));
+            }
+        }
+        assert_eq!(table.reachable(), solvable);
+    }
+}
+
+#[test]
+fn one_table_serves_every_goal() {
+    let rules = Ruleset::parse("goal=a80", 3, 3).unwrap();
+    let table = StateTable::with_ruleset(3, 3, &rules).unwrap();
+    for board in every_board(3, 3) {
+        let board = board.with_ruleset(rules.clone());
+        assert!(table.covers(&board));
+        assert_eq!(
+            table.distance(&board),
+            board.optimal_length(),
+            "{}",
+            board.to_code()
+        );
+    }
+    assert!(!table.covers(&Board::new(3, 3).with_ruleset(Ruleset::parse("wrap", 3, 3).unwrap())));
+}
+
+#[test]
+fn boards_past_the_cell_limit_get_no_table() {
+    assert!(StateTable::new(5, 6).is_none());
+    assert!(StateTable::new(0, 3).is_none());
+}
@@ -0,0 +1,55 @@
+use lightsout::{
+    search::{BucketFrontier, FifoFrontier, Frontier, LifoFrontier, PriorityFrontier, SearchState},
+    Board,
+};
+
+/// 3x1 boards with 0, 1, 2 and 3 lit cells, pushed in a scrambled order.
+fn boards() -> Vec<Board> {
+    ["#.#", "...", "###", "#.."]
+        .iter()
+        .map(|row| row.parse().unwrap())
+        .collect()
+}
+
+fn drain<F: Frontier<Board>>(mut frontier: F) -> Vec<usize> {
+    for board in boards() {
+        frontier.push(SearchState::from(board));
+    }
+    assert_eq!(frontier.len(), 4);
+    let mut scores = Vec::new();
+    while let Some(state) = frontier.pop() {
+        scores.push(state.score);
+    }
+    assert!(frontier.is_empty());
+    scores
+}
+
+#[test]
+fn score_ordered_frontiers_pop_fewest_lit_first() {
+    assert_eq!(drain(PriorityFrontier::default()), [3, 2, 1, 0]);
+    assert_eq!(drain(BucketFrontier::default()), [3, 2, 1, 0]);
+}
+
+#[test]
+fn fifo_and_lifo_follow_push_order() {
+    assert_eq!(drain(FifoFrontier::default()), [1, 3, 0, 2]);
+    assert_eq!(drain(LifoFrontier::default()), [2, 0, 3, 1]);
+}
+
+fn top<F: Frontier<Board>>(mut frontier: F) -> Vec<usize> {
+    for board in boards() {
+        frontier.push(SearchState::from(board));
+    }
+    let top = frontier.top(3).iter().map(|state| state.score).collect();
+    // Looking doesn't take anything off.
+    assert_eq!(frontier.len(), 4);
+    top
+}
+
+#[test]
+fn top_lists_entries_in_pop_order() {
+    assert_eq!(top(PriorityFrontier::default()), [3, 2, 1]);
+    assert_eq!(top(BucketFrontier::default()), [3, 2, 1]);
+    assert_eq!(top(FifoFrontier::default()), [1, 3, 0]);
+    assert_eq!(top(LifoFrontier::default()), [2, 0, 3]);
+}
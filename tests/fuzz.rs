@@ -0,0 +1,45 @@
+//! The fuzz targets run over their seed inputs in `fuzz/seeds`, the same
+//! files `cargo fuzz run <target> fuzz/seeds/<target>` starts from.
+
+use std::{fs, path::Path};
+
+use lightsout::fuzz;
+
+fn seeds(target: &str) -> Vec<(String, Vec<u8>)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/seeds").join(target);
+    let mut seeds: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, fs::read(path).unwrap())
+        })
+        .collect();
+    seeds.sort();
+    assert!(!seeds.is_empty(), "no seeds for {target}");
+    seeds
+}
+
+#[test]
+fn code_decoder_seeds() {
+    for (_, data) in seeds("code_decoder") {
+        fuzz::code_decoder(&data);
+    }
+}
+
+#[test]
+fn solution_verifier_seeds() {
+    for (_, data) in seeds("solution_verifier") {
+        fuzz::solution_verifier(&data);
+    }
+}
+
+/// A seed that doesn't decode only ever tests the error path.
+#[test]
+fn solution_verifier_seeds_decode() {
+    for (name, data) in seeds("solution_verifier") {
+        let code = String::from_utf8_lossy(&data);
+        let code = code.lines().next().unwrap();
+        assert!(lightsout::Board::from_code(code).is_ok(), "{name}");
+    }
+}
@@ -0,0 +1,77 @@
+use lightsout::{
+    game::{Engine, Evaluation, Game, Player},
+    generate::generate_boards,
+    Board,
+};
+
+#[test]
+fn players_alternate_until_the_board_is_clear() {
+    let mut game = Game::new(Board::from_code("3x3-5d0").unwrap());
+    assert_eq!(game.to_move(), Player::First);
+    assert!(game.press(0, 0));
+    assert_eq!(game.to_move(), Player::Second);
+    // Pressing a cell again undoes it, but still takes a turn.
+    assert!(game.press(0, 0));
+    assert_eq!((game.to_move(), game.presses()), (Player::First, 2));
+    assert_eq!(game.winner(), None);
+
+    assert!(game.press(1, 1));
+    assert_eq!(game.winner(), Some(Player::First));
+    assert!(game.is_over());
+    assert!(!game.press(0, 0));
+
+    assert_eq!(Game::new(Board::new(3, 3)).winner(), None);
+}
+
+/// The value of `game` to the player to move, searching every line of
+/// play `depth` presses deep.
+fn every_line(game: &Game, depth: usize) -> Evaluation {
+    if depth == 0 {
+        return Evaluation::Even;
+    }
+    let rank = |value| match value {
+        Evaluation::Win { plies } => 100 - plies as i32,
+        Evaluation::Even => 0,
+        Evaluation::Loss { plies } => plies as i32 - 100,
+    };
+    game.moves()
+        .into_iter()
+        .map(|(x, y)| {
+            let mut next = game.clone();
+            next.press(x, y);
+            match next.winner() {
+                Some(_) => Evaluation::Win { plies: 1 },
+                None => match every_line(&next, depth - 1) {
+                    Evaluation::Win { plies } => Evaluation::Loss { plies: plies + 1 },
+                    Evaluation::Loss { plies } => Evaluation::Win { plies: plies + 1 },
+                    Evaluation::Even => Evaluation::Even,
+                },
+            }
+        })
+        .max_by_key(|&value| rank(value))
+        .unwrap_or(Evaluation::Even)
+}
+
+#[test]
+fn the_engine_agrees_with_every_line_of_play() {
+    for generated in generate_boards(3, 3, 1, 10, 1, Default::default()) {
+        let game = Game::new(generated.board);
+        let value = Engine::new(3).evaluate(&game);
+        assert_eq!(value, every_line(&game, 3), "{}", game.board().to_code());
+    }
+}
+
+#[test]
+fn the_engine_takes_wins_and_avoids_handing_them_over() {
+    let mut engine = Engine::new(4);
+    let game = Game::new(Board::from_code("3x3-5d0").unwrap());
+    assert_eq!(engine.best_move(&game), Some(((1, 1), Evaluation::Win { plies: 1 })));
+
+    // Two presses from clear: either press of the solution would leave the
+    // other for the opponent.
+    let mut game = Game::new(Board::from_code("3x3-5d0").unwrap().clone_toggle(0, 0));
+    let (cell, value) = engine.best_move(&game).unwrap();
+    assert_eq!(value, Evaluation::Even);
+    game.press(cell.0, cell.1);
+    assert_ne!(engine.evaluate(&game), Evaluation::Win { plies: 1 });
+}
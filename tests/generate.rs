@@ -0,0 +1,49 @@
+use lightsout::{
+    generate::{generate_boards, Shape, Target},
+    BitMask, Board,
+};
+
+fn shaped(width: usize, height: usize, shape: Shape) -> Vec<Board> {
+    let target = Target {
+        shape: Some(shape),
+        ..Target::default()
+    };
+    generate_boards(width, height, 0, 5, 1, target)
+        .map(|generated| generated.board)
+        .collect()
+}
+
+fn distance(board: &Board, image: &BitMask) -> usize {
+    board.cells().filter(|&((x, y), lit)| lit != image.get(x, y)).count()
+}
+
+#[test]
+fn mirrored_and_border_boards_keep_their_shape() {
+    for board in shaped(5, 4, Shape::Mirrored) {
+        assert!(board.solve_linear().is_some());
+        for ((x, y), lit) in board.cells() {
+            assert_eq!(board.get(4 - x, y), lit, "{board}");
+            assert_eq!(board.get(x, 3 - y), lit, "{board}");
+        }
+    }
+    for board in shaped(5, 5, Shape::Border) {
+        assert!(board.solve_linear().is_some());
+        assert!(board.cells().all(|((x, y), lit)| !lit || x % 4 == 0 || y % 4 == 0), "{board}");
+    }
+}
+
+#[test]
+fn images_are_repaired_as_little_as_possible() {
+    let mut image = BitMask::new(4, 4);
+    image.set(0, 0, true);
+    image.set(2, 1, true);
+    let closest = Board::enumerate_solvable(4, 4)
+        .map(|board| distance(&board, &image))
+        .min()
+        .unwrap();
+    assert!(closest > 0);
+    for board in shaped(4, 4, Shape::Image(image.clone())) {
+        assert!(board.solve_linear().is_some());
+        assert_eq!(distance(&board, &image), closest, "{board}");
+    }
+}
@@ -0,0 +1,42 @@
+use lightsout::{core::gf2::BitMatrix, Board, Ruleset};
+
+fn matrix(rows: &[&str]) -> BitMatrix {
+    let mut matrix = BitMatrix::new(rows.len(), rows[0].len());
+    for (r, row) in rows.iter().enumerate() {
+        for (c, bit) in row.chars().enumerate() {
+            matrix.set(r, c, bit == '1');
+        }
+    }
+    matrix
+}
+
+#[test]
+fn classic_toggle_matrix_matches_the_kernel() {
+    let toggles = BitMatrix::toggles(5, 5, &Ruleset::default());
+    assert_eq!(toggles.rank(), 23);
+    let nullspace = toggles.nullspace();
+    assert_eq!(nullspace.len(), Board::new(5, 5).kernel_dimension());
+    for vector in &nullspace {
+        assert!(toggles.apply(vector).iter().all(|&bit| !bit));
+    }
+}
+
+#[test]
+fn solve_gives_a_preimage_or_none() {
+    let a = matrix(&["110", "011", "101"]);
+    assert_eq!(a.rank(), 2);
+    let solution = a.solve(&[true, false, true]).unwrap();
+    assert_eq!(a.apply(&solution), [true, false, true]);
+    assert_eq!(a.solve(&[true, false, false]), None);
+}
+
+#[test]
+fn multiply_composes_and_identity_is_neutral() {
+    let a = matrix(&["110", "011"]);
+    let b = matrix(&["10", "11", "01"]);
+    assert_eq!(a.multiply(&b), matrix(&["01", "10"]));
+    assert_eq!(BitMatrix::identity(2).multiply(&a), a);
+    let vector = [true, true, false];
+    assert_eq!(a.multiply(&b).apply(&[true, false]), a.apply(&b.apply(&[true, false])));
+    assert_eq!(a.apply(&vector), [false, true]);
+}
@@ -0,0 +1,67 @@
+use lightsout::{
+    generate::generate_boards,
+    search::{a_star, MultiGoalBoard},
+    BitMask, Board, Goals,
+};
+
+#[test]
+fn the_cheapest_goal_wins() {
+    // Every light on but one press, so lights on is a press away.
+    let board = Board::from_code("5x5-ffffff8").unwrap().clone_toggle(2, 2);
+    let (presses, reached) = board.solve_goals(&Goals::off_or_on(5, 5)).unwrap();
+    assert_eq!((presses.count(), reached), (1, 1));
+
+    // Towards lights off alone it takes far more.
+    let off = Goals::parse("off", 5, 5).unwrap();
+    let (presses, reached) = board.solve_goals(&off).unwrap();
+    assert_eq!(Some(presses.count()), board.optimal_length());
+    assert_eq!(reached, 0);
+}
+
+#[test]
+fn unsolvable_towards_one_goal_can_reach_another() {
+    // A lone corner light can't be cleared on 5x5, but it can be turned
+    // into the board a press in the middle makes.
+    let board = Board::from_code("5x5-8000000").unwrap();
+    assert_eq!(board.solve_linear(), None);
+    let pressed = board.clone_toggle(2, 2);
+    let mut pattern = BitMask::new(5, 5);
+    for (x, y) in (0..5).flat_map(|y| (0..5).map(move |x| (x, y))) {
+        pattern.set(x, y, pressed.get(x, y));
+    }
+    let goals = Goals::new(vec![BitMask::new(5, 5), pattern]);
+    let (presses, reached) = board.solve_goals(&goals).unwrap();
+    assert_eq!((presses.positions(), reached), (vec![(2, 2)], 1));
+    assert_eq!(goals.reached(&pressed), Some(1));
+    assert!(goals.retarget(&board, reached).verify(&presses.positions()));
+}
+
+#[test]
+fn search_finds_goals_as_short_as_linear() {
+    let goals = Goals::parse("off on rows", 4, 4).unwrap();
+    for generated in generate_boards(4, 4, 7, 20, 1, Default::default()) {
+        let board = generated.board;
+        let (linear, _) = board.solve_goals(&goals).unwrap();
+        let (found, _) = a_star(MultiGoalBoard::new(&board, &goals), linear.count());
+        let found = found.unwrap();
+        let presses = found.history.len() + usize::from(found.latest_move_index.is_some());
+        assert_eq!(presses, linear.count(), "{}", board.to_code());
+        assert!(goals.reached(found.latest.board()).is_some());
+    }
+}
+
+#[test]
+fn goals_skip_obstacles_and_read_back() {
+    let board = Board::from_code("3x3-000:holes=100").unwrap();
+    let goals = Goals::parse("on", 3, 3).unwrap();
+    // The hole can't be lit, so every other cell on is the goal.
+    let target = goals.retarget(&board, 0);
+    assert_eq!(target.ruleset().goal.as_ref().unwrap().count(), 8);
+
+    let goals = Goals::parse("off, on 1f0", 3, 3).unwrap();
+    assert_eq!(goals.patterns.len(), 3);
+    assert_eq!(goals.to_string(), "off on 1f0");
+    assert_eq!(Goals::parse(&goals.to_string(), 3, 3), Ok(goals));
+    assert!(Goals::parse("", 3, 3).is_err());
+    assert!(Goals::parse("12345", 3, 3).is_err());
+}
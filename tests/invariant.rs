@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use lightsout::{core::Invariant, BitMask, Board, Ruleset};
+
+/// Whether pressing any cell leaves the invariant's total where it was.
+fn preserved(invariant: &Invariant, rules: &Ruleset) -> bool {
+    let (width, height) = (invariant.width, invariant.height);
+    (0..width * height).all(|press| {
+        let added: u32 = rules
+            .affected(press % width, press / width, width, height)
+            .map(|(x, y)| invariant.weight(x, y))
+            .sum();
+        added.is_multiple_of(invariant.modulus)
+    })
+}
+
+#[test]
+fn only_unsolvable_boards_break_an_invariant() {
+    for rules in [
+        "classic",
+        "square",
+        "wrap+diagonal",
+        "holes=4400000",
+        "pattern=0.0_1.0_0.1",
+    ] {
+        let rules = Ruleset::parse(rules, 5, 5).unwrap();
+        for seed in 0..40 {
+            let mut board = Board::new(5, 5).with_ruleset(rules.clone());
+            board.randomize(seed);
+            match board.invariant() {
+                None => assert!(board.solve_linear().is_some(), "{rules} seed {seed}"),
+                Some(invariant) => {
+                    assert!(board.solve_linear().is_none(), "{rules} seed {seed}");
+                    assert_eq!((invariant.modulus, invariant.total), (2, 1));
+                    assert!(preserved(&invariant, &rules), "{rules} seed {seed}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn a_lone_corner_light_is_one_of_the_quiet_patterns() {
+    let mut board = Board::new(5, 5);
+    board.set(0, 0, true);
+    let invariant = board.invariant().unwrap();
+    assert_eq!(invariant.cells().len(), 12);
+    assert!(invariant.cells().contains(&(0, 0)));
+    assert!(invariant
+        .to_string()
+        .starts_with("every press toggles an even number of a1 "));
+}
+
+#[test]
+fn cells_nothing_toggles_are_named() {
+    // Every press toggles the cell to its right, so nothing reaches a1.
+    let rules = Ruleset::parse("pattern=1.0", 3, 3).unwrap();
+    let mut board = Board::new(3, 3).with_ruleset(rules);
+    board.set(0, 0, true);
+    let invariant = board.invariant().unwrap();
+    assert_eq!(invariant.cells(), [(0, 0)]);
+    assert_eq!(
+        invariant.to_string(),
+        "no press toggles a1, and it differs from the goal"
+    );
+}
+
+#[test]
+fn obstacles_are_left_out_of_the_system() {
+    // With b1 and a2 missing, pressing a1 only toggles a1 itself.
+    let mut holes = BitMask::new(3, 3);
+    holes.set(1, 0, true);
+    holes.set(0, 1, true);
+    let rules = Ruleset {
+        obstacles: Some(holes),
+        ..Ruleset::CLASSIC
+    };
+    let mut board = Board::new(3, 3).with_ruleset(rules);
+    board.set(0, 0, true);
+    assert_eq!(board.invariant(), None);
+    assert_eq!(board.solve_linear().unwrap().positions(), [(0, 0)]);
+}
+
+#[test]
+fn two_states_agree_with_the_board() {
+    for rules in ["classic", "holes=4400000", "wrap+square"] {
+        let rules = Ruleset::parse(rules, 5, 5).unwrap();
+        for seed in 0..20 {
+            let mut board = Board::new(5, 5).with_ruleset(rules.clone());
+            board.randomize(seed);
+            let cells: Vec<u8> = board.cells().map(|(_, lit)| u8::from(lit)).collect();
+            let invariant = rules.invariant(5, 5, &cells);
+            assert_eq!(invariant.is_some(), board.invariant().is_some());
+            if let Some(invariant) = invariant {
+                assert!(preserved(&invariant, &rules));
+            }
+        }
+    }
+}
+
+/// Every state of a `width` x `height` board of `rules` that presses can
+/// reach from all zero, by breadth-first search.
+fn reachable(rules: &Ruleset, width: usize, height: usize) -> HashSet<Vec<u8>> {
+    let states = rules.states;
+    let mut seen = HashSet::from([vec![0; width * height]]);
+    let mut queue = vec![vec![0; width * height]];
+    while let Some(cells) = queue.pop() {
+        for press in 0..width * height {
+            let mut next = cells.clone();
+            for (x, y) in rules.affected(press % width, press / width, width, height) {
+                next[y * width + x] = (next[y * width + x] + 1) % states;
+            }
+            if seen.insert(next.clone()) {
+                queue.push(next);
+            }
+        }
+    }
+    seen
+}
+
+#[test]
+fn more_states_are_solved_mod_each_prime_power() {
+    for (states, width, height) in [(3, 2, 2), (4, 2, 2), (6, 2, 2), (3, 3, 3), (4, 3, 2)] {
+        let rules = Ruleset::parse(&format!("states={states}"), width, height).unwrap();
+        let reachable = reachable(&rules, width, height);
+        let n = width * height;
+        let mut unsolvable = 0;
+        for index in 0..(states as usize).pow(n as u32) {
+            let cells: Vec<u8> = (0..n)
+                .map(|cell| (index / (states as usize).pow(cell as u32) % states as usize) as u8)
+                .collect();
+            // Presses have to undo each cell's state to reach all zero.
+            let undo: Vec<u8> = cells
+                .iter()
+                .map(|&state| (states - state) % states)
+                .collect();
+            match rules.invariant(width, height, &cells) {
+                None => assert!(reachable.contains(&undo), "{cells:?} with {states} states"),
+                Some(invariant) => {
+                    unsolvable += 1;
+                    assert!(!reachable.contains(&undo), "{cells:?} with {states} states");
+                    assert_eq!(invariant.modulus, u32::from(states));
+                    assert_ne!(invariant.total, 0);
+                    assert!(
+                        preserved(&invariant, &rules),
+                        "{cells:?} with {states} states"
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            unsolvable + reachable.len(),
+            (states as usize).pow(n as u32)
+        );
+    }
+}
+
+#[test]
+fn a_prime_factor_of_the_states_can_be_what_fails() {
+    // Mod 2 every 2x2 board can be cleared, but mod 3 the total of all four
+    // cells never changes, so mod 6 it fails there.
+    let rules = Ruleset::parse("states=6", 2, 2).unwrap();
+    let invariant = rules.invariant(2, 2, &[1, 0, 0, 0]).unwrap();
+    assert_eq!(invariant.weights, [2, 2, 2, 2]);
+    assert_eq!(invariant.total, 4);
+    assert_eq!(
+        invariant.to_string(),
+        "every press adds a multiple of 6 to 2xa1 + 2xb1 + 2xa2 + 2xb2, but the differences \
+         from the goal add up to 4 mod 6"
+    );
+}
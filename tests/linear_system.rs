@@ -0,0 +1,80 @@
+use lightsout::{core::LinearSystem, Board, CancellationToken, Ruleset};
+
+#[test]
+fn one_system_solves_every_board_with_its_rules() {
+    let rules = Ruleset::parse("wrap+square", 5, 4).unwrap();
+    let system = LinearSystem::new(5, 4, &rules);
+    for seed in 0..50 {
+        let mut board = Board::new(5, 4).with_ruleset(rules.clone());
+        board.randomize(seed);
+        assert!(system.covers(&board));
+        assert_eq!(system.solve(&board), board.solve_linear());
+    }
+    assert_eq!(system.kernel_dimension(), Board::new(5, 4).with_ruleset(rules).kernel_dimension());
+    assert!(!system.covers(&Board::new(5, 4)));
+}
+
+#[test]
+fn optimal_length_matches_the_shortest_solution() {
+    for board in Board::enumerate_solvable(4, 4).step_by(7) {
+        let shortest = board.solve_linear().map(|solution| solution.count());
+        assert_eq!(board.optimal_length(), shortest);
+    }
+    let mut unsolvable = Board::new(4, 4);
+    unsolvable.set(0, 0, true);
+    assert_eq!(unsolvable.optimal_length(), None);
+}
+
+#[test]
+fn anytime_solutions_shorten_until_the_optimum() {
+    let cancel = CancellationToken::new();
+    for board in Board::enumerate_solvable(4, 4).step_by(31) {
+        let mut found = Vec::new();
+        let best = board
+            .solve_anytime(&cancel, |solution, tried| found.push((solution.clone(), tried)))
+            .unwrap();
+        assert_eq!(found[0], (board.solve_chase().unwrap(), 1));
+        assert!(found.windows(2).all(|pair| pair[1].0.count() < pair[0].0.count()));
+        assert!(found.windows(2).all(|pair| pair[1].1 > pair[0].1));
+        assert_eq!(Some(best.count()), board.optimal_length());
+        assert!(board.verify(&best.positions()));
+    }
+}
+
+#[test]
+fn cancelled_anytime_solves_keep_the_first_solution() {
+    let board = Board::enumerate_solvable(4, 4).nth(100).unwrap();
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let mut improvements = 0;
+    let best = board.solve_anytime(&cancel, |_, _| improvements += 1);
+    assert_eq!(best, board.solve_chase());
+    assert_eq!(improvements, 1);
+    let unsolvable = Board::from_code("5x5-8000000").unwrap();
+    assert_eq!(unsolvable.solve_anytime(&cancel, |_, _| ()), None);
+}
+
+#[test]
+fn kernels_too_large_to_search_settle_for_the_first_solution() {
+    // Presses only toggle the cells either side, so pressing both ends of
+    // a row changes nothing: one kernel vector for each of the 200 rows.
+    let code = format!("3x200-{}:pattern=1.0_-1.0", "0".repeat(150));
+    let mut board = Board::from_code(&code).unwrap();
+    board.set(1, 7, true);
+    board.set(1, 150, true);
+    assert!(board.kernel_dimension() > 127);
+    let first = board.solve_chase().unwrap();
+    assert_eq!(board.solve_linear(), Some(first.clone()));
+    assert_eq!(board.optimal_length(), Some(first.count()));
+    assert_eq!(board.solutions().collect::<Vec<_>>(), vec![first.clone()]);
+    assert_eq!(board.minimal_solutions(), vec![first.clone()]);
+    assert!(board.verify(&first.positions()));
+
+    let cancel = CancellationToken::new();
+    let mut tried = 0;
+    board.solve_anytime(&cancel, |_, _| {
+        tried += 1;
+        cancel.cancel();
+    });
+    assert_eq!(tried, 1);
+}
@@ -0,0 +1,179 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+fn temp(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("lightsout-manifest-{}-{name}", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn lightsout(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// The csv rows of a solve without their timings.
+fn rows(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|row| row.rsplit_once(',').unwrap().0.to_string())
+        .collect()
+}
+
+fn boards() -> PathBuf {
+    let path = temp("boards.txt");
+    fs::write(&path, "5x5-c408f38\n\n5x5-2e99a38\n\n5x5-00008e0\n").unwrap();
+    path
+}
+
+#[test]
+fn interrupted_solves_pick_up_where_they_stopped() {
+    let (input, manifest) = (boards(), temp("solve"));
+    let args = |manifest: &PathBuf| {
+        let (input, manifest) = (input.to_str().unwrap(), manifest.to_str().unwrap());
+        [
+            "solve",
+            "--input",
+            input,
+            "--output",
+            "csv",
+            "--manifest",
+            manifest,
+        ]
+        .map(String::from)
+    };
+    let run = |args: &[String]| lightsout(&args.iter().map(String::as_str).collect::<Vec<_>>());
+    let first = run(&args(&manifest));
+    assert!(first.status.success());
+    let text = fs::read_to_string(&manifest).unwrap();
+    assert_eq!(text.lines().count(), 4);
+
+    // As if killed while writing the last board's result.
+    let cut = text.trim_end().len() - 10;
+    fs::write(&manifest, &text[..cut]).unwrap();
+    let resumed = run(&args(&manifest));
+    assert!(resumed.status.success());
+    assert_eq!(rows(&resumed), rows(&first));
+    let text = fs::read_to_string(&manifest).unwrap();
+    assert_eq!(text.lines().count(), 4);
+    // The first two came back from the manifest, timings and all.
+    let first = String::from_utf8_lossy(&first.stdout).to_string();
+    let resumed = String::from_utf8_lossy(&resumed.stdout).to_string();
+    assert_eq!(first.lines().nth(2), resumed.lines().nth(2));
+}
+
+#[test]
+fn manifests_only_resume_the_job_they_came_from() {
+    let (input, manifest) = (boards(), temp("mismatch"));
+    let (input, path) = (input.to_str().unwrap(), manifest.to_str().unwrap());
+    assert!(
+        lightsout(&["solve", "--input", input, "--manifest", path, "-q"])
+            .status
+            .success()
+    );
+
+    let other = temp("other.txt");
+    fs::write(&other, "5x5-00008e0\n").unwrap();
+    let other = other.to_str().unwrap();
+    let output = lightsout(&["solve", "--input", other, "--manifest", path]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("the manifest has board"));
+
+    let output = lightsout(&["analyze", "prospect", "--seeds", "10", "--manifest", path]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is the manifest for solve"));
+}
+
+#[test]
+fn resumed_sweeps_find_what_a_whole_one_does() {
+    let manifest = temp("prospect");
+    let path = manifest.to_str().unwrap();
+    let sweep = [
+        "analyze",
+        "prospect",
+        "--width",
+        "3",
+        "--height",
+        "3",
+        "--hardest",
+    ];
+    let args = |seeds: &str, manifest: Option<&str>| {
+        let mut args = sweep.to_vec();
+        args.extend(["--seeds", seeds]);
+        args.extend(
+            manifest
+                .map(|path| ["--manifest", path])
+                .into_iter()
+                .flatten(),
+        );
+        args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>()
+    };
+    let run = |args: Vec<String>| {
+        let output = lightsout(&args.iter().map(String::as_str).collect::<Vec<_>>());
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let whole = run(args("25000", None));
+
+    run(args("15000", Some(path)));
+    assert_eq!(fs::read_to_string(&manifest).unwrap().lines().count(), 3);
+    // The first chunk is reused and the second, cut short last time, redone.
+    assert_eq!(run(args("25000", Some(path))), whole);
+    assert_eq!(fs::read_to_string(&manifest).unwrap().lines().count(), 5);
+    assert_eq!(run(args("25000", Some(path))), whole);
+    assert_eq!(fs::read_to_string(&manifest).unwrap().lines().count(), 5);
+}
+
+#[test]
+fn batched_solves_resume_part_way_through_a_chunk() {
+    let (input, manifest) = (temp("many.txt"), temp("batched"));
+    let codes: Vec<String> = (0..1100)
+        .map(|seed| {
+            let mut board = lightsout::Board::new(5, 5);
+            board.randomize(seed);
+            board.to_code()
+        })
+        .collect();
+    fs::write(&input, codes.join("\n\n")).unwrap();
+    let (input, path) = (input.to_str().unwrap(), manifest.to_str().unwrap());
+    let solve = |algorithm: &str| {
+        lightsout(&[
+            "solve",
+            "--input",
+            input,
+            "--output",
+            "csv",
+            "--algorithm",
+            algorithm,
+            "--manifest",
+            path,
+        ])
+    };
+    // Three quarters of random 5x5 boards can't be cleared.
+    let first = solve("bitsliced");
+    assert_eq!(first.status.code(), Some(1));
+    let text = fs::read_to_string(&manifest).unwrap();
+    assert_eq!(text.lines().count(), 1101);
+    assert!(text.starts_with("# lightsout manifest for solve with --algorithm bitsliced --prefer "));
+
+    // Cut off in the middle of the second chunk.
+    let kept: Vec<&str> = text.lines().take(1 + 700).collect();
+    fs::write(&manifest, kept.join("\n") + "\n").unwrap();
+    let resumed = solve("bitsliced");
+    assert_eq!(resumed.status.code(), Some(1));
+    assert_eq!(rows(&resumed), rows(&first));
+    assert_eq!(fs::read_to_string(&manifest).unwrap().lines().count(), 1101);
+
+    let other = solve("linear");
+    assert_eq!(other.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&other.stderr);
+    assert!(
+        stderr.contains("is the manifest for solve with --algorithm bitsliced"),
+        "{stderr}"
+    );
+}
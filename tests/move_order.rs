@@ -0,0 +1,127 @@
+use std::{env, fs, process::Command};
+
+use lightsout::{
+    search::{a_star, MoveOrder, OrderedBoard, Search},
+    Board,
+};
+
+const ORDERS: [MoveOrder; 5] = [
+    MoveOrder::Lexical,
+    MoveOrder::LitReduction,
+    MoveOrder::Chase,
+    MoveOrder::Random(0),
+    MoveOrder::Random(7),
+];
+
+#[test]
+fn orders_read_back_what_they_write() {
+    for order in ORDERS {
+        assert_eq!(order.to_string().parse::<MoveOrder>(), Ok(order));
+    }
+    assert_eq!("random( 12 )".parse(), Ok(MoveOrder::Random(12)));
+    assert!("random".parse::<MoveOrder>().is_err());
+    assert!("random(x)".parse::<MoveOrder>().is_err());
+    assert!("alphabetical".parse::<MoveOrder>().is_err());
+}
+
+#[test]
+fn every_order_finds_a_shortest_solution() {
+    for code in ["5x5-c408f38", "5x5-2e99a38", "5x5-00008e0"] {
+        let board = Board::from_code(code).unwrap();
+        let depth = board.depth_bound().unwrap();
+        for order in ORDERS {
+            let (found, _) = a_star(OrderedBoard::new(board.clone(), order), depth);
+            let found = found.unwrap();
+            assert!(found.latest.board().is_solved(), "{code} {order}");
+            assert_eq!(found.history.len() + 1, depth, "{code} {order}");
+        }
+    }
+}
+
+#[test]
+fn orders_only_rearrange_the_children() {
+    let board = Board::from_code("5x5-2e99a38").unwrap();
+    let mut expected: Vec<usize> = board.moves().into_iter().map(|(_, index)| index).collect();
+    expected.sort();
+    for order in ORDERS {
+        let ordered = OrderedBoard::new(board.clone(), order);
+        let indices: Vec<usize> = ordered
+            .moves()
+            .into_iter()
+            .map(|(_, index)| index)
+            .collect();
+        let mut sorted = indices.clone();
+        sorted.sort();
+        assert_eq!(sorted, expected, "{order}");
+        match order {
+            MoveOrder::Lexical => assert_eq!(indices, expected),
+            MoveOrder::Chase => {
+                let own: Vec<usize> = board.moves().into_iter().map(|(_, index)| index).collect();
+                assert_eq!(indices, own);
+            }
+            MoveOrder::LitReduction => {
+                let off: Vec<usize> = ordered
+                    .moves()
+                    .into_iter()
+                    .map(|(child, _)| child.board().mismatches())
+                    .collect();
+                assert!(off.windows(2).all(|pair| pair[0] >= pair[1]));
+            }
+            MoveOrder::Random(_) => {
+                let again = OrderedBoard::new(board.clone(), order).moves();
+                let again: Vec<usize> = again.into_iter().map(|(_, index)| index).collect();
+                assert_eq!(indices, again);
+            }
+        }
+    }
+}
+
+#[test]
+fn lit_reduction_counts_cells_off_the_goal() {
+    // Aiming for every light on, the centre press brings the most cells
+    // into line, though by lights left on it would be the last choice.
+    let board = Board::from_code("3x3-000:goal=ff8").unwrap();
+    let ordered = OrderedBoard::new(board, MoveOrder::LitReduction);
+    let moves = ordered.moves();
+    let (first, index) = moves.last().unwrap();
+    assert_eq!(*index, 4);
+    assert_eq!(first.board().mismatches(), 4);
+}
+
+#[test]
+fn ordered_warm_starts_are_profiled() {
+    let temp =
+        |name: &str| env::temp_dir().join(format!("lightsout-order-{}-{name}", std::process::id()));
+    let (input, profile) = (temp("boards.txt"), temp("profile"));
+    fs::write(&input, "5x5-2e99a38\n").unwrap();
+    let _ = fs::remove_file(&profile);
+    let output = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .args([
+            "solve",
+            "--algorithm",
+            "astar",
+            "--order",
+            "lexical",
+            "--input",
+        ])
+        .arg(&input)
+        .args([
+            "--warm-start",
+            "linear",
+            "--max-nodes",
+            "100000",
+            "--profile",
+        ])
+        .arg(&profile)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let written = fs::read_to_string(&profile).unwrap();
+    assert!(written.contains("explored"), "{written}");
+    fs::remove_file(input).unwrap();
+    fs::remove_file(profile).unwrap();
+}
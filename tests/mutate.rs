@@ -0,0 +1,57 @@
+use lightsout::{
+    core::LinearSystem,
+    mutate::{mutate, Mutation, Operator, OPERATORS},
+    Board,
+};
+
+fn solvable(board: &Board) -> bool {
+    LinearSystem::new(board.width(), board.height(), board.ruleset()).solvable(board)
+}
+
+#[test]
+fn mutants_stay_solvable() {
+    let board = Board::from_code("5x5-0000000").unwrap();
+    for seed in 0..20 {
+        let mutant = mutate(&board, OPERATORS, 4, seed).unwrap();
+        assert_eq!(mutant.mutations.len(), 4);
+        assert!(solvable(&mutant.board), "seed {seed}: {:?}", mutant.mutations);
+    }
+}
+
+#[test]
+fn recorded_presses_undo_press_mutations() {
+    let board: Board = "#.#\n.#.\n#.#".parse().unwrap();
+    let mutant = mutate(&board, &[Operator::Press], 5, 7).unwrap();
+    assert!(mutant.mutations.iter().all(|m| matches!(m, Mutation::Press(..))));
+    let mut undone = mutant.board.clone();
+    undone.apply_press_set(&mutant.presses());
+    assert_eq!(undone, board);
+}
+
+#[test]
+fn pairs_mirror_each_other() {
+    let board = Board::new(5, 5);
+    let mutant = mutate(&board, &[Operator::Pair], 3, 1).unwrap();
+    for mutation in &mutant.mutations {
+        let Mutation::Pair((x1, y1), (x2, y2)) = *mutation else {
+            panic!("expected a pair, got {mutation}");
+        };
+        let mirrored = [
+            (4 - x1, y1),
+            (x1, 4 - y1),
+            (4 - x1, 4 - y1),
+            (y1, x1),
+            (4 - y1, x1),
+            (y1, 4 - x1),
+            (4 - y1, 4 - x1),
+        ];
+        assert!(mirrored.contains(&(x2, y2)), "{mutation}");
+    }
+}
+
+#[test]
+fn unsolvable_boards_are_refused_and_missing_quiet_patterns_skipped() {
+    assert!(mutate(&Board::from_code("5x5-1000000").unwrap(), OPERATORS, 1, 0).is_none());
+    let mutant = mutate(&Board::new(3, 3), &[Operator::Quiet], 2, 0).unwrap();
+    assert!(mutant.mutations.is_empty());
+}
@@ -0,0 +1,55 @@
+use lightsout::{
+    search::{
+        search, search_observed, BucketFrontier, Observer, Search, SearchReport, SearchState,
+    },
+    Board, CancellationToken,
+};
+
+/// Records every hook call.
+#[derive(Default)]
+struct Log {
+    expanded: usize,
+    pushed: usize,
+    improvements: Vec<usize>,
+}
+
+impl Observer<Board> for Log {
+    fn on_expand(&mut self, _state: &SearchState<Board>, report: &SearchReport) {
+        self.expanded += 1;
+        assert_eq!(report.explored, self.expanded);
+    }
+
+    fn on_improvement(&mut self, state: &SearchState<Board>, _report: &SearchReport) {
+        self.improvements.push(state.score);
+    }
+
+    fn on_push(&mut self, _state: &SearchState<Board>) {
+        self.pushed += 1;
+    }
+}
+
+#[test]
+fn observers_see_the_search_as_it_runs() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let depth = board.depth_bound().unwrap();
+    let mut log = Log::default();
+    let cancel = CancellationToken::new();
+    let (found, report) = search_observed(
+        board.clone(),
+        depth,
+        BucketFrontier::default(),
+        &cancel,
+        &mut log,
+    );
+    let found = found.unwrap();
+    assert_eq!(log.expanded, report.explored);
+    // The initial state, then every child of an expanded state that hadn't
+    // been explored yet.
+    assert!(log.pushed > log.expanded);
+    assert!(log.improvements.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(log.improvements[0], board.score());
+
+    // Watching doesn't change what's found.
+    let (unwatched, _) = search(board, depth, BucketFrontier::default());
+    assert_eq!(unwatched.unwrap().history, found.history);
+}
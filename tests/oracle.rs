@@ -0,0 +1,82 @@
+use lightsout::{
+    core::oracle::{
+        check_child_lit_counts, check_order, check_row_press, minimize, minimize_presses,
+        reference_press,
+    },
+    Board, Ruleset,
+};
+
+#[test]
+fn fast_paths_agree_with_the_reference() {
+    for rules in ["classic", "wrap+square", "holes=4400000", "pattern=0.0_2.1"] {
+        let rules = Ruleset::parse(rules, 5, 5).unwrap();
+        for seed in 0..20 {
+            let mut board = Board::new(5, 5).with_ruleset(rules.clone());
+            board.randomize(seed);
+            for y in 0..5 {
+                check_row_press(&board, y, seed % 32);
+            }
+            let presses: Vec<_> = (0..8)
+                .map(|i| ((seed as usize + i * 3) % 5, i % 5))
+                .collect();
+            check_order(&board, &presses);
+            check_child_lit_counts(&board, board.lit_count(), &board.child_lit_counts());
+        }
+    }
+}
+
+#[test]
+fn reference_presses_toggle_what_the_rules_say() {
+    let board = Board::new(3, 3);
+    assert_eq!(reference_press(&board, 1, 1), board.clone_toggle(1, 1));
+    assert_eq!(reference_press(&board, 0, 0).lit_count(), 3);
+    let square = Board::new(3, 3).with_ruleset(Ruleset::parse("square", 3, 3).unwrap());
+    assert_eq!(reference_press(&square, 1, 1).lit_count(), 9);
+}
+
+#[test]
+fn failures_shrink_to_what_still_fails() {
+    let board = Board::from_code("5x5-ffffff8").unwrap();
+    let smallest = minimize(&board, |board| board.get(2, 2) && board.get(4, 0));
+    assert_eq!(smallest.lit_count(), 2);
+    assert!(smallest.get(2, 2) && smallest.get(4, 0));
+
+    let presses = [(0, 0), (1, 1), (2, 2), (1, 1), (3, 3)];
+    let fewest = minimize_presses(&presses, |presses| presses.contains(&(2, 2)));
+    assert_eq!(fewest, [(2, 2)]);
+}
+
+#[test]
+fn patterns_reaching_past_the_board_are_rejected() {
+    for code in [
+        "3x3-000:pattern=9223372036854775807.0",
+        "3x3-000:pattern=0.-9223372036854775808",
+        "3x3-000:wrap+pattern=3.0",
+        "3x3-000:pattern=0.0_0.0",
+        "3x3-000:pattern=1.0_0.1_1.0",
+        "3x3-000:wrap+pattern=1.0_-2.0",
+        "3x3-000:pattern=1.0_-2.0+wrap",
+    ] {
+        assert!(Board::from_code(code).is_err(), "{code}");
+    }
+    let board = Board::from_code("3x3-000:wrap+pattern=2.0_0.-2").unwrap();
+    assert!(board.solve_linear().is_some());
+}
+
+#[test]
+#[should_panic(expected = "oracle: the incremental lit count after pressing a1 on 5x5-c408f38")]
+fn wrong_counts_are_reported_with_the_board() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let mut counts = board.child_lit_counts();
+    counts[0] += 1;
+    // The fast path is right about every smaller board, so there's nothing
+    // to shrink.
+    check_child_lit_counts(&board, board.lit_count(), &counts);
+}
+
+#[test]
+#[should_panic(expected = "were worked out from 3 lights, not its")]
+fn counts_from_the_wrong_lit_count_are_caught() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    check_child_lit_counts(&board, 3, &board.child_lit_counts_from(3));
+}
@@ -0,0 +1,34 @@
+use lightsout::{
+    search::{a_star, search, LifoFrontier, OrderingBoard},
+    Board,
+};
+
+fn board(rows: &[&str]) -> Board {
+    rows.join("\n").parse().unwrap()
+}
+
+#[test]
+fn orders_make_every_press_once() {
+    let board = board(&["##.", "#..", ".##"]);
+    let solution = board.solve_linear().unwrap();
+    let count = solution.count();
+    let ordering = OrderingBoard::new(board.clone(), solution.clone());
+    let (best, _) = a_star(ordering.clone(), count);
+    let (greedy, _) = search(ordering, count, LifoFrontier::default());
+    for found in [best.unwrap(), greedy.unwrap()] {
+        let mut presses: Vec<usize> = found
+            .history
+            .iter()
+            .chain(&found.latest_move_index)
+            .copied()
+            .collect();
+        presses.sort();
+        let expected: Vec<usize> = solution
+            .positions()
+            .into_iter()
+            .map(|(x, y)| y * 3 + x)
+            .collect();
+        assert_eq!(presses, expected);
+        assert!(found.latest.board().is_clear());
+    }
+}
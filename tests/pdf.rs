@@ -0,0 +1,65 @@
+use lightsout::{
+    pack::{Pack, Puzzle},
+    Board,
+};
+
+fn text(bytes: &[u8]) -> &str {
+    std::str::from_utf8(bytes).unwrap()
+}
+
+fn pack(puzzles: usize) -> Pack {
+    let mut corner = Board::new(5, 5);
+    corner.set(0, 0, true);
+    let mut puzzles: Vec<Puzzle> = (0..puzzles)
+        .map(|i| Puzzle::new(format!("level {i}"), Board::from_code("5x5-011c400").unwrap()))
+        .collect();
+    puzzles[0] = Puzzle::new("corner (unsolvable)", corner);
+    Pack {
+        name: "test".to_string(),
+        author: None,
+        puzzles,
+    }
+}
+
+#[test]
+fn sheets_are_well_formed_pdfs() {
+    let sheet = pack(7).sheet(false);
+    assert_eq!(sheet.page_count(), 2);
+    let pdf = sheet.to_bytes();
+    let file = text(&pdf);
+    assert!(file.starts_with("%PDF-1.4\n") && file.ends_with("%%EOF\n"));
+    assert!(file.contains("/Count 2 >>"));
+
+    let (body, tail) = file.rsplit_once("startxref\n").unwrap();
+    let xref: usize = tail.lines().next().unwrap().parse().unwrap();
+    assert!(file[xref..].starts_with("xref\n"));
+    let offsets: Vec<usize> = body[xref..]
+        .lines()
+        .skip(3)
+        .take_while(|line| line.ends_with(" n "))
+        .map(|line| line[..10].parse().unwrap())
+        .collect();
+    assert_eq!(offsets.len(), 3 + 2 * 2);
+    for (i, offset) in offsets.iter().enumerate() {
+        assert!(file[*offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+    }
+
+    for stream in file.split("<< /Length ").skip(1) {
+        let (length, rest) = stream.split_once(" >>\nstream\n").unwrap();
+        let length: usize = length.parse().unwrap();
+        assert!(rest[length..].starts_with("endstream"));
+    }
+}
+
+#[test]
+fn solutions_follow_the_puzzles() {
+    let sheet = pack(7).sheet(true);
+    assert_eq!(sheet.page_count(), 4);
+    let pdf = sheet.to_bytes();
+    let file = text(&pdf);
+    assert!(file.contains("(1. corner \\(unsolvable\\))"));
+    assert!(file.contains("(test: solutions)"));
+    assert!(file.contains("(no solution)"));
+    let presses = Board::from_code("5x5-011c400").unwrap().solve_linear().unwrap();
+    assert!(file.contains(&format!("({} press", presses.count())));
+}
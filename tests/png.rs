@@ -0,0 +1,56 @@
+#![cfg(feature = "image")]
+
+use lightsout::png::{decode, encode_rgb};
+
+#[test]
+fn decodes_every_filter_through_dynamic_codes() {
+    let image = decode(include_bytes!("data/gray.png")).unwrap();
+    assert_eq!((image.width, image.height), (60, 40));
+    for (i, pixel) in image.pixels.iter().enumerate() {
+        let (x, y) = (i % 60, i / 60);
+        let gray = if (x / 6 + y / 5) % 2 == 1 {
+            (x * 37 + y * 91) % 256
+        } else {
+            (x * y) % 7 * 30
+        } as u8;
+        assert_eq!(*pixel, [gray, gray, gray, 255], "pixel {x}, {y}");
+    }
+}
+
+#[test]
+fn decodes_palettes_and_sixteen_bit_channels() {
+    let palette = decode(include_bytes!("data/palette.png")).unwrap();
+    assert_eq!((palette.width, palette.height), (10, 3));
+    for (i, pixel) in palette.pixels.iter().enumerate() {
+        let blue = (i % 10 + i / 10) % 2 == 1;
+        assert_eq!(*pixel, if blue { [0, 0, 255, 255] } else { [255, 0, 0, 128] });
+    }
+    let deep = decode(include_bytes!("data/rgba16.png")).unwrap();
+    for (i, pixel) in deep.pixels.iter().enumerate() {
+        let (x, y) = (i % 5, i / 5);
+        assert_eq!(*pixel, [x as u8 * 50, y as u8 * 60, 255 - x as u8 * 20, 200]);
+    }
+}
+
+#[test]
+fn reads_back_what_it_writes_and_rejects_damage() {
+    let pixels: Vec<[u8; 3]> = (0..12).map(|i| [i * 20, 255 - i * 20, 7]).collect();
+    let png = encode_rgb(4, 3, &pixels);
+    let image = decode(&png).unwrap();
+    let rgb: Vec<[u8; 3]> = image.pixels.iter().map(|&[r, g, b, _]| [r, g, b]).collect();
+    assert_eq!(rgb, pixels);
+
+    let mut damaged = png.clone();
+    damaged[40] ^= 1;
+    assert!(decode(&damaged).is_err());
+    assert!(decode(&png[..png.len() - 20]).is_err());
+}
+
+#[test]
+fn dark_areas_threshold_to_lit_cells() {
+    let pixels: Vec<[u8; 3]> = (0..100)
+        .map(|i| if i % 10 < 5 && i / 10 < 5 { [20, 20, 20] } else { [240, 240, 240] })
+        .collect();
+    let lights = decode(&encode_rgb(10, 10, &pixels)).unwrap().threshold(2, 2);
+    assert_eq!(lights.positions(), [(0, 0)]);
+}
@@ -0,0 +1,40 @@
+use lightsout::{
+    search::{a_star, a_star_opening_observed, Profile},
+    Board, CancellationToken,
+};
+
+fn profile(code: &str) -> Profile {
+    let board = Board::from_code(code).unwrap();
+    let depth = board.depth_bound().unwrap();
+    let mut profile = Profile::default();
+    let cancel = CancellationToken::new();
+    let (found, report) =
+        a_star_opening_observed(board.clone(), &[], depth, &cancel, |_, _| {}, &mut profile);
+    profile.finish(&report);
+
+    // Profiling doesn't change the search.
+    let (unprofiled, unprofiled_report) = a_star(board, depth);
+    assert_eq!(found.unwrap().history, unprofiled.unwrap().history);
+    assert_eq!(report, unprofiled_report);
+    profile
+}
+
+#[test]
+fn profiles_count_every_expansion() {
+    let profile = profile("5x5-2e99a38");
+    assert_eq!(profile.expanded[0], 1);
+    let by_depth: usize = profile.expanded.iter().sum();
+    let by_score: usize = profile.scores.values().sum();
+    assert_eq!(by_depth, by_score);
+    // States reached twice before either is expanded are expanded twice.
+    assert!(by_depth >= profile.report.explored);
+    assert!(profile.report.duplicates > 0);
+}
+
+#[test]
+fn profiles_read_back_what_they_write() {
+    let profile = profile("5x5-c408f38");
+    assert_eq!(profile.to_string().parse::<Profile>().unwrap(), profile);
+    assert!("explored lots".parse::<Profile>().is_err());
+    assert!("depth 1".parse::<Profile>().is_err());
+}
@@ -0,0 +1,88 @@
+use lightsout::{
+    analysis::{prospect, Criteria},
+    BoardBuilder,
+};
+
+fn five() -> BoardBuilder {
+    BoardBuilder::new().width(5).height(5)
+}
+
+#[test]
+fn matches_meet_every_criterion() {
+    let criteria = Criteria {
+        optimal: Some(8),
+        unique: true,
+        ..Criteria::default()
+    };
+    let sweep = prospect(&five(), 0..4096, 3, &criteria).unwrap();
+    assert!(!sweep.matches.is_empty());
+    for found in &sweep.matches {
+        assert_eq!(found.board, five().seed(found.seed).build().unwrap());
+        assert_eq!(found.optimal, 8);
+        assert_eq!(found.board.minimal_solutions().len(), 1);
+    }
+    assert!(sweep
+        .matches
+        .windows(2)
+        .all(|pair| pair[0].seed < pair[1].seed));
+}
+
+#[test]
+fn hardest_keeps_only_the_longest_optimum() {
+    let everything = prospect(&five(), 100..3000, 2, &Criteria::default()).unwrap();
+    let longest = everything
+        .matches
+        .iter()
+        .map(|found| found.optimal)
+        .max()
+        .unwrap();
+    assert_eq!(everything.matches.len() as u64, everything.solvable);
+
+    let criteria = Criteria {
+        hardest: true,
+        ..Criteria::default()
+    };
+    let hardest = prospect(&five(), 100..3000, 2, &criteria).unwrap();
+    let expected: Vec<u64> = everything
+        .matches
+        .iter()
+        .filter(|found| found.optimal == longest)
+        .map(|found| found.seed)
+        .collect();
+    let seeds: Vec<u64> = hardest.matches.iter().map(|found| found.seed).collect();
+    assert_eq!(seeds, expected);
+}
+
+#[test]
+fn symmetric_matches_are_symmetric() {
+    let criteria = Criteria {
+        symmetric: true,
+        ..Criteria::default()
+    };
+    let sweep = prospect(&five(), 0..5000, 2, &criteria).unwrap();
+    assert!(!sweep.matches.is_empty());
+    assert!(sweep.matches.iter().all(|found| found.board.is_symmetric()));
+}
+
+#[test]
+fn sweeps_are_the_same_on_any_number_of_threads() {
+    let criteria = Criteria {
+        optimal: Some(10),
+        ..Criteria::default()
+    };
+    let seeds = |threads| {
+        let sweep = prospect(&five(), 0..2000, threads, &criteria).unwrap();
+        sweep
+            .matches
+            .iter()
+            .map(|found| found.seed)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(seeds(1), seeds(4));
+}
+
+#[test]
+fn bad_builders_are_errors() {
+    let builder = BoardBuilder::new().width(0);
+    assert!(prospect(&builder, 0..10, 1, &Criteria::default()).is_err());
+}
@@ -0,0 +1,57 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, Command, Stdio},
+};
+
+/// `lightsout serve` on a free port, and the address it listens on.
+fn serve() -> (Child, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .args(["serve", "127.0.0.1:0"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stderr.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let address = line
+        .trim()
+        .strip_prefix("listening on http://")
+        .unwrap()
+        .to_string();
+    (child, address)
+}
+
+/// The whole response to posting `body` to `/solve`.
+fn post_solve(address: &str, body: &str) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "POST /solve HTTP/1.1\r\nHost: {address}\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn oversized_random_boards_are_refused_before_they_are_built() {
+    let (mut child, address) = serve();
+    let huge = post_solve(&address, r#"{"width":4,"height":1099511627776}"#);
+    let overflowing = post_solve(&address, r#"{"width":4,"height":9223372036854775807}"#);
+    let fine = post_solve(&address, r#"{"width":3,"height":3,"seed":1}"#);
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    for response in [huge, overflowing] {
+        assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+        assert!(
+            response.contains("boards are limited to 1024 cells"),
+            "{response}"
+        );
+    }
+    assert!(fine.starts_with("HTTP/1.1 200"), "{fine}");
+}
@@ -0,0 +1,169 @@
+//! Golden-file tests of what players see: boards drawn in every theme,
+//! solutions in every output format, and play in the fog. Output that changes fails here until
+//! it's blessed by rerunning with `BLESS=1`, which rewrites the files under
+//! `tests/snapshots/`, so a format change shows up in review as a diff.
+
+use std::{
+    env, fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use lightsout::{theme::THEMES, BitMask, Board};
+
+/// Compare `actual` with the golden file `name`, or replace the file with it
+/// when blessing. Escape characters are written as `\e` to keep the files
+/// readable.
+fn snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    let actual = actual.replace('\x1b', "\\e");
+    if env::var_os("BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "{name} changed; rerun with BLESS=1 if that's intended\n\
+         --- expected\n{expected}\n--- actual\n{actual}"
+    );
+}
+
+/// `lightsout solve` on the board `code`, with timings masked since they
+/// differ between runs, and memory use since it differs between platforms.
+fn solve(code: &str, args: &[&str]) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .args(["solve", "--input", "-"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "{code}").unwrap();
+    let output = child.wait_with_output().unwrap();
+    let text = String::from_utf8(output.stdout).unwrap();
+    text.lines()
+        .map(|line| match line.split_once(' ') {
+            Some(("Took", _)) => "Took <time>".to_string(),
+            // Sizes differ with the pointer width.
+            Some(("Peak", _)) => "Peak memory <masked>".to_string(),
+            _ => mask(mask(line.into(), "\"seconds\":"), "\"peak_memory\":"),
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// `lightsout play` with `args`, typing the lines of `input`.
+fn play(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .arg("play")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    write!(child.stdin.take().unwrap(), "{input}").unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Replace the value after each `key` in a line of JSON.
+fn mask(line: String, key: &str) -> String {
+    let mut masked = String::new();
+    let mut rest = line.as_str();
+    while let Some(start) = rest.find(key) {
+        let value = &rest[start + key.len()..];
+        let end = value.find([',', '}']).unwrap_or(value.len());
+        masked += &rest[..start + key.len()];
+        masked += "\"<masked>\"";
+        rest = &value[end..];
+    }
+    masked + rest
+}
+
+#[test]
+fn themes() {
+    let board = Board::from_code("4x4-48d6:holes=8001").unwrap();
+    let mut presses = BitMask::new(4, 4);
+    presses.set(1, 0, true);
+    presses.set(2, 2, true);
+    for theme in THEMES {
+        let drawn = format!(
+            "{}\n{}",
+            theme.draw(&board, None),
+            theme.draw(&board, Some(&presses))
+        );
+        snapshot(&format!("theme-{}", theme.name), &drawn);
+    }
+}
+
+#[test]
+fn solution_text() {
+    snapshot("solve-text", &solve("5x5-c408f38", &[]));
+    snapshot("solve-verbose", &solve("5x5-c408f38", &["-vv"]));
+    snapshot("solve-explain", &solve("5x5-c408f38", &["--explain"]));
+    snapshot(
+        "solve-unsolvable",
+        &solve("5x5-8000000", &["--algorithm", "linear"]),
+    );
+}
+
+#[test]
+fn solution_plain() {
+    snapshot(
+        "solve-plain",
+        &solve(
+            "4x4-a5f0:square",
+            &["--render", "plain", "--algorithm", "linear"],
+        ),
+    );
+}
+
+#[test]
+fn solution_json() {
+    snapshot("solve-json", &solve("5x5-c408f38", &["--output", "json"]));
+    snapshot(
+        "solve-json-linear",
+        &solve(
+            "3x3-ce8:wrap",
+            &["--output", "json", "--algorithm", "linear"],
+        ),
+    );
+}
+
+#[test]
+fn solution_csv() {
+    let csv = solve(
+        "6x5-c247bd4c",
+        &["--output", "csv", "--algorithm", "bitsliced"],
+    );
+    // Seconds are the last column.
+    let masked: String = csv
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match (i, line.rsplit_once(',')) {
+            (1.., Some((row, _))) => format!("{row},<time>\n"),
+            _ => format!("{line}\n"),
+        })
+        .collect();
+    snapshot("solve-csv", &masked);
+}
+
+#[test]
+fn play_fog() {
+    let board = ["--width", "3", "--height", "3", "--seed", "5", "--fog"];
+    let cleared = play(
+        &[&board[..], &["1"]].concat(),
+        "a1\na1\nhint\nc1\nc2\na3\nc3\n",
+    );
+    snapshot("play-fog", &cleared);
+    let abandoned = play(
+        &[&board[..], &["0", "--render", "plain"]].concat(),
+        "b2\nquit\n",
+    );
+    snapshot("play-fog-plain", &abandoned);
+}
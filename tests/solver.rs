@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use lightsout::{prelude::*, solver::AStar};
+
+#[test]
+fn every_solver_clears_boards_it_can() {
+    let solvers = Registry::default();
+    assert_eq!(
+        solvers.names().collect::<Vec<_>>(),
+        ["linear", "chase", "astar"]
+    );
+    let boards = [
+        ("5x5-c408f38", Some(3)),
+        ("3x3-ce8:wrap", Some(2)),
+        ("5x5-8000000", None),
+    ];
+    for (code, optimal) in boards {
+        let board = Board::from_code(code).unwrap();
+        for name in solvers.names() {
+            let solver = solvers.get(name).unwrap();
+            let solution = solver.solve(&board, &Budget::default()).solution();
+            assert_eq!(solution.is_some(), optimal.is_some(), "{name} on {code}");
+            let Some(solution) = solution else {
+                continue;
+            };
+            assert!(board.verify(solution.presses()), "{name} on {code}");
+            if solver.is_optimal() {
+                assert_eq!(Some(solution.len()), optimal, "{name} on {code}");
+            }
+        }
+    }
+}
+
+#[test]
+fn searches_stop_at_the_budget() {
+    let board = Board::from_code("5x5-e8cc620").unwrap();
+    let exhausted =
+        |budget: Budget| assert_eq!(AStar.solve(&board, &budget), SolveOutcome::BudgetExhausted);
+    exhausted(Budget::nodes(20_000));
+    exhausted(Budget::time(Duration::from_millis(50)));
+    exhausted(Budget {
+        max_memory: Some(1 << 20),
+        ..Budget::default()
+    });
+
+    let budget = Budget::default();
+    budget.cancel.cancel();
+    exhausted(budget.clone());
+    assert_eq!(
+        Registry::default()
+            .get("chase")
+            .unwrap()
+            .solve(&board, &budget)
+            .solution()
+            .unwrap()
+            .len(),
+        15
+    );
+}
+
+#[test]
+fn solutions_past_the_depth_limit_exhaust_the_budget() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let solvers = Registry::default();
+    for name in ["linear", "chase", "astar"] {
+        let solver = solvers.get(name).unwrap();
+        let depth = |max_depth| Budget {
+            max_depth: Some(max_depth),
+            max_time: Some(Duration::from_secs(10)),
+            ..Budget::default()
+        };
+        let outcome = solver.solve(&board, &depth(2));
+        assert_eq!(outcome, SolveOutcome::BudgetExhausted, "{name}");
+        let solution = solver.solve(&board, &depth(3)).solution();
+        assert_eq!(solution.map(|solution| solution.len()), Some(3), "{name}");
+    }
+}
+
+/// Solves by pressing every cell of a 1x1 board, to register alongside the
+/// built-in solvers.
+struct Tap;
+
+impl Solver for Tap {
+    fn name(&self) -> &str {
+        "tap"
+    }
+
+    fn solve(&self, board: &Board, _budget: &Budget) -> SolveOutcome {
+        match (board.width(), board.height()) {
+            (1, 1) => SolveOutcome::Solved(Solution::new(vec![(0, 0)])),
+            _ => SolveOutcome::BudgetExhausted,
+        }
+    }
+}
+
+#[test]
+fn custom_solvers_register_by_name() {
+    let mut solvers = Registry::default();
+    assert!(solvers.register(Tap).is_none());
+    let board = Board::from_code("1x1-8").unwrap();
+    let tap = solvers.get("tap").unwrap();
+    assert_eq!(
+        tap.solve(&board, &Budget::default())
+            .solution()
+            .unwrap()
+            .presses(),
+        [(0, 0)]
+    );
+    // Registering a name again replaces the solver.
+    assert_eq!(solvers.register(Tap).unwrap().name(), "tap");
+    assert_eq!(solvers.names().filter(|&name| name == "tap").count(), 1);
+    assert!(solvers.get("sat").is_none());
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "sound")]
+
+use lightsout::sound::{wav, Cue};
+
+fn samples(wav: &[u8]) -> Vec<i16> {
+    wav[44..]
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+#[test]
+fn cues_are_wav_files() {
+    for cue in [Cue::Press, Cue::Win] {
+        let wav = wav(cue, 1.0);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(&wav[36..40], b"data");
+        let riff = u32::from_le_bytes(wav[4..8].try_into().unwrap()) as usize;
+        let data = u32::from_le_bytes(wav[40..44].try_into().unwrap()) as usize;
+        assert_eq!((riff, data), (wav.len() - 8, wav.len() - 44));
+    }
+    assert!(wav(Cue::Win, 1.0).len() > wav(Cue::Press, 1.0).len());
+}
+
+#[test]
+fn volume_scales_the_samples() {
+    let peak = |volume| samples(&wav(Cue::Win, volume)).iter().map(|s| s.unsigned_abs()).max();
+    assert_eq!(peak(0.0), Some(0));
+    let (quiet, loud) = (peak(0.25).unwrap(), peak(1.0).unwrap());
+    assert!(loud > 20_000 && quiet.abs_diff(loud / 4) < 200, "{quiet} {loud}");
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use lightsout::{
+    search::{search, BucketFrontier, Frontier, Observer, Search, SearchState, Step, Stepper},
+    Board,
+};
+
+#[test]
+fn stepping_finds_what_search_finds() {
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let depth = board.depth_bound().unwrap();
+    let mut stepper = Stepper::new(board.clone(), depth, BucketFrontier::default());
+    assert_eq!(stepper.frontier().len(), 1);
+
+    let Step::Expanded { state, pushed } = stepper.step() else {
+        panic!("the first step should expand the board");
+    };
+    assert_eq!(state.latest, board);
+    assert_eq!(pushed, stepper.frontier().len());
+    assert_eq!(stepper.report().explored, 1);
+
+    let solved = loop {
+        match stepper.step() {
+            Step::Solved(state) => break state,
+            Step::Exhausted => panic!("the board is solvable within {depth}"),
+            _ => {}
+        }
+    };
+    let (found, report) = search(board, depth, BucketFrontier::default());
+    assert_eq!(found.unwrap().history, solved.history);
+    assert_eq!(report.explored, stepper.report().explored);
+}
+
+#[test]
+fn stepping_past_the_last_state_is_exhausted() {
+    // Three presses away, deeper than states are expanded to.
+    let board = Board::from_code("5x5-c408f38").unwrap();
+    let mut stepper = Stepper::new(board, 1, BucketFrontier::default());
+    let mut cut_off = 0;
+    loop {
+        match stepper.step() {
+            Step::Exhausted => break,
+            Step::CutOff(_) => cut_off += 1,
+            Step::Solved(_) => panic!("the board takes three presses"),
+            Step::Expanded { .. } => {}
+        }
+    }
+    assert!(cut_off > 0);
+    assert_eq!(stepper.report().depth_cutoffs, cut_off);
+}
+
+/// Adds up the memory of every state pushed.
+#[derive(Default)]
+struct Pushed(usize);
+
+impl Observer<Board> for Pushed {
+    fn on_push(&mut self, state: &SearchState<Board>) {
+        self.0 += state.memory();
+    }
+}
+
+#[test]
+fn states_expanded_twice_are_counted_once() {
+    // Five presses away, so every state within three is expanded, many of
+    // them after being pushed by more than one parent.
+    let board = Board::from_code("3x3-ff8").unwrap();
+    let mut stepper = Stepper::new(board.clone(), 3, BucketFrontier::default());
+    let mut pushed = Pushed(SearchState::from(board).memory());
+    let (mut popped, mut expansions) = (0, 0);
+    let mut explored = HashSet::new();
+    let mut explored_memory = 0;
+    let mut peak = pushed.0;
+    loop {
+        let state = match stepper.step_observed(&mut pushed) {
+            Step::Exhausted => break,
+            Step::Solved(_) => panic!("3x3-ff8 takes five presses"),
+            Step::CutOff(state) => state,
+            Step::Expanded { state, .. } => {
+                expansions += 1;
+                if explored.insert(state.latest.clone()) {
+                    explored_memory += std::mem::size_of::<Board>() + state.latest.heap_bytes();
+                }
+                popped += state.memory();
+                peak = peak.max(pushed.0 - popped + explored_memory);
+                continue;
+            }
+        };
+        popped += state.memory();
+    }
+    assert!(expansions > explored.len());
+    assert_eq!(stepper.report().explored, explored.len());
+    assert_eq!(stepper.report().peak_memory, peak);
+}
@@ -0,0 +1,73 @@
+use lightsout::Board;
+
+fn board(rows: &[&str]) -> Board {
+    rows.join("\n").parse().unwrap()
+}
+
+#[test]
+fn rotations_and_reflections_are_equivalent() {
+    let corner = board(&["##.", "#..", "..."]);
+    let rotated = board(&[".##", "..#", "..."]);
+    let flipped = board(&["...", "#..", "##."]);
+    assert!(corner.equivalent_to(&rotated));
+    assert!(corner.equivalent_to(&flipped));
+    assert_eq!(corner.canonical_form(), rotated.canonical_form());
+    assert!(!corner.equivalent_to(&board(&["##.", ".#.", "..."])));
+}
+
+#[test]
+fn boards_that_are_not_square_lie_on_their_side() {
+    let tall = board(&["#.", "..", ".."]);
+    let wide = board(&["..#", "..."]);
+    assert!(tall.equivalent_to(&wide));
+}
+
+#[test]
+fn shifts_only_count_when_presses_wrap() {
+    let code = |cells: &str| Board::from_code(&format!("4x4-{cells}")).unwrap();
+    let wrapping = |cells: &str| Board::from_code(&format!("4x4-{cells}:wrap")).unwrap();
+    assert!(!code("8000").equivalent_to(&code("0400")));
+    assert!(wrapping("8000").equivalent_to(&wrapping("0400")));
+}
+
+#[test]
+fn obstacles_turn_with_the_board() {
+    let holed = Board::from_code("3x3-000:holes=800").unwrap();
+    let turned = Board::from_code("3x3-000:holes=200").unwrap();
+    let lit = Board::from_code("3x3-800").unwrap();
+    assert!(holed.equivalent_to(&turned));
+    assert!(!holed.equivalent_to(&lit));
+}
+
+#[test]
+fn symmetric_starts_map_onto_themselves() {
+    assert!(board(&["#.#", "...", "..."]).is_symmetric());
+    assert!(board(&["##.", "#..", "..."]).is_symmetric());
+    assert!(board(&["#.", "#.", "#."]).is_symmetric());
+    assert!(!board(&["#..", "...", ".#."]).is_symmetric());
+    // The lights alone mirror along a diagonal, but the hole doesn't.
+    assert!(Board::from_code("3x3-800").unwrap().is_symmetric());
+    assert!(!Board::from_code("3x3-800:holes=400").unwrap().is_symmetric());
+}
+
+#[test]
+fn puzzle_ids_are_shared_by_rotations_and_reflections() {
+    let corner = board(&["##.", "#..", "..."]);
+    let id = corner.puzzle_id();
+    assert_eq!(id.len(), 16);
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(board(&["...", "..#", ".##"]).puzzle_id(), id);
+    assert_ne!(board(&["##.", ".#.", "..."]).puzzle_id(), id);
+    assert!(corner.matches_id(&id[..6]));
+    assert!(corner.matches_id(&id.to_uppercase()));
+    assert!(!corner.matches_id(""));
+}
+
+#[test]
+fn built_in_puzzles_are_found_by_id() {
+    let puzzle = lightsout::pack::lookup("starter/7").unwrap();
+    let id = puzzle.board.puzzle_id();
+    let found = lightsout::pack::find_id(&id[..8]);
+    assert!(found.contains(&("starter/7".to_string(), puzzle)));
+    assert!(found.iter().all(|(_, puzzle)| puzzle.board.puzzle_id() == id));
+}
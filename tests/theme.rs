@@ -0,0 +1,43 @@
+use lightsout::{
+    theme::{Theme, CLASSIC, THEMES},
+    BitMask, Board,
+};
+
+#[test]
+fn classic_draws_boards_like_display() {
+    let board = Board::from_code("3x3-000:holes=800").unwrap();
+    let mut lit = board.clone();
+    lit.toggle(1, 1);
+    for board in [board, lit] {
+        assert_eq!(CLASSIC.draw(&board, None), board.to_string());
+    }
+}
+
+#[test]
+fn themes_tell_every_kind_of_cell_apart_by_shape() {
+    for theme in THEMES.iter().filter(|theme| theme.shapes) {
+        for (i, glyph) in theme.glyphs.iter().enumerate() {
+            assert_eq!(glyph.chars().count(), 2, "{}", theme.name);
+            assert!(!theme.glyphs[..i].contains(glyph), "{}", theme.name);
+        }
+        // Presses stand out from both kinds of unpressed cell.
+        for lit in [true, false] {
+            assert_ne!(theme.color(lit, true), theme.color(true, false), "{}", theme.name);
+            assert_ne!(theme.color(lit, true), theme.color(false, false), "{}", theme.name);
+        }
+        assert_eq!(Theme::named(theme.name), Some(theme));
+    }
+    assert_eq!(Theme::named("sepia"), None);
+}
+
+#[test]
+fn rows_mark_presses() {
+    let board = Board::from_code("3x1-0").unwrap();
+    let mut presses = BitMask::new(3, 1);
+    presses.set(1, 0, true);
+    let theme = Theme::named("protanopia").unwrap();
+    let row = theme.row(&board, 0, Some(&presses));
+    assert_eq!(row.matches(theme.glyph(false, true)).count(), 1);
+    assert_eq!(row.matches(theme.glyph(false, false)).count(), 2);
+    assert_eq!(CLASSIC.row(&board, 0, Some(&presses)), "░░\x1b[41m░░\x1b[0m░░");
+}
@@ -0,0 +1,25 @@
+use std::process::Command;
+
+#[test]
+fn help_lines_up_within_each_section() {
+    let output = Command::new(env!("CARGO_BIN_EXE_lightsout"))
+        .arg("--help")
+        .output()
+        .unwrap();
+    let usage = String::from_utf8(output.stdout).unwrap();
+    for section in usage.split("\n\n").skip(1) {
+        let columns: Vec<usize> = section
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let name = 2 + line[2..].find("  ").unwrap();
+                name + line[name..].find(|c: char| c != ' ').unwrap()
+            })
+            .collect();
+        assert!(
+            columns.windows(2).all(|pair| pair[0] == pair[1]),
+            "{section}"
+        );
+    }
+    assert!(usage.contains("  --target-optimal  "), "{usage}");
+}